@@ -6,6 +6,7 @@ use liboxen::command::migrate::{
 use liboxen::constants::{DEFAULT_BRANCH_NAME, DEFAULT_REMOTE_NAME};
 
 pub const ADD: &str = "add";
+pub const APPLY: &str = "apply";
 pub const BRANCH: &str = "branch";
 pub const CHECKOUT: &str = "checkout";
 pub const CLONE: &str = "clone";
@@ -27,6 +28,8 @@ pub const LS: &str = "ls";
 pub const MERGE: &str = "merge";
 pub const METADATA: &str = "metadata";
 pub const MIGRATE: &str = "migrate";
+pub const MR: &str = "mr";
+pub const MV: &str = "mv";
 pub const PULL: &str = "pull";
 pub const PUSH: &str = "push";
 pub const READ_LINES: &str = "read-lines";
@@ -35,6 +38,7 @@ pub const RESTORE: &str = "restore";
 pub const RM: &str = "rm";
 pub const SAVE: &str = "save";
 pub const SCHEMAS: &str = "schemas";
+pub const SPLIT: &str = "split";
 pub const STATUS: &str = "status";
 pub const UPLOAD: &str = "upload";
 
@@ -157,6 +161,7 @@ pub fn remote() -> Command {
         .subcommand(rm())
         .subcommand(status())
         .subcommand(metadata())
+        .subcommand(mv())
         .arg(
             Arg::new("verbose")
                 .long("verbose")
@@ -213,6 +218,12 @@ pub fn info() -> Command {
                 .help("If present, will print the metadata info as json.")
                 .action(clap::ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new("profile")
+                .long("profile")
+                .help("If present, will also print the column stats of a tabular file.")
+                .action(clap::ArgAction::SetTrue),
+        )
 }
 
 pub fn metadata() -> Command {
@@ -239,9 +250,66 @@ pub fn metadata() -> Command {
 }
 
 pub fn log() -> Command {
-    Command::new(LOG).about("See log of commits").arg(
-        arg!([REVISION] "The commit or branch id you want to get history from. Defaults to main."),
-    )
+    Command::new(LOG)
+        .about("See log of commits")
+        .arg(arg!([REVISION] "The commit or branch id you want to get history from. Defaults to main."))
+        .arg(arg!([PATHS] ... "Only show commits that changed these files or directories"))
+        .arg(
+            Arg::new("author")
+                .long("author")
+                .help("Only show commits where the author name or email contains this text.")
+                .action(clap::ArgAction::Set),
+        )
+        .arg(
+            Arg::new("since")
+                .long("since")
+                .help("Only show commits made on or after this date, as YYYY-MM-DD or RFC 3339.")
+                .action(clap::ArgAction::Set),
+        )
+        .arg(
+            Arg::new("until")
+                .long("until")
+                .help("Only show commits made on or before this date, as YYYY-MM-DD or RFC 3339.")
+                .action(clap::ArgAction::Set),
+        )
+        .arg(
+            Arg::new("grep")
+                .long("grep")
+                .help("Only show commits where the message contains this text, ignoring case.")
+                .action(clap::ArgAction::Set),
+        )
+        .arg(
+            Arg::new("max-count")
+                .long("max-count")
+                .short('n')
+                .help("Limit the number of commits to show.")
+                .value_parser(clap::value_parser!(usize))
+                .action(clap::ArgAction::Set),
+        )
+        .arg(
+            Arg::new("follow")
+                .long("follow")
+                .help("Continue listing the history of a file past the commits that moved it with `oxen mv`. Requires exactly one path.")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("oneline")
+                .long("oneline")
+                .help("Print each commit on a single line.")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("graph")
+                .long("graph")
+                .help("Draw the branches and merge commits of the history next to the log.")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("json")
+                .long("json")
+                .help("Print the commits as json.")
+                .action(clap::ArgAction::SetTrue),
+        )
 }
 
 pub fn fetch() -> Command {
@@ -305,6 +373,18 @@ pub fn df() -> Command {
                 .help("Output file to store the transformed data")
                 .action(clap::ArgAction::Set),
         )
+        .arg(
+            Arg::new("profile")
+                .long("profile")
+                .help("Print the null counts, distinct counts, min, max, mean, std and most frequent values of each column.")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("in-place")
+                .long("in-place")
+                .help("Write the transformed data back to the input file, keeping its format, delimiter, column order and schema metadata, and stage it. With --output, writes there instead and stages it if it is in the repository.")
+                .action(clap::ArgAction::SetTrue),
+        )
         .arg(
             Arg::new("delimiter")
                 .long("delimiter")
@@ -323,7 +403,7 @@ pub fn df() -> Command {
             Arg::new("filter")
                 .long("filter")
                 .short('f')
-                .help("An filter the row data based on an expression. Supported Ops (=, !=, >, <, <= , >=) Supported dtypes (str,int,float)")
+                .help("Filter the row data based on an expression ie: \"label in [cat, dog] && (score > 0.5 || is_correct == true)\". Supported Ops (==, !=, >, <, <=, >=, in, contains, =~, is null, is not null, &&, ||, !) Supported dtypes (str,int,float,bool,date,datetime)")
                 .action(clap::ArgAction::Set),
         )
         .arg(
@@ -388,6 +468,12 @@ pub fn df() -> Command {
                 .help("A comma separated set of row indices to look at. Ex 1,22,313")
                 .action(clap::ArgAction::Set),
         )
+        .arg(
+            Arg::new("lenient")
+                .long("lenient")
+                .help("Skip csv rows that do not parse, truncate ragged lines and replace invalid utf8 instead of failing.")
+                .action(clap::ArgAction::SetTrue),
+        )
         .arg(
             Arg::new("index")
                 .long("index")
@@ -412,6 +498,25 @@ pub fn df() -> Command {
                 .help("Delete a row from a data frame. Currently only works with remote data frames with the value from _id column.")
                 .action(clap::ArgAction::Set),
         )
+        .arg(
+            Arg::new("update-row")
+                .long("update-row")
+                .help("Update a row of a remote data frame in place by its _oxen_id. The new values are passed with --row-data.")
+                .requires("row-data")
+                .action(clap::ArgAction::Set),
+        )
+        .arg(
+            Arg::new("row-data")
+                .long("row-data")
+                .help("Values for --update-row. Only the given columns are changed with json content, ie '{\"label\": \"cat\"}'.")
+                .action(clap::ArgAction::Set),
+        )
+        .arg(
+            Arg::new("batch")
+                .long("batch")
+                .help("Path to a json file of rows to change in a remote data frame in one request. Format '{\"added\": [{..}], \"modified\": [{\"_oxen_id\": .., ..}], \"removed\": [\"<_oxen_id>\"]}'")
+                .action(clap::ArgAction::Set),
+        )
         .arg(
             Arg::new("get-row")
                 .long("get-row")
@@ -437,6 +542,13 @@ pub fn df() -> Command {
                 .help("Run a sql query on the data frame.")
                 .action(clap::ArgAction::Set),
         )
+        .arg(
+            Arg::new("revision")
+                .long("revision")
+                .short('r')
+                .help("Read the data frame at a commit id or branch name instead of the working copy. Sql queries can also select from other revisions with tables named \"path@revision\".")
+                .action(clap::ArgAction::Set),
+        )
         .arg(
             Arg::new("committed")
                 .long("committed")
@@ -468,6 +580,24 @@ pub fn df() -> Command {
                 .help("Randomize the order of the table")
                 .action(clap::ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new("sample")
+                .long("sample")
+                .help("Take a random sample of the rows. Either a number of rows ie: '100' or a fraction ie: '0.1'")
+                .action(clap::ArgAction::Set),
+        )
+        .arg(
+            Arg::new("stratify")
+                .long("stratify")
+                .help("Sample each value of this column in proportion to how often it occurs. Used with --sample.")
+                .action(clap::ArgAction::Set),
+        )
+        .arg(
+            Arg::new("seed")
+                .long("seed")
+                .help("Seed for --randomize and --sample so the same rows are returned each time.")
+                .action(clap::ArgAction::Set),
+        )
         .arg(
             Arg::new("reverse")
                 .long("reverse")
@@ -488,6 +618,45 @@ pub fn df() -> Command {
         )
 }
 
+pub fn split() -> Command {
+    Command::new(SPLIT)
+        .about("Split a data frame into train, validation and test files and stage them.")
+        .arg(arg!(<PATH> "The data frame you want to split."))
+        .arg_required_else_help(true)
+        .arg(
+            Arg::new("ratios")
+                .long("ratios")
+                .help("Comma separated size of each split. Default '0.8,0.1,0.1'")
+                .default_value("0.8,0.1,0.1")
+                .action(clap::ArgAction::Set),
+        )
+        .arg(
+            Arg::new("names")
+                .long("names")
+                .help("Comma separated name of each split. Default 'train,val,test'")
+                .action(clap::ArgAction::Set),
+        )
+        .arg(
+            Arg::new("group-by")
+                .long("group-by")
+                .help("Keep all the rows with the same value in this column in the same split, so a key never leaks across splits.")
+                .action(clap::ArgAction::Set),
+        )
+        .arg(
+            Arg::new("seed")
+                .long("seed")
+                .help("Seed for the shuffle so the split can be reproduced. A random seed is recorded if not provided.")
+                .action(clap::ArgAction::Set),
+        )
+        .arg(
+            Arg::new("output-dir")
+                .long("output-dir")
+                .short('o')
+                .help("Directory to write the splits to. Defaults to the directory of the input file.")
+                .action(clap::ArgAction::Set),
+        )
+}
+
 pub fn schemas() -> Command {
     Command::new(SCHEMAS)
         .about("Manage schemas that are created from committing tabular data")
@@ -537,6 +706,17 @@ pub fn schemas() -> Command {
                         .help("Set the metadata for a specific column. Must pass in the -c flag.")
                 ),
         )
+        .subcommand(
+            Command::new("contract")
+                .about("Set how the schema of a file is allowed to change in future commits.")
+                .arg(Arg::new("PATH").help("The path of the data frame file.").required(true))
+                .arg(
+                    Arg::new("MODE")
+                        .help("'strict' for no changes, 'additive' to allow new columns, 'backward' to also allow widening types, or 'none' to remove the contract.")
+                        .value_parser(["strict", "additive", "backward", "none"])
+                        .required(true),
+                ),
+        )
         .subcommand(
             Command::new("rm")
                 .about("Remove a schema from the list of committed or added schemas.")
@@ -568,6 +748,12 @@ pub fn add() -> Command {
         )
 }
 
+pub fn apply() -> Command {
+    Command::new(APPLY)
+        .about("Applies a patch created with `oxen diff --output <file>.oxenpatch` to the working directory")
+        .arg(arg!(<PATCH> "Path to the .oxenpatch file"))
+}
+
 pub fn download() -> Command {
     Command::new(DOWNLOAD)
         .about("Download a specific file from the remote repository")
@@ -683,6 +869,14 @@ pub fn rm() -> Command {
         )
 }
 
+pub fn mv() -> Command {
+    Command::new(MV)
+        .about("Moves or renames a file or directory and stages the move")
+        .arg(arg!(<SOURCE> "The file or directory to move"))
+        .arg(arg!(<DESTINATION> "The path to move it to. If it is an existing directory, the source is moved into it."))
+        .arg_required_else_help(true)
+}
+
 pub fn restore() -> Command {
     Command::new(RESTORE)
         .about("Restore specified paths in the working tree with some contents from a restore source.")
@@ -784,6 +978,67 @@ pub fn merge() -> Command {
         .arg(arg!(<BRANCH> "The name of the branch you want to merge in."))
 }
 
+pub fn merge_request() -> Command {
+    Command::new(MR)
+        .about("Open, list, and merge merge requests on a remote repository")
+        .subcommand_required(true)
+        .arg_required_else_help(true)
+        .arg(
+            Arg::new("remote")
+                .long("remote")
+                .short('r')
+                .help("The name of the remote the merge requests live on")
+                .default_value(DEFAULT_REMOTE_NAME)
+                .global(true)
+                .action(clap::ArgAction::Set),
+        )
+        .subcommand(
+            Command::new("create")
+                .about("Open a merge request from a branch into another branch")
+                .arg(arg!([SOURCE] "The branch you want to merge. Defaults to the current branch."))
+                .arg(
+                    Arg::new("target")
+                        .long("target")
+                        .short('t')
+                        .help("The branch you want to merge into")
+                        .default_value(DEFAULT_BRANCH_NAME)
+                        .action(clap::ArgAction::Set),
+                )
+                .arg(
+                    Arg::new("title")
+                        .long("title")
+                        .short('m')
+                        .help("The title of the merge request")
+                        .required(true)
+                        .action(clap::ArgAction::Set),
+                )
+                .arg(
+                    Arg::new("description")
+                        .long("description")
+                        .short('d')
+                        .help("A longer description of the changes")
+                        .action(clap::ArgAction::Set),
+                ),
+        )
+        .subcommand(
+            Command::new("list")
+                .about("List the merge requests on the remote")
+                .arg(
+                    Arg::new("status")
+                        .long("status")
+                        .short('s')
+                        .help("Only list merge requests with this status: open, merged, or closed")
+                        .action(clap::ArgAction::Set),
+                ),
+        )
+        .subcommand(
+            Command::new("merge")
+                .about("Merge an open merge request into its target branch")
+                .arg_required_else_help(true)
+                .arg(arg!(<ID> "The id of the merge request you want to merge.")),
+        )
+}
+
 pub fn clone() -> Command {
     Command::new(CLONE)
         .about("Clone a repository by its URL")
@@ -868,10 +1123,10 @@ pub fn pull() -> Command {
 
 pub fn diff() -> Command {
     Command::new(DIFF)
-        .about("Compare two files against each other or against versions. The two resource paramaters can be specified by filepath or `file:revision` syntax.")
+        .about("Compare two files against each other or against versions. The two resource paramaters can be specified by filepath or `file:revision` syntax. Without resources, or with --revision or --staged, compares the whole working directory instead.")
         .arg(Arg::new("RESOURCE1")
-            .required(true)
-            .help("First resource, in format `file` or `file:revision`")
+            .required(false)
+            .help("First resource, in format `file` or `file:revision`. When comparing the working directory, only show changes within this path.")
             .index(1)
         )
         .arg(Arg::new("RESOURCE2")
@@ -896,8 +1151,43 @@ pub fn diff() -> Command {
             .required(false)
             .long("output")
             .short('o')
-            .help("Output directory path to write the results of the comparison. Will write both match.csv (rows with same keys and compares) and diff.csv (rows with different compares between files.")
+            .help("Output directory path to write the results of the comparison. Will write both match.csv (rows with same keys and compares) and diff.csv (rows with different compares between files. A path ending in .oxenpatch writes a patch that can be applied with `oxen apply`. For images, writes a mask highlighting the changed pixels.")
+            .action(clap::ArgAction::Set))
+        .arg(Arg::new("context")
+            .required(false)
+            .long("context")
+            .short('U')
+            .help("Number of unchanged lines to show around each change in text files.")
+            .default_value("3")
+            .value_parser(clap::value_parser!(usize))
+            .action(clap::ArgAction::Set))
+        .arg(Arg::new("stat")
+            .long("stat")
+            .help("Only print a summary of the number of changes instead of the full diff. For a directory, summarizes the changes by data type, ie. `oxen diff --stat images:main images:my-branch`.")
+            .action(clap::ArgAction::SetTrue))
+        .arg(Arg::new("tolerance")
+            .long("tolerance")
+            .help("Numbers in the compare columns that differ by no more than this are treated as equal.")
+            .value_parser(clap::value_parser!(f64))
+            .action(clap::ArgAction::Set))
+        .arg(Arg::new("ignore-case")
+            .long("ignore-case")
+            .help("Compare strings in the compare columns regardless of case.")
+            .action(clap::ArgAction::SetTrue))
+        .arg(Arg::new("ignore-whitespace")
+            .long("ignore-whitespace")
+            .help("Ignore leading, trailing and repeated whitespace in the compare columns.")
+            .action(clap::ArgAction::SetTrue))
+        .arg(Arg::new("revision")
+            .long("revision")
+            .short('r')
+            .help("Compare the working directory (or the staged files with --staged) against this commit id or branch name instead of HEAD.")
             .action(clap::ArgAction::Set))
+        .arg(Arg::new("staged")
+            .long("staged")
+            .visible_alias("cached")
+            .help("Compare the staged files instead of the working directory.")
+            .action(clap::ArgAction::SetTrue))
 }
 
 pub fn commit_cache() -> Command {
@@ -7,26 +7,40 @@ use liboxen::command::migrate::UpdateVersionFilesMigration;
 use liboxen::config::{AuthConfig, UserConfig};
 use liboxen::constants;
 use liboxen::core::df::pretty_print;
+use liboxen::core::df::sql;
 use liboxen::core::df::tabular;
 use liboxen::error;
 use liboxen::error::OxenError;
-use liboxen::model::diff::text_diff::TextDiff;
+use liboxen::model::diff::diff_entry_status::DiffEntryStatus;
+use liboxen::model::diff::dir_diff_summary::DirDiffSummary;
+use liboxen::model::diff::image_diff::ImageDiff;
+use liboxen::model::diff::json_diff::JsonDiff;
+use liboxen::model::diff::patch::OXEN_PATCH_EXT;
+use liboxen::model::diff::text_diff::{TextDiff, DEFAULT_CONTEXT_LINES};
 use liboxen::model::diff::ChangeType;
 use liboxen::model::file::FileNew;
+use liboxen::model::metadata::MetadataImage;
 use liboxen::model::schema;
+use liboxen::model::Commit;
 use liboxen::model::EntryDataType;
+use liboxen::model::NewMergeRequest;
+use liboxen::model::RemoteRepository;
 use liboxen::model::RepoNew;
 use liboxen::model::{staged_data::StagedDataOpts, LocalRepository};
 use liboxen::opts::AddOpts;
 use liboxen::opts::CloneOpts;
 use liboxen::opts::DFOpts;
+use liboxen::opts::DiffTreeOpts;
 use liboxen::opts::DownloadOpts;
 use liboxen::opts::InfoOpts;
 use liboxen::opts::ListOpts;
 use liboxen::opts::LogOpts;
+use liboxen::opts::MvOpts;
 use liboxen::opts::PaginateOpts;
 use liboxen::opts::RestoreOpts;
 use liboxen::opts::RmOpts;
+use liboxen::opts::SplitOpts;
+use liboxen::opts::TabularDiffOpts;
 use liboxen::opts::UploadOpts;
 use liboxen::util;
 use liboxen::util::oxen_version::OxenVersion;
@@ -36,6 +50,7 @@ use liboxen::model::diff::tabular_diff::TabularDiffMods;
 use liboxen::model::diff::DiffResult;
 use liboxen::view::PaginatedDirEntries;
 use minus::Pager;
+use std::collections::HashSet;
 use std::env;
 use std::fmt::Write;
 use std::path::{Path, PathBuf};
@@ -513,6 +528,14 @@ pub async fn rm(paths: Vec<PathBuf>, opts: &RmOpts) -> Result<(), OxenError> {
     Ok(())
 }
 
+pub async fn mv(opts: &MvOpts) -> Result<(), OxenError> {
+    let repo_dir = env::current_dir().unwrap();
+    let repository = LocalRepository::from_dir(&repo_dir)?;
+    check_repo_migration_needed(&repository)?;
+
+    command::mv(&repository, opts).await
+}
+
 pub async fn restore(opts: RestoreOpts) -> Result<(), OxenError> {
     let repo_dir = env::current_dir().unwrap();
     let repository = LocalRepository::from_dir(&repo_dir)?;
@@ -561,64 +584,219 @@ pub async fn diff(
     revision_2: Option<String>,
     keys: Vec<String>,
     targets: Vec<String>,
+    opts: TabularDiffOpts,
     output: Option<PathBuf>,
     is_remote: bool,
+    stat: bool,
 ) -> Result<(), OxenError> {
+    let print_opts = DiffPrintOpts {
+        old_name: format!("a/{}", path_1.display()),
+        new_name: format!("b/{}", path_2.as_ref().unwrap_or(&path_1).display()),
+        context: opts.context_lines.unwrap_or(DEFAULT_CONTEXT_LINES),
+        stat,
+    };
     if is_remote {
         let repo_dir = env::current_dir().unwrap();
         let repository = LocalRepository::from_dir(&repo_dir)?;
         check_repo_migration_needed(&repository)?;
 
         let mut remote_diff = command::remote::diff(&repository, revision_1, &path_1).await?;
-        print_compare_result(&remote_diff)?;
+        print_compare_result(&remote_diff, &print_opts)?;
         maybe_save_compare_output(&mut remote_diff, output)?;
 
         // TODO: Allow them to save a remote diff to disk
-    } else {
-        // If the user specifies two files without revisions, we will compare the files on disk
-        let mut compare_result = if revision_1.is_none() && revision_2.is_none() && path_2.is_some()
-        {
-            // If we do not have revisions set, just compare the files on disk
-            command::diff(path_1, path_2, keys, targets, None, revision_1, revision_2)?
+    } else if stat && path_1.is_dir() {
+        // Directories are summarized by data type between two committed revisions
+        let repo_dir = env::current_dir().unwrap();
+        let repository = LocalRepository::from_dir(&repo_dir)?;
+        let summary = command::diff_dir_summary(&repository, &path_1, revision_1, revision_2)?;
+        print_dir_diff_summary(&path_1, &summary);
+    } else if is_patch_output(&output) {
+        let repo_dir = env::current_dir().unwrap();
+        let output = output.unwrap();
+        let patch = if path_1.is_dir() && path_2.is_none() {
+            // Every change within the directory goes in the one bundle
+            let repository = LocalRepository::from_dir(&repo_dir)?;
+            let opts = DiffTreeOpts {
+                revision: revision_1,
+                staged: false,
+                paths: vec![dunce::canonicalize(&path_1)?],
+            };
+            command::diff_tree_to_patch(&repository, &opts, keys, &output)?
         } else {
-            // If we have revisions set, pass in the repo_dir to be able
-            // to compare the files at those revisions within the .oxen repo
-            let repo_dir = env::current_dir().unwrap();
-            command::diff(
+            command::diff_to_patch(
                 path_1,
                 path_2,
                 keys,
-                targets,
                 Some(repo_dir),
                 revision_1,
                 revision_2,
+                &output,
             )?
         };
+        println!(
+            "Wrote patch with {} change(s) to {}",
+            patch.entries.len(),
+            output.display()
+        );
+    } else {
+        // If the user specifies two files without revisions, we will compare the files on disk
+        let repo_dir = if revision_1.is_none() && revision_2.is_none() && path_2.is_some() {
+            None
+        } else {
+            // If we have revisions set, pass in the repo_dir to be able
+            // to compare the files at those revisions within the .oxen repo
+            Some(env::current_dir().unwrap())
+        };
+        let mut compare_result = command::diff(
+            &path_1,
+            path_2.clone(),
+            keys,
+            targets,
+            &opts,
+            repo_dir.clone(),
+            revision_1.clone(),
+            revision_2.clone(),
+        )?;
 
-        print_compare_result(&compare_result)?;
-        maybe_save_compare_output(&mut compare_result, output)?;
+        print_compare_result(&compare_result, &print_opts)?;
+        match (&compare_result, output) {
+            // The output of an image diff is a rendered mask of the changed pixels
+            (DiffResult::Image(_), Some(output)) => {
+                command::diff_image_mask(
+                    path_1, path_2, repo_dir, revision_1, revision_2, &output,
+                )?;
+                println!("Wrote difference mask to {}", output.display());
+            }
+            (_, output) => maybe_save_compare_output(&mut compare_result, output)?,
+        }
     };
 
     Ok(())
 }
 
+pub fn diff_tree(
+    opts: &DiffTreeOpts,
+    keys: Vec<String>,
+    output: Option<PathBuf>,
+) -> Result<(), OxenError> {
+    let repo_dir = env::current_dir().unwrap();
+    let repository = LocalRepository::from_dir(&repo_dir)?;
+    check_repo_migration_needed(&repository)?;
+
+    // The path filters are relative to where oxen is run, the entries to the root of the repo
+    let mut opts = opts.clone();
+    opts.paths = opts
+        .paths
+        .iter()
+        .map(|path| repo_relative_path(&repository, &repo_dir, path))
+        .collect::<Result<Vec<PathBuf>, OxenError>>()?;
+
+    if let Some(output) = output {
+        if !is_patch_output(&Some(output.clone())) {
+            return Err(OxenError::basic_str(format!(
+                "Diffing the whole tree can only be saved as a .{OXEN_PATCH_EXT} patch"
+            )));
+        }
+        let patch = command::diff_tree_to_patch(&repository, &opts, keys, &output)?;
+        println!(
+            "Wrote patch with {} change(s) to {}",
+            patch.entries.len(),
+            output.display()
+        );
+        return Ok(());
+    }
+
+    let entries = command::diff_tree(&repository, &opts)?;
+    if entries.is_empty() {
+        println!("No changes");
+        return Ok(());
+    }
+
+    for entry in entries.iter() {
+        let line = format!("  {:<10}{}", format!("{}:", entry.status), entry.filename);
+        match entry.status.parse::<DiffEntryStatus>() {
+            Ok(DiffEntryStatus::Added) => println!("{}", line.green()),
+            Ok(DiffEntryStatus::Removed) => println!("{}", line.red()),
+            _ => println!("{}", line.yellow()),
+        }
+    }
+
+    let counts = api::local::diff::get_add_remove_modify_counts(&entries);
+    println!(
+        " {} files changed, {} added, {} removed, {} modified",
+        entries.len(),
+        counts.added,
+        counts.removed,
+        counts.modified
+    );
+    Ok(())
+}
+
+fn is_patch_output(output: &Option<PathBuf>) -> bool {
+    output
+        .as_ref()
+        .and_then(|p| p.extension())
+        .map(|ext| ext == OXEN_PATCH_EXT)
+        .unwrap_or(false)
+}
+
 fn maybe_save_compare_output(
     result: &mut DiffResult,
     output: Option<PathBuf>,
 ) -> Result<(), OxenError> {
     match result {
         DiffResult::Tabular(result) => {
-            let mut df = result.contents.clone();
             // Save to disk if we have an output
             if let Some(file_path) = output {
+                let is_delimited = file_path
+                    .extension()
+                    .map(|ext| ext == "csv" || ext == "tsv")
+                    .unwrap_or(false);
+                let mut df = if is_delimited {
+                    result.flat_contents()?
+                } else {
+                    result.contents.clone()
+                };
                 tabular::write_df(&mut df, file_path.clone())?;
             }
         }
         DiffResult::Text(_) => {
             println!("Saving to disk not supported for text output");
         }
+        DiffResult::Json(result) => {
+            if let Some(file_path) = output {
+                util::fs::write_to_path(file_path, serde_json::to_string_pretty(result)?)?;
+            }
+        }
+        DiffResult::Image(result) => {
+            if let Some(file_path) = output {
+                util::fs::write_to_path(file_path, serde_json::to_string_pretty(result)?)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn apply(patch: &Path) -> Result<(), OxenError> {
+    let repo_dir = env::current_dir().unwrap();
+    let repository = LocalRepository::from_dir(&repo_dir)?;
+
+    let result = command::apply(&repository, patch)?;
+    for path in result.applied.iter() {
+        println!("Applied patch to {}", path.display());
+    }
+    for conflict in result.conflicts.iter() {
+        println!("{}", format!("Conflict: {conflict}").red());
     }
 
+    if result.has_conflicts() {
+        return Err(OxenError::basic_str(format!(
+            "Could not apply patch to {} file(s), they were left unchanged",
+            result.conflicts.len()
+        )));
+    }
     Ok(())
 }
 
@@ -631,6 +809,92 @@ pub fn merge(branch: &str) -> Result<(), OxenError> {
     Ok(())
 }
 
+async fn get_remote_repo_by_name(
+    repo: &LocalRepository,
+    remote_name: &str,
+) -> Result<RemoteRepository, OxenError> {
+    let remote = repo
+        .get_remote(remote_name)
+        .ok_or(OxenError::remote_not_set(remote_name))?;
+    api::remote::repositories::get_by_remote(&remote)
+        .await?
+        .ok_or(OxenError::remote_not_found(remote.clone()))
+}
+
+pub async fn create_merge_request(
+    remote_name: &str,
+    source: Option<String>,
+    target: &str,
+    title: &str,
+    description: &str,
+) -> Result<(), OxenError> {
+    let repo_dir = env::current_dir().unwrap();
+    let repository = LocalRepository::from_dir(&repo_dir)?;
+    let host = get_host_from_repo(&repository)?;
+    check_remote_version(host).await?;
+
+    let source = match source {
+        Some(source) => source,
+        None => {
+            api::local::branches::current_branch(&repository)?
+                .ok_or(OxenError::must_be_on_valid_branch())?
+                .name
+        }
+    };
+
+    let remote_repo = get_remote_repo_by_name(&repository, remote_name).await?;
+    let new_merge_request = NewMergeRequest {
+        title: title.to_string(),
+        description: description.to_string(),
+        source_branch: source,
+        target_branch: target.to_string(),
+        author: UserConfig::get()?.to_user(),
+    };
+    let merge_request =
+        api::remote::merge_requests::create(&remote_repo, &new_merge_request).await?;
+    println!("Opened merge request {merge_request}");
+    Ok(())
+}
+
+pub async fn list_merge_requests(remote_name: &str, status: Option<&str>) -> Result<(), OxenError> {
+    let repo_dir = env::current_dir().unwrap();
+    let repository = LocalRepository::from_dir(&repo_dir)?;
+    let host = get_host_from_repo(&repository)?;
+    check_remote_version(host).await?;
+
+    let remote_repo = get_remote_repo_by_name(&repository, remote_name).await?;
+    let merge_requests = api::remote::merge_requests::list(&remote_repo, status).await?;
+    for merge_request in merge_requests {
+        let id = format!("#{}", merge_request.id).yellow();
+        println!(
+            "{id}\t{}\t{} -> {}\t[{}] by {}",
+            merge_request.title,
+            merge_request.source_branch,
+            merge_request.target_branch,
+            merge_request.status,
+            merge_request.author.name
+        );
+    }
+    Ok(())
+}
+
+pub async fn merge_merge_request(remote_name: &str, id: u64) -> Result<(), OxenError> {
+    let repo_dir = env::current_dir().unwrap();
+    let repository = LocalRepository::from_dir(&repo_dir)?;
+    let host = get_host_from_repo(&repository)?;
+    check_remote_version(host).await?;
+
+    let remote_repo = get_remote_repo_by_name(&repository, remote_name).await?;
+    let merge_request = api::remote::merge_requests::merge(&remote_repo, id).await?;
+    println!(
+        "Merged {} into {} with commit {}",
+        merge_request.source_branch,
+        merge_request.target_branch,
+        merge_request.merge_commit_id.unwrap_or_default()
+    );
+    Ok(())
+}
+
 pub async fn commit(message: &str, is_remote: bool) -> Result<(), OxenError> {
     let repo_dir = env::current_dir().unwrap();
     let repo = LocalRepository::from_dir(&repo_dir)?;
@@ -669,31 +933,86 @@ pub async fn fetch() -> Result<(), OxenError> {
     Ok(())
 }
 
-pub async fn log_commits(opts: LogOpts) -> Result<(), OxenError> {
+/// How `oxen log` prints the commits
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LogFormat {
+    pub oneline: bool,
+    pub graph: bool,
+    pub json: bool,
+}
+
+pub async fn log_commits(mut opts: LogOpts, format: LogFormat) -> Result<(), OxenError> {
     // Look up from the current dir for .oxen directory
     let current_dir = env::current_dir().unwrap();
     let repo_dir =
         util::fs::get_repo_root(&current_dir).ok_or(OxenError::basic_str(error::NO_REPO_FOUND))?;
     let repository = LocalRepository::from_dir(&repo_dir)?;
 
+    resolve_log_paths(&repository, &current_dir, &mut opts)?;
     let commits = api::local::commits::list_with_opts(&repository, &opts).await?;
 
+    if format.json {
+        println!("{}", serde_json::to_string_pretty(&commits)?);
+        return Ok(());
+    }
+
     // Fri, 21 Oct 2022 16:08:39 -0700
-    let format = format_description::parse(
+    let date_format = format_description::parse(
         "[weekday], [day] [month repr:long] [year] [hour]:[minute]:[second] [offset_hour sign:mandatory]",
     ).unwrap();
 
+    let graph = if format.graph {
+        log_graph(&commits)
+    } else {
+        commits.iter().map(|_| LogGraphRow::default()).collect()
+    };
+
     let mut output = Pager::new();
 
-    for commit in commits {
+    for (commit, row) in commits.iter().zip(graph) {
+        if format.oneline {
+            let short_id = commit.id.chars().take(8).collect::<String>().yellow();
+            let message = commit.message.lines().next().unwrap_or_default();
+            write_to_pager(
+                &mut output,
+                &graph_line(&row.node, &format!("{short_id} {message}")),
+            )?;
+            for line in row.after.iter() {
+                write_to_pager(&mut output, line)?;
+            }
+            continue;
+        }
+
         let commit_id_str = format!("commit {}", commit.id).yellow();
-        write_to_pager(&mut output, &format!("{}\n", commit_id_str))?;
-        write_to_pager(&mut output, &format!("Author: {}", commit.author))?;
         write_to_pager(
             &mut output,
-            &format!("Date:   {}\n", commit.timestamp.format(&format).unwrap()),
+            &graph_line(&row.node, &commit_id_str.to_string()),
+        )?;
+        for line in row.after.iter() {
+            write_to_pager(&mut output, line)?;
+        }
+        if commit.parent_ids.len() > 1 {
+            let merge = format!("Merge:  {}", commit.parent_ids.join(" "));
+            write_to_pager(&mut output, &graph_line(&row.padding, &merge))?;
+        }
+        write_to_pager(
+            &mut output,
+            &graph_line(&row.padding, &format!("Author: {}", commit.author)),
+        )?;
+        write_to_pager(
+            &mut output,
+            &graph_line(
+                &row.padding,
+                &format!(
+                    "Date:   {}\n",
+                    commit.timestamp.format(&date_format).unwrap()
+                ),
+            ),
+        )?;
+        write_to_pager(
+            &mut output,
+            &graph_line(&row.padding, &format!("    {}\n", commit.message)),
         )?;
-        write_to_pager(&mut output, &format!("    {}\n", commit.message))?;
     }
 
     match minus::page_all(output) {
@@ -706,6 +1025,159 @@ pub async fn log_commits(opts: LogOpts) -> Result<(), OxenError> {
     Ok(())
 }
 
+// Paths are given relative to the current dir and are sent relative to the repository root, for
+// the remote as well. Like `git log`, a single argument that is a file or directory in the
+// repository is a path rather than the revision to list the history from, and one that is neither
+// is an error. Remote revisions cannot be checked here, so they are left for the remote.
+fn resolve_log_paths(
+    repo: &LocalRepository,
+    current_dir: &Path,
+    opts: &mut LogOpts,
+) -> Result<(), OxenError> {
+    if let Some(revision) = opts.revision.clone() {
+        if current_dir.join(&revision).exists() {
+            opts.paths.insert(0, PathBuf::from(revision));
+            opts.revision = None;
+        } else if !opts.remote && api::local::revisions::get(repo, &revision)?.is_none() {
+            return Err(OxenError::basic_str(format!(
+                "ambiguous argument '{revision}': unknown revision or path not in the working tree"
+            )));
+        }
+    }
+
+    opts.paths = opts
+        .paths
+        .iter()
+        .map(|path| repo_relative_path(repo, current_dir, path))
+        .collect::<Result<Vec<PathBuf>, OxenError>>()?;
+    Ok(())
+}
+
+// Paths on the command line are relative to the current dir, the repo wants them relative to its root
+fn repo_relative_path(
+    repo: &LocalRepository,
+    current_dir: &Path,
+    path: &Path,
+) -> Result<PathBuf, OxenError> {
+    let full_path =
+        dunce::canonicalize(current_dir.join(path)).unwrap_or_else(|_| current_dir.join(path));
+    util::fs::path_relative_to_dir(full_path, &repo.path)
+}
+
+// The lanes of the history graph drawn to the left of each commit
+#[derive(Default)]
+struct LogGraphRow {
+    // The lanes with a `*` in the lane of the commit
+    node: String,
+    // Lines that join or split lanes after the commit
+    after: Vec<String>,
+    // The lanes to print next to the rest of the commit
+    padding: String,
+}
+
+fn graph_line(lanes: &str, text: &str) -> String {
+    if lanes.is_empty() {
+        text.to_string()
+    } else {
+        format!("{lanes} {text}")
+    }
+}
+
+// Draws the marks at their columns, each lane takes two columns
+fn draw_lanes(marks: impl Iterator<Item = (usize, char)>) -> String {
+    let mut line: Vec<char> = vec![];
+    for (pos, mark) in marks {
+        if pos >= line.len() {
+            line.resize(pos + 1, ' ');
+        }
+        line[pos] = mark;
+    }
+    line.into_iter().collect()
+}
+
+fn straight_lanes(lanes: std::ops::Range<usize>) -> impl Iterator<Item = (usize, char)> {
+    lanes.map(|i| (i * 2, '|'))
+}
+
+// Each lane waits on the next commit of a line of history. Merge commits open a lane for each
+// extra parent and lanes close when they reach a commit another lane already leads to.
+// Parents that were filtered out of the log end their lane.
+fn log_graph(commits: &[Commit]) -> Vec<LogGraphRow> {
+    let shown: HashSet<&str> = commits.iter().map(|commit| commit.id.as_str()).collect();
+    let mut lanes: Vec<String> = vec![];
+    let mut rows: Vec<LogGraphRow> = vec![];
+
+    for commit in commits {
+        let col = match lanes.iter().position(|id| id == &commit.id) {
+            Some(col) => col,
+            None => {
+                lanes.push(commit.id.to_owned());
+                lanes.len() - 1
+            }
+        };
+        let node = draw_lanes(straight_lanes(0..lanes.len()).map(|(pos, mark)| {
+            if pos == col * 2 {
+                (pos, '*')
+            } else {
+                (pos, mark)
+            }
+        }));
+        let mut after: Vec<String> = vec![];
+
+        let parents: Vec<&String> = commit
+            .parent_ids
+            .iter()
+            .filter(|id| shown.contains(id.as_str()))
+            .collect();
+        match parents.split_first() {
+            None => {
+                if col + 1 < lanes.len() {
+                    let shifted = (col + 1..lanes.len()).map(|i| (i * 2 - 1, '/'));
+                    after.push(draw_lanes(straight_lanes(0..col).chain(shifted)));
+                }
+                lanes.remove(col);
+            }
+            Some((first, rest)) => {
+                // If another lane already leads to the parent, the later lane joins the earlier one
+                // and the lanes after it shift left
+                let col = match lanes.iter().position(|id| id == *first) {
+                    Some(other) => {
+                        let joined = col.max(other);
+                        let shifted = (joined..lanes.len()).map(|i| (i * 2 - 1, '/'));
+                        after.push(draw_lanes(straight_lanes(0..joined).chain(shifted)));
+                        lanes.remove(joined);
+                        lanes[col.min(other)] = first.to_string();
+                        col.min(other)
+                    }
+                    None => {
+                        lanes[col] = first.to_string();
+                        col
+                    }
+                };
+                let num_lanes = lanes.len();
+                let mut opened = 0;
+                for parent in rest {
+                    if !lanes.contains(parent) {
+                        opened += 1;
+                        lanes.insert(col + opened, parent.to_string());
+                    }
+                }
+                if opened > 0 {
+                    let shifted = (col..num_lanes).map(|i| (i * 2 + 1, '\\'));
+                    after.push(draw_lanes(straight_lanes(0..col + 1).chain(shifted)));
+                }
+            }
+        }
+
+        rows.push(LogGraphRow {
+            node,
+            after,
+            padding: draw_lanes(straight_lanes(0..lanes.len())),
+        });
+    }
+    rows
+}
+
 pub async fn status(directory: Option<PathBuf>, opts: &StagedDataOpts) -> Result<(), OxenError> {
     if opts.is_remote {
         return remote_status(directory, opts).await;
@@ -773,6 +1245,10 @@ pub fn info(opts: InfoOpts) -> Result<(), OxenError> {
             metadata.extension,
             last_updated_commit_id
         );
+
+        if let Some(profile) = &metadata.profile {
+            println!("{}", pretty_print::df_to_str(&profile.to_df()?));
+        }
     }
 
     Ok(())
@@ -916,8 +1392,36 @@ fn maybe_display_types(entries: &PaginatedDirEntries) {
     }
 }
 
-pub fn df<P: AsRef<Path>>(input: P, opts: DFOpts) -> Result<(), OxenError> {
-    command::df(input, opts)?;
+pub fn df<P: AsRef<Path>>(input: P, mut opts: DFOpts) -> Result<(), OxenError> {
+    let has_revision_tables = opts.sql.as_ref().is_some_and(sql::has_revision_tables);
+    if opts.in_place {
+        let repo_dir = env::current_dir().unwrap();
+        let repo = LocalRepository::from_dir(&repo_dir)?;
+        opts.output = opts.output.map(|output| repo_dir.join(output));
+        command::df::df_in_place(&repo, repo_dir.join(input), opts)?;
+    } else if opts.revision.is_some() || has_revision_tables {
+        let repo_dir = env::current_dir().unwrap();
+        let repo = LocalRepository::from_dir(&repo_dir)?;
+        command::df::df_in_repo(&repo, repo_dir.join(input), opts)?;
+    } else {
+        command::df(input, opts)?;
+    }
+    Ok(())
+}
+
+pub fn split(mut opts: SplitOpts) -> Result<(), OxenError> {
+    let repo_dir = env::current_dir().unwrap();
+    let repo = LocalRepository::from_dir(&repo_dir)?;
+    check_repo_migration_needed(&repo)?;
+
+    opts.path = repo_dir.join(&opts.path);
+    opts.output_dir = opts.output_dir.map(|dir| repo_dir.join(dir));
+    let outputs = command::split(&repo, &opts)?;
+    for output in outputs {
+        let df = tabular::read_df(&output, DFOpts::empty())?;
+        let path = util::fs::path_relative_to_dir(&output, &repo.path)?;
+        println!("{} rows -> {}", df.height(), path.to_string_lossy());
+    }
     Ok(())
 }
 
@@ -1055,6 +1559,27 @@ pub fn schema_add_metadata(
     Ok(())
 }
 
+pub fn schema_set_compatibility(
+    schema_ref: impl AsRef<str>,
+    mode: impl AsRef<str>,
+) -> Result<(), OxenError> {
+    let repo_dir = env::current_dir().unwrap();
+    let repository = LocalRepository::from_dir(&repo_dir)?;
+
+    let compatibility = match mode.as_ref() {
+        "none" => None,
+        mode => Some(schema::SchemaCompatibility::from_string(mode)?),
+    };
+
+    for (path, schema) in
+        command::schemas::set_compatibility(&repository, schema_ref, compatibility)?
+    {
+        println!("{:?}\n{}", path, schema.verbose_str());
+    }
+
+    Ok(())
+}
+
 pub fn create_branch(name: &str) -> Result<(), OxenError> {
     let repo_dir = env::current_dir().unwrap();
     let repository = LocalRepository::from_dir(&repo_dir)?;
@@ -1204,33 +1729,196 @@ pub fn load(src_path: &Path, dest_path: &Path, no_working_dir: bool) -> Result<(
     Ok(())
 }
 
-fn print_compare_result(result: &DiffResult) -> Result<(), OxenError> {
+struct DiffPrintOpts {
+    old_name: String,
+    new_name: String,
+    context: usize,
+    stat: bool,
+}
+
+fn print_compare_result(result: &DiffResult, opts: &DiffPrintOpts) -> Result<(), OxenError> {
     match result {
         DiffResult::Tabular(result) => {
             // println!("{:?}", ct.summary);
             print_column_changes(&result.summary.modifications)?;
             print_row_changes(&result.summary.modifications)?;
-            println!("{}", pretty_print::df_to_str(&result.contents));
+            if !opts.stat {
+                println!("{}", pretty_print::df_to_str(&result.contents));
+            }
         }
         DiffResult::Text(diff) => {
-            print_text_diff(diff);
+            if opts.stat {
+                print_text_diff_stat(diff, opts);
+            } else {
+                print_text_diff(diff, opts);
+            }
+        }
+        DiffResult::Json(diff) => {
+            print_json_diff(diff, opts);
+        }
+        DiffResult::Image(diff) => {
+            print_image_diff(diff);
         }
     }
 
     Ok(())
 }
 
-fn print_text_diff(diff: &TextDiff) {
-    for line in &diff.lines {
-        match line.modification {
-            ChangeType::Unchanged => println!("{}", line.text),
-            ChangeType::Added => println!("{}", line.text.green()),
-            ChangeType::Removed => println!("{}", line.text.red()),
-            ChangeType::Modified => println!("{}", line.text.yellow()),
+fn print_text_diff(diff: &TextDiff, opts: &DiffPrintOpts) {
+    // Colors are dropped when piping, so the output can be fed straight into `patch` or `oxen apply`
+    let unified = diff.to_unified(&opts.old_name, &opts.new_name, opts.context);
+    for line in unified.lines() {
+        if line.starts_with("---") || line.starts_with("+++") {
+            println!("{}", line.bold());
+        } else if line.starts_with("@@") {
+            println!("{}", line.cyan());
+        } else if line.starts_with('+') {
+            println!("{}", line.green());
+        } else if line.starts_with('-') {
+            println!("{}", line.red());
+        } else {
+            println!("{line}");
         }
     }
 }
 
+fn print_text_diff_stat(diff: &TextDiff, opts: &DiffPrintOpts) {
+    let added = diff.num_added();
+    let removed = diff.num_removed();
+    let name = opts.new_name.trim_start_matches("b/");
+    // Scale the +/- bar down for large changes, like `git diff --stat`
+    let total = added + removed;
+    let width = total.min(50);
+    let num_plus = if total == 0 { 0 } else { added * width / total };
+    let num_minus = width - num_plus;
+    println!(
+        " {} | {} {}{}",
+        name,
+        total,
+        "+".repeat(num_plus).green(),
+        "-".repeat(num_minus).red()
+    );
+    println!(
+        " 1 file changed, {} insertion{}(+), {} deletion{}(-)",
+        added,
+        if added == 1 { "" } else { "s" },
+        removed,
+        if removed == 1 { "" } else { "s" }
+    );
+}
+
+fn print_json_diff(diff: &JsonDiff, opts: &DiffPrintOpts) {
+    if !diff.has_changes() {
+        println!("No changes");
+        return;
+    }
+
+    if !opts.stat {
+        for change in diff.changes.iter() {
+            let value = |v: &Option<serde_json::Value>| v.as_ref().map(|v| v.to_string());
+            match change.modification {
+                ChangeType::Added => println!(
+                    "{}",
+                    format!(
+                        "   + {}: {}",
+                        change.path,
+                        value(&change.new).unwrap_or_default()
+                    )
+                    .green()
+                ),
+                ChangeType::Removed => println!(
+                    "{}",
+                    format!(
+                        "   - {}: {}",
+                        change.path,
+                        value(&change.old).unwrap_or_default()
+                    )
+                    .red()
+                ),
+                _ => println!(
+                    "{}",
+                    format!(
+                        "   Δ {}: {} -> {}",
+                        change.path,
+                        value(&change.old).unwrap_or_default(),
+                        value(&change.new).unwrap_or_default()
+                    )
+                    .yellow()
+                ),
+            }
+        }
+        println!();
+    }
+
+    println!(
+        "{} added, {} removed, {} modified",
+        diff.counts.added, diff.counts.removed, diff.counts.modified
+    );
+}
+
+fn print_dir_diff_summary(dir: &Path, summary: &DirDiffSummary) {
+    let counts = &summary.dir.file_counts;
+    if summary.dir.data_types.is_empty() {
+        println!("No changes in {}", dir.display());
+        return;
+    }
+
+    for data_type in summary.dir.data_types.iter() {
+        println!(" {}", data_type);
+    }
+    println!(
+        " {} files changed, {} added, {} removed, {} modified",
+        counts.added + counts.removed + counts.modified,
+        counts.added,
+        counts.removed,
+        counts.modified
+    );
+}
+
+fn print_image_diff(diff: &ImageDiff) {
+    if !diff.has_changes() {
+        println!("No changes");
+        return;
+    }
+
+    let describe = |meta: &Option<MetadataImage>| match meta {
+        Some(meta) => format!(
+            "{}x{} {:?}",
+            meta.image.width, meta.image.height, meta.image.color_space
+        ),
+        None => "none".to_string(),
+    };
+    if diff.hash_only {
+        println!(
+            "{}",
+            format!(
+                "   Δ {} -> {}, could not decode the images to compare pixels",
+                describe(&diff.base),
+                describe(&diff.head)
+            )
+            .yellow()
+        );
+        return;
+    }
+
+    if diff.dimensions_changed || diff.color_space_changed {
+        println!(
+            "{}",
+            format!("   Δ {} -> {}", describe(&diff.base), describe(&diff.head)).yellow()
+        );
+    } else {
+        println!("   {}", describe(&diff.head));
+    }
+
+    println!(
+        "{} pixels changed ({:.2}%), mean difference {:.2}%, perceptual similarity {:.2}%",
+        diff.num_changed_pixels,
+        diff.pixel_diff_ratio * 100.0,
+        diff.mean_abs_diff * 100.0,
+        diff.perceptual_similarity * 100.0
+    );
+}
+
 // TODO: Truncate to "and x more"
 fn print_column_changes(mods: &TabularDiffMods) -> Result<(), OxenError> {
     let mut outputs: Vec<ColoredString> = vec![];
@@ -1265,6 +1953,9 @@ fn print_row_changes(mods: &TabularDiffMods) -> Result<(), OxenError> {
     outputs.push("\nRow changes: \n".into());
     if mods.row_counts.modified > 0 {
         outputs.push(format!("   Δ {} (modified)\n", mods.row_counts.modified).yellow());
+        for count in mods.col_counts.iter().filter(|c| c.num_modified > 0) {
+            outputs.push(format!("       {}: {}\n", count.name, count.num_modified).yellow());
+        }
     }
 
     if mods.row_counts.added > 0 {
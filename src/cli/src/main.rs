@@ -16,6 +16,7 @@ async fn main() {
         .arg_required_else_help(true)
         .allow_external_subcommands(true)
         .subcommand(cmd_setup::add())
+        .subcommand(cmd_setup::apply())
         .subcommand(cmd_setup::branch())
         .subcommand(cmd_setup::checkout())
         .subcommand(cmd_setup::clone())
@@ -34,6 +35,8 @@ async fn main() {
         .subcommand(cmd_setup::log())
         .subcommand(cmd_setup::merge())
         .subcommand(cmd_setup::migrate())
+        .subcommand(cmd_setup::merge_request())
+        .subcommand(cmd_setup::mv())
         .subcommand(cmd_setup::pull())
         .subcommand(cmd_setup::push())
         .subcommand(cmd_setup::read_lines())
@@ -42,6 +45,7 @@ async fn main() {
         .subcommand(cmd_setup::rm())
         .subcommand(cmd_setup::save())
         .subcommand(cmd_setup::schemas())
+        .subcommand(cmd_setup::split())
         .subcommand(cmd_setup::status())
         .subcommand(cmd_setup::upload());
 
@@ -49,6 +53,7 @@ async fn main() {
 
     match matches.subcommand() {
         Some((cmd_setup::ADD, sub_matches)) => parse_and_run::add(sub_matches).await,
+        Some((cmd_setup::APPLY, sub_matches)) => parse_and_run::apply(sub_matches),
         Some((cmd_setup::BRANCH, sub_matches)) => parse_and_run::branch(sub_matches).await,
         Some((cmd_setup::CHECKOUT, sub_matches)) => parse_and_run::checkout(sub_matches).await,
         Some((cmd_setup::CLONE, sub_matches)) => parse_and_run::clone(sub_matches).await,
@@ -71,6 +76,8 @@ async fn main() {
         Some((cmd_setup::LOG, sub_matches)) => parse_and_run::log(sub_matches).await,
         Some((cmd_setup::MERGE, sub_matches)) => parse_and_run::merge(sub_matches),
         Some((cmd_setup::MIGRATE, sub_matches)) => parse_and_run::migrate(sub_matches).await,
+        Some((cmd_setup::MR, sub_matches)) => parse_and_run::merge_request(sub_matches).await,
+        Some((cmd_setup::MV, sub_matches)) => parse_and_run::mv(sub_matches).await,
         Some((cmd_setup::PULL, sub_matches)) => parse_and_run::pull(sub_matches).await,
         Some((cmd_setup::PUSH, sub_matches)) => parse_and_run::push(sub_matches).await,
         Some((cmd_setup::READ_LINES, sub_matches)) => parse_and_run::read_lines(sub_matches),
@@ -79,6 +86,7 @@ async fn main() {
         Some((cmd_setup::RM, sub_matches)) => parse_and_run::rm(sub_matches).await,
         Some((cmd_setup::SAVE, sub_matches)) => parse_and_run::save(sub_matches).await,
         Some((cmd_setup::SCHEMAS, sub_matches)) => parse_and_run::schemas(sub_matches),
+        Some((cmd_setup::SPLIT, sub_matches)) => parse_and_run::split(sub_matches),
         Some((cmd_setup::STATUS, sub_matches)) => parse_and_run::status(sub_matches).await,
         Some((cmd_setup::UPLOAD, sub_matches)) => parse_and_run::upload(sub_matches).await,
         // TODO: Get these in the help command instead of just falling back
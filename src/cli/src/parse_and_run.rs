@@ -4,7 +4,9 @@
 //           * create local repo
 //           * printing errors as strings
 
-use crate::cmd_setup::{ADD, COMMIT, DF, DIFF, DOWNLOAD, LOG, LS, METADATA, RESTORE, RM, STATUS};
+use crate::cmd_setup::{
+    ADD, COMMIT, DF, DIFF, DOWNLOAD, LOG, LS, METADATA, MV, RESTORE, RM, STATUS,
+};
 use crate::dispatch;
 use clap::ArgMatches;
 use liboxen::command::migrate::{
@@ -17,7 +19,8 @@ use liboxen::model::staged_data::StagedDataOpts;
 use liboxen::model::LocalRepository;
 use liboxen::model::{ContentType, EntryDataType};
 use liboxen::opts::{
-    AddOpts, CloneOpts, DownloadOpts, InfoOpts, ListOpts, LogOpts, RmOpts, UploadOpts,
+    AddOpts, CloneOpts, DiffTreeOpts, DownloadOpts, InfoOpts, ListOpts, LogOpts, MvOpts, RmOpts,
+    SplitOpts, TabularDiffOpts, UploadOpts,
 };
 use liboxen::util;
 use liboxen::{command, opts::RestoreOpts};
@@ -147,6 +150,9 @@ pub async fn remote(sub_matches: &ArgMatches) {
             (RM, sub_matches) => {
                 remote_rm(sub_matches).await;
             }
+            (MV, sub_matches) => {
+                remote_mv(sub_matches).await;
+            }
             (RESTORE, sub_matches) => {
                 remote_restore(sub_matches).await;
             }
@@ -511,12 +517,14 @@ pub fn info(sub_matches: &ArgMatches) {
     let path = path.unwrap();
     let verbose = sub_matches.get_flag("verbose");
     let output_as_json = sub_matches.get_flag("json");
+    let profile = sub_matches.get_flag("profile");
 
     let opts = InfoOpts {
         path,
         revision,
         verbose,
         output_as_json,
+        profile,
     };
 
     match dispatch::info(opts) {
@@ -528,13 +536,14 @@ pub fn info(sub_matches: &ArgMatches) {
 }
 
 async fn remote_log(sub_matches: &ArgMatches) {
-    let revision = sub_matches.get_one::<String>("REVISION").map(String::from);
-
-    let opts = LogOpts {
-        revision,
-        remote: true,
+    let (opts, format) = match parse_log_opts(sub_matches, true) {
+        Ok(opts) => opts,
+        Err(err) => {
+            eprintln!("{err}");
+            return;
+        }
     };
-    match dispatch::log_commits(opts).await {
+    match dispatch::log_commits(opts, format).await {
         Ok(_) => {}
         Err(err) => {
             eprintln!("{err}")
@@ -543,13 +552,14 @@ async fn remote_log(sub_matches: &ArgMatches) {
 }
 
 pub async fn log(sub_matches: &ArgMatches) {
-    let revision = sub_matches.get_one::<String>("REVISION").map(String::from);
-
-    let opts = LogOpts {
-        revision,
-        remote: false,
+    let (opts, format) = match parse_log_opts(sub_matches, false) {
+        Ok(opts) => opts,
+        Err(err) => {
+            eprintln!("{err}");
+            return;
+        }
     };
-    match dispatch::log_commits(opts).await {
+    match dispatch::log_commits(opts, format).await {
         Ok(_) => {}
         Err(err) => {
             eprintln!("{err}")
@@ -557,6 +567,43 @@ pub async fn log(sub_matches: &ArgMatches) {
     }
 }
 
+fn parse_log_opts(
+    sub_matches: &ArgMatches,
+    remote: bool,
+) -> Result<(LogOpts, dispatch::LogFormat), OxenError> {
+    let revision = sub_matches.get_one::<String>("REVISION").map(String::from);
+    let paths: Vec<PathBuf> = sub_matches
+        .get_many::<String>("PATHS")
+        .map(|paths| paths.map(PathBuf::from).collect())
+        .unwrap_or_default();
+    let since = match sub_matches.get_one::<String>("since") {
+        Some(since) => Some(LogOpts::parse_time(since)?),
+        None => None,
+    };
+    let until = match sub_matches.get_one::<String>("until") {
+        Some(until) => Some(LogOpts::parse_time(until)?),
+        None => None,
+    };
+
+    let opts = LogOpts {
+        revision,
+        remote,
+        paths,
+        author: sub_matches.get_one::<String>("author").map(String::from),
+        since,
+        until,
+        grep: sub_matches.get_one::<String>("grep").map(String::from),
+        max_count: sub_matches.get_one::<usize>("max-count").copied(),
+        follow: sub_matches.get_flag("follow"),
+    };
+    let format = dispatch::LogFormat {
+        oneline: sub_matches.get_flag("oneline"),
+        graph: sub_matches.get_flag("graph"),
+        json: sub_matches.get_flag("json"),
+    };
+    Ok((opts, format))
+}
+
 pub async fn fetch(_: &ArgMatches) {
     match dispatch::fetch().await {
         Ok(_) => {}
@@ -593,6 +640,7 @@ fn parse_df_sub_matches(sub_matches: &ArgMatches) -> liboxen::opts::DFOpts {
         page: sub_matches
             .get_one::<String>("page")
             .map(|x| x.parse::<usize>().expect("page must be valid int")),
+        revision: sub_matches.get_one::<String>("revision").map(String::from),
         head: sub_matches
             .get_one::<String>("head")
             .map(|x| x.parse::<usize>().expect("head must be valid int")),
@@ -608,19 +656,33 @@ fn parse_df_sub_matches(sub_matches: &ArgMatches) -> liboxen::opts::DFOpts {
         aggregate: sub_matches.get_one::<String>("aggregate").map(String::from),
         col_at: sub_matches.get_one::<String>("col-at").map(String::from),
         vstack,
+        in_place: sub_matches.get_flag("in-place"),
+        profile: sub_matches.get_flag("profile"),
         index: sub_matches.get_flag("index"),
+        lenient: sub_matches.get_flag("lenient"),
         add_col: sub_matches.get_one::<String>("add-col").map(String::from),
         add_row: sub_matches.get_one::<String>("add-row").map(String::from),
         get_row: sub_matches.get_one::<String>("get-row").map(String::from),
         delete_row: sub_matches
             .get_one::<String>("delete-row")
             .map(String::from),
+        update_row: sub_matches
+            .get_one::<String>("update-row")
+            .map(String::from),
+        row_data: sub_matches.get_one::<String>("row-data").map(String::from),
+        batch: sub_matches.get_one::<String>("batch").map(PathBuf::from),
         sort_by: sub_matches.get_one::<String>("sort").map(String::from),
         sql: sub_matches.get_one::<String>("sql").map(String::from),
+        sql_tables: None,
+        stratify: sub_matches.get_one::<String>("stratify").map(String::from),
         text2sql: sub_matches.get_one::<String>("text2sql").map(String::from),
         host: sub_matches.get_one::<String>("host").map(String::from),
         unique: sub_matches.get_one::<String>("unique").map(String::from),
         content_type: ContentType::from_str(content_type).unwrap(),
+        sample: sub_matches.get_one::<String>("sample").map(String::from),
+        seed: sub_matches
+            .get_one::<String>("seed")
+            .map(|x| x.parse::<u64>().expect("seed must be valid int")),
         should_randomize: sub_matches.get_flag("randomize"),
         should_reverse: sub_matches.get_flag("reverse"),
         committed: sub_matches.get_flag("committed"),
@@ -659,6 +721,39 @@ pub fn df(sub_matches: &ArgMatches) {
     }
 }
 
+pub fn split(sub_matches: &ArgMatches) {
+    let path = sub_matches.get_one::<String>("PATH").expect("required");
+    let ratios = sub_matches
+        .get_one::<String>("ratios")
+        .expect("Must supply ratios")
+        .split(',')
+        .map(|x| {
+            x.trim()
+                .parse::<f64>()
+                .expect("ratios must be valid numbers")
+        })
+        .collect();
+
+    let mut opts = SplitOpts::from_path_ratios(path, ratios);
+    opts.names = sub_matches
+        .get_one::<String>("names")
+        .map(|x| x.split(',').map(|name| name.trim().to_string()).collect());
+    opts.group_by = sub_matches.get_one::<String>("group-by").map(String::from);
+    opts.seed = sub_matches
+        .get_one::<String>("seed")
+        .map(|x| x.parse::<u64>().expect("seed must be valid int"));
+    opts.output_dir = sub_matches
+        .get_one::<String>("output-dir")
+        .map(PathBuf::from);
+
+    match dispatch::split(opts) {
+        Ok(_) => {}
+        Err(err) => {
+            eprintln!("{err}")
+        }
+    }
+}
+
 pub fn schemas(sub_matches: &ArgMatches) {
     if let Some(subcommand) = sub_matches.subcommand() {
         match subcommand {
@@ -711,6 +806,16 @@ pub fn schemas(sub_matches: &ArgMatches) {
                     }
                 }
             }
+            ("contract", sub_matches) => {
+                let path = sub_matches.get_one::<String>("PATH").expect("required");
+                let mode = sub_matches.get_one::<String>("MODE").expect("required");
+                match dispatch::schema_set_compatibility(path, mode) {
+                    Ok(_) => {}
+                    Err(err) => {
+                        eprintln!("{err}")
+                    }
+                }
+            }
             ("rm", sub_matches) => {
                 let val = sub_matches
                     .get_one::<String>("NAME_OR_HASH")
@@ -814,6 +919,39 @@ pub async fn rm(sub_matches: &ArgMatches) {
     }
 }
 
+pub async fn remote_mv(sub_matches: &ArgMatches) {
+    let source = sub_matches.get_one::<String>("SOURCE").expect("required");
+    let destination = sub_matches
+        .get_one::<String>("DESTINATION")
+        .expect("required");
+
+    let mut opts = MvOpts::from_paths(source, destination);
+    opts.remote = true;
+
+    match dispatch::mv(&opts).await {
+        Ok(_) => {}
+        Err(err) => {
+            eprintln!("{err}")
+        }
+    }
+}
+
+pub async fn mv(sub_matches: &ArgMatches) {
+    let source = sub_matches.get_one::<String>("SOURCE").expect("required");
+    let destination = sub_matches
+        .get_one::<String>("DESTINATION")
+        .expect("required");
+
+    let opts = MvOpts::from_paths(source, destination);
+
+    match dispatch::mv(&opts).await {
+        Ok(_) => {}
+        Err(err) => {
+            eprintln!("{err}")
+        }
+    }
+}
+
 pub async fn remote_restore(sub_matches: &ArgMatches) {
     let path = sub_matches.get_one::<String>("PATH").expect("required");
 
@@ -934,6 +1072,18 @@ pub async fn checkout(sub_matches: &ArgMatches) {
     }
 }
 
+pub fn apply(sub_matches: &ArgMatches) {
+    let patch = sub_matches
+        .get_one::<String>("PATCH")
+        .expect("Must supply a patch");
+    match dispatch::apply(Path::new(patch)) {
+        Ok(_) => {}
+        Err(err) => {
+            eprintln!("{err}")
+        }
+    }
+}
+
 pub fn merge(sub_matches: &ArgMatches) {
     let branch = sub_matches
         .get_one::<String>("BRANCH")
@@ -946,6 +1096,48 @@ pub fn merge(sub_matches: &ArgMatches) {
     }
 }
 
+pub async fn merge_request(sub_matches: &ArgMatches) {
+    let remote = sub_matches
+        .get_one::<String>("remote")
+        .expect("Must supply a remote");
+
+    let result = match sub_matches.subcommand() {
+        Some(("create", sub_matches)) => {
+            let source = sub_matches.get_one::<String>("SOURCE").map(String::from);
+            let target = sub_matches
+                .get_one::<String>("target")
+                .expect("Must supply a target branch");
+            let title = sub_matches
+                .get_one::<String>("title")
+                .expect("Must supply a title");
+            let description = sub_matches
+                .get_one::<String>("description")
+                .map(String::from)
+                .unwrap_or_default();
+            dispatch::create_merge_request(remote, source, target, title, &description).await
+        }
+        Some(("list", sub_matches)) => {
+            let status = sub_matches.get_one::<String>("status").map(String::as_str);
+            dispatch::list_merge_requests(remote, status).await
+        }
+        Some(("merge", sub_matches)) => {
+            let id = sub_matches.get_one::<String>("ID").expect("required");
+            match id.parse::<u64>() {
+                Ok(id) => dispatch::merge_merge_request(remote, id).await,
+                Err(_) => Err(OxenError::basic_str(format!(
+                    "Invalid merge request id '{id}'"
+                ))),
+            }
+        }
+        Some((cmd, _)) => Err(OxenError::basic_str(format!("Unknown mr subcommand {cmd}"))),
+        None => Ok(()),
+    };
+
+    if let Err(err) = result {
+        eprintln!("{err}")
+    }
+}
+
 pub async fn push(sub_matches: &ArgMatches) {
     let remote = sub_matches
         .get_one::<String>("REMOTE")
@@ -1000,10 +1192,43 @@ pub async fn diff(sub_matches: &ArgMatches) {
 }
 
 async fn p_diff(sub_matches: &ArgMatches, is_remote: bool) {
-    let resource1 = sub_matches
-        .get_one::<String>("RESOURCE1")
-        .expect("required");
+    let resource1 = sub_matches.get_one::<String>("RESOURCE1");
     let resource2 = sub_matches.get_one::<String>("RESOURCE2");
+    let revision = sub_matches.get_one::<String>("revision").cloned();
+    let staged = sub_matches.get_flag("staged");
+
+    let keys: Vec<String> = match sub_matches.get_many::<String>("keys") {
+        Some(values) => values.cloned().collect(),
+        None => Vec::new(),
+    };
+    let output = sub_matches.get_one::<String>("output").map(PathBuf::from);
+    let stat = sub_matches.get_flag("stat");
+
+    // Without resources to compare, diff the whole tree and treat any paths as filters
+    if !is_remote && (resource1.is_none() || revision.is_some() || staged) {
+        if stat {
+            eprintln!("--stat is not supported when diffing the whole tree");
+            return;
+        }
+        let opts = DiffTreeOpts {
+            revision,
+            staged,
+            paths: resource1
+                .into_iter()
+                .chain(resource2)
+                .map(PathBuf::from)
+                .collect(),
+        };
+        if let Err(err) = dispatch::diff_tree(&opts, keys, output) {
+            eprintln!("{err}")
+        }
+        return;
+    }
+
+    let Some(resource1) = resource1 else {
+        eprintln!("Must supply a file to compare");
+        return;
+    };
 
     let (file1, revision1) = parse_file_and_revision(resource1);
 
@@ -1017,11 +1242,6 @@ async fn p_diff(sub_matches: &ArgMatches, is_remote: bool) {
         None => (None, None),
     };
 
-    let keys: Vec<String> = match sub_matches.get_many::<String>("keys") {
-        Some(values) => values.cloned().collect(),
-        None => Vec::new(),
-    };
-
     // We changed the external name to compares, need to refactor internals still
     let maybe_targets = sub_matches.get_many::<String>("compares");
 
@@ -1030,10 +1250,15 @@ async fn p_diff(sub_matches: &ArgMatches, is_remote: bool) {
         None => Vec::new(),
     };
 
-    let output = sub_matches.get_one::<String>("output").map(PathBuf::from);
+    let opts = TabularDiffOpts {
+        numeric_tolerance: sub_matches.get_one::<f64>("tolerance").copied(),
+        ignore_case: sub_matches.get_flag("ignore-case"),
+        ignore_whitespace: sub_matches.get_flag("ignore-whitespace"),
+        context_lines: sub_matches.get_one::<usize>("context").copied(),
+    };
 
     match dispatch::diff(
-        file1, revision1, file2, revision2, keys, targets, output, is_remote,
+        file1, revision1, file2, revision2, keys, targets, opts, output, is_remote, stat,
     )
     .await
     {
@@ -5,6 +5,7 @@ pub mod branches;
 pub mod commits;
 pub mod diff;
 pub mod entries;
+pub mod merge_requests;
 pub mod metadata;
 pub mod migrations;
 pub mod namespaces;
@@ -12,3 +13,4 @@ pub mod repositories;
 pub mod resource;
 pub mod revisions;
 pub mod schemas;
+pub mod usage;
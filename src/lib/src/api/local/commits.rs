@@ -4,18 +4,20 @@
 //!
 
 use crate::constants::{
-    HISTORY_DIR, OBJECT_DIRS_DIR, OBJECT_FILES_DIR, OBJECT_SCHEMAS_DIR, OBJECT_VNODES_DIR, TREE_DIR,
+    COMMIT_METADATA_FILE, COMMIT_MOVES_METADATA_KEY, HISTORY_DIR, OBJECT_DIRS_DIR,
+    OBJECT_FILES_DIR, OBJECT_SCHEMAS_DIR, OBJECT_VNODES_DIR, TREE_DIR,
 };
 use crate::core::cache::cachers::content_validator;
 use crate::core::db::tree_db::{self, TreeObject};
 use crate::core::db::{self, path_db};
+use crate::core::index::object_db_reader::ObjectDBReader;
 use crate::core::index::tree_db_reader::TreeDBMerger;
 use crate::core::index::{
     self, CommitEntryReader, CommitEntryWriter, CommitReader, CommitWriter, RefReader, RefWriter,
     Stager, TreeObjectReader,
 };
 use crate::error::OxenError;
-use crate::model::{Commit, CommitEntry, LocalRepository, StagedData};
+use crate::model::{Commit, CommitEntry, LocalRepository, MovedEntry, StagedData};
 use crate::opts::LogOpts;
 use crate::util::fs::commit_content_is_valid_path;
 use crate::view::{PaginatedCommits, StatusMessage};
@@ -23,7 +25,7 @@ use crate::{api, util};
 use rayon::prelude::*;
 use rocksdb::{DBWithThreadMode, MultiThreaded};
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
 /// Iterate over commits and get the one with the latest timestamp
@@ -208,6 +210,43 @@ pub fn commit(
     Ok(commit)
 }
 
+/// Record a value under `key` in the metadata of the next commit
+pub fn add_staged_metadata(
+    repo: &LocalRepository,
+    key: impl AsRef<str>,
+    value: serde_json::Value,
+) -> Result<(), OxenError> {
+    let mut metadata = match get_staged_metadata(repo)? {
+        Some(serde_json::Value::Object(metadata)) => metadata,
+        _ => serde_json::Map::new(),
+    };
+    metadata.insert(key.as_ref().to_string(), value);
+
+    let path = util::fs::oxen_hidden_dir(&repo.path).join(COMMIT_METADATA_FILE);
+    util::fs::write_to_path(path, serde_json::to_string(&metadata)?)?;
+    Ok(())
+}
+
+/// Metadata that will be recorded on the next commit
+pub fn get_staged_metadata(repo: &LocalRepository) -> Result<Option<serde_json::Value>, OxenError> {
+    let path = util::fs::oxen_hidden_dir(&repo.path).join(COMMIT_METADATA_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let metadata = util::fs::read_from_path(&path)?;
+    Ok(Some(serde_json::from_str(&metadata)?))
+}
+
+/// The files that were moved in the commit, as (from, to) pairs recorded when it was made
+pub fn list_moves(commit: &Commit) -> Vec<MovedEntry> {
+    commit
+        .metadata
+        .as_ref()
+        .and_then(|metadata| metadata.get(COMMIT_MOVES_METADATA_KEY))
+        .and_then(|moves| serde_json::from_value(moves.clone()).ok())
+        .unwrap_or_default()
+}
+
 pub fn create_commit_object_with_committers(
     _repo_dir: &Path,
     branch_name: impl AsRef<str>,
@@ -292,7 +331,8 @@ pub fn list_all_paginated(
     })
 }
 
-/// Get commit history given options
+/// Get commit history given options, from the default remote if `opts.remote` is set.
+/// The paths in `opts` are relative to the repository root either way.
 pub async fn list_with_opts(
     repo: &LocalRepository,
     opts: &LogOpts,
@@ -304,7 +344,9 @@ pub async fn list_with_opts(
         } else {
             api::local::branches::current_branch(repo)?.unwrap().name
         };
-        let commits = api::remote::commits::list_commit_history(&remote_repo, &revision).await?;
+        let commits =
+            api::remote::commits::list_commit_history_with_opts(&remote_repo, &revision, opts)
+                .await?;
         Ok(commits)
     } else {
         let committer = CommitReader::new(repo)?;
@@ -316,8 +358,193 @@ pub async fn list_with_opts(
         } else {
             committer.history_from_head()?
         };
-        Ok(commits)
+        filter_history(repo, commits, opts)
+    }
+}
+
+/// List the history for a specific branch or commit (revision), keeping the commits that match `opts`
+pub fn list_from_with_opts(
+    repo: &LocalRepository,
+    revision: &str,
+    opts: &LogOpts,
+) -> Result<Vec<Commit>, OxenError> {
+    let commits = list_from(repo, revision)?;
+    filter_history(repo, commits, opts)
+}
+
+/// Keep the commits that match the filters in `opts`, in the order they were given.
+/// With paths, a commit is kept if the paths differ from every one of its parents. The paths are
+/// compared by their hashes in the merkle tree, so the files within directories are not listed.
+/// With `follow`, the path is renamed to where it was moved from in the commits before a move.
+pub fn filter_history(
+    repo: &LocalRepository,
+    commits: Vec<Commit>,
+    opts: &LogOpts,
+) -> Result<Vec<Commit>, OxenError> {
+    if !opts.is_filtered() {
+        return Ok(commits);
+    }
+    if opts.follow && opts.paths.len() != 1 {
+        return Err(OxenError::basic_str("--follow requires exactly one path"));
     }
+
+    let author = opts.author.as_ref().map(|author| author.to_lowercase());
+    let grep = opts.grep.as_ref().map(|grep| grep.to_lowercase());
+    let mut path_hashes: PathHashCache = HashMap::new();
+    let mut paths = opts.paths.clone();
+
+    let mut filtered: Vec<Commit> = vec![];
+    for commit in commits {
+        if opts
+            .max_count
+            .is_some_and(|max_count| filtered.len() >= max_count)
+        {
+            break;
+        }
+        // The paths before this commit, the same unless it moved them. Moves are followed even
+        // through the commits that the other filters skip.
+        let parent_paths = if opts.follow {
+            paths_before_moves(&paths, &list_moves(&commit))
+        } else {
+            paths.clone()
+        };
+        let keep = matches_commit_filters(&commit, &author, &grep, opts)
+            && (paths.is_empty()
+                || parent_paths != paths
+                || commit_changed_paths(repo, &commit, &paths, &parent_paths, &mut path_hashes)?);
+        paths = parent_paths;
+
+        if keep {
+            filtered.push(commit);
+        }
+    }
+    Ok(filtered)
+}
+
+// The author, date and message filters, `author` and `grep` are lowercased
+fn matches_commit_filters(
+    commit: &Commit,
+    author: &Option<String>,
+    grep: &Option<String>,
+    opts: &LogOpts,
+) -> bool {
+    if let Some(author) = author {
+        if !commit.author.to_lowercase().contains(author)
+            && !commit.email.to_lowercase().contains(author)
+        {
+            return false;
+        }
+    }
+    if opts.since.is_some_and(|since| commit.timestamp < since)
+        || opts.until.is_some_and(|until| commit.timestamp > until)
+    {
+        return false;
+    }
+    match grep {
+        Some(grep) => commit.message.to_lowercase().contains(grep),
+        None => true,
+    }
+}
+
+type PathHashCache = HashMap<(String, Vec<PathBuf>), Vec<Option<String>>>;
+
+fn commit_changed_paths(
+    repo: &LocalRepository,
+    commit: &Commit,
+    paths: &[PathBuf],
+    parent_paths: &[PathBuf],
+    cache: &mut PathHashCache,
+) -> Result<bool, OxenError> {
+    let hashes = hashes_at_paths(repo, &commit.id, paths, cache)?;
+    if commit.parent_ids.is_empty() {
+        return Ok(hashes.iter().any(|hash| hash.is_some()));
+    }
+
+    for parent_id in commit.parent_ids.iter() {
+        if hashes_at_paths(repo, parent_id, parent_paths, cache)? == hashes {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+// Where each path was moved from in `moves`. A directory is followed to where the files within it
+// were moved from, paths that were not moved are kept.
+fn paths_before_moves(paths: &[PathBuf], moves: &[MovedEntry]) -> Vec<PathBuf> {
+    paths
+        .iter()
+        .map(|path| {
+            for moved in moves {
+                if &moved.to == path {
+                    return moved.from.clone();
+                }
+                if let Ok(rest) = moved.to.strip_prefix(path) {
+                    let from = moved.from.to_string_lossy();
+                    let rest = rest.to_string_lossy();
+                    if let Some(from_dir) = from.strip_suffix(rest.as_ref()) {
+                        return PathBuf::from(from_dir.trim_end_matches(['/', '\\']));
+                    }
+                }
+            }
+            path.clone()
+        })
+        .collect()
+}
+
+// The hash of each path in the commit, or None if it does not exist there. Parents are shared
+// between neighboring commits in the history, so the hashes are cached by commit id and paths.
+fn hashes_at_paths(
+    repo: &LocalRepository,
+    commit_id: &str,
+    paths: &[PathBuf],
+    cache: &mut PathHashCache,
+) -> Result<Vec<Option<String>>, OxenError> {
+    let key = (commit_id.to_string(), paths.to_vec());
+    if let Some(hashes) = cache.get(&key) {
+        return Ok(hashes.clone());
+    }
+
+    let dir_hashes_db_path = CommitEntryWriter::commit_dir_hash_db(&repo.path, commit_id);
+    let dir_hashes_db: Option<DBWithThreadMode<MultiThreaded>> = if dir_hashes_db_path.exists() {
+        Some(DBWithThreadMode::open_for_read_only(
+            &db::opts::default(),
+            dunce::simplified(&dir_hashes_db_path),
+            false,
+        )?)
+    } else {
+        None
+    };
+    let object_reader = ObjectDBReader::new(repo)?;
+    let entry_reader = CommitEntryReader::new_from_commit_id(repo, commit_id, object_reader)?;
+
+    let mut hashes: Vec<Option<String>> = vec![];
+    for path in paths {
+        let dir_hash: Option<String> = match &dir_hashes_db {
+            Some(db) => path_db::get_entry(db, path)?,
+            None => None,
+        };
+        let hash = match dir_hash {
+            Some(hash) => Some(hash),
+            None => match entry_reader.get_entry(path)? {
+                Some(entry) => Some(entry.hash),
+                // Commits made before dir hashes were recorded, hash the entries within the dir
+                None if entry_reader.has_dir(path) => {
+                    let mut entries = entry_reader.list_directory(path)?;
+                    entries.sort_by(|a, b| a.path.cmp(&b.path));
+                    let entries: Vec<String> = entries
+                        .iter()
+                        .map(|entry| format!("{}:{}", entry.path.to_string_lossy(), entry.hash))
+                        .collect();
+                    Some(util::hasher::hash_str(entries.join("\n")))
+                }
+                None => None,
+            },
+        };
+        hashes.push(hash);
+    }
+
+    cache.insert(key, hashes.clone());
+    Ok(hashes)
 }
 
 /// List the history for a specific branch or commit (revision)
@@ -511,10 +738,14 @@ pub fn merge_objects_dbs(repo_objects_dir: &Path, tmp_objects_dir: &Path) -> Res
 }
 #[cfg(test)]
 mod tests {
+    use std::path::PathBuf;
+
     use crate::api;
     use crate::command;
     use crate::error::OxenError;
+    use crate::opts::{LogOpts, MvOpts};
     use crate::test;
+    use crate::util;
 
     #[tokio::test]
     async fn test_commit_history_is_complete() -> Result<(), OxenError> {
@@ -590,4 +821,124 @@ mod tests {
         })
         .await
     }
+
+    #[test]
+    fn test_list_with_opts_scoped_to_paths() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            util::fs::write_to_path(repo.path.join("data").join("a.txt"), "a")?;
+            util::fs::write_to_path(repo.path.join("README.md"), "readme")?;
+            command::add(&repo, &repo.path)?;
+            let first = command::commit(&repo, "Adding data and readme")?;
+
+            util::fs::write_to_path(repo.path.join("README.md"), "readme v2")?;
+            command::add(&repo, repo.path.join("README.md"))?;
+            command::commit(&repo, "Updating readme")?;
+
+            util::fs::write_to_path(repo.path.join("data").join("b.txt"), "b")?;
+            command::add(&repo, repo.path.join("data"))?;
+            let third = command::commit(&repo, "Adding b to data")?;
+
+            let commits = api::local::commits::list(&repo)?;
+            let opts = LogOpts {
+                paths: vec![PathBuf::from("data")],
+                ..LogOpts::default()
+            };
+            let filtered = api::local::commits::filter_history(&repo, commits.clone(), &opts)?;
+            let ids: Vec<&str> = filtered.iter().map(|c| c.id.as_str()).collect();
+            assert_eq!(ids, vec![third.id.as_str(), first.id.as_str()]);
+
+            let opts = LogOpts {
+                paths: vec![PathBuf::from("data").join("a.txt")],
+                ..LogOpts::default()
+            };
+            let filtered = api::local::commits::filter_history(&repo, commits.clone(), &opts)?;
+            assert_eq!(filtered.len(), 1);
+            assert_eq!(filtered[0].id, first.id);
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_list_with_opts_grep_and_max_count() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            for i in 0..3 {
+                util::fs::write_to_path(repo.path.join(format!("{i}.txt")), "text")?;
+                command::add(&repo, &repo.path)?;
+                command::commit(&repo, &format!("Adding file {i}"))?;
+            }
+
+            let commits = api::local::commits::list(&repo)?;
+            let opts = LogOpts {
+                grep: Some("ADDING FILE".to_string()),
+                max_count: Some(2),
+                ..LogOpts::default()
+            };
+            let filtered = api::local::commits::filter_history(&repo, commits.clone(), &opts)?;
+            assert_eq!(filtered.len(), 2);
+            assert_eq!(filtered[0].message, "Adding file 2");
+
+            let opts = LogOpts {
+                author: Some("nobody-with-this-name".to_string()),
+                ..LogOpts::default()
+            };
+            let filtered = api::local::commits::filter_history(&repo, commits, &opts)?;
+            assert!(filtered.is_empty());
+
+            Ok(())
+        })
+    }
+
+    #[tokio::test]
+    async fn test_list_with_opts_follow_moved_file() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            util::fs::write_to_path(repo.path.join("a.txt"), "a")?;
+            command::add(&repo, &repo.path)?;
+            let first = command::commit(&repo, "Adding a")?;
+
+            util::fs::write_to_path(repo.path.join("a.txt"), "a v2")?;
+            command::add(&repo, &repo.path)?;
+            let second = command::commit(&repo, "Updating a")?;
+
+            let opts = MvOpts::from_paths(
+                repo.path.join("a.txt"),
+                repo.path.join("moved").join("a.txt"),
+            );
+            command::mv(&repo, &opts).await?;
+            let third = command::commit(&repo, "Moving a")?;
+
+            util::fs::write_to_path(repo.path.join("moved").join("a.txt"), "a v3")?;
+            command::add(&repo, &repo.path)?;
+            let fourth = command::commit(&repo, "Updating moved a")?;
+
+            let commits = api::local::commits::list(&repo)?;
+            let opts = LogOpts {
+                paths: vec![PathBuf::from("moved").join("a.txt")],
+                ..LogOpts::default()
+            };
+            let filtered = api::local::commits::filter_history(&repo, commits.clone(), &opts)?;
+            let ids: Vec<&str> = filtered.iter().map(|c| c.id.as_str()).collect();
+            assert_eq!(ids, vec![fourth.id.as_str(), third.id.as_str()]);
+
+            let opts = LogOpts {
+                paths: vec![PathBuf::from("moved").join("a.txt")],
+                follow: true,
+                ..LogOpts::default()
+            };
+            let filtered = api::local::commits::filter_history(&repo, commits, &opts)?;
+            let ids: Vec<&str> = filtered.iter().map(|c| c.id.as_str()).collect();
+            assert_eq!(
+                ids,
+                vec![
+                    fourth.id.as_str(),
+                    third.id.as_str(),
+                    second.id.as_str(),
+                    first.id.as_str()
+                ]
+            );
+
+            Ok(())
+        })
+        .await
+    }
 }
@@ -8,18 +8,21 @@ use crate::core::db::{self, path_db};
 use crate::model::diff::generic_diff_summary::GenericDiffSummary;
 use rocksdb::{DBWithThreadMode, MultiThreaded};
 
+use crate::core::cache::cachers;
 use crate::core::df::tabular;
 use crate::core::index::object_db_reader::ObjectDBReader;
 use crate::core::index::{self, remote_dir_stager};
 use crate::error::OxenError;
 use crate::model::diff::diff_entry_status::DiffEntryStatus;
+use crate::model::diff::dir_diff_summary::DataTypeDiffSummary;
 use crate::model::diff::tabular_diff::{
-    TabularDiff, TabularDiffDupes, TabularDiffMods, TabularDiffParameters, TabularDiffSchemas,
-    TabularDiffSummary, TabularSchemaDiff,
+    ColumnChangeCount, TabularDiff, TabularDiffDupes, TabularDiffMods, TabularDiffParameters,
+    TabularDiffSchemas, TabularDiffSummary, TabularSchemaDiff,
 };
 
 use crate::model::{
-    Branch, Commit, CommitEntry, DataFrameDiff, DiffEntry, LocalRepository, Schema,
+    Branch, Commit, CommitEntry, DataFrameDiff, DiffEntry, EntryDataType, LocalRepository,
+    MovedEntry, Schema,
 };
 
 use crate::{api, constants, util};
@@ -28,28 +31,44 @@ use crate::core::index::CommitEntryReader;
 use polars::prelude::DataFrame;
 use polars::prelude::IntoLazy;
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::ffi::OsString;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 use crate::model::diff::diff_commit_entry::DiffCommitEntry;
 use crate::model::diff::diff_entries_counts::DiffEntriesCounts;
 use crate::model::diff::schema_diff::SchemaDiff;
+use crate::model::diff::text_diff::DEFAULT_CONTEXT_LINES;
 use crate::model::diff::AddRemoveModifyCounts;
 use crate::model::diff::DiffResult;
 
-use crate::opts::DFOpts;
+use crate::opts::{DFOpts, TabularDiffOpts};
 
+pub mod image_diff;
 pub mod join_diff;
+pub mod json_diff;
+pub mod patch;
+pub mod tree_diff;
 pub mod utf8_diff;
 
 const TARGETS_HASH_COL: &str = "_targets_hash";
 const KEYS_HASH_COL: &str = "_keys_hash";
 const DUPES_PATH: &str = "dupes.json";
+// Share of rows a moved tabular file has to keep to be shown as renamed rather than removed and added
+const TABULAR_RENAME_SIMILARITY: f64 = 0.5;
+// Tabular files are read in full to compare their rows, so larger files are not matched on content
+const TABULAR_RENAME_MAX_BYTES: u64 = 100_000_000;
 
 fn is_files_tabular(file_1: impl AsRef<Path>, file_2: impl AsRef<Path>) -> bool {
     util::fs::is_tabular(file_1.as_ref()) && util::fs::is_tabular(file_2.as_ref())
 }
+fn is_files_image(file_1: impl AsRef<Path>, file_2: impl AsRef<Path>) -> bool {
+    util::fs::is_image(file_1.as_ref()) && util::fs::is_image(file_2.as_ref())
+}
+fn is_files_json(file_1: impl AsRef<Path>, file_2: impl AsRef<Path>) -> bool {
+    util::fs::is_json(file_1.as_ref()) && util::fs::is_json(file_2.as_ref())
+}
 fn is_files_utf8(file_1: impl AsRef<Path>, file_2: impl AsRef<Path>) -> bool {
     util::fs::is_utf8(file_1.as_ref()) && util::fs::is_utf8(file_2.as_ref())
 }
@@ -60,12 +79,38 @@ pub fn diff_files(
     keys: Vec<String>,
     targets: Vec<String>,
     display: Vec<String>,
+) -> Result<DiffResult, OxenError> {
+    diff_files_with_opts(
+        file_1,
+        file_2,
+        keys,
+        targets,
+        display,
+        &TabularDiffOpts::default(),
+    )
+}
+
+/// Same as `diff_files`, with `opts` controlling how the target columns of tabular files are compared
+pub fn diff_files_with_opts(
+    file_1: impl AsRef<Path>,
+    file_2: impl AsRef<Path>,
+    keys: Vec<String>,
+    targets: Vec<String>,
+    display: Vec<String>,
+    opts: &TabularDiffOpts,
 ) -> Result<DiffResult, OxenError> {
     if is_files_tabular(&file_1, &file_2) {
-        let result = tabular(file_1, file_2, keys, targets, display)?;
+        let result = tabular(file_1, file_2, keys, targets, display, opts)?;
         Ok(result)
+    } else if is_files_json(&file_1, &file_2) {
+        let context = opts.context_lines.unwrap_or(DEFAULT_CONTEXT_LINES);
+        json_diff::diff_with_context(file_1, file_2, context)
+    } else if is_files_image(&file_1, &file_2) {
+        let result = image_diff::diff(file_1, file_2)?;
+        Ok(DiffResult::Image(result))
     } else if is_files_utf8(&file_1, &file_2) {
-        let result = utf8_diff::diff(file_1, file_2)?;
+        let context = opts.context_lines.unwrap_or(DEFAULT_CONTEXT_LINES);
+        let result = utf8_diff::diff_with_context(file_1, file_2, context)?;
         Ok(DiffResult::Text(result))
     } else {
         Err(OxenError::invalid_file_type(format!(
@@ -82,6 +127,7 @@ pub fn tabular(
     keys: Vec<String>,
     targets: Vec<String>,
     display: Vec<String>,
+    opts: &TabularDiffOpts,
 ) -> Result<DiffResult, OxenError> {
     let df_1 = tabular::read_df(file_1, DFOpts::empty())?;
     let df_2 = tabular::read_df(file_2, DFOpts::empty())?;
@@ -91,7 +137,7 @@ pub fn tabular(
 
     validate_required_fields(schema_1, schema_2, keys.clone(), targets.clone())?;
 
-    diff_dfs(&df_1, &df_2, keys, targets, display)
+    diff_dfs(&df_1, &df_2, keys, targets, display, opts)
 }
 
 fn validate_required_fields(
@@ -125,6 +171,7 @@ pub fn diff_dfs(
     keys: Vec<String>,
     targets: Vec<String>,
     display: Vec<String>,
+    opts: &TabularDiffOpts,
 ) -> Result<DiffResult, OxenError> {
     let schema_diff = get_schema_diff(df_1, df_2);
 
@@ -136,7 +183,7 @@ pub fn diff_dfs(
 
     let (df_1, df_2) = hash_dfs(df_1.clone(), df_2.clone(), &keys, &targets)?;
 
-    let compare = join_diff::diff(&df_1, &df_2, schema_diff, &keys, &targets, &display)?;
+    let compare = join_diff::diff(&df_1, &df_2, schema_diff, &keys, &targets, &display, opts)?;
 
     Ok(compare)
 }
@@ -158,8 +205,8 @@ pub fn diff_staged_df(
 
     let branch_repo = remote_dir_stager::init_or_get(repo, branch, identifier)?;
     let base_path = util::fs::version_path(repo, &entry);
-    let head_path = if index::remote_df_stager::dataset_is_indexed(repo, branch, identifier, &path)?
-    {
+    let is_indexed = index::remote_df_stager::dataset_is_indexed(repo, branch, identifier, &path)?;
+    let head_path = if is_indexed {
         index::remote_df_stager::extract_dataset_to_working_dir(
             repo,
             &branch_repo,
@@ -175,7 +222,38 @@ pub fn diff_staged_df(
     let staged_df = tabular::read_df(&head_path, DFOpts::empty())?;
     let committed_df = tabular::read_df(&base_path, DFOpts::empty())?;
 
-    let diff = diff_dfs(&committed_df, &staged_df, vec![], vec![], vec![])?;
+    let diff = if is_indexed {
+        // Key rows on their position in the committed file so rows updated in place show up as modified
+        let mut committed_df = committed_df.with_row_index(constants::OXEN_ROW_IDX_COL, None)?;
+        let row_idx = committed_df
+            .column(constants::OXEN_ROW_IDX_COL)?
+            .cast(&polars::prelude::DataType::Int64)?;
+        committed_df.with_column(row_idx)?;
+        let staged_row_idx = staged_df
+            .column(constants::OXEN_ROW_IDX_COL)?
+            .cast(&polars::prelude::DataType::Int64)?;
+        let mut staged_df = staged_df;
+        staged_df.with_column(staged_row_idx)?;
+
+        let keys = vec![constants::OXEN_ROW_IDX_COL.to_string()];
+        diff_dfs(
+            &committed_df,
+            &staged_df,
+            keys,
+            vec![],
+            vec![],
+            &TabularDiffOpts::default(),
+        )?
+    } else {
+        diff_dfs(
+            &committed_df,
+            &staged_df,
+            vec![],
+            vec![],
+            vec![],
+            &TabularDiffOpts::default(),
+        )?
+    };
 
     // Clean up the staged df if we performed the export
     if head_path != base_path {
@@ -670,16 +748,29 @@ pub fn list_diff_entries_in_dir_top_level(
     let mut modified_commit_entries: Vec<DiffCommitEntry> = vec![];
     collect_modified_entries(&base_entries, &head_entries, &mut modified_commit_entries)?;
 
-    let counts = AddRemoveModifyCounts {
-        added: added_commit_entries.len(),
-        removed: removed_commit_entries.len(),
-        modified: modified_commit_entries.len(),
-    };
+    let mut renamed_commit_entries: Vec<DiffCommitEntry> = vec![];
+    collect_renamed_entries(
+        repo,
+        &recorded_moves(base_commit, head_commit),
+        &base_entries,
+        &head_entries,
+        &mut added_commit_entries,
+        &mut removed_commit_entries,
+        &mut renamed_commit_entries,
+    )?;
+
+    let counts = count_commit_entries(
+        &added_commit_entries,
+        &removed_commit_entries,
+        &modified_commit_entries,
+        &renamed_commit_entries,
+    );
 
     let mut combined: Vec<_> = added_commit_entries
         .into_iter()
         .chain(removed_commit_entries)
         .chain(modified_commit_entries)
+        .chain(renamed_commit_entries)
         .collect();
 
     // Filter out the entries that are not direct children of the provided dir
@@ -974,6 +1065,7 @@ pub fn get_cached_diff(
         modifications: TabularDiffMods {
             row_counts: row_mods,
             col_changes: schema_diff,
+            col_counts: ColumnChangeCount::from_diff_df(&diff_df)?,
         },
         dupes: read_dupes(repo, compare_id)?,
     };
@@ -1001,12 +1093,14 @@ fn read_dupes(repo: &LocalRepository, compare_id: &str) -> Result<TabularDiffDup
     Ok(dupes)
 }
 // Abbreviated version for when summary is known (e.g. computing top-level self node of an already-calculated dir diff)
+// The summary by data type is only computed with `include_data_types`, and only if the dir changed
 pub fn get_dir_diff_entry_with_summary(
     repo: &LocalRepository,
     dir: PathBuf,
     base_commit: &Commit,
     head_commit: &Commit,
     summary: GenericDiffSummary,
+    include_data_types: bool,
 ) -> Result<Option<DiffEntry>, OxenError> {
     // Dir hashes db is cheaper to open than objects reader
     let base_dir_hashes_db_path = ObjectDBReader::commit_dir_hash_db(&repo.path, &base_commit.id);
@@ -1027,46 +1121,210 @@ pub fn get_dir_diff_entry_with_summary(
     let maybe_base_dir_hash: Option<String> = path_db::get_entry(&base_dir_hashes_db, &dir)?;
     let maybe_head_dir_hash: Option<String> = path_db::get_entry(&head_dir_hashes_db, &dir)?;
 
-    match (maybe_base_dir_hash, maybe_head_dir_hash) {
-        (Some(base_dir_hash), Some(head_dir_hash)) => {
-            let base_dir_hash = base_dir_hash.to_string();
-            let head_dir_hash = head_dir_hash.to_string();
+    let status = match (&maybe_base_dir_hash, &maybe_head_dir_hash) {
+        (Some(base_dir_hash), Some(head_dir_hash)) if base_dir_hash == head_dir_hash => {
+            return Ok(None);
+        }
+        (Some(_), Some(_)) => DiffEntryStatus::Modified,
+        (None, Some(_)) => DiffEntryStatus::Added,
+        (Some(_), None) => DiffEntryStatus::Removed,
+        (None, None) => {
+            return Err(OxenError::basic_str(
+                "Could not calculate dir diff tree: dir does not exist in either commit.",
+            ))
+        }
+    };
 
-            if base_dir_hash == head_dir_hash {
-                Ok(None)
+    let summary = match summary {
+        GenericDiffSummary::DirDiffSummary(mut summary) if include_data_types => {
+            summary.dir.data_types =
+                get_dir_diff_summary_by_data_type(repo, &dir, base_commit, head_commit)?;
+            GenericDiffSummary::DirDiffSummary(summary)
+        }
+        summary => summary,
+    };
+
+    let base_dir = maybe_base_dir_hash.as_ref().map(|_| &dir);
+    let head_dir = maybe_head_dir_hash.as_ref().map(|_| &dir);
+    Ok(Some(DiffEntry::from_dir_with_summary(
+        repo,
+        base_dir,
+        base_commit,
+        head_dir,
+        head_commit,
+        summary,
+        status,
+    )?))
+}
+
+/// Groups the changes of all the files below `dir` by data type, counting the bytes and,
+/// for tabular files, the rows that were added and removed
+pub fn get_dir_diff_summary_by_data_type(
+    repo: &LocalRepository,
+    dir: impl AsRef<Path>,
+    base_commit: &Commit,
+    head_commit: &Commit,
+) -> Result<Vec<DataTypeDiffSummary>, OxenError> {
+    let dir = dir.as_ref().to_path_buf();
+    let mut summaries =
+        get_dir_diff_summaries_by_data_type(repo, &[dir.clone()], base_commit, head_commit)?;
+    Ok(summaries.remove(&dir).unwrap_or_default())
+}
+
+/// Same as `get_dir_diff_summary_by_data_type` for many dirs at once, only reading the entries
+/// of the commits and looking at each changed file one time
+pub fn get_dir_diff_summaries_by_data_type(
+    repo: &LocalRepository,
+    dirs: &[PathBuf],
+    base_commit: &Commit,
+    head_commit: &Commit,
+) -> Result<HashMap<PathBuf, Vec<DataTypeDiffSummary>>, OxenError> {
+    let mut summaries: HashMap<PathBuf, HashMap<EntryDataType, DataTypeDiffSummary>> = dirs
+        .iter()
+        .map(|dir| (dir.to_owned(), HashMap::new()))
+        .collect();
+
+    // Only read the entries below the common prefix of all the dirs
+    let mut root = dirs.first().cloned().unwrap_or_default();
+    for dir in dirs.iter() {
+        while !dir.starts_with(&root) {
+            root = root.parent().map(Path::to_path_buf).unwrap_or_default();
+        }
+    }
+
+    let object_reader = ObjectDBReader::new(repo)?;
+    let base_reader =
+        CommitEntryReader::new_from_commit_id(repo, &base_commit.id, object_reader.clone())?;
+    let head_reader = CommitEntryReader::new_from_commit_id(repo, &head_commit.id, object_reader)?;
+    let base_entries = base_reader.list_directory_set(&root)?;
+    let head_entries = head_reader.list_directory_set(&root)?;
+    let base_sizes = cachers::df_size::open_cache(repo, base_commit)?;
+    let head_sizes = cachers::df_size::open_cache(repo, head_commit)?;
+
+    let mut add_change = |base: Option<&CommitEntry>, head: Option<&CommitEntry>| {
+        let Some(entry) = head.or(base) else {
+            return;
+        };
+        let mut entry_dirs = summaries
+            .iter_mut()
+            .filter(|(dir, _)| entry.path.starts_with(dir))
+            .peekable();
+        if entry_dirs.peek().is_none() {
+            return;
+        }
+
+        let change = data_type_change(
+            repo,
+            base.map(|e| (e, base_sizes.as_ref())),
+            head.map(|e| (e, head_sizes.as_ref())),
+        );
+        for (_, dir_summaries) in entry_dirs {
+            let summary = dir_summaries
+                .entry(change.data_type.clone())
+                .or_insert_with(|| DataTypeDiffSummary::new(change.data_type.clone()));
+            change.add_to(summary);
+        }
+    };
+
+    for head_entry in head_entries.iter() {
+        match base_entries.get(head_entry) {
+            Some(base_entry) if base_entry.hash == head_entry.hash => {}
+            base_entry => add_change(base_entry, Some(head_entry)),
+        }
+    }
+    for base_entry in base_entries.difference(&head_entries) {
+        add_change(Some(base_entry), None);
+    }
+
+    Ok(summaries
+        .into_iter()
+        .map(|(dir, summaries)| {
+            let mut summaries: Vec<DataTypeDiffSummary> = summaries.into_values().collect();
+            summaries.sort_by_key(|s| s.data_type.to_string());
+            (dir, summaries)
+        })
+        .collect())
+}
+
+/// How a single file changed, to be added to the summary of each dir it is in
+struct DataTypeChange {
+    data_type: EntryDataType,
+    status: DiffEntryStatus,
+    base_bytes: u64,
+    head_bytes: u64,
+    base_rows: usize,
+    head_rows: usize,
+}
+
+impl DataTypeChange {
+    fn add_to(&self, summary: &mut DataTypeDiffSummary) {
+        match self.status {
+            DiffEntryStatus::Added => summary.file_counts.added += 1,
+            DiffEntryStatus::Removed => summary.file_counts.removed += 1,
+            _ => summary.file_counts.modified += 1,
+        }
+
+        if self.head_bytes > self.base_bytes {
+            summary.num_bytes_added += self.head_bytes - self.base_bytes;
+        } else {
+            summary.num_bytes_removed += self.base_bytes - self.head_bytes;
+        }
+
+        if self.data_type == EntryDataType::Tabular {
+            if self.head_rows > self.base_rows {
+                *summary.num_rows_added.get_or_insert(0) += self.head_rows - self.base_rows;
             } else {
-                Ok(Some(DiffEntry::from_dir_with_summary(
-                    repo,
-                    Some(&dir),
-                    base_commit,
-                    Some(&dir),
-                    head_commit,
-                    summary,
-                    DiffEntryStatus::Modified,
-                )?))
+                *summary.num_rows_removed.get_or_insert(0) += self.base_rows - self.head_rows;
             }
         }
-        (None, Some(_)) => Ok(Some(DiffEntry::from_dir_with_summary(
-            repo,
-            None,
-            base_commit,
-            Some(&dir),
-            head_commit,
-            summary,
-            DiffEntryStatus::Added,
-        )?)),
-        (Some(_), None) => Ok(Some(DiffEntry::from_dir_with_summary(
-            repo,
-            Some(&dir),
-            base_commit,
-            None,
-            head_commit,
-            summary,
-            DiffEntryStatus::Removed,
-        )?)),
-        (None, None) => Err(OxenError::basic_str(
-            "Could not calculate dir diff tree: dir does not exist in either commit.",
-        )),
+    }
+}
+
+// A changed entry along with the df_size cache of its commit, if it has been computed
+type EntryWithSizes<'a> = (&'a CommitEntry, Option<&'a DBWithThreadMode<MultiThreaded>>);
+
+fn data_type_change(
+    repo: &LocalRepository,
+    base: Option<EntryWithSizes>,
+    head: Option<EntryWithSizes>,
+) -> DataTypeChange {
+    // Callers always pass at least one side
+    let (entry, _) = head.or(base).unwrap();
+    // Only read the version file when the extension does not tell the type
+    let data_type = util::fs::data_type_from_extension(&entry.path)
+        .unwrap_or_else(|| util::fs::file_data_type(&util::fs::version_path(repo, entry)));
+    let status = match (base, head) {
+        (None, Some(_)) => DiffEntryStatus::Added,
+        (Some(_), None) => DiffEntryStatus::Removed,
+        _ => DiffEntryStatus::Modified,
+    };
+
+    let num_bytes = |side: Option<EntryWithSizes>| side.map(|(e, _)| e.num_bytes);
+
+    // Row counts only come from the df_size cache of the commit, files that have not been
+    // cached yet count as 0 rows rather than being read here
+    let num_rows = |side: Option<EntryWithSizes>| -> usize {
+        if data_type != EntryDataType::Tabular {
+            return 0;
+        }
+        side.and_then(|(entry, sizes)| {
+            let version_path = util::fs::version_path(repo, entry);
+            cachers::df_size::get_from_db(sizes?, &version_path)
+                .map_err(|err| log::warn!("Could not get size of {:?}: {}", entry.path, err))
+                .ok()
+                .flatten()
+        })
+        .map(|size| size.height)
+        .unwrap_or(0)
+    };
+
+    DataTypeChange {
+        status,
+        base_bytes: num_bytes(base).unwrap_or(0),
+        head_bytes: num_bytes(head).unwrap_or(0),
+        base_rows: num_rows(base),
+        head_rows: num_rows(head),
+        data_type,
     }
 }
 
@@ -1171,15 +1429,33 @@ pub fn list_diff_entries(
         "Collected {} collect_modified_entries",
         modified_commit_entries.len()
     );
-    let counts = AddRemoveModifyCounts {
-        added: added_commit_entries.len(),
-        removed: removed_commit_entries.len(),
-        modified: modified_commit_entries.len(),
-    };
+
+    let mut renamed_commit_entries: Vec<DiffCommitEntry> = vec![];
+    collect_renamed_entries(
+        repo,
+        &recorded_moves(base_commit, head_commit),
+        &base_entries,
+        &head_entries,
+        &mut added_commit_entries,
+        &mut removed_commit_entries,
+        &mut renamed_commit_entries,
+    )?;
+    log::debug!(
+        "Collected {} collect_renamed_entries",
+        renamed_commit_entries.len()
+    );
+
+    let counts = count_commit_entries(
+        &added_commit_entries,
+        &removed_commit_entries,
+        &modified_commit_entries,
+        &renamed_commit_entries,
+    );
     let mut combined: Vec<_> = added_commit_entries
         .into_iter()
         .chain(removed_commit_entries)
         .chain(modified_commit_entries)
+        .chain(renamed_commit_entries)
         .collect();
     combined.sort_by(|a, b| a.path.cmp(&b.path));
 
@@ -1391,26 +1667,264 @@ fn collect_modified_entries(
     Ok(())
 }
 
+// Moves are only recorded against the parent, so they apply when HEAD is a direct child of BASE
+fn recorded_moves(base_commit: &Commit, head_commit: &Commit) -> Vec<MovedEntry> {
+    if head_commit.parent_ids.contains(&base_commit.id) {
+        api::local::commits::list_moves(head_commit)
+    } else {
+        vec![]
+    }
+}
+
+// Pair up entries removed from BASE with entries added in HEAD that have the same content as renames,
+// and mark added entries that duplicate a file still in HEAD as copies. Tabular files that kept their
+// name but were edited while moving are matched on the share of rows they have in common.
+// Moves recorded on the commit with `oxen mv` are paired first.
+fn collect_renamed_entries(
+    repo: &LocalRepository,
+    moves: &[MovedEntry],
+    base_entries: &HashSet<CommitEntry>,
+    head_entries: &HashSet<CommitEntry>,
+    added_entries: &mut Vec<DiffCommitEntry>,
+    removed_entries: &mut Vec<DiffCommitEntry>,
+    diff_entries: &mut Vec<DiffCommitEntry>,
+) -> Result<(), OxenError> {
+    if added_entries.is_empty() {
+        return Ok(());
+    }
+
+    // Sort so that the pairing is the same every time when several files share a hash
+    added_entries.sort_by(|a, b| a.path.cmp(&b.path));
+    removed_entries.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let mut moved_from: HashSet<PathBuf> = HashSet::new();
+    let mut moved_to: HashSet<PathBuf> = HashSet::new();
+    for moved in moves {
+        let base_entry = removed_entries
+            .iter()
+            .find(|removed| removed.path == moved.from)
+            .and_then(|removed| removed.base_entry.clone());
+        let head_entry = added_entries
+            .iter()
+            .find(|added| added.path == moved.to)
+            .and_then(|added| added.head_entry.clone());
+        if let (Some(base_entry), Some(head_entry)) = (base_entry, head_entry) {
+            moved_from.insert(base_entry.path.to_owned());
+            moved_to.insert(head_entry.path.to_owned());
+            diff_entries.push(DiffCommitEntry {
+                path: head_entry.path.to_owned(),
+                base_entry: Some(base_entry),
+                head_entry: Some(head_entry),
+                status: DiffEntryStatus::Renamed,
+            });
+        }
+    }
+    removed_entries.retain(|removed| !moved_from.contains(&removed.path));
+    added_entries.retain(|added| !moved_to.contains(&added.path));
+
+    let mut removed_by_hash: HashMap<&str, VecDeque<&CommitEntry>> = HashMap::new();
+    for removed in removed_entries.iter() {
+        if let Some(base_entry) = &removed.base_entry {
+            removed_by_hash
+                .entry(base_entry.hash.as_str())
+                .or_default()
+                .push_back(base_entry);
+        }
+    }
+
+    // CommitEntry equality only looks at the path, so check the file was not modified as well
+    let mut kept_by_hash: HashMap<&str, &CommitEntry> = HashMap::new();
+    for base_entry in base_entries.iter() {
+        let is_kept = head_entries
+            .get(base_entry)
+            .is_some_and(|head_entry| head_entry.hash == base_entry.hash);
+        if is_kept {
+            kept_by_hash
+                .entry(base_entry.hash.as_str())
+                .or_insert(base_entry);
+        }
+    }
+
+    let mut renamed_from: HashSet<PathBuf> = HashSet::new();
+    let mut still_added: Vec<DiffCommitEntry> = vec![];
+    for added in added_entries.iter() {
+        let Some(head_entry) = &added.head_entry else {
+            still_added.push(added.to_owned());
+            continue;
+        };
+
+        let renamed = removed_by_hash
+            .get_mut(head_entry.hash.as_str())
+            .and_then(|queue| queue.pop_front());
+        if let Some(base_entry) = renamed {
+            renamed_from.insert(base_entry.path.to_owned());
+            diff_entries.push(DiffCommitEntry {
+                path: head_entry.path.to_owned(),
+                base_entry: Some(base_entry.to_owned()),
+                head_entry: Some(head_entry.to_owned()),
+                status: DiffEntryStatus::Renamed,
+            });
+        } else if let Some(base_entry) = kept_by_hash.get(head_entry.hash.as_str()) {
+            diff_entries.push(DiffCommitEntry {
+                path: head_entry.path.to_owned(),
+                base_entry: Some((*base_entry).to_owned()),
+                head_entry: Some(head_entry.to_owned()),
+                status: DiffEntryStatus::Copied,
+            });
+        } else {
+            still_added.push(added.to_owned());
+        }
+    }
+    removed_entries.retain(|removed| !renamed_from.contains(&removed.path));
+
+    // Moved and edited tabular files, only compared against removed files with the same name
+    let mut removed_tabular_by_name: HashMap<OsString, Vec<CommitEntry>> = HashMap::new();
+    for removed in removed_entries.iter() {
+        if let (Some(base_entry), Some(name)) = (&removed.base_entry, removed.path.file_name()) {
+            if util::fs::is_tabular(&removed.path) {
+                removed_tabular_by_name
+                    .entry(name.to_owned())
+                    .or_default()
+                    .push(base_entry.to_owned());
+            }
+        }
+    }
+
+    let mut remaining_added: Vec<DiffCommitEntry> = vec![];
+    for added in still_added {
+        let candidates = match (&added.head_entry, added.path.file_name()) {
+            (Some(_), Some(name)) => removed_tabular_by_name.get_mut(name),
+            _ => None,
+        };
+        let Some(candidates) = candidates else {
+            remaining_added.push(added);
+            continue;
+        };
+
+        let head_entry = added.head_entry.as_ref().unwrap();
+        let mut matched: Option<usize> = None;
+        for (i, base_entry) in candidates.iter().enumerate() {
+            // A file that can not be read is listed as added and removed rather than failing the diff
+            let similarity =
+                tabular_similarity(repo, base_entry, head_entry).unwrap_or_else(|err| {
+                    log::warn!(
+                        "Could not compare {:?} to {:?}: {}",
+                        base_entry.path,
+                        head_entry.path,
+                        err
+                    );
+                    0.0
+                });
+            if similarity >= TABULAR_RENAME_SIMILARITY {
+                matched = Some(i);
+                break;
+            }
+        }
+
+        if let Some(i) = matched {
+            let base_entry = candidates.remove(i);
+            renamed_from.insert(base_entry.path.to_owned());
+            diff_entries.push(DiffCommitEntry {
+                path: added.path.to_owned(),
+                base_entry: Some(base_entry),
+                head_entry: added.head_entry,
+                status: DiffEntryStatus::Renamed,
+            });
+        } else {
+            remaining_added.push(added);
+        }
+    }
+    removed_entries.retain(|removed| !renamed_from.contains(&removed.path));
+    *added_entries = remaining_added;
+
+    Ok(())
+}
+
+/// Share of rows two versions of a tabular file have in common, 0.0 if the columns differ or
+/// either file is over `TABULAR_RENAME_MAX_BYTES`
+pub fn tabular_similarity(
+    repo: &LocalRepository,
+    base_entry: &CommitEntry,
+    head_entry: &CommitEntry,
+) -> Result<f64, OxenError> {
+    if base_entry.num_bytes > TABULAR_RENAME_MAX_BYTES
+        || head_entry.num_bytes > TABULAR_RENAME_MAX_BYTES
+    {
+        return Ok(0.0);
+    }
+
+    let base_df = tabular::read_df(util::fs::version_path(repo, base_entry), DFOpts::empty())?;
+    let head_df = tabular::read_df(util::fs::version_path(repo, head_entry), DFOpts::empty())?;
+
+    if base_df.get_column_names() != head_df.get_column_names() {
+        return Ok(0.0);
+    }
+    let num_rows = base_df.height().max(head_df.height());
+    if num_rows == 0 {
+        return Ok(1.0);
+    }
+
+    let base_df = tabular::df_hash_rows(base_df)?;
+    let head_df = tabular::df_hash_rows(head_df)?;
+    let base_hashes: HashSet<&str> = base_df
+        .column(constants::ROW_HASH_COL_NAME)?
+        .str()?
+        .into_iter()
+        .flatten()
+        .collect();
+    let shared = head_df
+        .column(constants::ROW_HASH_COL_NAME)?
+        .str()?
+        .into_iter()
+        .flatten()
+        .filter(|hash| base_hashes.contains(hash))
+        .count();
+
+    Ok(shared as f64 / num_rows as f64)
+}
+
+fn count_commit_entries(
+    added: &[DiffCommitEntry],
+    removed: &[DiffCommitEntry],
+    modified: &[DiffCommitEntry],
+    renamed: &[DiffCommitEntry],
+) -> AddRemoveModifyCounts {
+    // Copies are new files, so they count as added
+    let copied = renamed
+        .iter()
+        .filter(|entry| entry.status == DiffEntryStatus::Copied)
+        .count();
+    AddRemoveModifyCounts {
+        added: added.len() + copied,
+        removed: removed.len(),
+        modified: modified.len(),
+        renamed: renamed.len() - copied,
+    }
+}
+
 // TODO: linear scan is not the most efficient way to do this
 pub fn get_add_remove_modify_counts(entries: &[DiffEntry]) -> AddRemoveModifyCounts {
     let mut added = 0;
     let mut removed = 0;
     let mut modified = 0;
+    let mut renamed = 0;
     for entry in entries {
         if entry.is_dir {
             continue;
         }
 
         match DiffEntryStatus::from_str(&entry.status).unwrap() {
-            DiffEntryStatus::Added => added += 1,
+            DiffEntryStatus::Added | DiffEntryStatus::Copied => added += 1,
             DiffEntryStatus::Removed => removed += 1,
             DiffEntryStatus::Modified => modified += 1,
+            DiffEntryStatus::Renamed => renamed += 1,
         }
     }
     AddRemoveModifyCounts {
         added,
         removed,
         modified,
+        renamed,
     }
 }
 
@@ -1423,7 +1937,10 @@ fn subset_dir_diffs_to_direct_children(
     for entry in entries {
         let status = DiffEntryStatus::from_str(&entry.status)?;
         let relevant_entry = match status {
-            DiffEntryStatus::Added | DiffEntryStatus::Modified => entry.head_entry.as_ref(),
+            DiffEntryStatus::Added
+            | DiffEntryStatus::Modified
+            | DiffEntryStatus::Renamed
+            | DiffEntryStatus::Copied => entry.head_entry.as_ref(),
             DiffEntryStatus::Removed => entry.base_entry.as_ref(),
         };
 
@@ -1448,7 +1965,10 @@ fn subset_file_diffs_to_direct_children(
 
     for entry in entries {
         let relevant_entry = match entry.status {
-            DiffEntryStatus::Added | DiffEntryStatus::Modified => entry.head_entry.as_ref(),
+            DiffEntryStatus::Added
+            | DiffEntryStatus::Modified
+            | DiffEntryStatus::Renamed
+            | DiffEntryStatus::Copied => entry.head_entry.as_ref(),
             DiffEntryStatus::Removed => entry.base_entry.as_ref(),
         };
 
@@ -1551,8 +2071,11 @@ mod tests {
 
     use crate::api;
     use crate::command;
+    use crate::core::cache::cachers;
     use crate::error::OxenError;
     use crate::model::diff::diff_entry_status::DiffEntryStatus;
+    use crate::model::EntryDataType;
+    use crate::opts::MvOpts;
     use crate::opts::RmOpts;
     use crate::test;
     use crate::util;
@@ -1634,6 +2157,65 @@ train/cat_2.jpg,cat,30.5,44.0,333,396
         })
     }
 
+    #[test]
+    fn test_diff_dir_summary_by_data_type() -> Result<(), OxenError> {
+        test::run_bounding_box_csv_repo_test_fully_committed(|repo| {
+            let base_commit = api::local::commits::head_commit(&repo)?;
+
+            // Keep only the header and one row of the bounding boxes
+            let bbox_file = repo
+                .path
+                .join("annotations")
+                .join("train")
+                .join("bounding_box.csv");
+            let bbox_file = test::modify_txt_file(
+                bbox_file,
+                "file,label,min_x,min_y,width,height\ntrain/dog_1.jpg,dog,101.5,32.0,385,330\n",
+            )?;
+            let readme = repo.path.join("annotations").join("notes.txt");
+            test::write_txt_file_to_path(&readme, "Bounding boxes")?;
+            let cat = repo.path.join("annotations").join("cat_1.jpg");
+            util::fs::copy(test::test_img_file_with_name("cat_1.jpg"), &cat)?;
+
+            command::add(&repo, &repo.path.join("annotations"))?;
+            let head_commit = command::commit(&repo, "Updating annotations")?;
+
+            // Row counts are read from the df_size cache
+            cachers::df_size::compute(&repo, &base_commit)?;
+            cachers::df_size::compute(&repo, &head_commit)?;
+
+            let summaries = api::local::diff::get_dir_diff_summary_by_data_type(
+                &repo,
+                "annotations",
+                &base_commit,
+                &head_commit,
+            )?;
+            let find = |data_type: EntryDataType| {
+                summaries
+                    .iter()
+                    .find(|s| s.data_type == data_type)
+                    .unwrap()
+                    .clone()
+            };
+
+            let tabular = find(EntryDataType::Tabular);
+            assert_eq!(tabular.file_counts.modified, 1);
+            assert!(tabular.num_bytes_removed > 0);
+            assert!(tabular.num_rows_removed.unwrap() > 0);
+            assert_eq!(tabular.num_rows_added, Some(0));
+
+            let image = find(EntryDataType::Image);
+            assert_eq!(image.file_counts.added, 1);
+            assert_eq!(image.num_bytes_added, util::fs::metadata(&cat)?.len());
+            assert_eq!(image.num_rows_added, None);
+
+            let text = find(EntryDataType::Text);
+            assert_eq!(text.file_counts.added, 1);
+
+            Ok(())
+        })
+    }
+
     #[tokio::test]
     async fn test_diff_entries_remove_one_tabular() -> Result<(), OxenError> {
         test::run_bounding_box_csv_repo_test_fully_committed_async(|repo| async move {
@@ -1679,6 +2261,133 @@ train/cat_2.jpg,cat,30.5,44.0,333,396
         .await
     }
 
+    #[tokio::test]
+    async fn test_diff_entries_rename_tabular() -> Result<(), OxenError> {
+        test::run_bounding_box_csv_repo_test_fully_committed_async(|repo| async move {
+            let bbox_filename = Path::new("annotations")
+                .join("train")
+                .join("bounding_box.csv");
+            let moved_filename = Path::new("annotations").join("bounding_box.csv");
+
+            let base_commit = api::local::commits::head_commit(&repo)?;
+
+            // Move the file and add an unrelated one
+            std::fs::rename(
+                repo.path.join(&bbox_filename),
+                repo.path.join(&moved_filename),
+            )?;
+            let hello_file = repo.path.join("Hello.txt");
+            test::write_txt_file_to_path(&hello_file, "Hello")?;
+
+            let opts = RmOpts::from_path(&bbox_filename);
+            command::rm(&repo, &opts).await?;
+            command::add(&repo, repo.path.join(&moved_filename))?;
+            command::add(&repo, &hello_file)?;
+            let head_commit = command::commit(&repo, "Moving the training data up a dir")?;
+
+            let diff = api::local::diff::list_diff_entries(
+                &repo,
+                &base_commit,
+                &head_commit,
+                PathBuf::from(""),
+                0,
+                10,
+            )?;
+            assert_eq!(diff.counts.renamed, 1);
+            assert_eq!(diff.counts.added, 1);
+            assert_eq!(diff.counts.removed, 0);
+
+            let renamed: Vec<_> = diff
+                .entries
+                .iter()
+                .filter(|entry| entry.status == DiffEntryStatus::Renamed.to_string())
+                .collect();
+            assert_eq!(renamed.len(), 1);
+            assert_eq!(renamed[0].filename, moved_filename.to_str().unwrap());
+            assert_eq!(
+                renamed[0].old_filename,
+                Some(bbox_filename.to_str().unwrap().to_string())
+            );
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_diff_entries_recorded_move_with_edit() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            util::fs::write_to_path(repo.path.join("notes.txt"), "Some notes")?;
+            command::add(&repo, &repo.path)?;
+            let base_commit = command::commit(&repo, "Adding notes")?;
+
+            // The contents change, so the move is only known because it was recorded
+            let opts = MvOpts::from_paths(
+                repo.path.join("notes.txt"),
+                repo.path.join("docs").join("notes.txt"),
+            );
+            command::mv(&repo, &opts).await?;
+            let moved_file = repo.path.join("docs").join("notes.txt");
+            util::fs::write_to_path(&moved_file, "Some edited notes")?;
+            command::add(&repo, &moved_file)?;
+            let head_commit = command::commit(&repo, "Moving notes")?;
+
+            let diff = api::local::diff::list_diff_entries(
+                &repo,
+                &base_commit,
+                &head_commit,
+                PathBuf::from(""),
+                0,
+                10,
+            )?;
+            assert_eq!(diff.counts.renamed, 1);
+            assert_eq!(diff.counts.added, 0);
+            assert_eq!(diff.counts.removed, 0);
+            let renamed = diff
+                .entries
+                .iter()
+                .find(|entry| entry.status == DiffEntryStatus::Renamed.to_string())
+                .unwrap();
+            assert_eq!(renamed.filename, "docs/notes.txt");
+            assert_eq!(renamed.old_filename, Some("notes.txt".to_string()));
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[test]
+    fn test_diff_entries_copy_of_modified_file_is_added() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            util::fs::write_to_path(repo.path.join("notes.txt"), "Some notes")?;
+            command::add(&repo, &repo.path)?;
+            let base_commit = command::commit(&repo, "Adding notes")?;
+
+            // The old contents only live on in the new file, notes.txt does not have them anymore
+            util::fs::write_to_path(repo.path.join("notes.txt"), "Some edited notes")?;
+            util::fs::write_to_path(repo.path.join("copy.txt"), "Some notes")?;
+            command::add(&repo, &repo.path)?;
+            let head_commit = command::commit(&repo, "Editing notes")?;
+
+            let diff = api::local::diff::list_diff_entries(
+                &repo,
+                &base_commit,
+                &head_commit,
+                PathBuf::from(""),
+                0,
+                10,
+            )?;
+            assert_eq!(diff.counts.added, 1);
+            assert_eq!(diff.counts.modified, 1);
+            assert!(!diff
+                .entries
+                .iter()
+                .any(|entry| entry.status == DiffEntryStatus::Copied.to_string()));
+
+            Ok(())
+        })
+    }
+
     #[tokio::test]
     async fn test_diff_get_add_remove_modify_counts() -> Result<(), OxenError> {
         test::run_bounding_box_csv_repo_test_fully_committed_async(|repo| async move {
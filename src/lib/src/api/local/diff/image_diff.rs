@@ -0,0 +1,245 @@
+use std::path::Path;
+
+use image::DynamicImage;
+
+use crate::api;
+use crate::error::OxenError;
+use crate::model::diff::image_diff::ImageDiff;
+use crate::model::metadata::MetadataImage;
+use crate::model::{CommitEntry, LocalRepository};
+use crate::util;
+
+/// Compares two images by their metadata, their pixels and their perceptual hashes. Images
+/// that cannot be decoded are only compared by the hash of their contents.
+pub fn diff(
+    version_file_1: impl AsRef<Path>,
+    version_file_2: impl AsRef<Path>,
+) -> Result<ImageDiff, OxenError> {
+    let base = read_image(version_file_1, None)?;
+    let head = read_image(version_file_2, None)?;
+    diff_images(base, head)
+}
+
+/// Diffs the versions of two commit entries, either of which can be missing
+pub fn diff_commit_entries(
+    repo: &LocalRepository,
+    base_entry: &Option<CommitEntry>,
+    head_entry: &Option<CommitEntry>,
+) -> Result<ImageDiff, OxenError> {
+    let base = match base_entry {
+        Some(entry) => read_image(util::fs::version_path(repo, entry), Some(&entry.hash))?,
+        None => None,
+    };
+    let head = match head_entry {
+        Some(entry) => read_image(util::fs::version_path(repo, entry), Some(&entry.hash))?,
+        None => None,
+    };
+    diff_images(base, head)
+}
+
+/// Writes an image highlighting the pixels that changed between the two files to `output`
+pub fn render_mask(
+    version_file_1: impl AsRef<Path>,
+    version_file_2: impl AsRef<Path>,
+    output: impl AsRef<Path>,
+) -> Result<(), OxenError> {
+    let base = util::image::open(version_file_1)?;
+    let head = util::image::open(version_file_2)?;
+    let mask = util::image::diff_mask(
+        &base.to_rgba8(),
+        &util::image::rgba_like(&base, &head),
+        util::image::PIXEL_DIFF_TOLERANCE,
+    );
+    mask.save(output)?;
+    Ok(())
+}
+
+type ImageWithMeta = (DynamicImage, MetadataImage);
+
+struct ImageVersion {
+    hash: String,
+    /// None when the file could not be decoded as an image
+    image: Option<ImageWithMeta>,
+}
+
+fn read_image(
+    path: impl AsRef<Path>,
+    hash: Option<&str>,
+) -> Result<Option<ImageVersion>, OxenError> {
+    let path = path.as_ref();
+    if !path.exists() {
+        return Ok(None);
+    }
+    let hash = match hash {
+        Some(hash) => hash.to_string(),
+        None => util::hasher::hash_file_contents(path)?,
+    };
+    let image = match util::image::open(path) {
+        Ok(img) => {
+            let metadata = api::local::metadata::image::get_metadata(path)?;
+            Some((img, metadata))
+        }
+        Err(err) => {
+            log::debug!(
+                "Could not decode image {:?}, only comparing hashes: {}",
+                path,
+                err
+            );
+            None
+        }
+    };
+    Ok(Some(ImageVersion { hash, image }))
+}
+
+fn diff_images(
+    base: Option<ImageVersion>,
+    head: Option<ImageVersion>,
+) -> Result<ImageDiff, OxenError> {
+    let content_changed = base.as_ref().map(|v| &v.hash) != head.as_ref().map(|v| &v.hash);
+    let is_undecodable = |v: &Option<ImageVersion>| v.as_ref().is_some_and(|v| v.image.is_none());
+    if is_undecodable(&base) || is_undecodable(&head) {
+        return Ok(hash_only_diff(base, head, content_changed));
+    }
+    let base = base.and_then(|v| v.image);
+    let head = head.and_then(|v| v.image);
+
+    let dims = |m: &MetadataImage| (m.image.width, m.image.height);
+    let base_meta = base.as_ref().map(|(_, m)| m.clone());
+    let head_meta = head.as_ref().map(|(_, m)| m.clone());
+    let dimensions_changed = base_meta.as_ref().map(dims) != head_meta.as_ref().map(dims);
+    let color_space_changed = base_meta.as_ref().map(|m| &m.image.color_space)
+        != head_meta.as_ref().map(|m| &m.image.color_space);
+
+    let (num_changed_pixels, pixel_diff_ratio, mean_abs_diff, perceptual_similarity) =
+        match (&base, &head) {
+            (Some((base_img, _)), Some((head_img, _))) => {
+                let base_rgba = base_img.to_rgba8();
+                let head_rgba = util::image::rgba_like(base_img, head_img);
+                let (num_changed, mean_abs_diff) = util::image::pixel_diff(
+                    &base_rgba,
+                    &head_rgba,
+                    util::image::PIXEL_DIFF_TOLERANCE,
+                );
+                let num_pixels = (base_rgba.width() as usize * base_rgba.height() as usize).max(1);
+                (
+                    num_changed,
+                    num_changed as f64 / num_pixels as f64,
+                    mean_abs_diff,
+                    util::image::perceptual_similarity(base_img, head_img),
+                )
+            }
+            // An added or removed image is a change of every pixel
+            (Some((img, _)), None) | (None, Some((img, _))) => {
+                let num_pixels = img.width() as usize * img.height() as usize;
+                (num_pixels, 1.0, 1.0, 0.0)
+            }
+            (None, None) => (0, 0.0, 0.0, 1.0),
+        };
+
+    Ok(ImageDiff {
+        base: base_meta,
+        head: head_meta,
+        dimensions_changed,
+        color_space_changed,
+        num_changed_pixels,
+        pixel_diff_ratio,
+        mean_abs_diff,
+        perceptual_similarity,
+        content_changed,
+        hash_only: false,
+    })
+}
+
+/// Diff of two versions where at least one could not be decoded, the metadata of a version that
+/// did decode is still returned
+fn hash_only_diff(
+    base: Option<ImageVersion>,
+    head: Option<ImageVersion>,
+    content_changed: bool,
+) -> ImageDiff {
+    let metadata = |v: Option<ImageVersion>| v.and_then(|v| v.image).map(|(_, m)| m);
+    ImageDiff {
+        base: metadata(base),
+        head: metadata(head),
+        dimensions_changed: false,
+        color_space_changed: false,
+        num_changed_pixels: 0,
+        pixel_diff_ratio: 0.0,
+        mean_abs_diff: 0.0,
+        perceptual_similarity: 0.0,
+        content_changed,
+        hash_only: true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::local::diff::image_diff;
+    use crate::error::OxenError;
+    use crate::test;
+    use crate::util;
+
+    #[test]
+    fn test_image_diff_same_image() -> Result<(), OxenError> {
+        let img = test::test_img_file_with_name("cat_1.jpg");
+
+        let diff = image_diff::diff(&img, &img)?;
+        assert!(!diff.has_changes());
+        assert_eq!(diff.num_changed_pixels, 0);
+        assert_eq!(diff.perceptual_similarity, 1.0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_image_diff_edited_pixels_and_mask() -> Result<(), OxenError> {
+        test::run_empty_dir_test(|dir| {
+            let base_path = test::test_img_file_with_name("cat_1.jpg");
+            let head_path = dir.join("cat_1_edited.png");
+            let mask_path = dir.join("mask.png");
+
+            // Paint a 10x10 white square into the corner of a lossless copy
+            let base = util::image::open(&base_path)?;
+            let mut head = base.to_rgba8();
+            for x in 0..10 {
+                for y in 0..10 {
+                    head.put_pixel(x, y, image::Rgba([255, 255, 255, 255]));
+                }
+            }
+            head.save(&head_path)?;
+            let base_copy = dir.join("cat_1.png");
+            base.to_rgba8().save(&base_copy)?;
+
+            let diff = image_diff::diff(&base_copy, &head_path)?;
+            assert!(diff.has_changes());
+            assert!(!diff.dimensions_changed);
+            assert!(diff.num_changed_pixels > 0);
+            assert!(diff.num_changed_pixels <= 100);
+            assert!(diff.perceptual_similarity > 0.9);
+
+            image_diff::render_mask(&base_copy, &head_path, &mask_path)?;
+            let mask = util::image::open(&mask_path)?.to_rgba8();
+            assert_eq!(mask.get_pixel(0, 0).0, [255, 0, 0, 255]);
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_image_diff_undecodable_image_compares_hashes() -> Result<(), OxenError> {
+        test::run_empty_dir_test(|dir| {
+            let base_path = test::test_img_file_with_name("cat_1.jpg");
+            let head_path = dir.join("cat_1_truncated.jpg");
+            util::fs::write_to_path(&head_path, "not really a jpeg")?;
+
+            let diff = image_diff::diff(&base_path, &head_path)?;
+            assert!(diff.hash_only);
+            assert!(diff.has_changes());
+            assert!(diff.base.is_some());
+            assert!(diff.head.is_none());
+
+            let diff = image_diff::diff(&head_path, &head_path)?;
+            assert!(diff.hash_only);
+            assert!(!diff.has_changes());
+            Ok(())
+        })
+    }
+}
@@ -1,30 +1,32 @@
 use std::collections::{HashMap, HashSet};
 
+use crate::constants::{DIFF_CHANGED_COLS_COL, DIFF_STATUS_COL};
 use crate::error::OxenError;
 use crate::model::diff::tabular_diff::{
-    TabularDiffDupes, TabularDiffMods, TabularDiffParameters, TabularDiffSchemas,
-    TabularDiffSummary, TabularSchemaDiff,
+    ColumnChangeCount, TabularDiffDupes, TabularDiffMods, TabularDiffParameters,
+    TabularDiffSchemas, TabularDiffSummary, TabularSchemaDiff,
 };
 use crate::model::diff::{AddRemoveModifyCounts, DiffResult, TabularDiff};
 use crate::model::schema::Field;
 use crate::model::Schema;
+use crate::opts::TabularDiffOpts;
 use crate::view::compare::{
     TabularCompareFieldBody, TabularCompareFields, TabularCompareTargetBody,
 };
 
-use polars::datatypes::{AnyValue, StringChunked};
+use polars::datatypes::{AnyValue, DataType, StringChunked};
 use polars::lazy::dsl::coalesce;
-use polars::lazy::dsl::{all, as_struct, col, GetOutput};
+use polars::lazy::dsl::{all, as_struct, col, concat_list, lit, when, Expr, GetOutput};
 use polars::lazy::frame::IntoLazy;
 use polars::prelude::ChunkCompare;
-use polars::prelude::{DataFrame, DataFrameJoinOps};
+use polars::prelude::{DataFrame, DataFrameJoinOps, Null};
 use polars::series::IntoSeries;
 
 use super::{tabular, SchemaDiff};
 
 const TARGETS_HASH_COL: &str = "_targets_hash";
 const KEYS_HASH_COL: &str = "_keys_hash";
-const DIFF_STATUS_COL: &str = ".oxen.diff.status";
+const CHANGED_MASK_PREFIX: &str = "_changed_";
 
 const DIFF_STATUS_ADDED: &str = "added";
 const DIFF_STATUS_REMOVED: &str = "removed";
@@ -38,6 +40,7 @@ pub fn diff(
     keys: &[impl AsRef<str>],
     targets: &[impl AsRef<str>],
     display: &[impl AsRef<str>],
+    opts: &TabularDiffOpts,
 ) -> Result<DiffResult, OxenError> {
     if !targets.is_empty() && keys.is_empty() {
         let targets = targets.iter().map(|k| k.as_ref()).collect::<Vec<&str>>();
@@ -68,6 +71,14 @@ pub fn diff(
 
     let joined_df = add_diff_status_column(joined_df, keys.clone(), targets.clone())?;
 
+    let joined_df = joined_df.filter(
+        &joined_df
+            .column(DIFF_STATUS_COL)?
+            .not_equal(DIFF_STATUS_UNCHANGED)?,
+    )?;
+
+    // The hashes only tell us that something changed, check which cells changed under the options
+    let joined_df = add_changed_cols_column(joined_df, &targets, &schema_diff, opts)?;
     let mut joined_df = joined_df.filter(
         &joined_df
             .column(DIFF_STATUS_COL)?
@@ -87,6 +98,7 @@ pub fn diff(
     }
 
     let modifications = calculate_compare_mods(&joined_df)?;
+    let col_counts = calculate_col_counts(&joined_df, &targets)?;
 
     // Sort by all keys with primitive dtypes
     let joined_df = sort_df_on_keys(joined_df, keys.clone())?;
@@ -112,6 +124,7 @@ pub fn diff(
             modifications: TabularDiffMods {
                 row_counts: modifications,
                 col_changes: schema_diff,
+                col_counts,
             },
             schemas,
             dupes,
@@ -188,6 +201,7 @@ fn get_output_columns(
     schema_diff: SchemaDiff,
 ) -> Vec<String> {
     // Keys in df2 order. Targets in df2 order, then any additional. Then display in df2, then any additional.
+    let has_targets = !targets.is_empty();
     let df2_cols_set: HashSet<&str> = df2_schema.fields.iter().map(|f| f.name.as_str()).collect();
 
     // Get the column index of each column in df2schema
@@ -237,6 +251,9 @@ fn get_output_columns(
     }
 
     out_columns.push(DIFF_STATUS_COL.to_string());
+    if has_targets {
+        out_columns.push(DIFF_CHANGED_COLS_COL.to_string());
+    }
     out_columns
 }
 
@@ -373,6 +390,137 @@ fn add_diff_status_column(
     Ok(joined_df)
 }
 
+/// Lists the target columns that changed in each modified row. Rows whose hashes differ but
+/// whose values are equal under `opts` (ie. within the numeric tolerance) become unchanged.
+fn add_changed_cols_column(
+    joined_df: DataFrame,
+    targets: &[&str],
+    schema_diff: &SchemaDiff,
+    opts: &TabularDiffOpts,
+) -> Result<DataFrame, OxenError> {
+    // Build a boolean mask per target once, the status and the changed columns are combined from them
+    let mut masks: Vec<Expr> = vec![];
+    let mut mask_cols: Vec<String> = vec![];
+    let mut names: Vec<Expr> = vec![];
+    let null_name = || lit(Null {}).cast(DataType::String);
+    for (i, target) in targets.iter().enumerate() {
+        let mask_col = format!("{}{}", CHANGED_MASK_PREFIX, i);
+        // A target that only exists on one side always counts as changed
+        let mask = if schema_diff.unchanged_cols.contains(&target.to_string()) {
+            let left = format!("{}.left", target);
+            let right = format!("{}.right", target);
+            changed_mask(
+                (&left, joined_df.column(&left)?.dtype()),
+                (&right, joined_df.column(&right)?.dtype()),
+                opts,
+            )
+        } else {
+            lit(true)
+        };
+        masks.push(mask.alias(&mask_col));
+        names.push(
+            when(col(&mask_col))
+                .then(lit(target.to_string()))
+                .otherwise(null_name()),
+        );
+        mask_cols.push(mask_col);
+    }
+
+    let any_changed = mask_cols
+        .iter()
+        .fold(lit(false), |acc, mask_col| acc.or(col(mask_col)));
+    let is_modified = col(DIFF_STATUS_COL).eq(lit(DIFF_STATUS_MODIFIED));
+    let still_modified = is_modified.clone().and(any_changed.clone());
+
+    let status = when(is_modified.and(any_changed.not()))
+        .then(lit(DIFF_STATUS_UNCHANGED))
+        .otherwise(col(DIFF_STATUS_COL))
+        .alias(DIFF_STATUS_COL);
+    // List the names of the changed targets, the unchanged ones are null and dropped
+    let no_names = || lit(Null {}).cast(DataType::List(Box::new(DataType::String)));
+    let changed_cols = if names.is_empty() {
+        no_names()
+    } else {
+        when(still_modified)
+            .then(concat_list(names)?.list().drop_nulls())
+            .otherwise(no_names())
+    }
+    .alias(DIFF_CHANGED_COLS_COL);
+
+    let joined_df = joined_df
+        .lazy()
+        .with_columns(masks)
+        .with_columns([status, changed_cols])
+        .drop(mask_cols)
+        .collect()?;
+    Ok(joined_df)
+}
+
+/// True in the rows where the left and right columns differ under `opts`, two nulls are equal
+fn changed_mask(
+    (left, left_dtype): (&str, &DataType),
+    (right, right_dtype): (&str, &DataType),
+    opts: &TabularDiffOpts,
+) -> Expr {
+    let both_null = col(left).is_null().and(col(right).is_null());
+
+    if let Some(tolerance) = opts.numeric_tolerance {
+        if left_dtype.is_numeric() && right_dtype.is_numeric() {
+            let diff =
+                (col(left).cast(DataType::Float64) - col(right).cast(DataType::Float64)).abs();
+            // The difference is null when either side is, which is only a change if one side is
+            return diff.gt(lit(tolerance)).fill_null(both_null.not());
+        }
+    }
+
+    if left_dtype != right_dtype {
+        return both_null.not();
+    }
+
+    if (opts.ignore_case || opts.ignore_whitespace) && left_dtype == &DataType::String {
+        return normalize_str_expr(col(left), opts)
+            .neq_missing(normalize_str_expr(col(right), opts));
+    }
+
+    col(left).neq_missing(col(right))
+}
+
+/// Same normalization as `TabularDiffOpts::normalize_str`, as an expression
+fn normalize_str_expr(expr: Expr, opts: &TabularDiffOpts) -> Expr {
+    let expr = if opts.ignore_whitespace {
+        expr.str()
+            .replace_all(lit(r"\s+"), lit(" "), false)
+            .str()
+            .strip_chars(lit(Null {}))
+    } else {
+        expr
+    };
+    if opts.ignore_case {
+        expr.str().to_lowercase()
+    } else {
+        expr
+    }
+}
+
+fn calculate_col_counts(
+    joined_df: &DataFrame,
+    targets: &[&str],
+) -> Result<Vec<ColumnChangeCount>, OxenError> {
+    let counts = ColumnChangeCount::from_diff_df(joined_df)?;
+    // Report every target, in the order they were requested
+    Ok(targets
+        .iter()
+        .map(|target| ColumnChangeCount {
+            name: target.to_string(),
+            num_modified: counts
+                .iter()
+                .find(|count| count.name == *target)
+                .map(|count| count.num_modified)
+                .unwrap_or(0),
+        })
+        .collect())
+}
+
 fn calculate_compare_mods(joined_df: &DataFrame) -> Result<AddRemoveModifyCounts, OxenError> {
     // TODO: for reasons which are unclear to me it is ridiculously unclear how
     // to use the polars DSL to get the added, removed, and modified rows as a scalary without
@@ -394,6 +542,7 @@ fn calculate_compare_mods(joined_df: &DataFrame) -> Result<AddRemoveModifyCounts
         added: added_rows,
         removed: removed_rows,
         modified: modified_rows,
+        renamed: 0,
     })
 }
 
@@ -495,3 +644,112 @@ fn prepare_response_fields(
         display: res_display,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use polars::df;
+    use polars::prelude::DataFrame;
+
+    use crate::api;
+    use crate::constants::{DIFF_CHANGED_COLS_COL, DIFF_STATUS_COL};
+    use crate::error::OxenError;
+    use crate::model::diff::{DiffResult, TabularDiff};
+    use crate::opts::TabularDiffOpts;
+
+    fn diff(
+        df_1: &DataFrame,
+        df_2: &DataFrame,
+        targets: &[&str],
+        opts: &TabularDiffOpts,
+    ) -> Result<TabularDiff, OxenError> {
+        let keys = vec!["id".to_string()];
+        let targets = targets.iter().map(|t| t.to_string()).collect();
+        let result = api::local::diff::diff_dfs(df_1, df_2, keys, targets, vec![], opts)?;
+        let DiffResult::Tabular(diff) = result else {
+            panic!("expected tabular result");
+        };
+        Ok(diff)
+    }
+
+    fn changed_cols(diff: &TabularDiff) -> Result<Vec<Vec<String>>, OxenError> {
+        let mut changed = vec![];
+        for names in diff.contents.column(DIFF_CHANGED_COLS_COL)?.list()? {
+            let names = names.unwrap();
+            changed.push(
+                names
+                    .str()?
+                    .into_iter()
+                    .map(|name| name.unwrap().to_string())
+                    .collect(),
+            );
+        }
+        Ok(changed)
+    }
+
+    #[test]
+    fn test_join_diff_mixed_numeric_dtypes() -> Result<(), OxenError> {
+        let df_1 = df!("id" => &[1i64, 2, 3], "score" => &[1i64, 2, 3])?;
+        let df_2 = df!("id" => &[1i64, 2, 3], "score" => &[1.0f64, 2.5, 3.0])?;
+        let opts = TabularDiffOpts {
+            numeric_tolerance: Some(0.1),
+            ..TabularDiffOpts::default()
+        };
+
+        // Every hash differs, but only the row outside the tolerance is modified
+        let diff = diff(&df_1, &df_2, &["score"], &opts)?;
+        assert_eq!(diff.summary.modifications.row_counts.modified, 1);
+        assert_eq!(changed_cols(&diff)?, vec![vec!["score"]]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_join_diff_null_on_one_side() -> Result<(), OxenError> {
+        let df_1 = df!("id" => &[1i64, 2, 3], "score" => &[None, Some(2.0f64), None])?;
+        let df_2 = df!("id" => &[1i64, 2, 3], "score" => &[Some(1.0f64), None, None])?;
+        let opts = TabularDiffOpts {
+            numeric_tolerance: Some(0.5),
+            ..TabularDiffOpts::default()
+        };
+
+        let diff = diff(&df_1, &df_2, &["score"], &opts)?;
+        assert_eq!(diff.summary.modifications.row_counts.modified, 2);
+        assert_eq!(diff.summary.modifications.col_counts[0].num_modified, 2);
+        assert_eq!(changed_cols(&diff)?, vec![vec!["score"], vec!["score"]]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_join_diff_tolerance_boundary() -> Result<(), OxenError> {
+        let df_1 = df!("id" => &[1i64, 2], "score" => &[1.0f64, 1.0])?;
+        let df_2 = df!("id" => &[1i64, 2], "score" => &[1.5f64, 1.75])?;
+        let opts = TabularDiffOpts {
+            numeric_tolerance: Some(0.5),
+            ..TabularDiffOpts::default()
+        };
+
+        // A difference of exactly the tolerance is still equal
+        let diff = diff(&df_1, &df_2, &["score"], &opts)?;
+        assert_eq!(diff.summary.modifications.row_counts.modified, 1);
+        assert_eq!(diff.contents.column("id")?.i64()?.get(0), Some(2));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_join_diff_column_names_with_commas() -> Result<(), OxenError> {
+        let df_1 = df!("id" => &[1i64, 2], "a,b" => &[1i64, 2], "c" => &[1i64, 2])?;
+        let df_2 = df!("id" => &[1i64, 2], "a,b" => &[1i64, 5], "c" => &[1i64, 6])?;
+
+        let diff = diff(&df_1, &df_2, &["a,b", "c"], &TabularDiffOpts::default())?;
+        assert_eq!(changed_cols(&diff)?, vec![vec!["a,b", "c"]]);
+        let counts = &diff.summary.modifications.col_counts;
+        assert_eq!(counts[0].name, "a,b");
+        assert_eq!(counts[0].num_modified, 1);
+        assert_eq!(counts[1].name, "c");
+        assert_eq!(counts[1].num_modified, 1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,263 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::api::local::diff::utf8_diff;
+use crate::error::OxenError;
+use crate::model::diff::generic_diff::GenericDiff;
+use crate::model::diff::json_diff::{JsonChange, JsonDiff};
+use crate::model::diff::text_diff::DEFAULT_CONTEXT_LINES;
+use crate::model::diff::{AddRemoveModifyCounts, ChangeType, DiffResult};
+use crate::model::{CommitEntry, LocalRepository};
+use crate::util;
+
+/// Structural diff of two JSON documents. Object keys are compared regardless of their order,
+/// arrays are compared index by index. If either file is not valid json the files get a line
+/// diff instead.
+pub fn diff(
+    version_file_1: impl AsRef<Path>,
+    version_file_2: impl AsRef<Path>,
+) -> Result<DiffResult, OxenError> {
+    diff_with_context(version_file_1, version_file_2, DEFAULT_CONTEXT_LINES)
+}
+
+/// Same as `diff`, keeping `context` unchanged lines around each change of a line diff
+pub fn diff_with_context(
+    version_file_1: impl AsRef<Path>,
+    version_file_2: impl AsRef<Path>,
+    context: usize,
+) -> Result<DiffResult, OxenError> {
+    let old = read_contents(version_file_1)?;
+    let new = read_contents(version_file_2)?;
+    match parse_both(old.as_deref(), new.as_deref()) {
+        Some((old, new)) => Ok(DiffResult::Json(diff_values(old.as_ref(), new.as_ref()))),
+        None => Ok(DiffResult::Text(utf8_diff::diff_str_with_context(
+            old.as_deref().unwrap_or_default(),
+            new.as_deref().unwrap_or_default(),
+            context,
+        ))),
+    }
+}
+
+/// Diffs the versions of two commit entries, either of which can be missing
+pub fn diff_commit_entries(
+    repo: &LocalRepository,
+    base_entry: &Option<CommitEntry>,
+    head_entry: &Option<CommitEntry>,
+) -> Result<GenericDiff, OxenError> {
+    let old = match base_entry {
+        Some(entry) => read_contents(util::fs::version_path(repo, entry))?,
+        None => None,
+    };
+    let new = match head_entry {
+        Some(entry) => read_contents(util::fs::version_path(repo, entry))?,
+        None => None,
+    };
+    match parse_both(old.as_deref(), new.as_deref()) {
+        Some((old, new)) => Ok(GenericDiff::JsonDiff(diff_values(
+            old.as_ref(),
+            new.as_ref(),
+        ))),
+        None => Ok(GenericDiff::TextDiff(utf8_diff::diff_str(
+            old.as_deref().unwrap_or_default(),
+            new.as_deref().unwrap_or_default(),
+        ))),
+    }
+}
+
+/// Diffs two optional documents, a missing side shows up as adding or removing the root
+pub fn diff_values(old: Option<&Value>, new: Option<&Value>) -> JsonDiff {
+    let mut changes = vec![];
+    match (old, new) {
+        (Some(old), Some(new)) => r_diff(String::new(), old, new, &mut changes),
+        (None, Some(new)) => changes.push(added(String::new(), new)),
+        (Some(old), None) => changes.push(removed(String::new(), old)),
+        (None, None) => {}
+    }
+
+    let count = |t: ChangeType| changes.iter().filter(|c| c.modification == t).count();
+    JsonDiff {
+        counts: AddRemoveModifyCounts {
+            added: count(ChangeType::Added),
+            removed: count(ChangeType::Removed),
+            modified: count(ChangeType::Modified),
+            renamed: 0,
+        },
+        changes,
+    }
+}
+
+fn read_contents(path: impl AsRef<Path>) -> Result<Option<String>, OxenError> {
+    let path = path.as_ref();
+    if !path.exists() {
+        return Ok(None);
+    }
+    Ok(Some(util::fs::read_from_path(path)?))
+}
+
+/// Parses both sides of a diff, returns None if either of them is not valid json
+fn parse_both(old: Option<&str>, new: Option<&str>) -> Option<(Option<Value>, Option<Value>)> {
+    let parse = |contents: Option<&str>| match contents {
+        Some(contents) => match serde_json::from_str(contents) {
+            Ok(value) => Some(Some(value)),
+            Err(err) => {
+                log::debug!("Could not parse json, falling back to a text diff: {err}");
+                None
+            }
+        },
+        None => Some(None),
+    };
+    Some((parse(old)?, parse(new)?))
+}
+
+fn r_diff(path: String, old: &Value, new: &Value, changes: &mut Vec<JsonChange>) {
+    match (old, new) {
+        (Value::Object(old_map), Value::Object(new_map)) => {
+            // Sort the keys so the output does not depend on the key order in the files
+            let keys: BTreeSet<&String> = old_map.keys().chain(new_map.keys()).collect();
+            for key in keys {
+                let child = format!("{}/{}", path, escape_pointer(key));
+                match (old_map.get(key), new_map.get(key)) {
+                    (Some(o), Some(n)) => r_diff(child, o, n, changes),
+                    (None, Some(n)) => changes.push(added(child, n)),
+                    (Some(o), None) => changes.push(removed(child, o)),
+                    (None, None) => {}
+                }
+            }
+        }
+        (Value::Array(old_arr), Value::Array(new_arr)) => {
+            for i in 0..old_arr.len().max(new_arr.len()) {
+                let child = format!("{path}/{i}");
+                match (old_arr.get(i), new_arr.get(i)) {
+                    (Some(o), Some(n)) => r_diff(child, o, n, changes),
+                    (None, Some(n)) => changes.push(added(child, n)),
+                    (Some(o), None) => changes.push(removed(child, o)),
+                    (None, None) => {}
+                }
+            }
+        }
+        _ => {
+            if old != new {
+                changes.push(JsonChange {
+                    path,
+                    modification: ChangeType::Modified,
+                    old: Some(old.clone()),
+                    new: Some(new.clone()),
+                });
+            }
+        }
+    }
+}
+
+fn added(path: String, value: &Value) -> JsonChange {
+    JsonChange {
+        path,
+        modification: ChangeType::Added,
+        old: None,
+        new: Some(value.clone()),
+    }
+}
+
+fn removed(path: String, value: &Value) -> JsonChange {
+    JsonChange {
+        path,
+        modification: ChangeType::Removed,
+        old: Some(value.clone()),
+        new: None,
+    }
+}
+
+/// Escapes a key for use in a JSON pointer, `~` becomes `~0` and `/` becomes `~1`
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::api::local::diff::json_diff;
+    use crate::error::OxenError;
+    use crate::model::diff::{ChangeType, DiffResult};
+    use crate::test;
+    use crate::util;
+
+    #[test]
+    fn test_json_diff_ignores_key_order() {
+        let old = json!({"b": 1, "a": {"x": [1, 2]}});
+        let new = json!({"a": {"x": [1, 2]}, "b": 1});
+
+        let diff = json_diff::diff_values(Some(&old), Some(&new));
+        assert!(!diff.has_changes());
+    }
+
+    #[test]
+    fn test_json_diff_nested_changes() {
+        let old = json!({
+            "name": "cats",
+            "labels": ["cat", "dog"],
+            "meta": {"a/b": 1, "size": 10}
+        });
+        let new = json!({
+            "name": "cats",
+            "labels": ["cat", "wolf", "fish"],
+            "meta": {"size": 12},
+            "license": "MIT"
+        });
+
+        let diff = json_diff::diff_values(Some(&old), Some(&new));
+        let paths: Vec<(&str, ChangeType)> = diff
+            .changes
+            .iter()
+            .map(|c| (c.path.as_str(), c.modification))
+            .collect();
+
+        assert_eq!(
+            paths,
+            vec![
+                ("/labels/1", ChangeType::Modified),
+                ("/labels/2", ChangeType::Added),
+                ("/license", ChangeType::Added),
+                ("/meta/a~1b", ChangeType::Removed),
+                ("/meta/size", ChangeType::Modified),
+            ]
+        );
+        assert_eq!(diff.counts.added, 2);
+        assert_eq!(diff.counts.removed, 1);
+        assert_eq!(diff.counts.modified, 2);
+        assert_eq!(diff.changes[4].old, Some(json!(10)));
+        assert_eq!(diff.changes[4].new, Some(json!(12)));
+    }
+
+    #[test]
+    fn test_json_diff_falls_back_to_text_for_malformed_json() -> Result<(), OxenError> {
+        test::run_empty_dir_test(|dir| {
+            let old_file = dir.join("old.json");
+            let new_file = dir.join("new.json");
+            util::fs::write_to_path(&old_file, "{\"name\": \"cats\"}\n")?;
+            util::fs::write_to_path(&new_file, "{\"name\": \"cats\",\n")?;
+
+            match json_diff::diff(&old_file, &new_file)? {
+                DiffResult::Text(diff) => {
+                    let changes: Vec<(&str, ChangeType)> = diff
+                        .lines
+                        .iter()
+                        .filter(|l| l.modification != ChangeType::Unchanged)
+                        .map(|l| (l.text.as_str(), l.modification))
+                        .collect();
+                    assert_eq!(
+                        changes,
+                        vec![
+                            ("{\"name\": \"cats\"}", ChangeType::Removed),
+                            ("{\"name\": \"cats\",", ChangeType::Added),
+                        ]
+                    );
+                }
+                _ => panic!("Expected a text diff"),
+            }
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,853 @@
+//! # Patch bundles
+//!
+//! Create portable `.oxenpatch` bundles from a diff and apply them to a working tree.
+//! A bundle is a gzipped tarball holding a `patch.json` manifest and the contents of any
+//! file that could not be expressed as a text or row level diff under `blobs/`.
+//!
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Cursor, Read};
+use std::path::{Component, Path, PathBuf};
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use polars::prelude::*;
+use tar::Archive;
+
+use crate::constants::{DIFF_STATUS_COL, OXEN_HIDDEN_DIR, OXEN_VERSION};
+use crate::core::df::tabular;
+use crate::error::OxenError;
+use crate::model::diff::patch::{
+    OxenPatch, PatchApplyResult, PatchChange, PatchConflict, PatchEntry, PatchRow, PatchRowStatus,
+};
+use crate::model::diff::text_diff::DEFAULT_CONTEXT_LINES;
+use crate::model::diff::{DiffResult, TabularDiff};
+use crate::model::LocalRepository;
+use crate::opts::{DFOpts, TabularDiffOpts};
+use crate::util;
+use crate::view::JsonDataFrameView;
+
+use super::utf8_diff;
+
+const PATCH_MANIFEST: &str = "patch.json";
+const PATCH_BLOBS_DIR: &str = "blobs";
+
+type Row = serde_json::Map<String, serde_json::Value>;
+
+/// A file to write to a patch bundle. `old` is None for a file that was added and `new` is
+/// None for a file that was removed.
+#[derive(Debug, Clone)]
+pub struct PatchFile {
+    /// Where the file lives in the working tree
+    pub path: PathBuf,
+    pub old: Option<PathBuf>,
+    pub new: Option<PathBuf>,
+}
+
+/// Diffs `file_1` against `file_2` and writes the changes needed to turn the first into the
+/// second to a patch bundle at `output`. `path` is where the file lives in the working tree.
+pub fn create(
+    path: impl AsRef<Path>,
+    file_1: impl AsRef<Path>,
+    file_2: impl AsRef<Path>,
+    keys: Vec<String>,
+    output: impl AsRef<Path>,
+) -> Result<OxenPatch, OxenError> {
+    let file = PatchFile {
+        path: path.as_ref().to_path_buf(),
+        old: Some(file_1.as_ref().to_path_buf()),
+        new: Some(file_2.as_ref().to_path_buf()),
+    };
+    create_from_files(&[file], keys, output)
+}
+
+/// Writes the changes to all of `files` to a single patch bundle at `output`
+pub fn create_from_files(
+    files: &[PatchFile],
+    keys: Vec<String>,
+    output: impl AsRef<Path>,
+) -> Result<OxenPatch, OxenError> {
+    let mut patch = OxenPatch {
+        oxen_version: OXEN_VERSION.to_string(),
+        entries: vec![],
+    };
+    let mut blobs: HashMap<String, PathBuf> = HashMap::new();
+    for file in files.iter() {
+        let (entry, blob) = create_entry(file, keys.clone())?;
+        patch.entries.push(entry);
+        blobs.extend(blob);
+    }
+
+    write(&patch, &blobs, output)?;
+    Ok(patch)
+}
+
+/// Returns the patch entry and, for blob changes, the hash and location of the new contents
+pub fn create_entry(
+    file: &PatchFile,
+    keys: Vec<String>,
+) -> Result<(PatchEntry, Option<(String, PathBuf)>), OxenError> {
+    let path = file.path.as_path();
+    // A file that does not exist on one side was added or removed
+    let file_1 = file.old.as_deref().filter(|old| old.exists());
+    let Some(file_2) = file.new.as_deref().filter(|new| new.exists()) else {
+        let Some(file_1) = file_1 else {
+            return Err(OxenError::path_does_not_exist(path));
+        };
+        let entry = PatchEntry {
+            path: path.to_path_buf(),
+            change: PatchChange::Removed {
+                old_hash: util::hasher::hash_file_contents(file_1)?,
+            },
+        };
+        return Ok((entry, None));
+    };
+    let Some(file_1) = file_1 else {
+        return blob_entry(path, None, file_2);
+    };
+
+    if super::is_files_tabular(file_1, file_2) {
+        let df_1 = tabular::read_df(file_1, DFOpts::empty())?;
+        let df_2 = tabular::read_df(file_2, DFOpts::empty())?;
+
+        // Rows can only be matched up when the columns did not change, otherwise ship the whole file
+        if df_1.schema() == df_2.schema() {
+            if let DiffResult::Tabular(diff) = super::diff_dfs(
+                &df_1,
+                &df_2,
+                keys,
+                vec![],
+                vec![],
+                &TabularDiffOpts::default(),
+            )? {
+                let entry = PatchEntry {
+                    path: path.to_path_buf(),
+                    change: tabular_change(diff)?,
+                };
+                return Ok((entry, None));
+            }
+        }
+    } else if super::is_files_utf8(file_1, file_2) {
+        let diff = utf8_diff::diff(file_1, file_2)?;
+        let name = path.to_string_lossy();
+        let entry = PatchEntry {
+            path: path.to_path_buf(),
+            change: PatchChange::Text {
+                unified: diff.to_unified(
+                    &format!("a/{name}"),
+                    &format!("b/{name}"),
+                    DEFAULT_CONTEXT_LINES,
+                ),
+            },
+        };
+        return Ok((entry, None));
+    }
+
+    blob_entry(path, Some(file_1), file_2)
+}
+
+fn blob_entry(
+    path: &Path,
+    file_1: Option<&Path>,
+    file_2: &Path,
+) -> Result<(PatchEntry, Option<(String, PathBuf)>), OxenError> {
+    let old_hash = match file_1 {
+        Some(file_1) => Some(util::hasher::hash_file_contents(file_1)?),
+        None => None,
+    };
+    let hash = util::hasher::hash_file_contents(file_2)?;
+    let entry = PatchEntry {
+        path: path.to_path_buf(),
+        change: PatchChange::Blob {
+            old_hash,
+            hash: hash.clone(),
+        },
+    };
+    Ok((entry, Some((hash, file_2.to_path_buf()))))
+}
+
+fn tabular_change(mut diff: TabularDiff) -> Result<PatchChange, OxenError> {
+    let keys = diff.parameters.keys.clone();
+    let targets = diff.parameters.targets.clone();
+
+    let mut rows = vec![];
+    for record in json_records(&mut diff.contents)? {
+        let status = match record.get(DIFF_STATUS_COL).and_then(|s| s.as_str()) {
+            Some("added") => PatchRowStatus::Added,
+            Some("removed") => PatchRowStatus::Removed,
+            Some("modified") => PatchRowStatus::Modified,
+            _ => continue,
+        };
+
+        let mut row = PatchRow {
+            status,
+            keys: Row::new(),
+            old: Row::new(),
+            new: Row::new(),
+        };
+        for key in keys.iter() {
+            let value = record.get(key).cloned().unwrap_or_default();
+            row.keys.insert(key.clone(), value);
+        }
+        for target in targets.iter() {
+            if row.status != PatchRowStatus::Added {
+                let value = record.get(&format!("{target}.left")).cloned();
+                row.old.insert(target.clone(), value.unwrap_or_default());
+            }
+            if row.status != PatchRowStatus::Removed {
+                let value = record.get(&format!("{target}.right")).cloned();
+                row.new.insert(target.clone(), value.unwrap_or_default());
+            }
+        }
+        rows.push(row);
+    }
+
+    Ok(PatchChange::Tabular { keys, rows })
+}
+
+pub fn write(
+    patch: &OxenPatch,
+    blobs: &HashMap<String, PathBuf>,
+    output: impl AsRef<Path>,
+) -> Result<(), OxenError> {
+    let enc = GzEncoder::new(Vec::new(), Compression::default());
+    let mut tar = tar::Builder::new(enc);
+
+    let manifest = serde_json::to_vec_pretty(patch)?;
+    let mut header = tar::Header::new_gnu();
+    header.set_size(manifest.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    tar.append_data(&mut header, PATCH_MANIFEST, manifest.as_slice())?;
+
+    for (hash, path) in blobs.iter() {
+        tar.append_path_with_name(path, Path::new(PATCH_BLOBS_DIR).join(hash))?;
+    }
+    tar.finish()?;
+
+    let buffer: Vec<u8> = tar.into_inner()?.finish()?;
+    std::fs::write(output, buffer)?;
+    Ok(())
+}
+
+/// Reads a patch bundle, returning the manifest and the blob contents keyed by hash
+pub fn read(path: impl AsRef<Path>) -> Result<(OxenPatch, HashMap<String, Vec<u8>>), OxenError> {
+    let path = path.as_ref();
+    if !path.exists() {
+        return Err(OxenError::path_does_not_exist(path));
+    }
+
+    let mut archive = Archive::new(GzDecoder::new(File::open(path)?));
+    let mut patch: Option<OxenPatch> = None;
+    let mut blobs: HashMap<String, Vec<u8>> = HashMap::new();
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let entry_path = entry.path()?.to_path_buf();
+        let mut data = vec![];
+        entry.read_to_end(&mut data)?;
+
+        if entry_path == Path::new(PATCH_MANIFEST) {
+            patch = Some(serde_json::from_slice(&data)?);
+        } else if let Ok(hash) = entry_path.strip_prefix(PATCH_BLOBS_DIR) {
+            blobs.insert(hash.to_string_lossy().to_string(), data);
+        }
+    }
+
+    let patch = patch.ok_or_else(|| {
+        OxenError::basic_str(format!("Invalid patch {path:?}, missing {PATCH_MANIFEST}"))
+    })?;
+    Ok((patch, blobs))
+}
+
+/// Applies a patch bundle to the working tree of `repo`. Files with conflicts are left
+/// untouched and reported in the result, the rest of the entries are still applied.
+pub fn apply(
+    repo: &LocalRepository,
+    patch_path: impl AsRef<Path>,
+) -> Result<PatchApplyResult, OxenError> {
+    let (patch, blobs) = read(patch_path)?;
+
+    // Check every path before touching the working tree, bundles can come from anywhere
+    let targets = patch
+        .entries
+        .iter()
+        .map(|entry| target_path(repo, &entry.path))
+        .collect::<Result<Vec<PathBuf>, OxenError>>()?;
+
+    let mut result = PatchApplyResult::default();
+    for (entry, target) in patch.entries.iter().zip(targets.iter()) {
+        let conflict = match &entry.change {
+            PatchChange::Text { unified } => apply_text(target, unified)?,
+            PatchChange::Tabular { keys, rows } => apply_tabular(target, keys, rows)?,
+            PatchChange::Blob { old_hash, hash } => {
+                apply_blob(target, old_hash.as_deref(), hash, &blobs)?
+            }
+            PatchChange::Removed { old_hash } => apply_removed(target, old_hash)?,
+        };
+
+        match conflict {
+            Some(message) => {
+                log::debug!("patch::apply conflict on {:?}: {}", entry.path, message);
+                result.conflicts.push(PatchConflict {
+                    path: entry.path.clone(),
+                    message,
+                });
+            }
+            None => result.applied.push(entry.path.clone()),
+        }
+    }
+
+    Ok(result)
+}
+
+/// Where `path` from a patch lives in the working tree. Only plain relative paths are allowed,
+/// so a patch cannot write outside of the repository or into the .oxen dir.
+fn target_path(repo: &LocalRepository, path: &Path) -> Result<PathBuf, OxenError> {
+    let invalid = || OxenError::basic_str(format!("Invalid patch, cannot apply to path {path:?}"));
+    let mut components = path.components();
+    match components.next() {
+        Some(Component::Normal(first)) if !first.eq_ignore_ascii_case(OXEN_HIDDEN_DIR) => {}
+        _ => return Err(invalid()),
+    }
+    if !components.all(|component| matches!(component, Component::Normal(_))) {
+        return Err(invalid());
+    }
+    Ok(repo.path.join(path))
+}
+
+struct UnifiedHunk {
+    header: String,
+    old_start: usize,
+    old_count: usize,
+    lines: Vec<(char, String)>,
+}
+
+struct UnifiedPatch {
+    hunks: Vec<UnifiedHunk>,
+    old_missing_newline: bool,
+    new_missing_newline: bool,
+}
+
+fn parse_range(range: &str) -> Result<(usize, usize), OxenError> {
+    match range.split_once(',') {
+        Some((start, count)) => Ok((start.parse()?, count.parse()?)),
+        None => Ok((range.parse()?, 1)),
+    }
+}
+
+fn parse_unified(unified: &str) -> Result<UnifiedPatch, OxenError> {
+    let mut patch = UnifiedPatch {
+        hunks: vec![],
+        old_missing_newline: false,
+        new_missing_newline: false,
+    };
+
+    let (mut old_remaining, mut new_remaining) = (0, 0);
+    for line in unified.split('\n') {
+        if line.starts_with('\\') {
+            match patch.hunks.last().and_then(|h| h.lines.last()) {
+                Some(('-', _)) => patch.old_missing_newline = true,
+                Some(('+', _)) => patch.new_missing_newline = true,
+                Some(_) => {
+                    patch.old_missing_newline = true;
+                    patch.new_missing_newline = true;
+                }
+                None => {}
+            }
+            continue;
+        }
+
+        if old_remaining == 0 && new_remaining == 0 {
+            // Outside of a hunk, skip the file headers until the next @@ line
+            let Some(ranges) = line
+                .strip_prefix("@@ -")
+                .and_then(|l| l.split_once(" @@"))
+                .map(|(ranges, _)| ranges)
+            else {
+                continue;
+            };
+            let Some((old_range, new_range)) = ranges.split_once(" +") else {
+                return Err(OxenError::basic_str(format!("Invalid hunk header: {line}")));
+            };
+            let (old_start, old_count) = parse_range(old_range)?;
+            let (_, new_count) = parse_range(new_range)?;
+            old_remaining = old_count;
+            new_remaining = new_count;
+            patch.hunks.push(UnifiedHunk {
+                header: line.to_string(),
+                old_start,
+                old_count,
+                lines: vec![],
+            });
+            continue;
+        }
+
+        let mut chars = line.chars();
+        let op = chars.next().unwrap_or(' ');
+        match op {
+            '-' => old_remaining = old_remaining.saturating_sub(1),
+            '+' => new_remaining = new_remaining.saturating_sub(1),
+            _ => {
+                old_remaining = old_remaining.saturating_sub(1);
+                new_remaining = new_remaining.saturating_sub(1);
+            }
+        }
+        if let Some(hunk) = patch.hunks.last_mut() {
+            hunk.lines.push((op, chars.as_str().to_string()));
+        }
+    }
+
+    Ok(patch)
+}
+
+/// Finds `needle` in `lines` at or after `min`, trying `expected` first and then
+/// searching outwards so hunks still apply to a file that shifted a bit
+fn find_lines(lines: &[String], needle: &[&str], expected: usize, min: usize) -> Option<usize> {
+    let fits = |at: usize| {
+        at >= min
+            && at + needle.len() <= lines.len()
+            && lines[at..at + needle.len()]
+                .iter()
+                .zip(needle.iter())
+                .all(|(a, b)| a == b)
+    };
+
+    for delta in 0..=lines.len() {
+        if delta <= expected && fits(expected - delta) {
+            return Some(expected - delta);
+        }
+        if fits(expected + delta) {
+            return Some(expected + delta);
+        }
+    }
+    None
+}
+
+fn apply_text(target: &Path, unified: &str) -> Result<Option<String>, OxenError> {
+    if !target.exists() {
+        return Ok(Some(String::from("file does not exist")));
+    }
+
+    let content = util::fs::read_from_path(target)?;
+    let patch = parse_unified(unified)?;
+
+    let lines: Vec<String> = if content.is_empty() {
+        vec![]
+    } else {
+        let body = content.strip_suffix('\n').unwrap_or(&content);
+        body.split('\n').map(String::from).collect()
+    };
+
+    let mut result: Vec<String> = vec![];
+    let mut pos = 0;
+    for hunk in patch.hunks.iter() {
+        let old: Vec<&str> = hunk
+            .lines
+            .iter()
+            .filter(|(op, _)| *op != '+')
+            .map(|(_, l)| l.as_str())
+            .collect();
+        let new = hunk.lines.iter().filter(|(op, _)| *op != '-');
+
+        // An empty old side points at the line before the insertion
+        let expected = if hunk.old_count == 0 {
+            hunk.old_start
+        } else {
+            hunk.old_start.saturating_sub(1)
+        };
+        let Some(at) = find_lines(&lines, &old, expected, pos) else {
+            return Ok(Some(format!("hunk {} does not apply", hunk.header)));
+        };
+
+        result.extend_from_slice(&lines[pos..at]);
+        result.extend(new.map(|(_, l)| l.clone()));
+        pos = at + old.len();
+    }
+    result.extend_from_slice(&lines[pos..]);
+
+    let missing_newline = if patch.new_missing_newline {
+        true
+    } else if patch.old_missing_newline {
+        false
+    } else {
+        !content.is_empty() && !content.ends_with('\n')
+    };
+
+    let mut output = result.join("\n");
+    if !missing_newline && !result.is_empty() {
+        output.push('\n');
+    }
+    util::fs::write_to_path(target, output)?;
+    Ok(None)
+}
+
+fn json_records(df: &mut DataFrame) -> Result<Vec<Row>, OxenError> {
+    match JsonDataFrameView::json_from_df(df) {
+        serde_json::Value::Array(values) => Ok(values
+            .into_iter()
+            .filter_map(|v| v.as_object().cloned())
+            .collect()),
+        _ => Ok(vec![]),
+    }
+}
+
+fn row_key(keys: &[String], row: &Row) -> String {
+    let values: Vec<&serde_json::Value> = keys
+        .iter()
+        .map(|k| row.get(k).unwrap_or(&serde_json::Value::Null))
+        .collect();
+    serde_json::to_string(&values).unwrap_or_default()
+}
+
+fn row_matches(row: &Row, values: &Row) -> bool {
+    values.iter().all(|(k, v)| row.get(k) == Some(v))
+}
+
+fn apply_tabular(
+    target: &Path,
+    keys: &[String],
+    rows: &[PatchRow],
+) -> Result<Option<String>, OxenError> {
+    if !target.exists() {
+        return Ok(Some(String::from("file does not exist")));
+    }
+
+    let df = tabular::read_df(target, DFOpts::empty())?;
+    let schema = df.schema();
+    let mut records: Vec<Option<Row>> = json_records(&mut df.clone())?
+        .into_iter()
+        .map(Some)
+        .collect();
+
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for (i, record) in records.iter().enumerate() {
+        if let Some(record) = record {
+            index.insert(row_key(keys, record), i);
+        }
+    }
+
+    let mut conflicts: Vec<String> = vec![];
+    for row in rows.iter() {
+        let key = row_key(keys, &row.keys);
+        let current = index.get(&key).and_then(|i| records[*i].as_ref());
+        match (&row.status, current) {
+            (PatchRowStatus::Added, None) => {
+                let mut record = row.keys.clone();
+                record.extend(row.new.clone());
+                index.insert(key, records.len());
+                records.push(Some(record));
+            }
+            (PatchRowStatus::Added, Some(current)) => {
+                if !row_matches(current, &row.new) {
+                    conflicts.push(format!("added row {key} already exists"));
+                }
+            }
+            (PatchRowStatus::Removed, None) => {}
+            (PatchRowStatus::Removed, Some(current)) => {
+                if row_matches(current, &row.old) {
+                    records[index[&key]] = None;
+                } else {
+                    conflicts.push(format!("removed row {key} was modified"));
+                }
+            }
+            (PatchRowStatus::Modified, None) => {
+                conflicts.push(format!("modified row {key} not found"));
+            }
+            (PatchRowStatus::Modified, Some(current)) => {
+                if row_matches(current, &row.new) {
+                    continue;
+                }
+                if !row_matches(current, &row.old) {
+                    conflicts.push(format!("modified row {key} was changed"));
+                    continue;
+                }
+                if let Some(record) = records[index[&key]].as_mut() {
+                    record.extend(row.new.clone());
+                }
+            }
+        }
+    }
+
+    if !conflicts.is_empty() {
+        return Ok(Some(conflicts.join(", ")));
+    }
+
+    let records: Vec<serde_json::Value> = records
+        .into_iter()
+        .flatten()
+        .map(serde_json::Value::Object)
+        .collect();
+    let mut df = if records.is_empty() {
+        df.clear()
+    } else {
+        let data = serde_json::Value::Array(records).to_string();
+        let df = JsonReader::new(Cursor::new(data.as_bytes()))
+            .with_schema(Arc::new(schema.clone()))
+            .finish()?;
+        df.select(schema.iter_names().map(|name| name.to_string()))?
+    };
+    // Keep the delimiter and compression the file already has
+    tabular::overwrite_df(&mut df, target, &DFOpts::empty())?;
+    Ok(None)
+}
+
+fn apply_blob(
+    target: &Path,
+    old_hash: Option<&str>,
+    hash: &str,
+    blobs: &HashMap<String, Vec<u8>>,
+) -> Result<Option<String>, OxenError> {
+    let Some(data) = blobs.get(hash) else {
+        return Err(OxenError::basic_str(format!(
+            "Invalid patch, missing contents for {target:?}"
+        )));
+    };
+
+    if target.exists() {
+        let current = util::hasher::hash_file_contents(target)?;
+        if current == hash {
+            return Ok(None);
+        }
+        if old_hash != Some(current.as_str()) {
+            return Ok(Some(String::from("file was modified")));
+        }
+    } else if old_hash.is_some() {
+        return Ok(Some(String::from("file does not exist")));
+    }
+
+    if let Some(parent) = target.parent() {
+        util::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(target, data)?;
+    Ok(None)
+}
+
+fn apply_removed(target: &Path, old_hash: &str) -> Result<Option<String>, OxenError> {
+    if !target.exists() {
+        return Ok(None);
+    }
+    if util::hasher::hash_file_contents(target)? != old_hash {
+        return Ok(Some(String::from("file was modified")));
+    }
+    util::fs::remove_file(target)?;
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::fs::File;
+    use std::io::{Read, Write};
+    use std::path::PathBuf;
+
+    use flate2::read::GzDecoder;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+
+    use crate::api;
+    use crate::api::local::diff::patch::PatchFile;
+    use crate::constants::OXEN_VERSION;
+    use crate::core::df::tabular;
+    use crate::error::OxenError;
+    use crate::model::diff::patch::{OxenPatch, PatchChange, PatchEntry};
+    use crate::opts::DFOpts;
+    use crate::test;
+    use crate::util;
+
+    #[test]
+    fn test_patch_apply_text_with_shifted_lines() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            let original: String = (1..=20).map(|i| format!("line {i}\n")).collect();
+            let changed = original.replace("line 10\n", "line ten\n");
+
+            let file_1 = repo.path.join("file1.txt");
+            let file_2 = repo.path.join("file2.txt");
+            util::fs::write_to_path(&file_1, &original)?;
+            util::fs::write_to_path(&file_2, &changed)?;
+
+            let patch_path = repo.path.join("fix.oxenpatch");
+            api::local::diff::patch::create("notes.txt", &file_1, &file_2, vec![], &patch_path)?;
+
+            // The receiving side has an extra line at the top
+            let notes = repo.path.join("notes.txt");
+            util::fs::write_to_path(&notes, format!("header\n{original}"))?;
+
+            let result = api::local::diff::patch::apply(&repo, &patch_path)?;
+            assert!(!result.has_conflicts());
+            assert_eq!(
+                util::fs::read_from_path(&notes)?,
+                format!("header\n{changed}")
+            );
+
+            // Applying a second time conflicts since the hunk is gone
+            let result = api::local::diff::patch::apply(&repo, &patch_path)?;
+            assert!(result.has_conflicts());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_patch_apply_tabular_rows() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            let file_1 = repo.path.join("old.csv");
+            let file_2 = repo.path.join("new.csv");
+            util::fs::write_to_path(&file_1, "id,label\n1,cat\n2,dog\n3,bird\n")?;
+            util::fs::write_to_path(&file_2, "id,label\n1,cat\n2,wolf\n4,fish\n")?;
+
+            let patch_path = repo.path.join("fix.oxenpatch");
+            let keys = vec![String::from("id")];
+            api::local::diff::patch::create("data.csv", &file_1, &file_2, keys, &patch_path)?;
+
+            // The receiving side has an extra row the patch does not know about
+            let data = repo.path.join("data.csv");
+            util::fs::write_to_path(&data, "id,label\n1,cat\n2,dog\n3,bird\n5,cow\n")?;
+
+            let result = api::local::diff::patch::apply(&repo, &patch_path)?;
+            assert!(!result.has_conflicts());
+
+            let df = tabular::read_df(&data, DFOpts::empty())?;
+            assert_eq!(df.height(), 4);
+            let labels: Vec<Option<&str>> = df.column("label")?.str()?.into_iter().collect();
+            assert_eq!(
+                labels,
+                vec![Some("cat"), Some("wolf"), Some("cow"), Some("fish")]
+            );
+
+            // A row that changed on both sides is reported as a conflict
+            util::fs::write_to_path(&data, "id,label\n1,cat\n2,coyote\n3,bird\n")?;
+            let result = api::local::diff::patch::apply(&repo, &patch_path)?;
+            assert_eq!(result.conflicts.len(), 1);
+            assert_eq!(
+                util::fs::read_from_path(&data)?,
+                "id,label\n1,cat\n2,coyote\n3,bird\n"
+            );
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_patch_apply_tabular_keeps_format() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            let file_1 = repo.path.join("old.csv");
+            let file_2 = repo.path.join("new.csv");
+            util::fs::write_to_path(&file_1, "id,label\n1,cat\n2,dog\n3,bird\n")?;
+            util::fs::write_to_path(&file_2, "id,label\n1,cat\n2,wolf\n4,fish\n")?;
+
+            let patch_path = repo.path.join("fix.oxenpatch");
+            let keys = vec![String::from("id")];
+            api::local::diff::patch::create("data.csv.gz", &file_1, &file_2, keys, &patch_path)?;
+
+            // The receiving side is a gzipped csv separated by semicolons
+            let data = repo.path.join("data.csv.gz");
+            let mut encoder = GzEncoder::new(File::create(&data)?, Compression::default());
+            encoder.write_all(b"id;label\n1;cat\n2;dog\n3;bird\n")?;
+            encoder.finish()?;
+
+            let result = api::local::diff::patch::apply(&repo, &patch_path)?;
+            assert!(!result.has_conflicts());
+
+            let mut contents = String::new();
+            GzDecoder::new(File::open(&data)?).read_to_string(&mut contents)?;
+            assert_eq!(contents, "id;label\n1;cat\n2;wolf\n4;fish\n");
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_patch_apply_added_and_removed_files() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            let old_dir = repo.path.join("old");
+            let new_dir = repo.path.join("new");
+            util::fs::write_to_path(old_dir.join("gone.csv"), "id,label\n1,cat\n")?;
+            util::fs::write_to_path(new_dir.join("added.csv"), "id,label\n2,dog\n")?;
+            util::fs::write_to_path(old_dir.join("notes.txt"), "hello\n")?;
+            util::fs::write_to_path(new_dir.join("notes.txt"), "hello world\n")?;
+
+            let files = vec![
+                PatchFile {
+                    path: PathBuf::from("data").join("gone.csv"),
+                    old: Some(old_dir.join("gone.csv")),
+                    new: None,
+                },
+                PatchFile {
+                    path: PathBuf::from("data").join("added.csv"),
+                    old: None,
+                    new: Some(new_dir.join("added.csv")),
+                },
+                PatchFile {
+                    path: PathBuf::from("notes.txt"),
+                    old: Some(old_dir.join("notes.txt")),
+                    new: Some(new_dir.join("notes.txt")),
+                },
+            ];
+            let patch_path = repo.path.join("changes.oxenpatch");
+            let patch = api::local::diff::patch::create_from_files(&files, vec![], &patch_path)?;
+            assert_eq!(patch.entries.len(), 3);
+
+            let gone = repo.path.join("data").join("gone.csv");
+            util::fs::write_to_path(&gone, "id,label\n1,cat\n")?;
+            util::fs::write_to_path(repo.path.join("notes.txt"), "hello\n")?;
+
+            let result = api::local::diff::patch::apply(&repo, &patch_path)?;
+            assert!(!result.has_conflicts());
+            assert_eq!(result.applied.len(), 3);
+            assert!(!gone.exists());
+            assert_eq!(
+                util::fs::read_from_path(repo.path.join("data").join("added.csv"))?,
+                "id,label\n2,dog\n"
+            );
+            assert_eq!(
+                util::fs::read_from_path(repo.path.join("notes.txt"))?,
+                "hello world\n"
+            );
+
+            // A removed file that changed since is left alone
+            util::fs::write_to_path(&gone, "id,label\n1,wolf\n")?;
+            let result = api::local::diff::patch::apply(&repo, &patch_path)?;
+            assert!(result
+                .conflicts
+                .iter()
+                .any(|c| c.path.ends_with("gone.csv")));
+            assert!(gone.exists());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_patch_apply_rejects_paths_outside_repo() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            let bad_paths = vec![
+                PathBuf::from("..").join("outside.txt"),
+                PathBuf::from("data")
+                    .join("..")
+                    .join("..")
+                    .join("outside.txt"),
+                repo.path.join("absolute.txt"),
+                PathBuf::from(".oxen").join("HEAD"),
+            ];
+            for (i, path) in bad_paths.into_iter().enumerate() {
+                let patch = OxenPatch {
+                    oxen_version: OXEN_VERSION.to_string(),
+                    entries: vec![PatchEntry {
+                        path,
+                        change: PatchChange::Removed {
+                            old_hash: String::from("abc"),
+                        },
+                    }],
+                };
+                let patch_path = repo.path.join(format!("bad_{i}.oxenpatch"));
+                api::local::diff::patch::write(&patch, &HashMap::new(), &patch_path)?;
+                assert!(api::local::diff::patch::apply(&repo, &patch_path).is_err());
+            }
+            assert!(repo.path.join(".oxen").join("HEAD").exists());
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,246 @@
+//! Compare the whole working directory or staged index against a commit.
+//!
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::api;
+use crate::core::index::{oxenignore, CommitEntryReader};
+use crate::error::OxenError;
+use crate::model::diff::diff_entry_status::DiffEntryStatus;
+use crate::model::{
+    Commit, CommitEntry, DiffEntry, LocalRepository, StagedData, StagedEntryStatus,
+};
+use crate::util;
+
+/// Lists the files that differ between `base_commit` and the working directory, or the staged
+/// index if `staged` is set. `status` is the status of the repository against HEAD, only the
+/// files it reports have to be hashed. If `paths` is not empty, only files within them are listed.
+pub fn diff_entries(
+    repo: &LocalRepository,
+    base_commit: &Commit,
+    status: &StagedData,
+    staged: bool,
+    paths: &[PathBuf],
+) -> Result<Vec<DiffEntry>, OxenError> {
+    let base_entries = list_commit_entries(repo, base_commit)?;
+    let head_hashes = if staged {
+        staged_hashes(repo, status)?
+    } else {
+        working_hashes(repo, status)?
+    };
+
+    let mut diff_entries: Vec<DiffEntry> = vec![];
+    for (path, hash) in head_hashes.iter() {
+        if !is_in_paths(path, paths) {
+            continue;
+        }
+
+        let status = match base_entries.get(path) {
+            None => DiffEntryStatus::Added,
+            Some(base_entry) if &base_entry.hash != hash => DiffEntryStatus::Modified,
+            Some(_) => continue,
+        };
+        diff_entries.push(DiffEntry::from_working_file(
+            repo,
+            base_entries.get(path).cloned(),
+            base_commit,
+            Some(path.to_owned()),
+            status,
+        )?);
+    }
+
+    for (path, base_entry) in base_entries.iter() {
+        if is_in_paths(path, paths) && !head_hashes.contains_key(path) {
+            diff_entries.push(DiffEntry::from_working_file(
+                repo,
+                Some(base_entry.to_owned()),
+                base_commit,
+                None,
+                DiffEntryStatus::Removed,
+            )?);
+        }
+    }
+
+    diff_entries.sort_by(|a, b| a.filename.cmp(&b.filename));
+    Ok(diff_entries)
+}
+
+fn is_in_paths(path: &Path, paths: &[PathBuf]) -> bool {
+    paths.is_empty() || paths.iter().any(|p| path.starts_with(p))
+}
+
+fn list_commit_entries(
+    repo: &LocalRepository,
+    commit: &Commit,
+) -> Result<HashMap<PathBuf, CommitEntry>, OxenError> {
+    let reader = CommitEntryReader::new(repo, commit)?;
+    Ok(reader
+        .list_entries()?
+        .into_iter()
+        .map(|entry| (entry.path.to_owned(), entry))
+        .collect())
+}
+
+fn head_hashes(repo: &LocalRepository) -> Result<HashMap<PathBuf, String>, OxenError> {
+    let head_commit = api::local::commits::head_commit(repo)?;
+    Ok(list_commit_entries(repo, &head_commit)?
+        .into_iter()
+        .map(|(path, entry)| (path, entry.hash))
+        .collect())
+}
+
+// The files in HEAD with the staged changes applied
+fn staged_hashes(
+    repo: &LocalRepository,
+    status: &StagedData,
+) -> Result<HashMap<PathBuf, String>, OxenError> {
+    let mut hashes = head_hashes(repo)?;
+    for (path, entry) in status.staged_files.iter() {
+        match entry.status {
+            StagedEntryStatus::Removed => {
+                hashes.remove(path);
+            }
+            StagedEntryStatus::Added | StagedEntryStatus::Modified => {
+                hashes.insert(path.to_owned(), entry.hash.to_owned());
+            }
+        }
+    }
+    Ok(hashes)
+}
+
+// The files in HEAD, re-hashing the ones that status reports as changed on disk
+fn working_hashes(
+    repo: &LocalRepository,
+    status: &StagedData,
+) -> Result<HashMap<PathBuf, String>, OxenError> {
+    let mut hashes = head_hashes(repo)?;
+    for path in status.removed_files.iter() {
+        hashes.remove(path);
+    }
+
+    let mut changed: Vec<PathBuf> = status.modified_files.clone();
+    changed.extend(status.untracked_files.iter().cloned());
+    changed.extend(status.staged_files.keys().cloned());
+
+    // Status only summarizes untracked dirs, so list the files within them
+    let ignore = oxenignore::create(repo);
+    for (dir, _) in status.untracked_dirs.iter() {
+        for file in util::fs::rlist_files_in_dir(&repo.path.join(dir)) {
+            if util::fs::is_in_oxen_hidden_dir(&file) {
+                continue;
+            }
+            if let Some(ignore) = &ignore {
+                if ignore.matched(&file, false).is_ignore() {
+                    continue;
+                }
+            }
+            changed.push(util::fs::path_relative_to_dir(&file, &repo.path)?);
+        }
+    }
+
+    for path in changed {
+        let full_path = repo.path.join(&path);
+        if full_path.is_file() {
+            hashes.insert(path, util::hasher::hash_file_contents(&full_path)?);
+        } else {
+            hashes.remove(&path);
+        }
+    }
+    Ok(hashes)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::api;
+    use crate::command;
+    use crate::error::OxenError;
+    use crate::model::diff::diff_entry_status::DiffEntryStatus;
+    use crate::model::DiffEntry;
+    use crate::test;
+    use crate::util;
+
+    fn find<'a>(entries: &'a [DiffEntry], filename: &str) -> Option<&'a DiffEntry> {
+        entries.iter().find(|entry| entry.filename == filename)
+    }
+
+    #[test]
+    fn test_tree_diff_working_dir_against_revision() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            util::fs::write_to_path(repo.path.join("hello.txt"), "Hello")?;
+            util::fs::write_to_path(repo.path.join("data").join("a.txt"), "a")?;
+            command::add(&repo, &repo.path)?;
+            let first_commit = command::commit(&repo, "Adding hello and a")?;
+
+            util::fs::write_to_path(repo.path.join("world.txt"), "World")?;
+            command::add(&repo, repo.path.join("world.txt"))?;
+            command::commit(&repo, "Adding world")?;
+
+            // Modify, remove, and add files without staging them
+            util::fs::write_to_path(repo.path.join("hello.txt"), "Hello, again")?;
+            util::fs::remove_file(repo.path.join("data").join("a.txt"))?;
+            util::fs::write_to_path(repo.path.join("new").join("b.txt"), "b")?;
+
+            let status = command::status(&repo)?;
+            let entries = api::local::diff::tree_diff::diff_entries(
+                &repo,
+                &first_commit,
+                &status,
+                false,
+                &[],
+            )?;
+            assert_eq!(entries.len(), 4);
+            let status_of = |name| find(&entries, name).unwrap().status.to_owned();
+            assert_eq!(
+                status_of("hello.txt"),
+                DiffEntryStatus::Modified.to_string()
+            );
+            assert_eq!(
+                status_of("data/a.txt"),
+                DiffEntryStatus::Removed.to_string()
+            );
+            assert_eq!(status_of("world.txt"), DiffEntryStatus::Added.to_string());
+            assert_eq!(status_of("new/b.txt"), DiffEntryStatus::Added.to_string());
+
+            // Only the changes within the paths
+            let paths = vec![PathBuf::from("new")];
+            let entries = api::local::diff::tree_diff::diff_entries(
+                &repo,
+                &first_commit,
+                &status,
+                false,
+                &paths,
+            )?;
+            assert_eq!(entries.len(), 1);
+            assert_eq!(entries[0].filename, "new/b.txt");
+            assert!(entries[0].head_entry.is_some());
+            assert!(entries[0].base_entry.is_none());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_tree_diff_staged_against_head() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            util::fs::write_to_path(repo.path.join("hello.txt"), "Hello")?;
+            command::add(&repo, &repo.path)?;
+            let head_commit = command::commit(&repo, "Adding hello")?;
+
+            util::fs::write_to_path(repo.path.join("staged.txt"), "staged")?;
+            util::fs::write_to_path(repo.path.join("unstaged.txt"), "unstaged")?;
+            command::add(&repo, repo.path.join("staged.txt"))?;
+
+            let status = command::status(&repo)?;
+            let entries =
+                api::local::diff::tree_diff::diff_entries(&repo, &head_commit, &status, true, &[])?;
+            assert_eq!(entries.len(), 1);
+            assert_eq!(entries[0].filename, "staged.txt");
+            assert_eq!(entries[0].status, DiffEntryStatus::Added.to_string());
+
+            Ok(())
+        })
+    }
+}
@@ -1,51 +1,91 @@
 use crate::error::OxenError;
 use crate::model::diff::change_type::ChangeType;
+use crate::model::diff::text_diff::build_hunks;
 use crate::model::diff::text_diff::LineDiff;
 use crate::model::diff::text_diff::TextDiff;
+use crate::model::diff::text_diff::DEFAULT_CONTEXT_LINES;
 use crate::util;
 
-use difference::{Changeset, Difference};
+use similar::{Algorithm, ChangeTag};
 use std::path::Path;
 
+/// Diffs two text files line by line, grouping the changes into hunks with
+/// `DEFAULT_CONTEXT_LINES` lines of context.
 pub fn diff(
     version_file_1: impl AsRef<Path>,
     version_file_2: impl AsRef<Path>,
+) -> Result<TextDiff, OxenError> {
+    diff_with_context(version_file_1, version_file_2, DEFAULT_CONTEXT_LINES)
+}
+
+/// Diffs two text files line by line, keeping `context` unchanged lines around each change
+pub fn diff_with_context(
+    version_file_1: impl AsRef<Path>,
+    version_file_2: impl AsRef<Path>,
+    context: usize,
 ) -> Result<TextDiff, OxenError> {
     let version_file_1 = version_file_1.as_ref();
     let version_file_2 = version_file_2.as_ref();
     let original_data = util::fs::read_from_path(version_file_1)?;
     let compare_data = util::fs::read_from_path(version_file_2)?;
-    let Changeset { diffs, .. } = Changeset::new(&original_data, &compare_data, "\n");
-
-    let mut result = TextDiff { lines: vec![] };
-    for diff in diffs {
-        match diff {
-            Difference::Same(ref x) => {
-                for split in x.split('\n') {
-                    result.lines.push(LineDiff {
-                        modification: ChangeType::Unchanged,
-                        text: split.to_string(),
-                    });
-                }
-            }
-            Difference::Add(ref x) => {
-                for split in x.split('\n') {
-                    result.lines.push(LineDiff {
-                        modification: ChangeType::Added,
-                        text: split.to_string(),
-                    });
-                }
-            }
-            Difference::Rem(ref x) => {
-                for split in x.split('\n') {
-                    result.lines.push(LineDiff {
-                        modification: ChangeType::Removed,
-                        text: split.to_string(),
-                    });
-                }
-            }
-        }
+    Ok(diff_str_with_context(
+        &original_data,
+        &compare_data,
+        context,
+    ))
+}
+
+pub fn diff_str(original_data: &str, compare_data: &str) -> TextDiff {
+    diff_str_with_context(original_data, compare_data, DEFAULT_CONTEXT_LINES)
+}
+
+/// Only the lines in the hunks are returned, with their line numbers in each file
+pub fn diff_str_with_context(original_data: &str, compare_data: &str, context: usize) -> TextDiff {
+    let changes = similar::TextDiff::configure()
+        .algorithm(Algorithm::Myers)
+        .diff_lines(original_data, compare_data);
+
+    let mut lines: Vec<LineDiff> = vec![];
+    for change in changes.iter_all_changes() {
+        let modification = match change.tag() {
+            ChangeTag::Equal => ChangeType::Unchanged,
+            ChangeTag::Insert => ChangeType::Added,
+            ChangeTag::Delete => ChangeType::Removed,
+        };
+        let text = change.value();
+        let text = text.strip_suffix('\n').unwrap_or(text);
+        lines.push(LineDiff {
+            modification,
+            text: text.to_string(),
+            old_line: change.old_index().map(|i| i + 1),
+            new_line: change.new_index().map(|i| i + 1),
+        });
     }
+    let mut hunks = build_hunks(&lines, context);
 
-    Ok(result)
+    // Drop the unchanged lines between the hunks, and point the hunks at the lines that are left
+    let mut hunk_lines: Vec<LineDiff> = vec![];
+    let mut remaining = lines.into_iter().enumerate();
+    for hunk in hunks.iter_mut() {
+        let start = hunk_lines.len();
+        hunk_lines.extend(
+            remaining
+                .by_ref()
+                .skip_while(|(i, _)| *i < hunk.start)
+                .take(hunk.end - hunk.start)
+                .map(|(_, line)| line),
+        );
+        hunk.start = start;
+        hunk.end = hunk_lines.len();
+    }
+
+    TextDiff {
+        lines: hunk_lines,
+        hunks,
+        context,
+        old_num_lines: original_data.lines().count(),
+        new_num_lines: compare_data.lines().count(),
+        old_missing_newline: !original_data.is_empty() && !original_data.ends_with('\n'),
+        new_missing_newline: !compare_data.is_empty() && !compare_data.ends_with('\n'),
+    }
 }
@@ -0,0 +1,409 @@
+//! # Merge Requests
+//!
+//! Open, review, and merge requests to bring one branch into another.
+//! Merge requests are stored per repository in `.oxen/merge_requests`.
+//!
+
+use std::path::{Path, PathBuf};
+
+use rocksdb::DB;
+use time::OffsetDateTime;
+
+use crate::api;
+use crate::constants::MERGE_REQUESTS_DIR;
+use crate::core::db::{self, str_json_db};
+use crate::core::index::{CommitReader, Merger};
+use crate::error::OxenError;
+use crate::model::merge_request::MergeRequestDiffSummary;
+use crate::model::{
+    Branch, LocalRepository, MergeRequest, MergeRequestComment, MergeRequestStatus,
+    MergeRequestUpdate, NewMergeRequest, NewMergeRequestComment,
+};
+use crate::util;
+
+fn db_path(repo: &LocalRepository) -> PathBuf {
+    util::fs::oxen_hidden_dir(&repo.path).join(Path::new(MERGE_REQUESTS_DIR))
+}
+
+fn open_db(repo: &LocalRepository) -> Result<DB, OxenError> {
+    let opts = db::opts::default();
+    Ok(DB::open(&opts, dunce::simplified(&db_path(repo)))?)
+}
+
+fn get_branch(repo: &LocalRepository, name: &str) -> Result<Branch, OxenError> {
+    api::local::branches::get_by_name(repo, name)?.ok_or(OxenError::local_branch_not_found(name))
+}
+
+fn next_id(db: &DB) -> Result<u64, OxenError> {
+    let max_id = str_json_db::list_keys(db)?
+        .iter()
+        .filter_map(|key| key.parse::<u64>().ok())
+        .max()
+        .unwrap_or(0);
+    Ok(max_id + 1)
+}
+
+fn write(db: &DB, merge_request: &MergeRequest) -> Result<(), OxenError> {
+    str_json_db::put(db, merge_request.id.to_string(), merge_request)
+}
+
+/// Open a new merge request from `source_branch` into `target_branch`
+pub fn create(
+    repo: &LocalRepository,
+    new_merge_request: &NewMergeRequest,
+) -> Result<MergeRequest, OxenError> {
+    if new_merge_request.title.trim().is_empty() {
+        return Err(OxenError::basic_str("Merge request must have a title"));
+    }
+
+    if new_merge_request.source_branch == new_merge_request.target_branch {
+        return Err(OxenError::basic_str(format!(
+            "Cannot open a merge request from '{}' into itself",
+            new_merge_request.source_branch
+        )));
+    }
+
+    // Make sure both branches exist before we persist anything
+    get_branch(repo, &new_merge_request.source_branch)?;
+    get_branch(repo, &new_merge_request.target_branch)?;
+
+    let db = open_db(repo)?;
+    let now = OffsetDateTime::now_utc();
+    let merge_request = MergeRequest {
+        id: next_id(&db)?,
+        title: new_merge_request.title.to_owned(),
+        description: new_merge_request.description.to_owned(),
+        source_branch: new_merge_request.source_branch.to_owned(),
+        target_branch: new_merge_request.target_branch.to_owned(),
+        author: new_merge_request.author.to_owned(),
+        status: MergeRequestStatus::Open,
+        comments: vec![],
+        merge_commit_id: None,
+        created_at: now,
+        updated_at: now,
+    };
+    write(&db, &merge_request)?;
+
+    Ok(merge_request)
+}
+
+/// List all the merge requests in the repository, ordered by id
+pub fn list(repo: &LocalRepository) -> Result<Vec<MergeRequest>, OxenError> {
+    let db = open_db(repo)?;
+    let mut merge_requests: Vec<MergeRequest> = str_json_db::list_vals(&db)?;
+    merge_requests.sort_by_key(|mr| mr.id);
+    Ok(merge_requests)
+}
+
+/// List the merge requests with a given status, ordered by id
+pub fn list_by_status(
+    repo: &LocalRepository,
+    status: &MergeRequestStatus,
+) -> Result<Vec<MergeRequest>, OxenError> {
+    Ok(list(repo)?
+        .into_iter()
+        .filter(|mr| &mr.status == status)
+        .collect())
+}
+
+pub fn get(repo: &LocalRepository, id: u64) -> Result<Option<MergeRequest>, OxenError> {
+    let db = open_db(repo)?;
+    str_json_db::get(&db, id.to_string())
+}
+
+fn get_or_not_found(db: &DB, id: u64) -> Result<MergeRequest, OxenError> {
+    str_json_db::get(db, id.to_string())?.ok_or(OxenError::resource_not_found(format!(
+        "Merge request #{id}"
+    )))
+}
+
+/// Update the title, description, target branch, or status of a merge request.
+/// Merged requests can no longer be modified.
+pub fn update(
+    repo: &LocalRepository,
+    id: u64,
+    update: &MergeRequestUpdate,
+) -> Result<MergeRequest, OxenError> {
+    let db = open_db(repo)?;
+    let mut merge_request = get_or_not_found(&db, id)?;
+
+    if merge_request.status == MergeRequestStatus::Merged {
+        return Err(OxenError::basic_str(format!(
+            "Merge request #{id} has already been merged"
+        )));
+    }
+
+    if let Some(title) = &update.title {
+        if title.trim().is_empty() {
+            return Err(OxenError::basic_str("Merge request must have a title"));
+        }
+        merge_request.title = title.to_owned();
+    }
+
+    if let Some(description) = &update.description {
+        merge_request.description = description.to_owned();
+    }
+
+    if let Some(target_branch) = &update.target_branch {
+        get_branch(repo, target_branch)?;
+        merge_request.target_branch = target_branch.to_owned();
+    }
+
+    if let Some(status) = &update.status {
+        // Merging has to go through `merge` so that the branches actually move
+        if status == &MergeRequestStatus::Merged {
+            return Err(OxenError::basic_str(format!(
+                "Merge request #{id} can only be marked as merged by merging it"
+            )));
+        }
+        merge_request.status = status.to_owned();
+    }
+
+    merge_request.updated_at = OffsetDateTime::now_utc();
+    write(&db, &merge_request)?;
+
+    Ok(merge_request)
+}
+
+/// Add a comment to the discussion on a merge request
+pub fn add_comment(
+    repo: &LocalRepository,
+    id: u64,
+    new_comment: &NewMergeRequestComment,
+) -> Result<MergeRequest, OxenError> {
+    if new_comment.body.trim().is_empty() {
+        return Err(OxenError::basic_str("Comment must have a body"));
+    }
+
+    let db = open_db(repo)?;
+    let mut merge_request = get_or_not_found(&db, id)?;
+
+    let now = OffsetDateTime::now_utc();
+    let comment_id = merge_request
+        .comments
+        .iter()
+        .map(|c| c.id)
+        .max()
+        .unwrap_or(0)
+        + 1;
+    merge_request.comments.push(MergeRequestComment {
+        id: comment_id,
+        author: new_comment.author.to_owned(),
+        body: new_comment.body.to_owned(),
+        timestamp: now,
+    });
+    merge_request.updated_at = now;
+    write(&db, &merge_request)?;
+
+    Ok(merge_request)
+}
+
+/// Merge the source branch into the target branch using the `Merger`,
+/// and mark the merge request as merged if it succeeds without conflicts.
+pub fn merge(repo: &LocalRepository, id: u64) -> Result<MergeRequest, OxenError> {
+    let db = open_db(repo)?;
+    let mut merge_request = get_or_not_found(&db, id)?;
+
+    if merge_request.status != MergeRequestStatus::Open {
+        return Err(OxenError::basic_str(format!(
+            "Merge request #{id} is {} and cannot be merged",
+            merge_request.status
+        )));
+    }
+
+    let base = get_branch(repo, &merge_request.target_branch)?;
+    let head = get_branch(repo, &merge_request.source_branch)?;
+
+    // `merge_into_base` reports an up to date branch the same way as a conflict
+    if base.commit_id == head.commit_id {
+        return Err(OxenError::basic_str(format!(
+            "Nothing to merge, '{}' is already up to date with '{}'",
+            base.name, head.name
+        )));
+    }
+
+    let merger = Merger::new(repo)?;
+    let merge_commit = merger
+        .merge_into_base(&head, &base)?
+        .ok_or(OxenError::merge_conflict(format!(
+            "Merge request #{id} has conflicts between '{}' and '{}'",
+            head.name, base.name
+        )))?;
+    api::local::branches::update(repo, &base.name, &merge_commit.id)?;
+
+    merge_request.status = MergeRequestStatus::Merged;
+    merge_request.merge_commit_id = Some(merge_commit.id);
+    merge_request.updated_at = OffsetDateTime::now_utc();
+    write(&db, &merge_request)?;
+
+    Ok(merge_request)
+}
+
+/// Summarize the changes a merge request would bring into the target branch
+pub fn diff_summary(
+    repo: &LocalRepository,
+    merge_request: &MergeRequest,
+    page: usize,
+    page_size: usize,
+) -> Result<MergeRequestDiffSummary, OxenError> {
+    let base = get_branch(repo, &merge_request.target_branch)?;
+    let head = get_branch(repo, &merge_request.source_branch)?;
+
+    let commit_reader = CommitReader::new(repo)?;
+    let base_commit = commit_reader
+        .get_commit_by_id(&base.commit_id)?
+        .ok_or(OxenError::commit_id_does_not_exist(&base.commit_id))?;
+    let head_commit = commit_reader
+        .get_commit_by_id(&head.commit_id)?
+        .ok_or(OxenError::commit_id_does_not_exist(&head.commit_id))?;
+
+    let merger = Merger::new(repo)?;
+    let is_mergeable = !merger.has_conflicts(&base, &head)?;
+    let commits = merger.list_commits_between_branches(&commit_reader, &base, &head)?;
+
+    let entries_diff = api::local::diff::list_diff_entries(
+        repo,
+        &base_commit,
+        &head_commit,
+        PathBuf::from(""),
+        page,
+        page_size,
+    )?;
+
+    Ok(MergeRequestDiffSummary {
+        base_commit,
+        head_commit,
+        is_mergeable,
+        commits,
+        counts: entries_diff.counts,
+        entries: entries_diff.entries,
+        pagination: entries_diff.pagination,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api;
+    use crate::command;
+    use crate::constants::DEFAULT_BRANCH_NAME;
+    use crate::error::OxenError;
+    use crate::model::{
+        MergeRequestStatus, MergeRequestUpdate, NewMergeRequest, NewMergeRequestComment, User,
+    };
+    use crate::test;
+    use crate::util;
+
+    fn author() -> User {
+        User {
+            name: "Ox".to_string(),
+            email: "ox@oxen.ai".to_string(),
+        }
+    }
+
+    fn new_merge_request(source: &str) -> NewMergeRequest {
+        NewMergeRequest {
+            title: "Add more data".to_string(),
+            description: "Adds a new file".to_string(),
+            source_branch: source.to_string(),
+            target_branch: DEFAULT_BRANCH_NAME.to_string(),
+            author: author(),
+        }
+    }
+
+    #[test]
+    fn test_merge_request_create_list_get() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            api::local::branches::create_checkout(&repo, "add-data")?;
+
+            let first = api::local::merge_requests::create(&repo, &new_merge_request("add-data"))?;
+            let second = api::local::merge_requests::create(&repo, &new_merge_request("add-data"))?;
+            assert_eq!(first.id, 1);
+            assert_eq!(second.id, 2);
+            assert_eq!(first.status, MergeRequestStatus::Open);
+
+            let merge_requests = api::local::merge_requests::list(&repo)?;
+            assert_eq!(merge_requests.len(), 2);
+
+            let found = api::local::merge_requests::get(&repo, 2)?.unwrap();
+            assert_eq!(found.source_branch, "add-data");
+            assert_eq!(found.target_branch, DEFAULT_BRANCH_NAME);
+            assert!(api::local::merge_requests::get(&repo, 3)?.is_none());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_merge_request_create_missing_branch() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            let result =
+                api::local::merge_requests::create(&repo, &new_merge_request("does-not-exist"));
+            assert!(result.is_err());
+            assert!(api::local::merge_requests::list(&repo)?.is_empty());
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_merge_request_update_and_comment() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            api::local::branches::create_checkout(&repo, "add-data")?;
+            let mr = api::local::merge_requests::create(&repo, &new_merge_request("add-data"))?;
+
+            let update = MergeRequestUpdate {
+                title: Some("Updated title".to_string()),
+                status: Some(MergeRequestStatus::Closed),
+                ..MergeRequestUpdate::default()
+            };
+            let mr = api::local::merge_requests::update(&repo, mr.id, &update)?;
+            assert_eq!(mr.title, "Updated title");
+            assert_eq!(mr.description, "Adds a new file");
+            assert_eq!(mr.status, MergeRequestStatus::Closed);
+
+            let comment = NewMergeRequestComment {
+                author: author(),
+                body: "Looks good".to_string(),
+            };
+            api::local::merge_requests::add_comment(&repo, mr.id, &comment)?;
+            let mr = api::local::merge_requests::add_comment(&repo, mr.id, &comment)?;
+            assert_eq!(mr.comments.len(), 2);
+            assert_eq!(mr.comments[1].id, 2);
+
+            // Closed requests cannot be merged
+            assert!(api::local::merge_requests::merge(&repo, mr.id).is_err());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_merge_request_diff_and_merge() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            api::local::branches::create_checkout(&repo, "add-data")?;
+            let path = repo.path.join("new_file.txt");
+            util::fs::write_to_path(&path, "hello")?;
+            command::add(&repo, &path)?;
+            command::commit(&repo, "adding new file")?;
+
+            let mr = api::local::merge_requests::create(&repo, &new_merge_request("add-data"))?;
+
+            let summary = api::local::merge_requests::diff_summary(&repo, &mr, 1, 10)?;
+            assert!(summary.is_mergeable);
+            assert_eq!(summary.commits.len(), 1);
+            assert_eq!(summary.counts.added, 1);
+            assert_eq!(summary.counts.removed, 0);
+            assert_eq!(summary.counts.modified, 0);
+
+            let mr = api::local::merge_requests::merge(&repo, mr.id)?;
+            assert_eq!(mr.status, MergeRequestStatus::Merged);
+
+            let main = api::local::branches::get_by_name(&repo, DEFAULT_BRANCH_NAME)?.unwrap();
+            assert_eq!(Some(main.commit_id), mr.merge_commit_id);
+
+            // Cannot merge twice
+            assert!(api::local::merge_requests::merge(&repo, mr.id).is_err());
+
+            Ok(())
+        })
+    }
+}
@@ -164,6 +164,7 @@ pub fn get_cli(
         data_type,
         mime_type,
         extension,
+        profile: None,
     })
 }
 
@@ -389,6 +389,7 @@ mod tests {
                 author: String::from("Ox"),
                 email: String::from("ox@oxen.ai"),
                 root_hash: None,
+                metadata: None,
                 timestamp,
             };
             let repo_new = RepoNew::from_root_commit(namespace, name, root_commit);
@@ -553,6 +554,7 @@ mod tests {
                 email: String::from("ox@oxen.ai"),
                 timestamp,
                 root_hash: None,
+                metadata: None,
             };
             let repo_new = RepoNew::from_root_commit(old_namespace, name, root_commit);
             let _repo = api::local::repositories::create(sync_dir, repo_new)?;
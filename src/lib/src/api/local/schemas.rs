@@ -5,7 +5,7 @@ use crate::{api, util};
 
 use crate::core::index::SchemaReader;
 use crate::error::OxenError;
-use crate::model::{LocalRepository, Schema};
+use crate::model::{Commit, LocalRepository, Schema};
 
 pub fn list(
     repo: &LocalRepository,
@@ -52,6 +52,73 @@ pub fn get_by_path(
     schema_reader.get_schema_for_file(path)
 }
 
+/// Check a new schema for a file against its compatibility contract. The contract is the
+/// compatibility mode of the version of the file in `schema_reader`, so that dropping the contract
+/// does not also let the same change break it.
+pub fn check_contract(
+    path: &Path,
+    schema: &Schema,
+    schema_reader: &SchemaReader,
+) -> Result<(), OxenError> {
+    match schema_reader.get_schema_for_file(path)? {
+        Some(committed) => check_contract_against(path, &committed, schema),
+        None => Ok(()),
+    }
+}
+
+/// Check the schemas of a commit against the compatibility contracts of each of the commit's
+/// parents. Pushed commits were never staged in the remote repository, so this is how the
+/// server enforces contracts on push.
+pub fn check_contracts(repo: &LocalRepository, commit: &Commit) -> Result<(), OxenError> {
+    let schema_reader = SchemaReader::new(repo, &commit.id)?;
+    for parent_id in commit.parent_ids.iter() {
+        let parent_schemas = SchemaReader::new(repo, parent_id)?.list_schemas()?;
+        for (path, committed) in parent_schemas {
+            if committed.compatibility.is_none() {
+                continue;
+            }
+            if let Some(schema) = schema_reader.get_schema_for_file(&path)? {
+                check_contract_against(&path, &committed, &schema)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn check_contract_against(
+    path: &Path,
+    committed: &Schema,
+    schema: &Schema,
+) -> Result<(), OxenError> {
+    let Some(mode) = committed.compatibility else {
+        return Ok(());
+    };
+
+    let mut violations = committed.compatibility_violations(schema);
+    // The contract can only be removed by a version of the file with the same columns
+    let same_fields = committed.fields.len() == schema.fields.len()
+        && committed
+            .fields
+            .iter()
+            .zip(schema.fields.iter())
+            .all(|(old, new)| old.name == new.name && old.dtype == new.dtype);
+    if schema.compatibility.is_none() && !same_fields {
+        violations.push(String::from(
+            "the contract can only be removed without changing the columns",
+        ));
+    }
+
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(OxenError::schema_contract_violation(
+            path,
+            mode,
+            &violations,
+        ))
+    }
+}
+
 /// Get a schema for a specific revision
 pub fn get_by_path_from_ref(
     repo: &LocalRepository,
@@ -0,0 +1,141 @@
+//! # Usage - Storage used by the version files of a repository
+//!
+//! Usage is kept up to date incrementally as version files are pushed, and recomputed from disk by
+//! the `repo_size` cacher to correct any drift.
+
+use fs_extra::dir::get_size;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::api;
+use crate::constants::VERSIONS_DIR;
+use crate::error::OxenError;
+use crate::model::LocalRepository;
+use crate::util;
+
+pub const USAGE_FILENAME: &str = "usage.json";
+
+lazy_static! {
+    /// Serializes read-modify-writes of the usage files within the process
+    static ref USAGE_LOCK: Mutex<()> = Mutex::new(());
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+struct RepoUsage {
+    version_bytes: u64,
+}
+
+pub fn usage_path(repo: &LocalRepository) -> PathBuf {
+    util::fs::oxen_hidden_dir(&repo.path).join(USAGE_FILENAME)
+}
+
+/// Number of bytes taken up by the version files in the repository
+pub fn get(repo: &LocalRepository) -> Result<u64, OxenError> {
+    let _lock = USAGE_LOCK.lock().unwrap();
+    match read(repo)? {
+        Some(usage) => Ok(usage.version_bytes),
+        None => {
+            let version_bytes = compute(repo)?;
+            write(repo, &RepoUsage { version_bytes })?;
+            Ok(version_bytes)
+        }
+    }
+}
+
+/// Record that `num_bytes` of version files were added to the repository, returns the new total
+pub fn add(repo: &LocalRepository, num_bytes: u64) -> Result<u64, OxenError> {
+    let _lock = USAGE_LOCK.lock().unwrap();
+    let mut usage = match read(repo)? {
+        Some(usage) => usage,
+        None => {
+            // Nothing was recorded yet, the new files are already on disk
+            let version_bytes = compute(repo)?;
+            write(repo, &RepoUsage { version_bytes })?;
+            return Ok(version_bytes);
+        }
+    };
+    usage.version_bytes = usage.version_bytes.saturating_add(num_bytes);
+    write(repo, &usage)?;
+    Ok(usage.version_bytes)
+}
+
+/// Recompute the usage from the version files on disk and save it
+pub fn reset(repo: &LocalRepository) -> Result<u64, OxenError> {
+    let _lock = USAGE_LOCK.lock().unwrap();
+    let version_bytes = compute(repo)?;
+    write(repo, &RepoUsage { version_bytes })?;
+    Ok(version_bytes)
+}
+
+/// Sum of the usage of every repository in the namespace
+pub fn get_for_namespace(sync_dir: &Path, namespace: &str) -> Result<u64, OxenError> {
+    let namespace_path = sync_dir.join(namespace);
+    let mut total: u64 = 0;
+    for repo in api::local::repositories::list_repos_in_namespace(&namespace_path) {
+        total = total.saturating_add(get(&repo)?);
+    }
+    Ok(total)
+}
+
+fn compute(repo: &LocalRepository) -> Result<u64, OxenError> {
+    let versions_dir = util::fs::oxen_hidden_dir(&repo.path).join(VERSIONS_DIR);
+    if !versions_dir.exists() {
+        return Ok(0);
+    }
+    get_size(&versions_dir).map_err(|err| OxenError::basic_str(err.to_string()))
+}
+
+fn read(repo: &LocalRepository) -> Result<Option<RepoUsage>, OxenError> {
+    let path = usage_path(repo);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let contents = util::fs::read_from_path(&path)?;
+    match serde_json::from_str(&contents) {
+        Ok(usage) => Ok(Some(usage)),
+        Err(err) => {
+            log::warn!(
+                "Could not parse usage file {:?}, recomputing: {}",
+                path,
+                err
+            );
+            Ok(None)
+        }
+    }
+}
+
+fn write(repo: &LocalRepository, usage: &RepoUsage) -> Result<(), OxenError> {
+    util::fs::write_to_path(usage_path(repo), serde_json::to_string(usage)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api;
+    use crate::error::OxenError;
+    use crate::test;
+    use crate::util;
+
+    #[test]
+    fn test_usage_add_and_reset() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            let initial = api::local::usage::get(&repo)?;
+            assert!(initial > 0);
+
+            let total = api::local::usage::add(&repo, 100)?;
+            assert_eq!(total, initial + 100);
+            assert_eq!(api::local::usage::get(&repo)?, initial + 100);
+
+            // Recomputing from disk drops the bytes that were never written
+            assert_eq!(api::local::usage::reset(&repo)?, initial);
+
+            // Missing usage file is recomputed
+            util::fs::remove_file(api::local::usage::usage_path(&repo))?;
+            assert_eq!(api::local::usage::get(&repo)?, initial);
+
+            Ok(())
+        })
+    }
+}
@@ -9,6 +9,7 @@ pub mod df;
 pub mod diff;
 pub mod dir;
 pub mod entries;
+pub mod merge_requests;
 pub mod merger;
 pub mod metadata;
 pub mod repositories;
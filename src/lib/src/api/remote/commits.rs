@@ -4,12 +4,12 @@ use crate::constants::{
 };
 
 use crate::core::db::{self};
-use crate::core::index::pusher::UnsyncedCommitEntries;
+use crate::core::index::pusher::{self, UnsyncedCommitEntries};
 use crate::core::index::{CommitDBReader, CommitEntryWriter, CommitReader, CommitWriter, Merger};
 use crate::error::OxenError;
 use crate::model::commit::CommitWithBranchName;
 use crate::model::{Branch, Commit, LocalRepository, RemoteRepository};
-use crate::opts::PaginateOpts;
+use crate::opts::{LogOpts, PaginateOpts};
 use crate::util::fs::oxen_hidden_dir;
 use crate::util::hasher::hash_buffer;
 use crate::util::progress_bar::{oxify_bar, ProgressBarType};
@@ -110,6 +110,15 @@ pub async fn list_all(remote_repo: &RemoteRepository) -> Result<Vec<Commit>, Oxe
 pub async fn list_commit_history(
     remote_repo: &RemoteRepository,
     revision: &str,
+) -> Result<Vec<Commit>, OxenError> {
+    list_commit_history_with_opts(remote_repo, revision, &LogOpts::default()).await
+}
+
+/// List the history of the revision on the remote, keeping the commits that match the filters in `opts`
+pub async fn list_commit_history_with_opts(
+    remote_repo: &RemoteRepository,
+    revision: &str,
+    opts: &LogOpts,
 ) -> Result<Vec<Commit>, OxenError> {
     let mut all_commits: Vec<Commit> = Vec::new();
     let mut page_num = DEFAULT_PAGE_NUM;
@@ -123,7 +132,7 @@ pub async fn list_commit_history(
             page_num,
             page_size,
         };
-        match list_commit_history_paginated(remote_repo, revision, &page_opts).await {
+        match list_commit_history_paginated(remote_repo, revision, &page_opts, opts).await {
             Ok(paginated_commits) => {
                 if page_num == DEFAULT_PAGE_NUM {
                     let bar = oxify_bar(bar.clone(), ProgressBarType::Counter);
@@ -152,10 +161,14 @@ async fn list_commit_history_paginated(
     remote_repo: &RemoteRepository,
     revision: &str,
     page_opts: &PaginateOpts,
+    opts: &LogOpts,
 ) -> Result<PaginatedCommits, OxenError> {
     let page_num = page_opts.page_num;
     let page_size = page_opts.page_size;
-    let uri = format!("/commits/{revision}/history?page={page_num}&page_size={page_size}");
+    let mut uri = format!("/commits/{revision}/history?page={page_num}&page_size={page_size}");
+    if opts.is_filtered() {
+        uri = format!("{uri}&{}", opts.to_http_query_params());
+    }
     let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
 
     let client = client::new_for_url(&url)?;
@@ -368,6 +381,12 @@ pub async fn can_push(
     let reader = CommitReader::new(local_repo)?;
     let lca = merger.lowest_common_ancestor_from_commits(&reader, &remote_head, local_head)?;
 
+    // Send the size of the data we are about to push so the server can check its quota
+    // before anything is transferred
+    let mut commits_to_push = reader.history_from_base_to_head(&lca.id, &local_head.id)?;
+    commits_to_push.retain(|commit| commit.id != lca.id);
+    let push_size = pusher::compute_push_size(local_repo, &commits_to_push)?;
+
     // Create a temporary local tree representing the head commit
     let local_head_writer = CommitEntryWriter::new(local_repo, local_head)?;
     let tmp_tree_path = local_head_writer.save_temp_commit_tree()?;
@@ -408,8 +427,8 @@ pub async fn can_push(
     util::fs::remove_dir_all(&tmp_tree_path)?;
 
     let uri = format!(
-        "/commits/{}/can_push?remote_head={}&lca={}",
-        local_head.id, remote_head.id, lca.id
+        "/commits/{}/can_push?remote_head={}&lca={}&size={}",
+        local_head.id, remote_head.id, lca.id, push_size
     );
 
     let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
@@ -1169,8 +1188,10 @@ mod tests {
     use crate::error::OxenError;
 
     use crate::model::entry::commit_entry::Entry;
+    use crate::opts::LogOpts;
     use crate::test;
     use rocksdb::{DBWithThreadMode, MultiThreaded};
+    use std::path::PathBuf;
 
     #[tokio::test]
     async fn test_remote_commits_post_commits_to_server() -> Result<(), OxenError> {
@@ -1360,6 +1381,33 @@ mod tests {
         .await
     }
 
+    #[tokio::test]
+    async fn test_list_remote_commit_history_with_opts() -> Result<(), OxenError> {
+        test::run_training_data_fully_sync_remote(|local_repo, remote_repo| async move {
+            let opts = LogOpts {
+                paths: vec![PathBuf::from("annotations")],
+                max_count: Some(2),
+                ..LogOpts::default()
+            };
+            let local_commits =
+                api::local::commits::list_from_with_opts(&local_repo, DEFAULT_BRANCH_NAME, &opts)?;
+            assert!(!local_commits.is_empty());
+
+            let remote_commits = api::remote::commits::list_commit_history_with_opts(
+                &remote_repo,
+                DEFAULT_BRANCH_NAME,
+                &opts,
+            )
+            .await?;
+            let local_ids: Vec<&String> = local_commits.iter().map(|c| &c.id).collect();
+            let remote_ids: Vec<&String> = remote_commits.iter().map(|c| &c.id).collect();
+            assert_eq!(local_ids, remote_ids);
+
+            Ok(remote_repo)
+        })
+        .await
+    }
+
     #[tokio::test]
     async fn test_download_commits_db() -> Result<(), OxenError> {
         test::run_training_data_fully_sync_remote(|local_repo, remote_repo| async move {
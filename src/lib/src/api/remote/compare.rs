@@ -1,6 +1,7 @@
 use crate::api;
 use crate::error::OxenError;
 use crate::model::RemoteRepository;
+use crate::opts::TabularDiffOpts;
 use crate::view::compare::CompareTabularResponse;
 use crate::view::compare::{
     TabularCompareBody, TabularCompareFieldBody, TabularCompareResourceBody,
@@ -38,6 +39,7 @@ pub async fn create_compare(
         keys,
         compare,
         display,
+        options: TabularDiffOpts::default(),
     };
 
     let uri = "/compare/data_frame".to_string();
@@ -87,6 +89,7 @@ pub async fn update_compare(
         keys,
         compare,
         display,
+        options: TabularDiffOpts::default(),
     };
 
     let uri = format!("/compare/data_frame/{compare_id}");
@@ -169,6 +169,55 @@ mod tests {
         .await
     }
 
+    #[tokio::test]
+    async fn test_diff_entries_modify_json() -> Result<(), OxenError> {
+        test::run_empty_data_repo_test_no_commits_async(|mut repo| async move {
+            let og_branch = api::local::branches::current_branch(&repo)?.unwrap();
+
+            let path = PathBuf::from("config.json");
+            let repo_filepath = repo.path.join(&path);
+            util::fs::write_to_path(&repo_filepath, r#"{"name": "cats", "size": 10}"#)?;
+            command::add(&repo, &repo_filepath)?;
+            command::commit(&repo, "Adding config")?;
+
+            let remote = test::repo_remote_url_from(&repo.dirname());
+            command::config::set_remote(&mut repo, constants::DEFAULT_REMOTE_NAME, &remote)?;
+            let remote_repo = test::create_remote_repo(&repo).await?;
+            command::push(&repo).await?;
+
+            let branch_name = "feat/bigger-config";
+            command::create_checkout(&repo, branch_name)?;
+
+            // Reorder the keys and change one value
+            util::fs::write_to_path(
+                &repo_filepath,
+                r#"{"size": 12, "name": "cats", "labels": ["cat"]}"#,
+            )?;
+            command::add(&repo, &repo_filepath)?;
+            command::commit(&repo, "Updating config")?;
+            command::push_remote_branch(&repo, constants::DEFAULT_REMOTE_NAME, branch_name).await?;
+
+            let compare =
+                api::remote::diff::diff_entries(&remote_repo, &og_branch.name, branch_name, &path)
+                    .await?;
+            assert_eq!(compare.status, "modified");
+
+            match compare.diff.as_ref().unwrap() {
+                GenericDiff::JsonDiff(diff) => {
+                    assert_eq!(diff.counts.added, 1);
+                    assert_eq!(diff.counts.modified, 1);
+                    assert_eq!(diff.counts.removed, 0);
+                    assert_eq!(diff.changes[0].path, "/labels");
+                    assert_eq!(diff.changes[1].path, "/size");
+                }
+                _ => panic!("Wrong diff type"),
+            }
+
+            Ok(())
+        })
+        .await
+    }
+
     // Test diff modify image
     #[tokio::test]
     async fn test_diff_entries_modify_image() -> Result<(), OxenError> {
@@ -258,6 +307,16 @@ mod tests {
                 _ => panic!("Wrong summary type"),
             }
 
+            match compare.diff.as_ref().unwrap() {
+                GenericDiff::ImageDiff(diff) => {
+                    assert!(diff.dimensions_changed);
+                    assert!(!diff.color_space_changed);
+                    assert_eq!(diff.head.as_ref().unwrap().image.width, dims);
+                    assert!(diff.perceptual_similarity > 0.5);
+                }
+                _ => panic!("Wrong diff type"),
+            }
+
             Ok(())
         })
         .await
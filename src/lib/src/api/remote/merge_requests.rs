@@ -0,0 +1,241 @@
+//! # Remote Merge Requests
+//!
+//! Open, review, and merge requests on a remote repository.
+//!
+
+use crate::api;
+use crate::api::remote::client;
+use crate::error::OxenError;
+use crate::model::merge_request::MergeRequestDiffSummary;
+use crate::model::{
+    MergeRequest, MergeRequestUpdate, NewMergeRequest, NewMergeRequestComment, RemoteRepository,
+};
+use crate::view::{ListMergeRequestsResponse, MergeRequestDiffResponse, MergeRequestResponse};
+
+async fn parse_merge_request_response(
+    url: &str,
+    res: reqwest::Response,
+) -> Result<MergeRequest, OxenError> {
+    let body = client::parse_json_body(url, res).await?;
+    log::debug!("got body: {}", body);
+    let response: Result<MergeRequestResponse, serde_json::Error> = serde_json::from_str(&body);
+    match response {
+        Ok(val) => Ok(val.merge_request),
+        Err(err) => Err(OxenError::basic_str(format!(
+            "api::remote::merge_requests error parsing response from {url}\n\nErr {err:?} \n\n{body}"
+        ))),
+    }
+}
+
+/// Open a new merge request on the remote repository
+pub async fn create(
+    remote_repo: &RemoteRepository,
+    new_merge_request: &NewMergeRequest,
+) -> Result<MergeRequest, OxenError> {
+    let url = api::endpoint::url_from_repo(remote_repo, "/merge_requests")?;
+    log::debug!("api::remote::merge_requests::create url: {url}");
+
+    let params = serde_json::to_string(new_merge_request)?;
+    let client = client::new_for_url(&url)?;
+    match client.post(&url).body(params).send().await {
+        Ok(res) => parse_merge_request_response(&url, res).await,
+        Err(err) => {
+            let err =
+                format!("api::remote::merge_requests::create Request failed: {url}\nErr {err:?}");
+            Err(OxenError::basic_str(err))
+        }
+    }
+}
+
+/// List the merge requests on the remote repository, optionally filtered by status
+pub async fn list(
+    remote_repo: &RemoteRepository,
+    status: Option<&str>,
+) -> Result<Vec<MergeRequest>, OxenError> {
+    let uri = match status {
+        Some(status) => format!("/merge_requests?status={status}"),
+        None => "/merge_requests".to_string(),
+    };
+    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
+    log::debug!("api::remote::merge_requests::list url: {url}");
+
+    let client = client::new_for_url(&url)?;
+    match client.get(&url).send().await {
+        Ok(res) => {
+            let body = client::parse_json_body(&url, res).await?;
+            let response: Result<ListMergeRequestsResponse, serde_json::Error> =
+                serde_json::from_str(&body);
+            match response {
+                Ok(val) => Ok(val.merge_requests),
+                Err(err) => Err(OxenError::basic_str(format!(
+                    "api::remote::merge_requests::list error parsing response from {url}\n\nErr {err:?} \n\n{body}"
+                ))),
+            }
+        }
+        Err(err) => {
+            let err =
+                format!("api::remote::merge_requests::list Request failed: {url}\nErr {err:?}");
+            Err(OxenError::basic_str(err))
+        }
+    }
+}
+
+pub async fn get(
+    remote_repo: &RemoteRepository,
+    id: u64,
+) -> Result<Option<MergeRequest>, OxenError> {
+    let uri = format!("/merge_requests/{id}");
+    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
+
+    let client = client::new_for_url(&url)?;
+    match client.get(&url).send().await {
+        Ok(res) => {
+            if res.status() == reqwest::StatusCode::NOT_FOUND {
+                return Ok(None);
+            }
+            Ok(Some(parse_merge_request_response(&url, res).await?))
+        }
+        Err(err) => {
+            let err =
+                format!("api::remote::merge_requests::get Request failed: {url}\nErr {err:?}");
+            Err(OxenError::basic_str(err))
+        }
+    }
+}
+
+pub async fn update(
+    remote_repo: &RemoteRepository,
+    id: u64,
+    update: &MergeRequestUpdate,
+) -> Result<MergeRequest, OxenError> {
+    let uri = format!("/merge_requests/{id}");
+    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
+
+    let params = serde_json::to_string(update)?;
+    let client = client::new_for_url(&url)?;
+    match client.put(&url).body(params).send().await {
+        Ok(res) => parse_merge_request_response(&url, res).await,
+        Err(err) => {
+            let err =
+                format!("api::remote::merge_requests::update Request failed: {url}\nErr {err:?}");
+            Err(OxenError::basic_str(err))
+        }
+    }
+}
+
+pub async fn comment(
+    remote_repo: &RemoteRepository,
+    id: u64,
+    new_comment: &NewMergeRequestComment,
+) -> Result<MergeRequest, OxenError> {
+    let uri = format!("/merge_requests/{id}/comments");
+    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
+
+    let params = serde_json::to_string(new_comment)?;
+    let client = client::new_for_url(&url)?;
+    match client.post(&url).body(params).send().await {
+        Ok(res) => parse_merge_request_response(&url, res).await,
+        Err(err) => {
+            let err =
+                format!("api::remote::merge_requests::comment Request failed: {url}\nErr {err:?}");
+            Err(OxenError::basic_str(err))
+        }
+    }
+}
+
+/// Merge the source branch into the target branch of the merge request on the server
+pub async fn merge(remote_repo: &RemoteRepository, id: u64) -> Result<MergeRequest, OxenError> {
+    let uri = format!("/merge_requests/{id}/merge");
+    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
+
+    let client = client::new_for_url(&url)?;
+    match client.post(&url).send().await {
+        Ok(res) => parse_merge_request_response(&url, res).await,
+        Err(err) => {
+            let err =
+                format!("api::remote::merge_requests::merge Request failed: {url}\nErr {err:?}");
+            Err(OxenError::basic_str(err))
+        }
+    }
+}
+
+/// Get a summary of the changes the merge request would bring into the target branch
+pub async fn diff(
+    remote_repo: &RemoteRepository,
+    id: u64,
+    page: usize,
+    page_size: usize,
+) -> Result<MergeRequestDiffSummary, OxenError> {
+    let uri = format!("/merge_requests/{id}/diff?page={page}&page_size={page_size}");
+    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
+
+    let client = client::new_for_url(&url)?;
+    match client.get(&url).send().await {
+        Ok(res) => {
+            let body = client::parse_json_body(&url, res).await?;
+            let response: Result<MergeRequestDiffResponse, serde_json::Error> =
+                serde_json::from_str(&body);
+            match response {
+                Ok(val) => Ok(val.diff),
+                Err(err) => Err(OxenError::basic_str(format!(
+                    "api::remote::merge_requests::diff error parsing response from {url}\n\nErr {err:?} \n\n{body}"
+                ))),
+            }
+        }
+        Err(err) => {
+            let err =
+                format!("api::remote::merge_requests::diff Request failed: {url}\nErr {err:?}");
+            Err(OxenError::basic_str(err))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::constants::{DEFAULT_BRANCH_NAME, DEFAULT_REMOTE_NAME};
+    use crate::error::OxenError;
+    use crate::model::{MergeRequestStatus, NewMergeRequest, User};
+    use crate::test;
+    use crate::{api, command};
+
+    #[tokio::test]
+    async fn test_remote_merge_request_create_and_merge() -> Result<(), OxenError> {
+        test::run_training_data_fully_sync_remote(|local_repo, remote_repo| async move {
+            let head = "add-data";
+
+            command::create_checkout(&local_repo, head)?;
+            let path = local_repo.path.join("file_1.txt");
+            test::write_txt_file_to_path(&path, "hello")?;
+            command::add(&local_repo, &path)?;
+            command::commit(&local_repo, "adding file 1")?;
+            command::push_remote_branch(&local_repo, DEFAULT_REMOTE_NAME, head).await?;
+
+            let new_merge_request = NewMergeRequest {
+                title: "Add file 1".to_string(),
+                description: "".to_string(),
+                source_branch: head.to_string(),
+                target_branch: DEFAULT_BRANCH_NAME.to_string(),
+                author: User {
+                    name: "Ox".to_string(),
+                    email: "ox@oxen.ai".to_string(),
+                },
+            };
+            let mr = api::remote::merge_requests::create(&remote_repo, &new_merge_request).await?;
+            assert_eq!(mr.status, MergeRequestStatus::Open);
+
+            let merge_requests = api::remote::merge_requests::list(&remote_repo, None).await?;
+            assert_eq!(merge_requests.len(), 1);
+
+            let diff = api::remote::merge_requests::diff(&remote_repo, mr.id, 1, 10).await?;
+            assert!(diff.is_mergeable);
+            assert_eq!(diff.counts.added, 1);
+
+            let mr = api::remote::merge_requests::merge(&remote_repo, mr.id).await?;
+            assert_eq!(mr.status, MergeRequestStatus::Merged);
+
+            Ok(remote_repo)
+        })
+        .await
+    }
+}
@@ -5,7 +5,7 @@ use crate::api;
 use crate::api::remote::client;
 use crate::error::OxenError;
 use crate::model::RemoteRepository;
-use crate::view::{JsonDataFrameViewResponse, MetadataEntryResponse};
+use crate::view::{DataFrameProfileResponse, JsonDataFrameViewResponse, MetadataEntryResponse};
 
 use std::path::Path;
 
@@ -62,6 +62,23 @@ pub async fn agg_dir(
     Ok(serde_json::from_str(&body)?)
 }
 
+/// Get the column stats of a tabular file from the remote.
+pub async fn get_profile(
+    remote_repo: &RemoteRepository,
+    revision: impl AsRef<str>,
+    path: impl AsRef<Path>,
+) -> Result<DataFrameProfileResponse, OxenError> {
+    let path = path.as_ref().to_string_lossy();
+    let revision = revision.as_ref();
+    let uri = format!("/meta/profile/{revision}/{path}");
+    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
+
+    let client = client::new_for_url(&url)?;
+    let response = client.get(&url).send().await?;
+    let body = client::parse_json_body(&url, response).await?;
+    Ok(serde_json::from_str(&body)?)
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -243,4 +260,28 @@ mod tests {
         })
         .await
     }
+
+    #[tokio::test]
+    async fn test_remote_metadata_profile() -> Result<(), OxenError> {
+        test::run_remote_repo_test_bounding_box_csv_pushed(|remote_repo| async move {
+            let branch = DEFAULT_BRANCH_NAME;
+            let path = Path::new("annotations")
+                .join("train")
+                .join("bounding_box.csv");
+
+            let profile = api::remote::metadata::get_profile(&remote_repo, branch, path)
+                .await?
+                .profile;
+
+            assert_eq!(profile.height, 6);
+            assert_eq!(profile.width, 6);
+            let label = profile.column("label").unwrap();
+            assert_eq!(label.distinct_count, 2);
+            assert_eq!(label.top_values[0].value, "dog");
+            assert_eq!(label.top_values[0].count, 4);
+
+            Ok(remote_repo)
+        })
+        .await
+    }
 }
@@ -4,10 +4,13 @@ pub mod dataset;
 pub mod diff;
 pub mod get_row;
 pub mod modify_df;
+pub mod modify_df_batch;
+pub mod mv_file;
 pub mod restore_df;
 pub mod rm_df_mod;
 pub mod rm_file;
 pub mod status;
+pub mod update_row;
 
 pub use add_file::{add_file, add_files};
 pub use commit::commit;
@@ -15,7 +18,10 @@ pub use dataset::index_dataset;
 pub use diff::diff;
 pub use get_row::get_row;
 pub use modify_df::modify_df;
+pub use modify_df_batch::modify_df_batch;
+pub use mv_file::mv_file;
 pub use restore_df::restore_df;
 pub use rm_df_mod::rm_df_mod;
 pub use rm_file::rm_file;
 pub use status::status;
+pub use update_row::update_row;
@@ -62,8 +62,10 @@ pub async fn diff(
                                 added: mods.added_rows,
                                 removed: mods.removed_rows,
                                 modified: mods.modified_rows,
+                                renamed: 0,
                             },
                             col_changes: schema_diff,
+                            col_counts: mods.modified_cols,
                         },
                         schemas,
                         dupes: ct.dfs.dupes.to_tabular_diff_dupes(),
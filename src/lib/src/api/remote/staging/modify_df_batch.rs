@@ -0,0 +1,50 @@
+use polars::frame::DataFrame;
+
+use crate::api;
+use crate::api::remote::client;
+use crate::error::OxenError;
+use crate::model::entry::mod_entry::ModBatch;
+use crate::model::RemoteRepository;
+use crate::view::JsonDataFrameViewResponse;
+
+use std::path::Path;
+
+/// Insert, update and delete many rows of a staged data frame in one request.
+/// Returns the rows that were added or modified.
+pub async fn modify_df_batch(
+    remote_repo: &RemoteRepository,
+    branch_name: &str,
+    identifier: &str,
+    path: impl AsRef<Path>,
+    batch: &ModBatch,
+) -> Result<DataFrame, OxenError> {
+    let file_path_str = path.as_ref().to_string_lossy();
+    let uri = format!("/staging/{identifier}/df/batch/{branch_name}/{file_path_str}");
+    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
+    log::debug!(
+        "modify_df_batch {url} added {} modified {} removed {}",
+        batch.added.len(),
+        batch.modified.len(),
+        batch.removed.len()
+    );
+
+    let client = client::new_for_url(&url)?;
+    match client.post(&url).json(batch).send().await {
+        Ok(res) => {
+            let body = client::parse_json_body(&url, res).await?;
+            let response: Result<JsonDataFrameViewResponse, serde_json::Error> =
+                serde_json::from_str(&body);
+            match response {
+                Ok(val) => Ok(val.data_frame.view.to_df()),
+                Err(err) => {
+                    let err = format!("api::staging::modify_df_batch error parsing response from {url}\n\nErr {err:?} \n\n{body}");
+                    Err(OxenError::basic_str(err))
+                }
+            }
+        }
+        Err(err) => {
+            let err = format!("api::staging::modify_df_batch Request failed: {url}\n\nErr {err:?}");
+            Err(OxenError::basic_str(err))
+        }
+    }
+}
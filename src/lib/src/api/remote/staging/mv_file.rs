@@ -0,0 +1,92 @@
+use crate::api;
+use crate::api::remote::client;
+use crate::error::OxenError;
+use crate::model::RemoteRepository;
+use crate::view::MoveFileBody;
+
+use std::path::Path;
+
+/// Move a file that is committed or staged on the branch to `destination` in the remote staging area
+pub async fn mv_file(
+    remote_repo: &RemoteRepository,
+    branch_name: &str,
+    identifier: &str,
+    path: impl AsRef<Path>,
+    destination: impl AsRef<Path>,
+) -> Result<(), OxenError> {
+    let file_name = path.as_ref().to_string_lossy();
+    let uri = format!("/staging/{identifier}/mv/{branch_name}/{file_name}");
+    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
+    log::debug!("mv_file {} -> {:?}", url, destination.as_ref());
+    let body = MoveFileBody {
+        destination: destination.as_ref().to_path_buf(),
+    };
+    let client = client::new_for_url(&url)?;
+    match client.post(&url).json(&body).send().await {
+        Ok(res) => {
+            let body = client::parse_json_body(&url, res).await?;
+            log::debug!("mv_file got body: {}", body);
+            Ok(())
+        }
+        Err(err) => {
+            let err = format!("mv_file Request failed: {url}\n\nErr {err:?}");
+            Err(OxenError::basic_str(err))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::config::UserConfig;
+    use crate::constants::DEFAULT_BRANCH_NAME;
+    use crate::error::OxenError;
+    use crate::test;
+    use crate::{api, constants};
+
+    use std::path::Path;
+
+    #[tokio::test]
+    async fn test_mv_file() -> Result<(), OxenError> {
+        test::run_remote_repo_test_bounding_box_csv_pushed(|remote_repo| async move {
+            let branch_name = "move-annotations";
+            let branch = api::remote::branches::create_from_or_get(
+                &remote_repo,
+                branch_name,
+                DEFAULT_BRANCH_NAME,
+            )
+            .await?;
+            assert_eq!(branch.name, branch_name);
+
+            let identifier = UserConfig::identifier()?;
+            let path = Path::new("annotations")
+                .join("train")
+                .join("bounding_box.csv");
+            let destination = Path::new("moved").join("bounding_box.csv");
+            api::remote::staging::mv_file(
+                &remote_repo,
+                branch_name,
+                &identifier,
+                &path,
+                &destination,
+            )
+            .await?;
+
+            // The file is staged at its new path
+            let entries = api::remote::staging::status(
+                &remote_repo,
+                branch_name,
+                &identifier,
+                Path::new("moved"),
+                constants::DEFAULT_PAGE_NUM,
+                constants::DEFAULT_PAGE_SIZE,
+            )
+            .await?;
+            assert_eq!(entries.added_files.entries.len(), 1);
+            assert_eq!(entries.added_files.total_entries, 1);
+
+            Ok(remote_repo)
+        })
+        .await
+    }
+}
@@ -0,0 +1,125 @@
+use polars::frame::DataFrame;
+
+use crate::api;
+use crate::api::remote::client;
+use crate::error::OxenError;
+use crate::model::ContentType;
+use crate::model::RemoteRepository;
+use crate::view::json_data_frame_view::JsonDataFrameRowResponse;
+
+use std::path::Path;
+
+pub async fn update_row(
+    remote_repo: &RemoteRepository,
+    branch_name: &str,
+    identifier: &str,
+    path: impl AsRef<Path>,
+    row_id: &str,
+    data: String,
+    content_type: ContentType,
+) -> Result<DataFrame, OxenError> {
+    let file_path_str = path.as_ref().to_string_lossy();
+    let uri = format!("/staging/{identifier}/df/rows/{row_id}/{branch_name}/{file_path_str}");
+    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
+    log::debug!("update_row [{row_id}] {url}\n{data}");
+
+    let client = client::new_for_url(&url)?;
+    match client
+        .put(&url)
+        .header("Content-Type", content_type.to_http_content_type())
+        .body(data)
+        .send()
+        .await
+    {
+        Ok(res) => {
+            let body = client::parse_json_body(&url, res).await?;
+            let response: Result<JsonDataFrameRowResponse, serde_json::Error> =
+                serde_json::from_str(&body);
+            match response {
+                Ok(val) => Ok(val.data_frame.view.to_df()),
+                Err(err) => {
+                    let err = format!("api::staging::update_row error parsing response from {url}\n\nErr {err:?} \n\n{body}");
+                    Err(OxenError::basic_str(err))
+                }
+            }
+        }
+        Err(err) => {
+            let err = format!("api::staging::update_row Request failed: {url}\n\nErr {err:?}");
+            Err(OxenError::basic_str(err))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::api;
+    use crate::config::UserConfig;
+    use crate::constants::{DEFAULT_BRANCH_NAME, DEFAULT_PAGE_NUM, DEFAULT_PAGE_SIZE, OXEN_ID_COL};
+    use crate::error::OxenError;
+    use crate::model::diff::DiffResult;
+    use crate::model::ContentType;
+    use crate::test;
+
+    use std::path::Path;
+
+    #[tokio::test]
+    async fn test_update_row_shows_as_modified() -> Result<(), OxenError> {
+        test::run_remote_repo_test_bounding_box_csv_pushed(|remote_repo| async move {
+            let branch_name = "relabel";
+            let branch = api::remote::branches::create_from_or_get(
+                &remote_repo,
+                branch_name,
+                DEFAULT_BRANCH_NAME,
+            )
+            .await?;
+            assert_eq!(branch.name, branch_name);
+            let identifier = UserConfig::identifier()?;
+
+            let path = Path::new("annotations")
+                .join("train")
+                .join("bounding_box.csv");
+            let df =
+                api::remote::staging::index_dataset(&remote_repo, branch_name, &identifier, &path)
+                    .await?;
+            let row_id = df.column(OXEN_ID_COL)?.get(0)?.to_string();
+            let row_id = row_id.trim_matches('"');
+
+            let data = "{\"label\": \"wolf\"}";
+            let row = api::remote::staging::update_row(
+                &remote_repo,
+                branch_name,
+                &identifier,
+                &path,
+                row_id,
+                data.to_string(),
+                ContentType::Json,
+            )
+            .await?;
+            assert_eq!(row.height(), 1);
+            assert_eq!(row.column("label")?.get(0)?.get_str(), Some("wolf"));
+
+            let diff = api::remote::staging::diff(
+                &remote_repo,
+                branch_name,
+                &identifier,
+                &path,
+                DEFAULT_PAGE_NUM,
+                DEFAULT_PAGE_SIZE,
+            )
+            .await?;
+            match diff {
+                DiffResult::Tabular(tabular_diff) => {
+                    let counts = tabular_diff.summary.modifications.row_counts;
+                    assert_eq!(counts.modified, 1);
+                    assert_eq!(counts.added, 0);
+                    assert_eq!(counts.removed, 0);
+                }
+                _ => panic!("Diff result is not of tabular type."),
+            }
+
+            Ok(remote_repo)
+        })
+        .await
+    }
+}
@@ -4,6 +4,7 @@
 //!
 
 pub mod add;
+pub mod apply;
 pub mod checkout;
 pub mod clone;
 pub mod commit;
@@ -19,6 +20,7 @@ pub mod init;
 pub mod load;
 pub mod merge;
 pub mod migrate;
+pub mod mv;
 pub mod pull;
 pub mod push;
 pub mod remote;
@@ -26,24 +28,31 @@ pub mod restore;
 pub mod rm;
 pub mod save;
 pub mod schemas;
+pub mod split;
 pub mod status;
 
 pub use crate::command::add::add;
+pub use crate::command::apply::apply;
 pub use crate::command::checkout::{
     checkout, checkout_combine, checkout_ours, checkout_theirs, create_checkout,
 };
 pub use crate::command::clone::{clone, clone_url, deep_clone_url, shallow_clone_url};
 pub use crate::command::commit::commit;
 pub use crate::command::df::{df, schema};
-pub use crate::command::diff::{diff, diff_commits};
+pub use crate::command::diff::{
+    diff, diff_commits, diff_dir_summary, diff_image_mask, diff_to_patch, diff_tree,
+    diff_tree_to_patch,
+};
 pub use crate::command::fetch::fetch;
 pub use crate::command::info::info;
 pub use crate::command::init::init;
 pub use crate::command::load::load;
 pub use crate::command::merge::merge;
+pub use crate::command::mv::mv;
 pub use crate::command::pull::{pull, pull_all, pull_remote_branch, pull_shallow};
 pub use crate::command::push::{push, push_remote_branch, push_remote_repo_branch_name};
 pub use crate::command::restore::restore;
 pub use crate::command::rm::rm;
 pub use crate::command::save::save;
+pub use crate::command::split::split;
 pub use crate::command::status::{status, status_from_dir};
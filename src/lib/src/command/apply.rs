@@ -0,0 +1,25 @@
+//! # oxen apply
+//!
+//! Apply a patch bundle created with `oxen diff --output <file>.oxenpatch`
+//!
+//! ## Usage
+//!
+//! ```shell
+//! oxen apply <patch>
+//! ```
+
+use std::path::Path;
+
+use crate::api;
+use crate::error::OxenError;
+use crate::model::diff::patch::PatchApplyResult;
+use crate::model::LocalRepository;
+
+/// Applies the patch to the working tree of the repository. Entries that conflict with the
+/// current contents are skipped and returned in `PatchApplyResult::conflicts`.
+pub fn apply(
+    repo: &LocalRepository,
+    patch: impl AsRef<Path>,
+) -> Result<PatchApplyResult, OxenError> {
+    api::local::diff::patch::apply(repo, patch)
+}
@@ -51,7 +51,7 @@ pub async fn compute_cache(
     let commits = if let Some(revision) = revision {
         let opts = LogOpts {
             revision: Some(revision),
-            remote: false,
+            ..LogOpts::default()
         };
         api::local::commits::list_with_opts(repo, &opts).await?
     } else {
@@ -5,13 +5,22 @@
 
 use std::path::Path;
 
-use crate::core::df::tabular;
+use polars::prelude::DataFrame;
+
+use crate::core::df::{pretty_print, profile, sql, tabular};
 use crate::error::OxenError;
+use crate::model::{DataFrameProfile, LocalRepository};
 use crate::opts::DFOpts;
 use crate::util;
+use crate::{api, command};
 
 /// Interact with DataFrames
 pub fn df<P: AsRef<Path>>(input: P, opts: DFOpts) -> Result<(), OxenError> {
+    if opts.profile {
+        profile(input, &opts)?;
+        return Ok(());
+    }
+
     let mut df = tabular::show_path(input, opts.clone())?;
 
     if let Some(output) = opts.output {
@@ -22,6 +31,101 @@ pub fn df<P: AsRef<Path>>(input: P, opts: DFOpts) -> Result<(), OxenError> {
     Ok(())
 }
 
+/// Print the column stats of a DataFrame after the filters and other transforms in the opts are
+/// applied, and write them as json if there is an output path
+pub fn profile(input: impl AsRef<Path>, opts: &DFOpts) -> Result<DataFrameProfile, OxenError> {
+    let df = tabular::scan_df(input, opts)?;
+    let df = tabular::transform_lazy(df, opts)?;
+    let profile = profile::profile_df(df)?;
+    println!("{} rows x {} columns", profile.height, profile.width);
+    println!("{}", pretty_print::df_to_str(&profile.to_df()?));
+
+    if let Some(output) = &opts.output {
+        println!("Writing {output:?}");
+        util::fs::write_to_path(output, serde_json::to_string_pretty(&profile)?)?;
+    }
+    Ok(profile)
+}
+
+/// Interact with DataFrames in a repository, reading the input at `opts.revision` if it is set, and
+/// resolving any `path@revision` tables in `opts.sql` to their version files
+pub fn df_in_repo(
+    repo: &LocalRepository,
+    input: impl AsRef<Path>,
+    mut opts: DFOpts,
+) -> Result<(), OxenError> {
+    let input = match &opts.revision {
+        Some(revision) => api::local::revisions::get_version_file(repo, revision, input)?,
+        None => input.as_ref().to_path_buf(),
+    };
+    sql::resolve_revision_tables(repo, &mut opts)?;
+    df(input, opts)
+}
+
+/// Transform a DataFrame and write it back over the input file, in the same format and delimiter
+/// and with the original columns in their original order, then stage it. Staging carries over the
+/// schema name, metadata and custom data types of the columns that are still there. If `opts.output`
+/// is set the result is written there instead, keeping the input's format when the output has the
+/// same extension, and it is staged if it is within the repository.
+pub fn df_in_place(
+    repo: &LocalRepository,
+    input: impl AsRef<Path>,
+    mut opts: DFOpts,
+) -> Result<DataFrame, OxenError> {
+    let path = input.as_ref();
+    if opts.revision.is_some() {
+        return Err(OxenError::basic_str(
+            "Cannot write in place with --revision",
+        ));
+    }
+    sql::resolve_revision_tables(repo, &mut opts)?;
+
+    let mut read_opts = DFOpts::empty();
+    read_opts.delimiter.clone_from(&opts.delimiter);
+    let df = tabular::read_df(path, read_opts)?;
+    let original_columns: Vec<String> = df
+        .get_column_names()
+        .iter()
+        .map(|name| name.to_string())
+        .collect();
+
+    let df = tabular::transform(df, opts.clone())?;
+    let mut df = with_column_order(df, &original_columns)?;
+    // Columns the user asked for explicitly are written in the order they asked for them
+    if let Some(columns) = opts.columns_names() {
+        df = df.select(columns)?;
+    }
+    println!("{}", pretty_print::df_to_str(&df));
+
+    let output = opts.output.clone().unwrap_or(path.to_path_buf());
+    println!("Writing {output:?}");
+    tabular::write_df_like(&mut df, path, &output, &opts)?;
+    if output.starts_with(&repo.path) {
+        command::add(repo, &output)?;
+    }
+
+    Ok(df)
+}
+
+// Original columns first in the order they were in, followed by any new columns
+fn with_column_order(df: DataFrame, original_columns: &[String]) -> Result<DataFrame, OxenError> {
+    let columns: Vec<String> = df
+        .get_column_names()
+        .iter()
+        .map(|name| name.to_string())
+        .collect();
+    let mut ordered: Vec<&String> = original_columns
+        .iter()
+        .filter(|name| columns.contains(name))
+        .collect();
+    ordered.extend(
+        columns
+            .iter()
+            .filter(|name| !original_columns.contains(name)),
+    );
+    Ok(df.select(ordered)?)
+}
+
 /// Get a human readable schema for a DataFrame
 pub fn schema<P: AsRef<Path>>(input: P, flatten: bool, opts: DFOpts) -> Result<String, OxenError> {
     tabular::schema_to_string(input, flatten, &opts)
@@ -52,3 +156,76 @@ pub fn add_column(path: &Path, data: &str) -> Result<(), OxenError> {
         Err(OxenError::basic_str(err))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::path::Path;
+
+    use flate2::write::GzEncoder;
+    use serde_json::json;
+
+    use crate::command;
+    use crate::core::df::compressed;
+    use crate::error::OxenError;
+    use crate::opts::DFOpts;
+    use crate::test;
+    use crate::util;
+
+    #[test]
+    fn test_command_df_in_place_keeps_format_and_schema_metadata() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            let path = repo.path.join("data.csv");
+            util::fs::write_to_path(&path, "file;label;score\nb.jpg;dog;2\na.jpg;cat;1\n")?;
+            command::add(&repo, &path)?;
+            let metadata = json!({"root": "images"});
+            command::schemas::add_column_metadata(&repo, "data.csv", "file", &metadata)?;
+            command::commit(&repo, "Adding data")?;
+
+            let mut opts = DFOpts::empty();
+            opts.columns = Some(String::from("score,label,file"));
+            opts.sort_by = Some(String::from("file"));
+            command::df::df_in_place(&repo, &path, opts)?;
+
+            let contents = util::fs::read_from_path(&path)?;
+            assert_eq!(contents, "score;label;file\n1;cat;a.jpg\n2;dog;b.jpg\n");
+
+            let status = command::status(&repo)?;
+            assert!(status.staged_files.contains_key(Path::new("data.csv")));
+
+            let schemas = command::schemas::get_staged(&repo, "data.csv")?;
+            let schema = schemas.values().next().unwrap();
+            let field = schema.fields.iter().find(|f| f.name == "file").unwrap();
+            assert_eq!(field.metadata, Some(metadata));
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_command_df_in_place_keeps_compression() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            let path = repo.path.join("data.csv.gz");
+            let mut encoder = GzEncoder::new(
+                std::fs::File::create(&path)?,
+                flate2::Compression::default(),
+            );
+            encoder.write_all(b"file;label\nb.jpg;dog\na.jpg;cat\n")?;
+            encoder.finish()?;
+
+            let mut opts = DFOpts::empty();
+            opts.sort_by = Some(String::from("file"));
+            opts.output = Some(repo.path.join("sorted.csv.gz"));
+            command::df::df_in_place(&repo, &path, opts)?;
+
+            let output = repo.path.join("sorted.csv.gz");
+            let contents = compressed::decompress(&output)?;
+            assert_eq!(contents, b"file;label\na.jpg;cat\nb.jpg;dog\n");
+
+            let status = command::status(&repo)?;
+            assert!(status.staged_files.contains_key(Path::new("sorted.csv.gz")));
+
+            Ok(())
+        })
+    }
+}
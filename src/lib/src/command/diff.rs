@@ -10,18 +10,23 @@
 
 use std::path::{Path, PathBuf};
 
+use crate::api::local::diff::patch::PatchFile;
 use crate::core::index::MergeConflictReader;
 use crate::error::OxenError;
-use crate::model::diff::DiffResult;
+use crate::model::diff::dir_diff_summary::{DirDiffSummary, DirDiffSummaryImpl};
+use crate::model::diff::{AddRemoveModifyCounts, DiffResult, OxenPatch};
 use crate::model::entry::commit_entry::CommitPath;
-use crate::model::LocalRepository;
-use crate::{api, util};
+use crate::model::{DiffEntry, LocalRepository};
+use crate::opts::{DiffTreeOpts, TabularDiffOpts};
+use crate::{api, command, util};
 
+#[allow(clippy::too_many_arguments)]
 pub fn diff(
     path_1: impl AsRef<Path>,
     path_2: Option<PathBuf>,
     keys: Vec<String>,
     targets: Vec<String>,
+    opts: &TabularDiffOpts,
     repo_dir: Option<PathBuf>,
     revision_1: Option<String>,
     revision_2: Option<String>,
@@ -32,12 +37,80 @@ pub fn diff(
         targets,
     );
 
+    let (file_1, file_2) = resolve_files(path_1, path_2, repo_dir, revision_1, revision_2)?;
+    let result =
+        api::local::diff::diff_files_with_opts(file_1, file_2, keys, targets, vec![], opts)?;
+
+    Ok(result)
+}
+
+/// Same arguments as `diff`, but writes the changes to a patch bundle at `output`
+/// so they can be applied to another working tree with `oxen apply`.
+pub fn diff_to_patch(
+    path_1: impl AsRef<Path>,
+    path_2: Option<PathBuf>,
+    keys: Vec<String>,
+    repo_dir: Option<PathBuf>,
+    revision_1: Option<String>,
+    revision_2: Option<String>,
+    output: impl AsRef<Path>,
+) -> Result<OxenPatch, OxenError> {
+    let path = path_1.as_ref().to_path_buf();
+    let (file_1, file_2) = resolve_files(path_1, path_2, repo_dir, revision_1, revision_2)?;
+    api::local::diff::patch::create(path, file_1, file_2, keys, output)
+}
+
+/// Writes every change listed by `diff_tree` to a single patch bundle at `output`. The new
+/// contents of the files are read from the working directory.
+pub fn diff_tree_to_patch(
+    repo: &LocalRepository,
+    opts: &DiffTreeOpts,
+    keys: Vec<String>,
+    output: impl AsRef<Path>,
+) -> Result<OxenPatch, OxenError> {
+    let base_commit = match &opts.revision {
+        Some(revision) => api::local::revisions::get(repo, revision)?
+            .ok_or(OxenError::revision_not_found(revision.to_owned().into()))?,
+        None => api::local::commits::head_commit(repo)?,
+    };
+
+    let mut files: Vec<PatchFile> = vec![];
+    for entry in diff_tree(repo, opts)? {
+        let path = PathBuf::from(&entry.filename);
+        let old = api::local::entries::get_commit_entry(repo, &base_commit, &path)?
+            .map(|entry| util::fs::version_path(repo, &entry));
+        let new = Some(repo.path.join(&path)).filter(|new| new.exists());
+        files.push(PatchFile { path, old, new });
+    }
+    api::local::diff::patch::create_from_files(&files, keys, output)
+}
+
+/// Same arguments as `diff`, but renders an image highlighting the pixels that changed
+/// between the two image versions to `output`.
+pub fn diff_image_mask(
+    path_1: impl AsRef<Path>,
+    path_2: Option<PathBuf>,
+    repo_dir: Option<PathBuf>,
+    revision_1: Option<String>,
+    revision_2: Option<String>,
+    output: impl AsRef<Path>,
+) -> Result<(), OxenError> {
+    let (file_1, file_2) = resolve_files(path_1, path_2, repo_dir, revision_1, revision_2)?;
+    api::local::diff::image_diff::render_mask(file_1, file_2, output)
+}
+
+/// Finds the two files on disk to compare, looking up the version files if revisions are given
+fn resolve_files(
+    path_1: impl AsRef<Path>,
+    path_2: Option<PathBuf>,
+    repo_dir: Option<PathBuf>,
+    revision_1: Option<String>,
+    revision_2: Option<String>,
+) -> Result<(PathBuf, PathBuf), OxenError> {
     // If the user specifies two files without revisions, we will compare the files on disk
     if revision_1.is_none() && revision_2.is_none() && path_2.is_some() {
         // If we do not have revisions set, just compare the files on disk
-        let result = api::local::diff::diff_files(path_1, path_2.unwrap(), keys, targets, vec![])?;
-
-        return Ok(result);
+        return Ok((path_1.as_ref().to_path_buf(), path_2.unwrap()));
     }
 
     // Make sure we have a repository to look up the revisions
@@ -95,9 +168,71 @@ pub fn diff(
         )
     };
 
-    let result = diff_commits(&repository, cpath_1, cpath_2, keys, targets, vec![])?;
+    resolve_commit_paths(&repository, cpath_1, cpath_2)
+}
 
-    Ok(result)
+/// Summarizes the changes below `dir` between two revisions, grouped by data type.
+/// Revisions that are not given default to HEAD.
+pub fn diff_dir_summary(
+    repo: &LocalRepository,
+    dir: impl AsRef<Path>,
+    base_revision: Option<String>,
+    head_revision: Option<String>,
+) -> Result<DirDiffSummary, OxenError> {
+    let get_commit = |revision: Option<String>| match revision {
+        Some(revision) => api::local::revisions::get(repo, &revision)?
+            .ok_or(OxenError::revision_not_found(revision.into())),
+        None => api::local::commits::head_commit(repo),
+    };
+    let base_commit = get_commit(base_revision)?;
+    let head_commit = get_commit(head_revision)?;
+
+    let data_types =
+        api::local::diff::get_dir_diff_summary_by_data_type(repo, dir, &base_commit, &head_commit)?;
+    let mut file_counts = AddRemoveModifyCounts {
+        added: 0,
+        removed: 0,
+        modified: 0,
+        renamed: 0,
+    };
+    for summary in data_types.iter() {
+        file_counts.added += summary.file_counts.added;
+        file_counts.removed += summary.file_counts.removed;
+        file_counts.modified += summary.file_counts.modified;
+    }
+
+    Ok(DirDiffSummary {
+        dir: DirDiffSummaryImpl {
+            file_counts,
+            data_types,
+        },
+    })
+}
+
+/// Diffs the working directory, or the staged index if `opts.staged` is set, against
+/// `opts.revision` (HEAD by default), listing the files that were added, modified or removed.
+/// Untracked files count as added.
+pub fn diff_tree(repo: &LocalRepository, opts: &DiffTreeOpts) -> Result<Vec<DiffEntry>, OxenError> {
+    let base_commit = match &opts.revision {
+        Some(revision) => api::local::revisions::get(repo, revision)?
+            .ok_or(OxenError::revision_not_found(revision.to_owned().into()))?,
+        None => api::local::commits::head_commit(repo)?,
+    };
+
+    let paths = opts
+        .paths
+        .iter()
+        .map(|path| {
+            if path.is_absolute() {
+                util::fs::path_relative_to_dir(path, &repo.path)
+            } else {
+                Ok(path.to_owned())
+            }
+        })
+        .collect::<Result<Vec<PathBuf>, OxenError>>()?;
+
+    let status = command::status(repo)?;
+    api::local::diff::tree_diff::diff_entries(repo, &base_commit, &status, opts.staged, &paths)
 }
 
 pub fn diff_commits(
@@ -114,6 +249,19 @@ pub fn diff_commits(
         cpath_2
     );
 
+    let (path_1, path_2) = resolve_commit_paths(repo, cpath_1, cpath_2)?;
+    let compare_result = api::local::diff::diff_files(path_1, path_2, keys, targets, display)?;
+
+    log::debug!("compare result: {:?}", compare_result);
+
+    Ok(compare_result)
+}
+
+fn resolve_commit_paths(
+    repo: &LocalRepository,
+    cpath_1: CommitPath,
+    cpath_2: CommitPath,
+) -> Result<(PathBuf, PathBuf), OxenError> {
     // TODONOW - anything we can clean up with this mut initialization?
     let mut path_1 = cpath_1.path.clone();
     let mut path_2 = cpath_2.path.clone();
@@ -147,11 +295,7 @@ pub fn diff_commits(
         path_2 = util::fs::version_path(repo, &entry_2);
     };
 
-    let compare_result = api::local::diff::diff_files(path_1, path_2, keys, targets, display)?;
-
-    log::debug!("compare result: {:?}", compare_result);
-
-    Ok(compare_result)
+    Ok((path_1, path_2))
 }
 
 #[cfg(test)]
@@ -162,10 +306,13 @@ mod tests {
     use polars::lazy::dsl::{col, lit};
     use polars::lazy::frame::IntoLazy;
 
+    use crate::api;
     use crate::command;
+    use crate::constants::DIFF_CHANGED_COLS_COL;
     use crate::error::OxenError;
     use crate::model::diff::{ChangeType, DiffResult};
     use crate::model::entry::commit_entry::CommitPath;
+    use crate::opts::{DiffTreeOpts, TabularDiffOpts};
     use crate::test;
     use crate::util;
 
@@ -178,7 +325,16 @@ mod tests {
             util::fs::write_to_path(&file1, "hello\nhi\nhow are you?")?;
             util::fs::write_to_path(&file2, "hello\nhi\nhow are you doing?")?;
 
-            let diff = command::diff(&file1, Some(file2), vec![], vec![], None, None, None)?;
+            let diff = command::diff(
+                &file1,
+                Some(file2),
+                vec![],
+                vec![],
+                &TabularDiffOpts::default(),
+                None,
+                None,
+                None,
+            )?;
 
             match diff {
                 DiffResult::Text(result) => {
@@ -209,6 +365,189 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_command_diff_txt_files_hunks() -> Result<(), OxenError> {
+        test::run_empty_dir_test(|dir| {
+            let file1 = dir.join("file1.txt");
+            let file2 = dir.join("file2.txt");
+
+            let original: String = (1..=20).map(|i| format!("line {i}\n")).collect();
+            let changed = original.replace("line 10\n", "line ten\n");
+            util::fs::write_to_path(&file1, &original)?;
+            util::fs::write_to_path(&file2, &changed)?;
+
+            let diff = command::diff(
+                &file1,
+                Some(file2),
+                vec![],
+                vec![],
+                &TabularDiffOpts::default(),
+                None,
+                None,
+                None,
+            )?;
+
+            let DiffResult::Text(result) = diff else {
+                panic!("expected text result");
+            };
+
+            assert_eq!(result.num_added(), 1);
+            assert_eq!(result.num_removed(), 1);
+            assert_eq!(result.hunks.len(), 1);
+            assert_eq!(result.hunks[0].header(), "@@ -7,7 +7,7 @@");
+
+            // Only the lines of the hunk are kept
+            assert_eq!(result.lines.len(), 8);
+            let removed = &result.lines[3];
+            assert_eq!(removed.modification, ChangeType::Removed);
+            assert_eq!(removed.old_line, Some(10));
+            assert_eq!(removed.new_line, None);
+
+            let unified = result.to_unified("a/file1.txt", "b/file2.txt", 1);
+            assert_eq!(
+                unified,
+                "--- a/file1.txt\n+++ b/file2.txt\n@@ -9,3 +9,3 @@\n line 9\n-line 10\n+line ten\n line 11\n"
+            );
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_command_diff_txt_files_no_newline_at_eof() -> Result<(), OxenError> {
+        test::run_empty_dir_test(|dir| {
+            let file1 = dir.join("file1.txt");
+            let file2 = dir.join("file2.txt");
+
+            util::fs::write_to_path(&file1, "a\nb")?;
+            util::fs::write_to_path(&file2, "a\nc\n")?;
+
+            let diff = command::diff(
+                &file1,
+                Some(file2),
+                vec![],
+                vec![],
+                &TabularDiffOpts::default(),
+                None,
+                None,
+                None,
+            )?;
+
+            let DiffResult::Text(result) = diff else {
+                panic!("expected text result");
+            };
+
+            let unified = result.to_unified("a/file1.txt", "b/file2.txt", 3);
+            assert_eq!(
+                unified,
+                "--- a/file1.txt\n+++ b/file2.txt\n@@ -1,2 +1,2 @@\n a\n-b\n\\ No newline at end of file\n+c\n"
+            );
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_command_diff_tree_against_branch() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            let hello_file = repo.path.join("hello.txt");
+            util::fs::write_to_path(&hello_file, "Hello")?;
+            command::add(&repo, &hello_file)?;
+            let commit = command::commit(&repo, "Adding hello")?;
+            api::local::branches::create(&repo, "before-edit", &commit.id)?;
+
+            util::fs::write_to_path(&hello_file, "Hello World")?;
+            command::add(&repo, &hello_file)?;
+            command::commit(&repo, "Editing hello")?;
+
+            // The working dir matches HEAD, but not the branch
+            let opts = DiffTreeOpts::default();
+            assert!(command::diff_tree(&repo, &opts)?.is_empty());
+
+            let opts = DiffTreeOpts {
+                revision: Some("before-edit".to_string()),
+                paths: vec![hello_file.clone()],
+                ..DiffTreeOpts::default()
+            };
+            let entries = command::diff_tree(&repo, &opts)?;
+            assert_eq!(entries.len(), 1);
+            assert_eq!(entries[0].filename, "hello.txt");
+            assert_eq!(entries[0].status, "modified");
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_command_diff_csv_changed_columns_with_tolerance() -> Result<(), OxenError> {
+        test::run_empty_dir_test(|dir| {
+            let file1 = dir.join("file1.csv");
+            let file2 = dir.join("file2.csv");
+
+            util::fs::write_to_path(&file1, "id,score,label\n1,0.5,Cat\n2,0.7,dog\n3,0.1,cow\n")?;
+            util::fs::write_to_path(
+                &file2,
+                "id,score,label\n1,0.50001,cat \n2,0.9,dog\n3,0.1,horse\n",
+            )?;
+
+            let keys = vec!["id".to_string()];
+            let targets = vec!["score".to_string(), "label".to_string()];
+
+            // Exact comparison sees every row as modified
+            let diff = command::diff(
+                &file1,
+                Some(file2.clone()),
+                keys.clone(),
+                targets.clone(),
+                &TabularDiffOpts::default(),
+                None,
+                None,
+                None,
+            )?;
+            let DiffResult::Tabular(diff) = diff else {
+                panic!("expected tabular result");
+            };
+            assert_eq!(diff.summary.modifications.row_counts.modified, 3);
+
+            let opts = TabularDiffOpts {
+                numeric_tolerance: Some(0.001),
+                ignore_case: true,
+                ignore_whitespace: true,
+                ..TabularDiffOpts::default()
+            };
+            let diff = command::diff(&file1, Some(file2), keys, targets, &opts, None, None, None)?;
+            let DiffResult::Tabular(diff) = diff else {
+                panic!("expected tabular result");
+            };
+
+            let mods = &diff.summary.modifications;
+            assert_eq!(mods.row_counts.modified, 2);
+            assert_eq!(mods.col_counts[0].name, "score");
+            assert_eq!(mods.col_counts[0].num_modified, 1);
+            assert_eq!(mods.col_counts[1].name, "label");
+            assert_eq!(mods.col_counts[1].num_modified, 1);
+
+            let changed: Vec<Vec<String>> = diff
+                .contents
+                .column(DIFF_CHANGED_COLS_COL)?
+                .list()?
+                .into_iter()
+                .map(|names| {
+                    names
+                        .unwrap()
+                        .str()
+                        .unwrap()
+                        .into_iter()
+                        .map(|name| name.unwrap().to_string())
+                        .collect()
+                })
+                .collect();
+            assert_eq!(changed, vec![vec!["score"], vec!["label"]]);
+
+            Ok(())
+        })
+    }
+
     // #[tokio::test]
     // async fn test_compare_same_dataframe_no_keys_no_targets() -> Result<(), OxenError> {
     //     test::run_empty_local_repo_test_async(|repo| async move {
@@ -352,7 +691,7 @@ mod tests {
                 DiffResult::Tabular(result) => {
                     let df = result.contents;
                     assert_eq!(df.height(), 4);
-                    assert_eq!(df.width(), 5); // 2 key columns, 1 target column * 2 views each, and diff status
+                    assert_eq!(df.width(), 6); // 2 key columns, 1 target column * 2 views each, diff status and changed columns
                     let added_df = df
                         .clone()
                         .lazy()
@@ -423,7 +762,7 @@ mod tests {
                 DiffResult::Tabular(result) => {
                     let df = result.contents;
                     assert_eq!(df.height(), 3);
-                    assert_eq!(df.width(), 5); // 2 key columns, 1 target column * 2 views each, and diff status
+                    assert_eq!(df.width(), 6); // 2 key columns, 1 target column * 2 views each, diff status and changed columns
                     let added_df = df
                         .clone()
                         .lazy()
@@ -492,7 +831,7 @@ mod tests {
                 DiffResult::Tabular(result) => {
                     let df = result.contents;
                     assert_eq!(df.height(), 0);
-                    assert_eq!(df.width(), 7); // 2 key columns, 2 targets * 2(right+left) + diff status
+                    assert_eq!(df.width(), 8); // 2 key columns, 2 targets * 2(right+left) + diff status + changed columns
                     let added_df = df
                         .clone()
                         .lazy()
@@ -3,9 +3,11 @@
 //! Get information about a path in the oxen repository
 //!
 
+use crate::core::cache::cachers::df_profile;
+use crate::core::df::profile;
 use crate::error::OxenError;
 use crate::model::entry::metadata_entry::CLIMetadataEntry;
-use crate::model::LocalRepository;
+use crate::model::{EntryDataType, LocalRepository};
 use crate::opts::InfoOpts;
 use crate::{api, util};
 
@@ -19,7 +21,12 @@ pub fn info(repository: &LocalRepository, opts: InfoOpts) -> Result<CLIMetadataE
 
         if let Some(entry) = api::local::entries::get_commit_entry(repository, &commit, &path)? {
             let version_path = util::fs::version_path(repository, &entry);
-            return api::local::metadata::get_cli(repository, path, version_path);
+            let mut metadata = api::local::metadata::get_cli(repository, path, version_path)?;
+            if opts.profile && metadata.data_type == EntryDataType::Tabular {
+                // Committed files are profiled by the commit cacher
+                metadata.profile = Some(df_profile::get_cache_for_entry(repository, &entry)?);
+            }
+            return Ok(metadata);
         } else {
             eprintln!(
                 "Path does not exist in revision: {}:{}",
@@ -37,5 +44,9 @@ pub fn info(repository: &LocalRepository, opts: InfoOpts) -> Result<CLIMetadataE
     }
 
     // get file metadata
-    api::local::metadata::get_cli(repository, &path, &path)
+    let mut metadata = api::local::metadata::get_cli(repository, &path, &path)?;
+    if opts.profile && metadata.data_type == EntryDataType::Tabular {
+        metadata.profile = Some(profile::profile_path(&path)?);
+    }
+    Ok(metadata)
 }
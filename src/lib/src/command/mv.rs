@@ -0,0 +1,14 @@
+//! # oxen mv
+//!
+//! Move or rename a file or directory and stage the move
+//!
+
+use crate::core::index;
+use crate::error::OxenError;
+use crate::model::LocalRepository;
+use crate::opts::MvOpts;
+
+/// Moves the file or directory on disk and stages it at its new path
+pub async fn mv(repo: &LocalRepository, opts: &MvOpts) -> Result<(), OxenError> {
+    index::mv(repo, opts).await
+}
@@ -11,9 +11,10 @@ use crate::api;
 use crate::config::UserConfig;
 use crate::core::df::tabular;
 use crate::error::OxenError;
-use crate::model::entry::mod_entry::ModType;
+use crate::model::entry::mod_entry::{ModBatch, ModType};
 use crate::model::LocalRepository;
 use crate::opts::DFOpts;
+use crate::util;
 
 /// Interact with Remote DataFrames
 pub async fn df<P: AsRef<Path>>(
@@ -26,6 +27,10 @@ pub async fn df<P: AsRef<Path>>(
         add_row(repo, input.as_ref(), row, &opts).await
     } else if let Some(uuid) = &opts.delete_row {
         delete_row(repo, input, uuid).await
+    } else if let Some(uuid) = &opts.update_row {
+        update_row(repo, input, uuid, &opts).await
+    } else if let Some(batch) = &opts.batch {
+        apply_batch(repo, input, batch).await
     } else if opts.index {
         let df = index_dataset(repo, input).await?;
         println!("Dataset successfully indexed ✅");
@@ -64,6 +69,10 @@ pub async fn staged_df<P: AsRef<Path>>(
         add_row(repo, input.as_ref(), row, &opts).await
     } else if let Some(uuid) = &opts.delete_row {
         delete_row(repo, input, uuid).await
+    } else if let Some(uuid) = &opts.update_row {
+        update_row(repo, input, uuid, &opts).await
+    } else if let Some(batch) = &opts.batch {
+        apply_batch(repo, input, batch).await
     } else if let Some(row_id) = &opts.get_row {
         get_row(repo, input, row_id).await
     } else if opts.index {
@@ -145,6 +154,76 @@ pub async fn delete_row(
     }
 }
 
+pub async fn update_row(
+    repository: &LocalRepository,
+    path: impl AsRef<Path>,
+    uuid: &str,
+    opts: &DFOpts,
+) -> Result<DataFrame, OxenError> {
+    let Some(data) = opts.row_data.clone() else {
+        return Err(OxenError::basic_str(
+            "Must pass the new values of the row to update it.",
+        ));
+    };
+    let remote_repo = api::remote::repositories::get_default_remote(repository).await?;
+    if let Some(branch) = api::local::branches::current_branch(repository)? {
+        let user_id = UserConfig::identifier()?;
+        let df = api::remote::staging::update_row(
+            &remote_repo,
+            &branch.name,
+            &user_id,
+            path,
+            uuid,
+            data,
+            opts.content_type.to_owned(),
+        )
+        .await?;
+
+        println!("\nUpdated row: {uuid:?}");
+        println!("{:?}", df);
+        Ok(df)
+    } else {
+        Err(OxenError::basic_str(
+            "Must be on a branch to stage remote changes.",
+        ))
+    }
+}
+
+pub async fn apply_batch(
+    repository: &LocalRepository,
+    path: impl AsRef<Path>,
+    batch_path: impl AsRef<Path>,
+) -> Result<DataFrame, OxenError> {
+    let contents = util::fs::read_from_path(batch_path)?;
+    let batch: ModBatch = serde_json::from_str(&contents)?;
+
+    let remote_repo = api::remote::repositories::get_default_remote(repository).await?;
+    if let Some(branch) = api::local::branches::current_branch(repository)? {
+        let user_id = UserConfig::identifier()?;
+        let df = api::remote::staging::modify_df_batch(
+            &remote_repo,
+            &branch.name,
+            &user_id,
+            path,
+            &batch,
+        )
+        .await?;
+
+        println!(
+            "\nAdded {} rows, modified {} rows, removed {} rows",
+            batch.added.len(),
+            batch.modified.len(),
+            batch.removed.len()
+        );
+        println!("{:?}", df);
+        Ok(df)
+    } else {
+        Err(OxenError::basic_str(
+            "Must be on a branch to stage remote changes.",
+        ))
+    }
+}
+
 pub async fn get_row(
     repository: &LocalRepository,
     path: impl AsRef<Path>,
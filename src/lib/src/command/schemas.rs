@@ -9,6 +9,7 @@ use std::path::PathBuf;
 use crate::api;
 use crate::core::index::Stager;
 use crate::error::OxenError;
+use crate::model::schema::SchemaCompatibility;
 use crate::model::{LocalRepository, Schema};
 
 /// List the saved off schemas for a commit id
@@ -147,6 +148,36 @@ pub fn add_schema_metadata(
     Ok(results)
 }
 
+/// Declare how the schema of a file is allowed to change in future commits, `None` removes the
+/// contract. New versions of the file are checked against it on `oxen add`, before a remote
+/// commit and when the commits are pushed. A contract can only be removed in a commit that does
+/// not change the columns of the file.
+pub fn set_compatibility(
+    repo: &LocalRepository,
+    schema_ref: impl AsRef<str>,
+    compatibility: Option<SchemaCompatibility>,
+) -> Result<HashMap<PathBuf, Schema>, OxenError> {
+    let head_commit = api::local::commits::head_commit(repo)?;
+    log::debug!("set_compatibility head_commit: {}", head_commit);
+
+    let mut all_schemas = api::local::schemas::list_from_ref(repo, head_commit.id, &schema_ref)?;
+
+    let stager = Stager::new(repo)?;
+    all_schemas.extend(stager.get_staged_schema(&schema_ref)?);
+
+    if all_schemas.is_empty() {
+        return Err(OxenError::schema_does_not_exist(schema_ref));
+    }
+
+    let mut results = HashMap::new();
+    for (path, mut schema) in all_schemas {
+        schema.set_compatibility(compatibility);
+        let schema = stager.update_schema_for_path(&path, &schema)?;
+        results.insert(path, schema);
+    }
+    Ok(results)
+}
+
 /// Add metadata to a specific column
 pub fn add_column_metadata(
     repo: &LocalRepository,
@@ -193,7 +224,12 @@ pub fn add_column_metadata(
 // unit tests
 #[cfg(test)]
 mod tests {
+    use crate::core::df::tabular;
+    use crate::core::index::SchemaReader;
     use crate::error::OxenError;
+    use crate::model::schema::SchemaCompatibility;
+    use crate::model::Schema;
+    use crate::opts::DFOpts;
     use crate::test;
     use crate::util;
     use crate::{api, command};
@@ -228,6 +264,93 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_schema_contract_checked_on_add() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            let bbox_path = Path::new("annotations")
+                .join("train")
+                .join("bounding_box.csv");
+            let bbox_file = repo.path.join(&bbox_path);
+
+            command::schemas::set_compatibility(
+                &repo,
+                bbox_path.to_string_lossy(),
+                Some(SchemaCompatibility::Backward),
+            )?;
+            command::commit(&repo, "Only allow backward compatible bounding boxes")?;
+
+            // Widening width from i64 to f64 and adding a column is allowed
+            util::fs::write(
+                &bbox_file,
+                "file,label,min_x,min_y,width,height,score\ntrain/dog_1.jpg,dog,101.5,32.0,385.5,330,0.9\n",
+            )?;
+            command::add(&repo, &bbox_file)?;
+            command::commit(&repo, "Adding a score")?;
+
+            // Dropping the label and making min_x a string is not
+            util::fs::write(
+                &bbox_file,
+                "file,min_x,min_y,width,height,score\ntrain/dog_1.jpg,left,32.0,385.5,330,0.9\n",
+            )?;
+            let result = command::add(&repo, &bbox_file);
+            assert!(matches!(result, Err(OxenError::IncompatibleSchemas(_))));
+            let err = result.unwrap_err().to_string();
+            assert!(err.contains("column 'label' was removed"));
+            assert!(err.contains("column 'min_x' changed type from f64 to str"));
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_schema_contract_removed_with_breaking_change() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            let bbox_path = Path::new("annotations")
+                .join("train")
+                .join("bounding_box.csv");
+            let bbox_file = repo.path.join(&bbox_path);
+
+            command::schemas::set_compatibility(
+                &repo,
+                bbox_path.to_string_lossy(),
+                Some(SchemaCompatibility::Backward),
+            )?;
+            let contract_commit =
+                command::commit(&repo, "Only allow backward compatible bounding boxes")?;
+            assert!(api::local::schemas::check_contracts(&repo, &contract_commit).is_ok());
+
+            // Dropping the contract and the label at the same time is not allowed
+            let original = util::fs::read_from_path(&bbox_file)?;
+            let breaking = "file,min_x,min_y,width,height\ntrain/dog_1.jpg,101.5,32.0,385,330\n";
+            util::fs::write(&bbox_file, breaking)?;
+            command::schemas::set_compatibility(&repo, bbox_path.to_string_lossy(), None)?;
+            let result = command::add(&repo, &bbox_file);
+            assert!(matches!(result, Err(OxenError::IncompatibleSchemas(_))));
+
+            // Which is also what the server checks for commits it did not stage
+            let df = tabular::read_df(&bbox_file, DFOpts::empty())?;
+            let schema = Schema::from_polars(&df.schema());
+            let schema_reader = SchemaReader::new(&repo, &contract_commit.id)?;
+            let result = api::local::schemas::check_contract(&bbox_path, &schema, &schema_reader);
+            let err = result.unwrap_err().to_string();
+            assert!(err.contains("column 'label' was removed"));
+            assert!(err.contains("the contract can only be removed without changing the columns"));
+
+            // Removing the contract on its own is fine, after that the file can change freely
+            util::fs::write(&bbox_file, &original)?;
+            command::add(&repo, &bbox_file)?;
+            let removed_commit = command::commit(&repo, "Removing the contract")?;
+            assert!(api::local::schemas::check_contracts(&repo, &removed_commit).is_ok());
+
+            util::fs::write(&bbox_file, breaking)?;
+            command::add(&repo, &bbox_file)?;
+            let commit = command::commit(&repo, "Dropping the label")?;
+            assert!(api::local::schemas::check_contracts(&repo, &commit).is_ok());
+
+            Ok(())
+        })
+    }
+
     #[test]
     fn test_stage_and_commit_schema() -> Result<(), OxenError> {
         test::run_training_data_repo_test_no_commits(|repo| {
@@ -0,0 +1,144 @@
+//! # oxen split
+//!
+//! Split a data frame into train, validation and test files
+//!
+
+use std::path::PathBuf;
+
+use polars::prelude::IntoLazy;
+use serde_json::json;
+
+use crate::api;
+use crate::command;
+use crate::core::df::{sample, tabular};
+use crate::error::OxenError;
+use crate::model::LocalRepository;
+use crate::opts::{DFOpts, SplitOpts};
+use crate::util;
+
+/// Writes one file per split next to the input (or into `output_dir`) named `{stem}_{split}.{ext}`,
+/// stages them, and records where they came from in the metadata of the next commit.
+///
+/// Returns the paths of the files that were written.
+pub fn split(repo: &LocalRepository, opts: &SplitOpts) -> Result<Vec<PathBuf>, OxenError> {
+    let names = opts.split_names()?;
+    let path = repo.path.join(&opts.path);
+    if !path.exists() {
+        return Err(OxenError::path_does_not_exist(path));
+    }
+
+    let (Some(stem), Some(extension)) = (path.file_stem(), path.extension()) else {
+        return Err(OxenError::basic_str(format!(
+            "Cannot split {:?}, expected a file with an extension",
+            opts.path
+        )));
+    };
+    let output_dir = match &opts.output_dir {
+        Some(dir) => repo.path.join(dir),
+        None => path.parent().unwrap_or(&repo.path).to_path_buf(),
+    };
+    util::fs::create_dir_all(&output_dir)?;
+
+    // Always record a seed so that the split can be reproduced
+    let seed = opts.seed.unwrap_or_else(rand::random);
+    let df = tabular::read_df(&path, DFOpts::empty())?;
+    let splits = sample::split_indices(&df, &opts.ratios, opts.group_by.as_deref(), Some(seed))?;
+
+    let mut outputs: Vec<PathBuf> = vec![];
+    let mut provenance: Vec<serde_json::Value> = vec![];
+    for (name, indices) in names.iter().zip(splits) {
+        let filename = format!(
+            "{}_{}.{}",
+            stem.to_string_lossy(),
+            name,
+            extension.to_string_lossy()
+        );
+        let output = output_dir.join(filename);
+        let num_rows = indices.len();
+        let mut split_df = tabular::take(df.clone().lazy(), indices)?;
+        tabular::write_df(&mut split_df, &output)?;
+        command::add(repo, &output)?;
+
+        log::debug!("split wrote {} rows to {:?}", num_rows, output);
+        provenance.push(json!({
+            "name": name,
+            "path": util::fs::path_relative_to_dir(&output, &repo.path)?,
+            "num_rows": num_rows,
+        }));
+        outputs.push(output);
+    }
+
+    let head = api::local::commits::head_commit(repo)?;
+    let metadata = json!({
+        "source": util::fs::path_relative_to_dir(&path, &repo.path)?,
+        "source_hash": util::hasher::hash_file_contents(&path)?,
+        "source_commit_id": head.id,
+        "ratios": opts.ratios,
+        "seed": seed,
+        "group_by": opts.group_by,
+        "outputs": provenance,
+    });
+    api::local::commits::add_staged_metadata(repo, "split", metadata)?;
+
+    Ok(outputs)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::command;
+    use crate::core::df::tabular;
+    use crate::error::OxenError;
+    use crate::opts::{DFOpts, SplitOpts};
+    use crate::test;
+    use crate::util;
+
+    #[test]
+    fn test_command_split_stages_files_and_records_provenance() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            let path = repo.path.join("data.csv");
+            let mut contents = String::from("id,user\n");
+            for i in 0..20 {
+                contents.push_str(&format!("{},{}\n", i, i / 2));
+            }
+            util::fs::write_to_path(&path, &contents)?;
+            command::add(&repo, &path)?;
+            command::commit(&repo, "Adding data")?;
+
+            let mut opts = SplitOpts::from_path_ratios("data.csv", vec![0.5, 0.25, 0.25]);
+            opts.group_by = Some(String::from("user"));
+            opts.seed = Some(42);
+            let outputs = command::split(&repo, &opts)?;
+            assert_eq!(outputs.len(), 3);
+            assert_eq!(outputs[0], repo.path.join("data_train.csv"));
+            assert_eq!(outputs[2], repo.path.join("data_test.csv"));
+
+            let total: usize = outputs
+                .iter()
+                .map(|p| tabular::read_df(p, DFOpts::empty()).unwrap().height())
+                .sum();
+            assert_eq!(total, 20);
+
+            let status = command::status(&repo)?;
+            assert_eq!(status.staged_files.len(), 3);
+            assert!(status.staged_files.contains_key(Path::new("data_val.csv")));
+
+            let commit = command::commit(&repo, "Splitting data")?;
+            let metadata = commit.metadata.unwrap();
+            assert_eq!(metadata["split"]["source"], "data.csv");
+            assert_eq!(metadata["split"]["seed"], 42);
+            assert_eq!(metadata["split"]["group_by"], "user");
+            assert_eq!(metadata["split"]["outputs"][1]["name"], "val");
+
+            // Only the next commit gets the metadata
+            let path = repo.path.join("other.txt");
+            util::fs::write_to_path(&path, "other")?;
+            command::add(&repo, &path)?;
+            let commit = command::commit(&repo, "Adding other")?;
+            assert!(commit.metadata.is_none());
+
+            Ok(())
+        })
+    }
+}
@@ -57,10 +57,18 @@ pub const RIGHT_COMPARE_COMMIT: &str = "RIGHT";
 pub const STATS_DIR: &str = "stats";
 /// prefix for the staged dirs
 pub const STAGED_DIR: &str = "staged";
+/// name of the db of files staged with `oxen mv`, new path -> original path
+pub const MOVES_DIR: &str = "moves";
+/// key in the commit metadata that lists the files moved in the commit
+pub const COMMIT_MOVES_METADATA_KEY: &str = "moves";
 /// Name of the table in the duckdb db used for remote staging
 pub const TABLE_NAME: &str = "STAGED_DATA";
 /// Oxen's internal id column in duckdb remote staging tables
 pub const OXEN_ID_COL: &str = "_oxen_id";
+/// Oxen's internal column recording a row's position in the committed file, used to diff updated rows
+pub const OXEN_ROW_IDX_COL: &str = "_oxen_row_idx";
+/// Sequence that hands out row indices for rows appended to duckdb remote staging tables
+pub const OXEN_ROW_IDX_SEQ: &str = "oxen_row_idx_seq";
 /// prefix for the sync status dirs to tell if commits are synced locally
 pub const SYNC_STATUS_DIR: &str = "sync_status";
 /// Flag for if the repository was cloned in a shallow fashion
@@ -85,6 +93,8 @@ pub const OBJECT_SCHEMAS_DIR: &str = "schemas";
 pub const VERSION_FILE_NAME: &str = "data";
 /// merge/ is where any merge conflicts are stored so that we can get rid of them
 pub const MERGE_DIR: &str = "merge";
+/// merge_requests/ is where the merge requests opened against a repository are stored
+pub const MERGE_REQUESTS_DIR: &str = "merge_requests";
 /// mods/ is where we can stage appends, modifications, deletions to files to be merged later
 pub const MODS_DIR: &str = "mods";
 /// data.arrow
@@ -94,6 +104,8 @@ pub const DATA_ARROW_FILE: &str = "data.arrow";
 pub const MERGE_HEAD_FILE: &str = "MERGE_HEAD";
 /// if we have merge conflicts we write to MERGE_HEAD and ORIG_HEAD to keep track of the parents
 pub const ORIG_HEAD_FILE: &str = "ORIG_HEAD";
+/// metadata to record on the next commit, ie. the provenance of generated files
+pub const COMMIT_METADATA_FILE: &str = "COMMIT_METADATA";
 
 /// Key for hash of the file
 pub const HASH_FILE: &str = "HASH";
@@ -127,6 +139,8 @@ pub const TARGETS_HASH_COL: &str = "_targets_hash";
 pub const KEYS_HASH_COL: &str = "_keys_hash";
 // Internal Name When Performing Computation
 pub const DIFF_STATUS_COL: &str = ".oxen.diff.status";
+/// Comma separated names of the target columns that changed in a modified row
+pub const DIFF_CHANGED_COLS_COL: &str = ".oxen.diff.changed_columns";
 
 // Data transfer
 // Average chunk size of ~4mb
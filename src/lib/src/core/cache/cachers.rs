@@ -1,5 +1,6 @@
 pub mod content_stats;
 pub mod content_validator;
 pub mod convert_to_arrow;
+pub mod df_profile;
 pub mod df_size;
 pub mod repo_size;
@@ -0,0 +1,70 @@
+//! Profiles the columns of every tabular file in a commit, so that the stats can be served and
+//! compared across commits without rescanning the files. Profiles are keyed by the hash of the
+//! file, so a file that is in many commits is only profiled once.
+
+use crate::constants::{CACHE_DIR, DATA_FRAMES_DIR};
+use crate::core::df::profile;
+use crate::core::index::CommitEntryReader;
+use crate::error::OxenError;
+use crate::model::{Commit, CommitEntry, DataFrameProfile, LocalRepository};
+use crate::util;
+use std::path::PathBuf;
+
+pub fn compute(repo: &LocalRepository, commit: &Commit) -> Result<(), OxenError> {
+    log::debug!(
+        "Running compute_df_profiles on {:?} for commit {}",
+        repo.path,
+        commit.id
+    );
+
+    let reader = CommitEntryReader::new(repo, commit)?;
+    for entry in reader.list_entries()? {
+        let cache_path = df_profile_cache_path(repo, &entry);
+        if cache_path.exists() {
+            continue;
+        }
+
+        let path = util::fs::version_path(repo, &entry);
+        if !util::fs::is_tabular(&path) {
+            continue;
+        }
+
+        // One file we cannot profile should not stop the rest of the commit from being profiled
+        match profile::profile_path(&path) {
+            Ok(profile) => {
+                if let Some(parent) = cache_path.parent() {
+                    util::fs::create_dir_all(parent)?;
+                }
+                util::fs::write_to_path(&cache_path, serde_json::to_string(&profile)?)?;
+            }
+            Err(err) => {
+                log::error!("Could not profile {:?}: {}", entry.path, err);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Profile from the cache, or computed from the version file if the cacher has not run yet
+pub fn get_cache_for_entry(
+    repo: &LocalRepository,
+    entry: &CommitEntry,
+) -> Result<DataFrameProfile, OxenError> {
+    let cache_path = df_profile_cache_path(repo, entry);
+    if cache_path.exists() {
+        let json = util::fs::read_from_path(&cache_path)?;
+        return Ok(serde_json::from_str(&json)?);
+    }
+
+    log::debug!("df_profile cache miss for {:?}", entry.path);
+    profile::profile_path(util::fs::version_path(repo, entry))
+}
+
+fn df_profile_cache_path(repo: &LocalRepository, entry: &CommitEntry) -> PathBuf {
+    util::fs::oxen_hidden_dir(&repo.path)
+        .join(CACHE_DIR)
+        .join(DATA_FRAMES_DIR)
+        .join("profiles")
+        .join(format!("{}.json", entry.hash))
+}
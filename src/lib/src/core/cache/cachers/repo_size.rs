@@ -210,6 +210,9 @@ pub fn compute(repo: &LocalRepository, commit: &Commit) -> Result<(), OxenError>
         }
     }
 
+    // Correct any drift in the usage that was counted as data was pushed
+    api::local::usage::reset(repo)?;
+
     Ok(())
 }
 
@@ -8,10 +8,17 @@ use crate::error::OxenError;
 use crate::model::{Commit, LocalRepository};
 use crate::util;
 
-use super::cachers::{content_stats, content_validator, df_size, repo_size};
+use super::cachers::{content_stats, content_validator, df_profile, df_size, repo_size};
 use lazy_static::lazy_static;
 use rocksdb::{DBWithThreadMode, MultiThreaded};
-use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use time::{Duration, OffsetDateTime};
+
+/// How long a LOCK file is honored, after that the process that wrote it is assumed to have died
+pub const LOCK_TIMEOUT: Duration = Duration::hours(6);
 
 type CommitCacher = fn(&LocalRepository, &Commit) -> Result<(), OxenError>;
 
@@ -23,10 +30,33 @@ lazy_static! {
         (String::from("REPO_SIZE"), repo_size::compute as CommitCacher),
         (String::from("COMMIT_STATS"), content_stats::compute as CommitCacher),
         (String::from("DF_SIZE"), df_size::compute as CommitCacher),
+        (String::from("DF_PROFILE"), df_profile::compute as CommitCacher),
         // (String::from("ARROW_CONVERSION"), convert_to_arrow::convert_to_arrow as CommitCacher),
     ];
 
+    /// How many times each cacher has succeeded or failed since the process started
+    static ref RUN_COUNTS: Mutex<HashMap<String, CacherRunCounts>> = Mutex::new(HashMap::new());
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CacherRunCounts {
+    pub successes: u64,
+    pub failures: u64,
+}
+
+fn record_run(name: &str, success: bool) {
+    let mut counts = RUN_COUNTS.lock().unwrap();
+    let count = counts.entry(name.to_string()).or_default();
+    if success {
+        count.successes += 1;
+    } else {
+        count.failures += 1;
+    }
+}
 
+/// Successes and failures of each cacher run by `run_all` in this process, keyed by cacher name
+pub fn run_counts() -> HashMap<String, CacherRunCounts> {
+    RUN_COUNTS.lock().unwrap().clone()
 }
 
 fn cached_status_db_path(repo: &LocalRepository, commit: &Commit) -> PathBuf {
@@ -37,6 +67,14 @@ fn cached_status_db_path(repo: &LocalRepository, commit: &Commit) -> PathBuf {
         .join("status.db")
 }
 
+/// Written to the LOCK file so that a lock left behind by a crash can be told apart
+#[derive(Serialize, Deserialize, Debug)]
+struct CacherLock {
+    pid: u32,
+    #[serde(with = "time::serde::rfc3339")]
+    created_at: OffsetDateTime,
+}
+
 fn cached_status_lock_path(repo: &LocalRepository, commit: &Commit) -> PathBuf {
     util::fs::oxen_hidden_dir(&repo.path)
         .join(HISTORY_DIR)
@@ -163,10 +201,14 @@ pub fn run_all(repo: &LocalRepository, commit: &Commit, force: bool) -> Result<(
     let lock_path = cached_status_lock_path(repo, commit);
     log::warn!("run_all called on commit {} force? {}", commit, force);
 
-    // If the LOCK exists we should not be running this again
+    // If the LOCK exists we should not be running this again, unless it is left from a crash
     if lock_path.exists() {
-        log::warn!("run_all LOCK file exists...skipping {:?}", lock_path);
-        return Err(OxenError::basic_str("Already processing"));
+        if !is_stale_lock(&lock_path)? {
+            log::warn!("run_all LOCK file exists...skipping {:?}", lock_path);
+            return Err(OxenError::basic_str("Already processing"));
+        }
+        log::warn!("run_all removing stale LOCK file {:?}", lock_path);
+        util::fs::remove_file(&lock_path)?;
     }
 
     // Create parent dir if not exists
@@ -176,8 +218,38 @@ pub fn run_all(repo: &LocalRepository, commit: &Commit, force: bool) -> Result<(
         }
     }
     log::debug!("run_all Creating lock file {:?}", lock_path);
-    util::fs::write_to_path(&lock_path, "LOCK")?;
+    let lock = CacherLock {
+        pid: std::process::id(),
+        created_at: OffsetDateTime::now_utc(),
+    };
+    util::fs::write_to_path(&lock_path, serde_json::to_string(&lock)?)?;
+
+    // Delete the LOCK file whether or not the cachers succeeded, so the commit can be retried
+    let result = run_cachers(repo, commit, force);
+    log::debug!("run_all Deleting lock file {:?}", lock_path);
+    util::fs::remove_file(lock_path)?;
+
+    result
+}
 
+// A lock is stale once it is older than `LOCK_TIMEOUT`. Locks from before the pid and time were
+// written fall back to the modified time of the file.
+fn is_stale_lock(lock_path: &Path) -> Result<bool, OxenError> {
+    let contents = util::fs::read_from_path(lock_path)?;
+    let created_at = match serde_json::from_str::<CacherLock>(&contents) {
+        Ok(lock) => {
+            // Another worker in this process is still running the cachers
+            if lock.pid == std::process::id() {
+                return Ok(false);
+            }
+            lock.created_at
+        }
+        Err(_) => OffsetDateTime::from(std::fs::metadata(lock_path)?.modified()?),
+    };
+    Ok(OffsetDateTime::now_utc() - created_at > LOCK_TIMEOUT)
+}
+
+fn run_cachers(repo: &LocalRepository, commit: &Commit, force: bool) -> Result<(), OxenError> {
     // Create kvdb of NAME -> STATUS
     let db: DBWithThreadMode<MultiThreaded> = get_db_connection(repo, commit)?;
 
@@ -200,6 +272,7 @@ pub fn run_all(repo: &LocalRepository, commit: &Commit, force: bool) -> Result<(
             Ok(_) => {
                 let status_success = CacherStatus::success();
                 str_json_db::put(&db, name, &status_success)?;
+                record_run(name, true);
                 log::debug!("run_all done running {:?}", name);
             }
             Err(err) => {
@@ -207,12 +280,48 @@ pub fn run_all(repo: &LocalRepository, commit: &Commit, force: bool) -> Result<(
                 log::error!("{}", err);
                 let status_failed = CacherStatus::failed(&err);
                 str_json_db::put(&db, name, &status_failed)?;
+                record_run(name, false);
             }
         }
     }
-    // Delete the LOCK file
-    log::debug!("run_all Deleting lock file {:?}", lock_path);
-    util::fs::remove_file(lock_path)?;
-
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use time::OffsetDateTime;
+
+    use crate::api;
+    use crate::core::cache::commit_cacher::{self, CacherLock, LOCK_TIMEOUT};
+    use crate::error::OxenError;
+    use crate::test;
+    use crate::util;
+
+    #[test]
+    fn test_commit_cacher_removes_stale_lock() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            let commit = api::local::commits::head_commit(&repo)?;
+            let lock_path = commit_cacher::cached_status_lock_path(&repo, &commit);
+            util::fs::create_dir_all(lock_path.parent().unwrap())?;
+
+            // A recent lock from another process is still honored
+            let lock = CacherLock {
+                pid: std::process::id() + 1,
+                created_at: OffsetDateTime::now_utc(),
+            };
+            util::fs::write_to_path(&lock_path, serde_json::to_string(&lock)?)?;
+            assert!(commit_cacher::run_all(&repo, &commit, false).is_err());
+
+            // One that outlived the timeout was left by a crash
+            let lock = CacherLock {
+                pid: std::process::id() + 1,
+                created_at: OffsetDateTime::now_utc() - LOCK_TIMEOUT - time::Duration::minutes(1),
+            };
+            util::fs::write_to_path(&lock_path, serde_json::to_string(&lock)?)?;
+            commit_cacher::run_all(&repo, &commit, false)?;
+            assert!(!lock_path.exists());
+
+            Ok(())
+        })
+    }
+}
@@ -1,12 +1,11 @@
 //! Abstraction over DuckDB database to write and read dataframes from disk.
 //!
 
-use crate::constants::{DEFAULT_PAGE_SIZE, OXEN_ID_COL};
+use crate::constants::{DEFAULT_PAGE_SIZE, OXEN_ID_COL, OXEN_ROW_IDX_COL};
 use crate::core::db::df_db;
 use crate::core::df::tabular;
 use crate::error::OxenError;
-use crate::model;
-use crate::model::schema::Field;
+use crate::model::schema::{DataType, Field};
 use crate::model::Schema;
 use crate::opts::DFOpts;
 use duckdb::arrow::record_batch::RecordBatch;
@@ -76,9 +75,38 @@ fn p_create_table_if_not_exists(
     let sql = format!("CREATE TABLE IF NOT EXISTS {} (\n{});", table_name, columns);
     log::debug!("create_table sql: {}", sql);
     conn.execute(&sql, [])?;
+
+    // TIMESTAMPTZ is stored in UTC, so the time zone and unit are kept in a comment on the column
+    for field in fields {
+        if let DataType::Datetime(_, Some(_)) = DataType::from_string(&field.dtype) {
+            let sql = format!(
+                "COMMENT ON COLUMN {}.{} IS '{}';",
+                table_name,
+                field.name,
+                field.dtype.replace('\'', "''")
+            );
+            conn.execute(&sql, [])?;
+        }
+    }
     Ok(table_name.to_owned())
 }
 
+// The data type of a column, from the comment on the column for timestamps with a time zone
+fn field_from_sql(column_name: &str, data_type: &str, comment: Option<String>) -> Field {
+    let dtype = match (DataType::from_sql(data_type), comment) {
+        (DataType::Datetime(_, Some(_)), Some(comment))
+            if matches!(
+                DataType::from_string(&comment),
+                DataType::Datetime(_, Some(_))
+            ) =>
+        {
+            comment
+        }
+        (dtype, _) => dtype.to_string(),
+    };
+    Field::new(column_name, &dtype)
+}
+
 /// Get the schema from the table.
 pub fn get_schema(
     conn: &duckdb::Connection,
@@ -86,7 +114,7 @@ pub fn get_schema(
 ) -> Result<Schema, OxenError> {
     let table_name = table_name.as_ref();
     let sql = format!(
-        "SELECT column_name, data_type FROM information_schema.columns WHERE table_name == '{}'",
+        "SELECT column_name, data_type, comment FROM duckdb_columns() WHERE table_name == '{}' ORDER BY column_index",
         table_name
     );
     let mut stmt = conn.prepare(&sql)?;
@@ -95,16 +123,14 @@ pub fn get_schema(
     let rows = stmt.query_map([], |row| {
         let column_name: String = row.get(0)?;
         let data_type: String = row.get(1)?;
+        let comment: Option<String> = row.get(2)?;
 
-        Ok((column_name, data_type))
+        Ok((column_name, data_type, comment))
     })?;
 
     for row in rows {
-        let (column_name, data_type) = row?;
-        fields.push(Field::new(
-            &column_name,
-            model::schema::DataType::from_sql(data_type).as_str(),
-        ));
+        let (column_name, data_type, comment) = row?;
+        fields.push(field_from_sql(&column_name, &data_type, comment));
     }
 
     Ok(Schema::new(table_name, fields))
@@ -116,8 +142,8 @@ pub fn get_schema_without_id(
 ) -> Result<Schema, OxenError> {
     let table_name = table_name.as_ref();
     let sql = format!(
-        "SELECT column_name, data_type FROM information_schema.columns WHERE table_name == '{}' AND column_name != '{}'",
-        table_name, OXEN_ID_COL
+        "SELECT column_name, data_type, comment FROM duckdb_columns() WHERE table_name == '{}' AND column_name NOT IN ('{}', '{}') ORDER BY column_index",
+        table_name, OXEN_ID_COL, OXEN_ROW_IDX_COL
     );
     let mut stmt = conn.prepare(&sql)?;
 
@@ -129,16 +155,14 @@ pub fn get_schema_without_id(
     let rows = stmt.query_map([], |row| {
         let column_name: String = row.get(0)?;
         let data_type: String = row.get(1)?;
+        let comment: Option<String> = row.get(2)?;
 
-        Ok((column_name, data_type))
+        Ok((column_name, data_type, comment))
     })?;
 
     for row in rows {
-        let (column_name, data_type) = row?;
-        fields.push(Field::new(
-            &column_name,
-            model::schema::DataType::from_sql(data_type).as_str(),
-        ));
+        let (column_name, data_type, comment) = row?;
+        fields.push(field_from_sql(&column_name, &data_type, comment));
     }
 
     Ok(Schema::new(table_name, fields))
@@ -185,6 +209,15 @@ pub fn count_where(
 
 /// Select fields from a table.
 pub fn select(conn: &duckdb::Connection, stmt: &sql::Select) -> Result<DataFrame, OxenError> {
+    select_with_params(conn, stmt, &[])
+}
+
+/// Select fields from a table, binding `params` to the `?` placeholders in the statement.
+pub fn select_with_params(
+    conn: &duckdb::Connection,
+    stmt: &sql::Select,
+    params: &[&dyn ToSql],
+) -> Result<DataFrame, OxenError> {
     let sql = stmt.as_string();
     log::debug!("select sql: {}", sql);
     let mut stmt = conn.prepare(&sql)?;
@@ -192,7 +225,7 @@ pub fn select(conn: &duckdb::Connection, stmt: &sql::Select) -> Result<DataFrame
     // let pl: Vec<DataFrame> = stmt.query_polars([])?.collect();
     // let df = accumulate_dataframes_vertical_unchecked(pl);
 
-    let records: Vec<RecordBatch> = stmt.query_arrow([])?.collect();
+    let records: Vec<RecordBatch> = stmt.query_arrow(params)?.collect();
     log::debug!("got records: {:?}", records.len());
 
     if records.is_empty() {
@@ -414,4 +447,28 @@ mod tests {
             Ok(())
         })
     }
+
+    #[test]
+    fn test_df_db_get_schema_nested_and_temporal() -> Result<(), OxenError> {
+        test::run_empty_dir_test(|data_dir| {
+            let db_file = data_dir.join("data.db");
+            let conn = get_connection(db_file)?;
+            let schema = Schema::new(
+                "events",
+                vec![
+                    Field::new("day", "date"),
+                    Field::new("seen_at", "datetime[μs]"),
+                    Field::new("sent_at", "datetime[ms, America/New_York]"),
+                    Field::new("scores", "list[i64]"),
+                    Field::new("bbox", "struct[x: f64, label: str]"),
+                ],
+            );
+            create_table_if_not_exists(&conn, &schema)?;
+
+            let found_schema = get_schema(&conn, "events")?;
+            assert_eq!(found_schema, schema);
+
+            Ok(())
+        })
+    }
 }
@@ -1,8 +1,10 @@
+use duckdb::{params, ToSql};
 use polars::frame::DataFrame;
 // use sql::Select;
 use sql_query_builder as sql;
 
-use crate::constants::OXEN_ID_COL;
+use crate::constants::{OXEN_ID_COL, OXEN_ROW_IDX_COL};
+use crate::core::df::tabular;
 
 use crate::{constants::TABLE_NAME, error::OxenError};
 
@@ -30,7 +32,7 @@ pub fn append_row(
 
     let inserted_df = df_db::insert_polars_df(conn, TABLE_NAME, df)?;
 
-    Ok(inserted_df)
+    drop_row_idx(inserted_df)
 
     // Proceed with appending `new_df` to the database
 }
@@ -38,21 +40,111 @@ pub fn append_row(
 pub fn delete_row(conn: &duckdb::Connection, uuid: &str) -> Result<DataFrame, OxenError> {
     let stmt = sql::Delete::new()
         .delete_from(TABLE_NAME)
-        .where_clause(&format!("{} = '{}'", OXEN_ID_COL, uuid));
+        .where_clause(&format!("{} = ?", OXEN_ID_COL));
 
+    // Select first - duckdb does't support DELETE RETURNING
+    let maybe_row = select_row(conn, uuid)?;
+
+    log::debug!("staged_df_db::delete_row() sql: {:?}", stmt);
+    conn.execute(&stmt.to_string(), params![uuid])?;
+    Ok(maybe_row)
+}
+
+/// Updates the columns present in `df` for the row with the given id, leaving the rest as they were.
+pub fn update_row(
+    conn: &duckdb::Connection,
+    uuid: &str,
+    df: &DataFrame,
+) -> Result<DataFrame, OxenError> {
+    if df.height() != 1 {
+        return Err(OxenError::basic_str(format!(
+            "Can only update one row at a time, got {} rows",
+            df.height()
+        )));
+    }
+
+    let table_schema = df_db::get_schema_without_id(conn, TABLE_NAME)?;
+    // The id can come back in the body if the client echoes a row it fetched, it is never updated
+    let col_names: Vec<String> = df
+        .get_column_names()
+        .iter()
+        .filter(|name| **name != OXEN_ID_COL)
+        .map(|name| name.to_string())
+        .collect();
+
+    if col_names.is_empty() {
+        return Err(OxenError::basic_str("No columns to update"));
+    }
+
+    if !table_schema.has_field_names(&col_names) {
+        return Err(OxenError::incompatible_schemas(&col_names, table_schema));
+    }
+
+    // Make sure the row exists so a bad id is a 404 rather than a silent no-op
+    select_row(conn, uuid)?;
+
+    let set_clause = col_names
+        .iter()
+        .map(|name| format!("\"{}\" = ?", name))
+        .collect::<Vec<String>>()
+        .join(", ");
+    let stmt = sql::Update::new()
+        .update(TABLE_NAME)
+        .set(&set_clause)
+        .where_clause(&format!("{} = ?", OXEN_ID_COL));
+    log::debug!("staged_df_db::update_row() sql: {:?}", stmt);
+
+    let boxed_values: Vec<Box<dyn ToSql>> = col_names
+        .iter()
+        .map(|name| -> Result<Box<dyn ToSql>, OxenError> {
+            let value = df.column(name)?.get(0)?;
+            Ok(tabular::value_to_tosql(value))
+        })
+        .collect::<Result<Vec<_>, OxenError>>()?;
+    let mut params: Vec<&dyn ToSql> = boxed_values
+        .iter()
+        .map(|boxed_value| &**boxed_value as &dyn ToSql)
+        .collect();
+    // The id binds to the placeholder in the where clause, after the set values
+    params.push(&uuid);
+
+    conn.execute(&stmt.to_string(), params.as_slice())?;
+
+    select_row(conn, uuid)
+}
+
+/// Select the rows with the given ids, without the row index column
+pub fn select_rows(conn: &duckdb::Connection, uuids: &[String]) -> Result<DataFrame, OxenError> {
+    if uuids.is_empty() {
+        return Ok(DataFrame::default());
+    }
+    let placeholders = vec!["?"; uuids.len()].join(", ");
     let select_stmt = sql::Select::new()
-        .select("*")
+        .select(&format!("* EXCLUDE ({})", OXEN_ROW_IDX_COL))
         .from(TABLE_NAME)
-        .where_clause(&format!("{} = '{}'", OXEN_ID_COL, uuid));
+        .where_clause(&format!("{} IN ({})", OXEN_ID_COL, placeholders));
+    let params: Vec<&dyn ToSql> = uuids.iter().map(|uuid| uuid as &dyn ToSql).collect();
+    df_db::select_with_params(conn, &select_stmt, params.as_slice())
+}
 
-    // Select first - duckdb does't support DELETE RETURNING
-    let maybe_row = df_db::select(conn, &select_stmt)?;
+/// Select a single row by id, without the row index column
+fn select_row(conn: &duckdb::Connection, uuid: &str) -> Result<DataFrame, OxenError> {
+    let select_stmt = sql::Select::new()
+        .select(&format!("* EXCLUDE ({})", OXEN_ROW_IDX_COL))
+        .from(TABLE_NAME)
+        .where_clause(&format!("{} = ?", OXEN_ID_COL));
+    let maybe_row = df_db::select_with_params(conn, &select_stmt, params![uuid])?;
 
     if maybe_row.height() == 0 {
         return Err(OxenError::resource_not_found(uuid));
     }
-
-    log::debug!("staged_df_db::delete_row() sql: {:?}", stmt);
-    conn.execute(&stmt.to_string(), [])?;
     Ok(maybe_row)
 }
+
+fn drop_row_idx(df: DataFrame) -> Result<DataFrame, OxenError> {
+    if df.get_column_names().contains(&OXEN_ROW_IDX_COL) {
+        Ok(df.drop(OXEN_ROW_IDX_COL)?)
+    } else {
+        Ok(df)
+    }
+}
@@ -2,6 +2,11 @@
 //!
 
 pub mod agg;
+pub mod compressed;
+pub mod excel;
 pub mod filter;
 pub mod pretty_print;
+pub mod profile;
+pub mod sample;
+pub mod sql;
 pub mod tabular;
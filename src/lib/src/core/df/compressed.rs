@@ -0,0 +1,223 @@
+//! Gzip and zstd compressed csv and jsonl files
+//!
+//! The format of the data inside comes from the inner extension, ie: `data.csv.gz`. Version
+//! files only keep the outer extension, ie: `data.gz`, so for those we sniff the start of the
+//! decompressed data.
+
+use std::fs::File;
+use std::io::{Cursor, Read, Write};
+use std::path::{Path, PathBuf};
+
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+
+use crate::constants::VERSION_FILE_NAME;
+use crate::error::OxenError;
+
+const TABULAR_FORMATS: [&str; 5] = ["csv", "tsv", "data", "jsonl", "ndjson"];
+// Bytes of decompressed data to sniff the format and delimiter from
+pub const SNIFF_BYTES: u64 = 1024 * 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Compression {
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Compression> {
+        match path.as_ref().extension().and_then(|ext| ext.to_str()) {
+            Some("gz") => Some(Compression::Gzip),
+            Some("zst") | Some("zstd") => Some(Compression::Zstd),
+            _ => None,
+        }
+    }
+}
+
+/// Streaming reader over the decompressed bytes
+pub fn reader(path: impl AsRef<Path>) -> Result<Box<dyn Read>, OxenError> {
+    let path = path.as_ref();
+    let file = File::open(path)?;
+    match Compression::from_path(path) {
+        Some(Compression::Gzip) => Ok(Box::new(MultiGzDecoder::new(file))),
+        Some(Compression::Zstd) => Ok(Box::new(zstd::stream::read::Decoder::new(file)?)),
+        None => Err(OxenError::basic_str(format!(
+            "Unknown compression for file {path:?}"
+        ))),
+    }
+}
+
+/// Streaming writer that compresses into `path`, finishing the stream when it is dropped
+pub fn writer(path: impl AsRef<Path>) -> Result<Box<dyn Write>, OxenError> {
+    let path = path.as_ref();
+    match Compression::from_path(path) {
+        Some(Compression::Gzip) => Ok(Box::new(GzEncoder::new(
+            File::create(path)?,
+            flate2::Compression::default(),
+        ))),
+        Some(Compression::Zstd) => Ok(Box::new(
+            zstd::stream::write::Encoder::new(File::create(path)?, 0)?.auto_finish(),
+        )),
+        None => Err(OxenError::basic_str(format!(
+            "Unknown compression for file {path:?}"
+        ))),
+    }
+}
+
+/// Up to the first `num_bytes` of the decompressed data
+pub fn head(path: impl AsRef<Path>, num_bytes: u64) -> Result<Vec<u8>, OxenError> {
+    let mut bytes = vec![];
+    reader(path)?.take(num_bytes).read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// A decompressed copy of a file in the temp dir, removed when it is dropped
+pub struct DecompressedFile {
+    pub path: PathBuf,
+}
+
+impl Drop for DecompressedFile {
+    fn drop(&mut self) {
+        if let Err(err) = std::fs::remove_file(&self.path) {
+            log::warn!(
+                "Could not remove decompressed file {:?}: {}",
+                self.path,
+                err
+            );
+        }
+    }
+}
+
+/// Stream the decompressed data into a temp file with the `format` extension, so it can be read
+/// like an uncompressed file without holding the decompressed bytes in memory
+pub fn decompress_to_file(
+    path: impl AsRef<Path>,
+    format: &str,
+) -> Result<DecompressedFile, OxenError> {
+    let temp_path = std::env::temp_dir().join(format!("oxen-{}.{format}", uuid::Uuid::new_v4()));
+    let mut file = File::create(&temp_path)?;
+    // Created before copying so the file is removed if decompressing fails
+    let decompressed = DecompressedFile { path: temp_path };
+    std::io::copy(&mut reader(path)?, &mut file)?;
+    Ok(decompressed)
+}
+
+/// Read the whole file into memory, decompressed
+pub fn decompress(path: impl AsRef<Path>) -> Result<Vec<u8>, OxenError> {
+    let mut bytes = vec![];
+    reader(path)?.read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Tabular format of the compressed data, `None` if it is not a compressed csv or jsonl
+pub fn inner_format(path: impl AsRef<Path>) -> Option<&'static str> {
+    let path = path.as_ref();
+    Compression::from_path(path)?;
+
+    let stem = Path::new(path.file_stem()?);
+    match stem.extension().and_then(|ext| ext.to_str()) {
+        Some(ext) => TABULAR_FORMATS.into_iter().find(|format| *format == ext),
+        None if stem == Path::new(VERSION_FILE_NAME) => sniff_format(path),
+        None => None,
+    }
+}
+
+// Jsonl if the first line is a json object, otherwise tsv or csv by the sniffed delimiter. None
+// if the data is not text or no delimiter can be found.
+fn sniff_format(path: &Path) -> Option<&'static str> {
+    let bytes = head(path, SNIFF_BYTES).ok()?;
+    // The head may end in the middle of a character
+    let text = match std::str::from_utf8(&bytes) {
+        Ok(text) => text,
+        Err(err) if err.error_len().is_none() => {
+            std::str::from_utf8(&bytes[..err.valid_up_to()]).ok()?
+        }
+        Err(_) => return None,
+    };
+    if text.trim().is_empty() || text.contains('\0') {
+        return None;
+    }
+
+    let first_line = text.lines().next().unwrap_or_default();
+    if serde_json::from_str::<serde_json::Value>(first_line).is_ok_and(|value| value.is_object()) {
+        return Some("jsonl");
+    }
+
+    let metadata = qsv_sniffer::Sniffer::new()
+        .sniff_reader(Cursor::new(text.as_bytes()))
+        .ok()?;
+    match metadata.dialect.delimiter {
+        b'\t' => Some("tsv"),
+        _ => Some("csv"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::path::Path;
+
+    use flate2::write::GzEncoder;
+
+    use crate::core::df::compressed::{self, Compression};
+    use crate::error::OxenError;
+    use crate::test;
+
+    #[test]
+    fn test_compressed_inner_format() -> Result<(), OxenError> {
+        assert_eq!(
+            Compression::from_path("train.csv.gz"),
+            Some(Compression::Gzip)
+        );
+        assert_eq!(
+            Compression::from_path("train.jsonl.zst"),
+            Some(Compression::Zstd)
+        );
+        assert_eq!(Compression::from_path("train.csv"), None);
+        assert_eq!(compressed::inner_format(Path::new("train.tar.gz")), None);
+
+        test::run_empty_dir_test(|dir| {
+            // Version files drop the inner extension
+            let path = dir.join("data.gz");
+            let mut encoder = GzEncoder::new(
+                std::fs::File::create(&path)?,
+                flate2::Compression::default(),
+            );
+            encoder.write_all(b"{\"id\": 1}\n{\"id\": 2}\n")?;
+            encoder.finish()?;
+
+            assert_eq!(compressed::inner_format(&path), Some("jsonl"));
+            assert_eq!(compressed::decompress(&path)?.len(), 20);
+            Ok(())
+        })
+    }
+
+    fn write_gz(path: &Path, bytes: &[u8]) -> Result<(), OxenError> {
+        let mut encoder =
+            GzEncoder::new(std::fs::File::create(path)?, flate2::Compression::default());
+        encoder.write_all(bytes)?;
+        encoder.finish()?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_compressed_sniff_version_file_format() -> Result<(), OxenError> {
+        test::run_empty_dir_test(|dir| {
+            let path = dir.join("data.gz");
+            write_gz(&path, b"file\tlabel\na.jpg\tcat\nb.jpg\tdog\nc.jpg\tcow\n")?;
+            assert_eq!(compressed::inner_format(&path), Some("tsv"));
+
+            // Not text, so not assumed to be csv
+            write_gz(&path, &[0, 159, 146, 150, 0, 1, 2, 3])?;
+            assert_eq!(compressed::inner_format(&path), None);
+
+            let decompressed = compressed::decompress_to_file(&path, "csv")?;
+            let decompressed_path = decompressed.path.clone();
+            assert_eq!(std::fs::read(&decompressed_path)?.len(), 8);
+            drop(decompressed);
+            assert!(!decompressed_path.exists());
+
+            Ok(())
+        })
+    }
+}
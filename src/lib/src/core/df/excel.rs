@@ -0,0 +1,85 @@
+//! Excel and OpenDocument spreadsheets
+//!
+//! Reads the first sheet, with the first row as the header. Columns that only hold numbers or
+//! booleans keep their type, anything else is read as a string.
+
+use std::path::Path;
+
+use calamine::{open_workbook_auto, Data, DataType as _, Reader};
+use polars::prelude::*;
+
+use crate::error::OxenError;
+
+pub const EXCEL_EXTENSIONS: [&str; 4] = ["xlsx", "xlsm", "xls", "ods"];
+
+pub fn is_excel(path: impl AsRef<Path>) -> bool {
+    match path.as_ref().extension().and_then(|ext| ext.to_str()) {
+        Some(ext) => EXCEL_EXTENSIONS.contains(&ext.to_lowercase().as_str()),
+        None => false,
+    }
+}
+
+pub fn read_df_excel(path: impl AsRef<Path>) -> Result<DataFrame, OxenError> {
+    let path = path.as_ref();
+    let error = |err: calamine::Error| {
+        OxenError::basic_str(format!("Could not read spreadsheet {path:?}: {err}"))
+    };
+    let mut workbook = open_workbook_auto(path).map_err(error)?;
+    let range = match workbook.worksheet_range_at(0) {
+        Some(range) => range.map_err(error)?,
+        None => return Ok(DataFrame::empty()),
+    };
+
+    let mut rows = range.rows();
+    let header: Vec<String> = match rows.next() {
+        Some(header) => header
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| match cell {
+                Data::Empty => format!("column_{i}"),
+                cell => cell.to_string(),
+            })
+            .collect(),
+        None => return Ok(DataFrame::empty()),
+    };
+
+    let mut columns: Vec<Vec<&Data>> = vec![vec![]; header.len()];
+    for row in rows {
+        for (i, column) in columns.iter_mut().enumerate() {
+            column.push(row.get(i).unwrap_or(&Data::Empty));
+        }
+    }
+
+    let series: Vec<Series> = header
+        .iter()
+        .zip(columns)
+        .map(|(name, cells)| cells_to_series(name, &cells))
+        .collect();
+    Ok(DataFrame::new(series)?)
+}
+
+fn cells_to_series(name: &str, cells: &[&Data]) -> Series {
+    let values = cells.iter().filter(|cell| !cell.is_empty());
+    let values_are = |is: fn(&Data) -> bool| values.clone().all(|cell| is(cell));
+
+    if values_are(|cell| cell.is_int()) {
+        let values: Vec<Option<i64>> = cells.iter().map(|cell| cell.get_int()).collect();
+        Series::new(name, values)
+    } else if values_are(|cell| cell.is_int() || cell.is_float()) {
+        let values: Vec<Option<f64>> = cells.iter().map(|cell| cell.as_f64()).collect();
+        Series::new(name, values)
+    } else if values_are(|cell| cell.is_bool()) {
+        let values: Vec<Option<bool>> = cells.iter().map(|cell| cell.get_bool()).collect();
+        Series::new(name, values)
+    } else {
+        let values: Vec<Option<String>> = cells
+            .iter()
+            .map(|cell| match cell {
+                Data::Empty => None,
+                Data::DateTime(_) => cell.as_datetime().map(|date| date.to_string()),
+                cell => Some(cell.to_string()),
+            })
+            .collect();
+        Series::new(name, values)
+    }
+}
@@ -1,6 +1,17 @@
-use crate::error::OxenError;
+/// # Filters
+/// label == person && (min_x > 0 || label in [cat, dog]) && caption is not null
+use chrono::{NaiveDate, NaiveDateTime};
+use nom::{
+    branch::alt,
+    bytes::complete::{tag, tag_no_case, take_while, take_while1},
+    character::complete::{char, multispace0, multispace1},
+    combinator::{all_consuming, map, map_res},
+    multi::{many0, separated_list0},
+    sequence::{delimited, pair, preceded, terminated, tuple},
+    IResult,
+};
 
-use crate::util;
+use crate::error::OxenError;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum DFLogicalOp {
@@ -9,14 +20,6 @@ pub enum DFLogicalOp {
 }
 
 impl DFLogicalOp {
-    pub fn from_str_op(s: &str) -> DFLogicalOp {
-        match s {
-            "&&" => DFLogicalOp::AND,
-            "||" => DFLogicalOp::OR,
-            _ => panic!("Unknown DFLogicalOp"),
-        }
-    }
-
     pub fn as_str(&self) -> &'static str {
         match self {
             DFLogicalOp::AND => "&&",
@@ -33,21 +36,14 @@ pub enum DFFilterOp {
     GTE,
     LTE,
     NEQ,
+    In,
+    Contains,
+    Regex,
+    IsNull,
+    IsNotNull,
 }
 
 impl DFFilterOp {
-    pub fn from_str_op(s: &str) -> DFFilterOp {
-        match s {
-            "==" => DFFilterOp::EQ,
-            "<" => DFFilterOp::LT,
-            ">" => DFFilterOp::GT,
-            "<=" => DFFilterOp::LTE,
-            ">=" => DFFilterOp::GTE,
-            "!=" => DFFilterOp::NEQ,
-            _ => panic!("Unknown DFFilterOp"),
-        }
-    }
-
     pub fn as_str(&self) -> &'static str {
         match self {
             DFFilterOp::EQ => "==",
@@ -56,126 +52,268 @@ impl DFFilterOp {
             DFFilterOp::LTE => "<=",
             DFFilterOp::GTE => ">=",
             DFFilterOp::NEQ => "!=",
+            DFFilterOp::In => "in",
+            DFFilterOp::Contains => "contains",
+            DFFilterOp::Regex => "=~",
+            DFFilterOp::IsNull => "is null",
+            DFFilterOp::IsNotNull => "is not null",
         }
     }
 }
 
-#[derive(Clone, Debug)]
+/// The right hand side of a filter. Strings are converted to the type of the column they are
+/// compared against when the filter is applied.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DFFilterValue {
+    Null,
+    Str(String),
+    Date(NaiveDate),
+    Datetime(NaiveDateTime),
+    List(Vec<DFFilterValue>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
 pub struct DFFilterVal {
     pub op: DFFilterOp,
     pub field: String,
-    pub value: String,
-}
-
-#[derive(Clone, Debug)]
-pub struct DFFilterExp {
-    // logical ops are all the ops we want to chain "&&","||"
-    pub logical_ops: Vec<DFLogicalOp>,
-    // vals are all the sub expressions like "label != person"
-    pub vals: Vec<DFFilterVal>,
-}
-
-// Scan query to find the first logical op if there is one
-fn find_next_logical_op(
-    query: &str,
-    logical_ops: &Vec<DFLogicalOp>,
-) -> Option<(DFLogicalOp, usize)> {
-    let mut logical_op: Option<(DFLogicalOp, usize)> = None;
-    let mut min_idx = query.len();
-    for op in logical_ops {
-        if let Some(idx) = query.find(op.as_str()) {
-            if idx < min_idx {
-                logical_op = Some((op.to_owned(), idx));
-                min_idx = idx;
-            }
-        }
+    pub value: DFFilterValue,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DFFilterExp {
+    // a single sub expression like "label != person"
+    Val(DFFilterVal),
+    // "!(expr)"
+    Not(Box<DFFilterExp>),
+    // two expressions chained with "&&" or "||"
+    Logical(DFLogicalOp, Box<DFFilterExp>, Box<DFFilterExp>),
+}
+
+/// Parses "2023-01-31", "2023-01-31 12:30:00" or "2023-01-31T12:30:00"
+pub fn parse_datetime(s: &str) -> Option<NaiveDateTime> {
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return date.and_hms_opt(0, 0, 0);
     }
-    logical_op
+    NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S")
+        .or_else(|_| NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S"))
+        .ok()
 }
 
-/// Can parse an expression such as "pred_label == person && is_correct == true"
-pub fn parse(query: Option<String>) -> Result<Option<DFFilterExp>, OxenError> {
-    if let Some(mut filter) = query {
-        if filter.is_empty() {
-            return Err(OxenError::parse_error(filter));
-        }
+fn ws<'a, O, F>(inner: F) -> impl FnMut(&'a str) -> IResult<&'a str, O>
+where
+    F: FnMut(&'a str) -> IResult<&'a str, O>,
+{
+    delimited(multispace0, inner, multispace0)
+}
+
+// Anything that is not whitespace or part of the syntax can be used without quotes
+fn is_bare_char(c: char) -> bool {
+    !c.is_whitespace() && !"()[],'\"!=<>&|~".contains(c)
+}
 
-        // 1) Iterate over string finding DFLogicalOps and collecting the sub expressions
-        let mut found_ops: Vec<DFLogicalOp> = vec![];
-        let mut sub_exprs: Vec<String> = vec![];
-        let candidate_ops = vec![DFLogicalOp::AND, DFLogicalOp::OR];
+fn quoted(input: &str) -> IResult<&str, &str> {
+    alt((
+        delimited(char('\''), take_while(|c| c != '\''), char('\'')),
+        delimited(char('"'), take_while(|c| c != '"'), char('"')),
+    ))(input)
+}
 
-        while let Some((op, idx)) = find_next_logical_op(&filter, &candidate_ops) {
-            // split at idx
-            let op_str = op.as_str();
-            let (sub_expr, remaining) = filter.split_at(idx);
-            let (_, remaining) = remaining.split_at(op_str.len());
+fn word(input: &str) -> IResult<&str, &str> {
+    alt((quoted, take_while1(is_bare_char)))(input)
+}
 
-            // add sub_expr to eval later
-            sub_exprs.push(sub_expr.trim().to_string());
+fn date_lit(input: &str) -> IResult<&str, DFFilterValue> {
+    map_res(
+        preceded(
+            tag_no_case("date"),
+            delimited(ws(char('(')), word, ws(char(')'))),
+        ),
+        |s| NaiveDate::parse_from_str(s, "%Y-%m-%d").map(DFFilterValue::Date),
+    )(input)
+}
 
-            // add op
-            found_ops.push(op);
-            filter = remaining.to_string();
-        }
+fn datetime_lit(input: &str) -> IResult<&str, DFFilterValue> {
+    map_res(
+        preceded(
+            tag_no_case("datetime"),
+            delimited(ws(char('(')), word, ws(char(')'))),
+        ),
+        |s| parse_datetime(s).map(DFFilterValue::Datetime).ok_or(s),
+    )(input)
+}
 
-        // push remaining
-        sub_exprs.push(filter.trim().to_string());
-
-        // 2) Parse each sub expression
-        // Order in which we check matters because some ops are substrings of others, put the longest ones first
-        let filter_ops = [
-            DFFilterOp::NEQ,
-            DFFilterOp::GTE,
-            DFFilterOp::LTE,
-            DFFilterOp::EQ,
-            DFFilterOp::GT,
-            DFFilterOp::LT,
-        ];
-
-        let mut filter_vals: Vec<DFFilterVal> = vec![];
-        for sub_expr in sub_exprs {
-            for op in filter_ops.iter() {
-                if sub_expr.contains(op.as_str()) {
-                    let split = util::str::split_and_trim(&sub_expr, op.as_str());
-                    let val = DFFilterVal {
-                        op: op.clone(),
-                        field: split[0].to_owned(),
-                        value: split[1].to_owned(),
-                    };
-                    filter_vals.push(val);
-                    break;
-                }
-            }
-        }
+fn scalar(input: &str) -> IResult<&str, DFFilterValue> {
+    alt((
+        datetime_lit,
+        date_lit,
+        map(word, |s| DFFilterValue::Str(s.to_string())),
+    ))(input)
+}
+
+fn list(input: &str) -> IResult<&str, DFFilterValue> {
+    map(
+        delimited(
+            ws(char('[')),
+            separated_list0(ws(char(',')), scalar),
+            ws(char(']')),
+        ),
+        DFFilterValue::List,
+    )(input)
+}
+
+fn compare_op(input: &str) -> IResult<&str, DFFilterOp> {
+    alt((
+        map(tag("=="), |_| DFFilterOp::EQ),
+        map(tag("!="), |_| DFFilterOp::NEQ),
+        map(tag("=~"), |_| DFFilterOp::Regex),
+        map(tag("<="), |_| DFFilterOp::LTE),
+        map(tag(">="), |_| DFFilterOp::GTE),
+        map(tag("<"), |_| DFFilterOp::LT),
+        map(tag(">"), |_| DFFilterOp::GT),
+    ))(input)
+}
+
+fn predicate(input: &str) -> IResult<&str, DFFilterExp> {
+    let (input, field) = ws(word)(input)?;
+    let (input, (op, value)) = terminated(
+        alt((
+            map(
+                tuple((
+                    tag_no_case("is"),
+                    multispace1,
+                    tag_no_case("not"),
+                    multispace1,
+                    tag_no_case("null"),
+                )),
+                |_| (DFFilterOp::IsNotNull, DFFilterValue::Null),
+            ),
+            map(
+                tuple((tag_no_case("is"), multispace1, tag_no_case("null"))),
+                |_| (DFFilterOp::IsNull, DFFilterValue::Null),
+            ),
+            pair(map(tag_no_case("in"), |_| DFFilterOp::In), list),
+            pair(
+                map(pair(tag_no_case("contains"), multispace1), |_| {
+                    DFFilterOp::Contains
+                }),
+                scalar,
+            ),
+            pair(compare_op, ws(scalar)),
+        )),
+        multispace0,
+    )(input)?;
+
+    let val = DFFilterVal {
+        op,
+        field: field.to_string(),
+        value,
+    };
+    Ok((input, DFFilterExp::Val(val)))
+}
+
+fn not_expr(input: &str) -> IResult<&str, DFFilterExp> {
+    map(preceded(ws(char('!')), primary), |exp| {
+        DFFilterExp::Not(Box::new(exp))
+    })(input)
+}
+
+fn primary(input: &str) -> IResult<&str, DFFilterExp> {
+    alt((
+        delimited(ws(char('(')), or_expr, ws(char(')'))),
+        not_expr,
+        predicate,
+    ))(input)
+}
 
-        return Ok(Some(DFFilterExp {
-            logical_ops: found_ops,
-            vals: filter_vals,
-        }));
+// "&&" binds tighter than "||", so "a || b && c" is "a || (b && c)"
+fn chain<'a>(
+    op: DFLogicalOp,
+    operand: fn(&'a str) -> IResult<&'a str, DFFilterExp>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, DFFilterExp> {
+    move |input: &'a str| {
+        let (input, first) = operand(input)?;
+        let (input, rest) = many0(preceded(ws(tag(op.as_str())), operand))(input)?;
+        let exp = rest.into_iter().fold(first, |left, right| {
+            DFFilterExp::Logical(op.clone(), Box::new(left), Box::new(right))
+        });
+        Ok((input, exp))
+    }
+}
+
+fn and_expr(input: &str) -> IResult<&str, DFFilterExp> {
+    chain(DFLogicalOp::AND, primary)(input)
+}
+
+fn or_expr(input: &str) -> IResult<&str, DFFilterExp> {
+    chain(DFLogicalOp::OR, and_expr)(input)
+}
+
+/// Can parse an expression such as "pred_label == person && (is_correct == true || score > 0.5)"
+///
+/// Supports `==, !=, <, >, <=, >=`, `in [a, b]`, `contains`, regex matches with `=~`,
+/// `is null` and `is not null`, `date(2023-01-31)` and `datetime('2023-01-31 12:30:00')` literals,
+/// negation with `!` and grouping with parentheses. Values with spaces or symbols must be quoted.
+pub fn parse(query: Option<String>) -> Result<Option<DFFilterExp>, OxenError> {
+    let Some(filter) = query else {
+        return Ok(None);
+    };
+
+    if filter.trim().is_empty() {
+        return Err(OxenError::parse_error(filter));
+    }
+
+    match all_consuming(or_expr)(filter.as_str()) {
+        Ok((_, exp)) => Ok(Some(exp)),
+        Err(nom::Err::Error(err)) | Err(nom::Err::Failure(err)) => Err(OxenError::invalid_filter(
+            &filter,
+            format!("unexpected input at '{}'", err.input),
+        )),
+        Err(nom::Err::Incomplete(_)) => Err(OxenError::invalid_filter(
+            &filter,
+            "unexpected end of input",
+        )),
     }
-    Ok(None)
 }
 
 #[cfg(test)]
 mod tests {
+    use chrono::NaiveDate;
+
     use crate::{
-        core::df::filter::{parse, DFFilterOp, DFLogicalOp},
+        core::df::filter::{
+            parse, DFFilterExp, DFFilterOp, DFFilterVal, DFFilterValue, DFLogicalOp,
+        },
         error::OxenError,
     };
 
+    fn val(exp: &DFFilterExp) -> &DFFilterVal {
+        match exp {
+            DFFilterExp::Val(val) => val,
+            exp => panic!("Expected a single expression, got {exp:?}"),
+        }
+    }
+
+    fn logical(exp: &DFFilterExp) -> (&DFLogicalOp, &DFFilterExp, &DFFilterExp) {
+        match exp {
+            DFFilterExp::Logical(op, left, right) => (op, left, right),
+            exp => panic!("Expected a logical expression, got {exp:?}"),
+        }
+    }
+
+    fn str_val(s: &str) -> DFFilterValue {
+        DFFilterValue::Str(s.to_string())
+    }
+
     #[test]
     fn test_parse_filter_single_expr() -> Result<(), OxenError> {
         let query = Some("label == person".to_string());
 
         let opt = parse(query)?.unwrap();
 
-        assert_eq!(opt.logical_ops.len(), 0);
-
-        assert_eq!(opt.vals.len(), 1);
-        assert_eq!(opt.vals[0].field, "label");
-        assert_eq!(opt.vals[0].op, DFFilterOp::EQ);
-        assert_eq!(opt.vals[0].value, "person");
+        let val = val(&opt);
+        assert_eq!(val.field, "label");
+        assert_eq!(val.op, DFFilterOp::EQ);
+        assert_eq!(val.value, str_val("person"));
         Ok(())
     }
 
@@ -185,16 +323,15 @@ mod tests {
 
         let opt = parse(query)?.unwrap();
 
-        assert_eq!(opt.logical_ops.len(), 1);
-        assert_eq!(opt.logical_ops[0], DFLogicalOp::AND);
+        let (op, left, right) = logical(&opt);
+        assert_eq!(*op, DFLogicalOp::AND);
 
-        assert_eq!(opt.vals.len(), 2);
-        assert_eq!(opt.vals[0].field, "label");
-        assert_eq!(opt.vals[0].op, DFFilterOp::EQ);
-        assert_eq!(opt.vals[0].value, "person");
-        assert_eq!(opt.vals[1].field, "is_true");
-        assert_eq!(opt.vals[1].op, DFFilterOp::EQ);
-        assert_eq!(opt.vals[1].value, "false");
+        assert_eq!(val(left).field, "label");
+        assert_eq!(val(left).op, DFFilterOp::EQ);
+        assert_eq!(val(left).value, str_val("person"));
+        assert_eq!(val(right).field, "is_true");
+        assert_eq!(val(right).op, DFFilterOp::EQ);
+        assert_eq!(val(right).value, str_val("false"));
         Ok(())
     }
 
@@ -204,20 +341,80 @@ mod tests {
 
         let opt = parse(query)?.unwrap();
 
-        assert_eq!(opt.logical_ops.len(), 2);
-        assert_eq!(opt.logical_ops[0], DFLogicalOp::AND);
-        assert_eq!(opt.logical_ops[1], DFLogicalOp::OR);
-
-        assert_eq!(opt.vals.len(), 3);
-        assert_eq!(opt.vals[0].field, "label");
-        assert_eq!(opt.vals[0].op, DFFilterOp::EQ);
-        assert_eq!(opt.vals[0].value, "person");
-        assert_eq!(opt.vals[1].field, "min_x");
-        assert_eq!(opt.vals[1].op, DFFilterOp::GT);
-        assert_eq!(opt.vals[1].value, "0");
-        assert_eq!(opt.vals[2].field, "max_x");
-        assert_eq!(opt.vals[2].op, DFFilterOp::GTE);
-        assert_eq!(opt.vals[2].value, "1.0");
+        // && binds tighter than ||
+        let (op, left, right) = logical(&opt);
+        assert_eq!(*op, DFLogicalOp::OR);
+        assert_eq!(val(right).field, "max_x");
+        assert_eq!(val(right).op, DFFilterOp::GTE);
+        assert_eq!(val(right).value, str_val("1.0"));
+
+        let (op, left, right) = logical(left);
+        assert_eq!(*op, DFLogicalOp::AND);
+        assert_eq!(val(left).field, "label");
+        assert_eq!(val(left).op, DFFilterOp::EQ);
+        assert_eq!(val(left).value, str_val("person"));
+        assert_eq!(val(right).field, "min_x");
+        assert_eq!(val(right).op, DFFilterOp::GT);
+        assert_eq!(val(right).value, str_val("0"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_filter_parens_group_expression() -> Result<(), OxenError> {
+        let query = Some("label == person && (min_x > 0 || max_x >= 1.0)".to_string());
+
+        let opt = parse(query)?.unwrap();
+
+        let (op, left, right) = logical(&opt);
+        assert_eq!(*op, DFLogicalOp::AND);
+        assert_eq!(val(left).field, "label");
+
+        let (op, left, right) = logical(right);
+        assert_eq!(*op, DFLogicalOp::OR);
+        assert_eq!(val(left).field, "min_x");
+        assert_eq!(val(right).field, "max_x");
         Ok(())
     }
+
+    #[test]
+    fn test_parse_filter_extended_ops() -> Result<(), OxenError> {
+        let opt = parse(Some("label in [cat, 'big dog']".to_string()))?.unwrap();
+        assert_eq!(val(&opt).op, DFFilterOp::In);
+        assert_eq!(
+            val(&opt).value,
+            DFFilterValue::List(vec![str_val("cat"), str_val("big dog")])
+        );
+
+        let opt = parse(Some("caption contains \"a dog\"".to_string()))?.unwrap();
+        assert_eq!(val(&opt).op, DFFilterOp::Contains);
+        assert_eq!(val(&opt).value, str_val("a dog"));
+
+        let opt = parse(Some("file =~ '^images/.*\\.jpg$'".to_string()))?.unwrap();
+        assert_eq!(val(&opt).op, DFFilterOp::Regex);
+        assert_eq!(val(&opt).value, str_val("^images/.*\\.jpg$"));
+
+        let opt = parse(Some("caption is not null".to_string()))?.unwrap();
+        assert_eq!(val(&opt).op, DFFilterOp::IsNotNull);
+        let opt = parse(Some("caption IS NULL".to_string()))?.unwrap();
+        assert_eq!(val(&opt).op, DFFilterOp::IsNull);
+
+        let opt = parse(Some("created_at >= date(2023-01-31)".to_string()))?.unwrap();
+        assert_eq!(
+            val(&opt).value,
+            DFFilterValue::Date(NaiveDate::from_ymd_opt(2023, 1, 31).unwrap())
+        );
+
+        let opt = parse(Some("!(label == cat)".to_string()))?.unwrap();
+        assert!(matches!(opt, DFFilterExp::Not(_)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_filter_invalid_returns_error() {
+        assert!(parse(Some("label ~~ cat".to_string())).is_err());
+        assert!(parse(Some("label == cat &&".to_string())).is_err());
+        assert!(parse(Some("(label == cat".to_string())).is_err());
+        assert!(parse(Some("label in [cat".to_string())).is_err());
+        assert!(parse(Some("created_at > date(yesterday)".to_string())).is_err());
+    }
 }
@@ -0,0 +1,299 @@
+//! Column level statistics of a data frame
+//!
+//! Null and distinct counts for every column, min and max for the columns that can be ordered,
+//! and mean, std and a histogram for the numeric columns. Comparing the profiles of a file at two
+//! commits shows how the labels or values drifted.
+
+use std::path::Path;
+
+use polars::prelude::*;
+
+use crate::core::df::tabular;
+use crate::error::OxenError;
+use crate::model::data_frame_profile::{HistogramBin, ValueCount};
+use crate::model::{ColumnProfile, DataFrameProfile};
+use crate::opts::DFOpts;
+
+pub const TOP_K: usize = 5;
+pub const HISTOGRAM_BINS: usize = 10;
+
+const HEIGHT_COL: &str = "_height";
+const VALUE_COL: &str = "_value";
+// Name `value_counts` gives the counts
+const COUNT_COL: &str = "count";
+
+pub fn profile_path(path: impl AsRef<Path>) -> Result<DataFrameProfile, OxenError> {
+    let df = tabular::scan_df(path, &DFOpts::empty())?;
+    profile_df(df)
+}
+
+pub fn profile_df(df: LazyFrame) -> Result<DataFrameProfile, OxenError> {
+    let schema = df.schema()?;
+
+    // Compute all the single value stats in one pass over the data
+    let mut exprs = vec![len().alias(HEIGHT_COL)];
+    for (i, (name, dtype)) in schema.iter().enumerate() {
+        let name = name.as_str();
+        exprs.push(col(name).null_count().alias(&stat_col(i, "nulls")));
+        exprs.push(col(name).n_unique().alias(&stat_col(i, "distinct")));
+        if is_ordered(dtype) {
+            exprs.push(
+                col(name)
+                    .min()
+                    .cast(DataType::String)
+                    .alias(&stat_col(i, "min")),
+            );
+            exprs.push(
+                col(name)
+                    .max()
+                    .cast(DataType::String)
+                    .alias(&stat_col(i, "max")),
+            );
+        }
+        if dtype.is_numeric() {
+            let values = col(name).cast(DataType::Float64);
+            exprs.push(values.clone().mean().alias(&stat_col(i, "mean")));
+            exprs.push(values.std(1).alias(&stat_col(i, "std")));
+        }
+    }
+    let stats = tabular::collect_streaming(df.clone().select(exprs))?;
+    let height = stat_usize(&stats, HEIGHT_COL)?;
+
+    // The top values and histograms of every column in a second pass, the bins need the min and max
+    let mut exprs = vec![];
+    let mut ranges: Vec<Option<(f64, f64)>> = vec![];
+    for (i, (name, dtype)) in schema.iter().enumerate() {
+        let name = name.as_str();
+        if !dtype.is_nested() {
+            exprs.push(top_values_expr(name).alias(&stat_col(i, "top")));
+        }
+
+        let range = if dtype.is_numeric() {
+            let min = stat_str(&stats, &stat_col(i, "min"))?.and_then(|v| v.parse::<f64>().ok());
+            let max = stat_str(&stats, &stat_col(i, "max"))?.and_then(|v| v.parse::<f64>().ok());
+            min.zip(max)
+        } else {
+            None
+        };
+        if let Some((min, max)) = range {
+            if let Some(width) = bin_width(min, max) {
+                exprs.push(histogram_expr(name, min, width).alias(&stat_col(i, "bins")));
+            }
+        }
+        ranges.push(range);
+    }
+    let counts = if exprs.is_empty() {
+        DataFrame::default()
+    } else {
+        tabular::collect_streaming(df.select(exprs))?
+    };
+
+    let mut columns = vec![];
+    for (i, (name, dtype)) in schema.iter().enumerate() {
+        let name = name.as_str();
+        let null_count = stat_usize(&stats, &stat_col(i, "nulls"))?;
+        let histogram = match ranges[i] {
+            Some((min, max)) => Some(histogram(
+                &counts,
+                &stat_col(i, "bins"),
+                min,
+                max,
+                height - null_count,
+            )?),
+            None => None,
+        };
+
+        columns.push(ColumnProfile {
+            name: name.to_string(),
+            dtype: dtype.to_string(),
+            null_count,
+            distinct_count: stat_usize(&stats, &stat_col(i, "distinct"))?,
+            min: stat_str(&stats, &stat_col(i, "min"))?,
+            max: stat_str(&stats, &stat_col(i, "max"))?,
+            mean: stat_f64(&stats, &stat_col(i, "mean"))?,
+            std: stat_f64(&stats, &stat_col(i, "std"))?,
+            top_values: top_values(&counts, &stat_col(i, "top"))?,
+            histogram,
+        });
+    }
+
+    Ok(DataFrameProfile {
+        height,
+        width: schema.len(),
+        columns,
+    })
+}
+
+fn is_ordered(dtype: &DataType) -> bool {
+    dtype.is_numeric() || dtype.is_temporal() || dtype.is_bool() || dtype == &DataType::String
+}
+
+fn stat_col(i: usize, stat: &str) -> String {
+    format!("_{i}_{stat}")
+}
+
+fn stat_usize(stats: &DataFrame, name: &str) -> Result<usize, OxenError> {
+    let column = stats.column(name)?.cast(&DataType::UInt64)?;
+    Ok(column.u64()?.get(0).unwrap_or(0) as usize)
+}
+
+fn stat_f64(stats: &DataFrame, name: &str) -> Result<Option<f64>, OxenError> {
+    match stats.column(name) {
+        Ok(column) => Ok(column
+            .cast(&DataType::Float64)?
+            .f64()?
+            .get(0)
+            .filter(|v| v.is_finite())),
+        Err(_) => Ok(None),
+    }
+}
+
+fn stat_str(stats: &DataFrame, name: &str) -> Result<Option<String>, OxenError> {
+    match stats.column(name) {
+        Ok(column) => Ok(column.str()?.get(0).map(String::from)),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Counts of the values in a stat column, a single list of `{VALUE_COL, count}` structs
+fn value_counts(counts: &DataFrame, name: &str) -> Result<Option<(Series, Series)>, OxenError> {
+    let Ok(column) = counts.column(name) else {
+        return Ok(None);
+    };
+    let value_counts = column.explode()?;
+    let value_counts = value_counts.struct_()?;
+    let values = value_counts.field_by_name(VALUE_COL)?;
+    let counts = value_counts
+        .field_by_name(COUNT_COL)?
+        .cast(&DataType::UInt64)?;
+    Ok(Some((values, counts)))
+}
+
+/// Most frequent values, ties broken by the value so the result is stable
+fn top_values_expr(name: &str) -> Expr {
+    let counts = col(name)
+        .cast(DataType::String)
+        .alias(VALUE_COL)
+        .drop_nulls()
+        .value_counts(false, false);
+    let count = counts.clone().struct_().field_by_name(COUNT_COL);
+    let value = counts.clone().struct_().field_by_name(VALUE_COL);
+    counts
+        .sort_by([count, value], [true, false])
+        .head(Some(TOP_K))
+        .implode()
+}
+
+fn top_values(counts: &DataFrame, name: &str) -> Result<Vec<ValueCount>, OxenError> {
+    let Some((values, counts)) = value_counts(counts, name)? else {
+        return Ok(vec![]);
+    };
+    Ok(values
+        .str()?
+        .into_iter()
+        .zip(counts.u64()?.into_iter())
+        .filter_map(|(value, count)| {
+            Some(ValueCount {
+                value: value?.to_string(),
+                count: count? as usize,
+            })
+        })
+        .collect())
+}
+
+/// Width of the equal width bins between min and max, None if they are all the same value
+fn bin_width(min: f64, max: f64) -> Option<f64> {
+    let width = (max - min) / HISTOGRAM_BINS as f64;
+    (width.is_finite() && width > 0.0).then_some(width)
+}
+
+fn histogram_expr(name: &str, min: f64, width: f64) -> Expr {
+    // Values are >= min, so truncating is the same as flooring
+    let last_bin = HISTOGRAM_BINS as i64 - 1;
+    let bin = ((col(name).cast(DataType::Float64) - lit(min)) / lit(width)).cast(DataType::Int64);
+    when(bin.clone().gt(lit(last_bin)))
+        .then(lit(last_bin))
+        .otherwise(bin)
+        .alias(VALUE_COL)
+        .drop_nulls()
+        .value_counts(false, false)
+        .implode()
+}
+
+fn histogram(
+    counts: &DataFrame,
+    name: &str,
+    min: f64,
+    max: f64,
+    num_values: usize,
+) -> Result<Vec<HistogramBin>, OxenError> {
+    let Some(width) = bin_width(min, max) else {
+        return Ok(vec![HistogramBin {
+            start: min,
+            end: max,
+            count: num_values,
+        }]);
+    };
+
+    let mut bins: Vec<HistogramBin> = (0..HISTOGRAM_BINS)
+        .map(|i| HistogramBin {
+            start: min + width * i as f64,
+            end: min + width * (i + 1) as f64,
+            count: 0,
+        })
+        .collect();
+    let Some((bin_idx, bin_counts)) = value_counts(counts, name)? else {
+        return Ok(bins);
+    };
+    let bin_idx = bin_idx.cast(&DataType::Int64)?;
+    for (idx, count) in bin_idx
+        .i64()?
+        .into_iter()
+        .zip(bin_counts.u64()?.into_iter())
+    {
+        if let (Some(idx), Some(count)) = (idx, count) {
+            bins[idx as usize].count = count as usize;
+        }
+    }
+    Ok(bins)
+}
+
+#[cfg(test)]
+mod tests {
+    use polars::prelude::*;
+
+    use crate::core::df::profile;
+    use crate::error::OxenError;
+
+    #[test]
+    fn test_profile_df() -> Result<(), OxenError> {
+        let df = df!(
+            "label" => &[Some("cat"), Some("dog"), Some("cat"), None, Some("cat")],
+            "score" => &[0.0, 2.5, 5.0, 7.5, 10.0],
+        )
+        .unwrap();
+
+        let profile = profile::profile_df(df.lazy())?;
+        assert_eq!(profile.height, 5);
+        assert_eq!(profile.width, 2);
+
+        let label = profile.column("label").unwrap();
+        assert_eq!(label.null_count, 1);
+        assert_eq!(label.distinct_count, 3);
+        assert_eq!(label.top_values[0].value, "cat");
+        assert_eq!(label.top_values[0].count, 3);
+        assert_eq!(label.mean, None);
+        assert!(label.histogram.is_none());
+
+        let score = profile.column("score").unwrap();
+        assert_eq!(score.mean, Some(5.0));
+        let max: Option<f64> = score.max.as_ref().and_then(|max| max.parse().ok());
+        assert_eq!(max, Some(10.0));
+        let histogram = score.histogram.as_ref().unwrap();
+        assert_eq!(histogram.len(), profile::HISTOGRAM_BINS);
+        assert_eq!(histogram.iter().map(|bin| bin.count).sum::<usize>(), 5);
+        assert_eq!(histogram.last().unwrap().count, 1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,267 @@
+//! Reproducible shuffling, sampling and splitting of data frames
+//!
+//! Everything takes an optional seed, so that the same seed on the same data always returns the
+//! same rows.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use polars::prelude::*;
+use rand::prelude::SliceRandom;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::error::OxenError;
+
+/// How many rows to sample, either a number of rows or a fraction of the data frame
+#[derive(Clone, Debug, PartialEq)]
+pub enum SampleSize {
+    Count(usize),
+    Fraction(f64),
+}
+
+impl FromStr for SampleSize {
+    type Err = OxenError;
+
+    /// `100` is a count of rows, `0.1` is a fraction of the rows
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.contains('.') {
+            match s.parse::<f64>() {
+                Ok(fraction) if fraction > 0.0 && fraction <= 1.0 => {
+                    Ok(SampleSize::Fraction(fraction))
+                }
+                _ => Err(OxenError::basic_str(format!(
+                    "Invalid sample fraction '{s}', must be between 0 and 1"
+                ))),
+            }
+        } else {
+            s.parse::<usize>().map(SampleSize::Count).map_err(|_| {
+                OxenError::basic_str(format!(
+                    "Invalid sample size '{s}', must be a number of rows or a fraction ie: 0.1"
+                ))
+            })
+        }
+    }
+}
+
+impl SampleSize {
+    /// Number of rows to take out of `total`
+    pub fn num_rows(&self, total: usize) -> usize {
+        match self {
+            SampleSize::Count(n) => (*n).min(total),
+            SampleSize::Fraction(f) => ((total as f64) * f).round() as usize,
+        }
+    }
+}
+
+/// Seeded rng if there is a seed, otherwise seeded from entropy
+pub fn rng(seed: Option<u64>) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    }
+}
+
+pub fn shuffle_indices(height: usize, seed: Option<u64>) -> Vec<u32> {
+    let mut indices: Vec<u32> = (0..height as u32).collect();
+    indices.shuffle(&mut rng(seed));
+    indices
+}
+
+/// Pick the row indices of a random sample, in their original order.
+///
+/// When stratified by a column, each value of the column is sampled in proportion to how often it
+/// occurs in the data frame.
+pub fn sample_indices(
+    df: &DataFrame,
+    size: &SampleSize,
+    stratify: Option<&str>,
+    seed: Option<u64>,
+) -> Result<Vec<u32>, OxenError> {
+    let groups = match stratify {
+        Some(column) => group_indices(df, column)?,
+        None => vec![(0..df.height() as u32).collect()],
+    };
+    Ok(sample_groups(groups, size, seed))
+}
+
+/// Pick the row indices of a random sample out of `height` rows, in their original order
+pub fn sample_rows(height: usize, size: &SampleSize, seed: Option<u64>) -> Vec<u32> {
+    sample_groups(vec![(0..height as u32).collect()], size, seed)
+}
+
+fn sample_groups(groups: Vec<Vec<u32>>, size: &SampleSize, seed: Option<u64>) -> Vec<u32> {
+    let total: usize = groups.iter().map(|group| group.len()).sum();
+    let num_rows = size.num_rows(total);
+
+    let mut rng = rng(seed);
+    let mut indices: Vec<u32> = vec![];
+    for mut group in groups {
+        let group_rows = ((num_rows * group.len()) as f64 / total as f64).round() as usize;
+        group.shuffle(&mut rng);
+        indices.extend_from_slice(&group[..group_rows.min(group.len())]);
+    }
+    indices.sort_unstable();
+    indices
+}
+
+/// Split the row indices into one set per ratio, ie: [0.8, 0.1, 0.1] for train, val and test.
+///
+/// When grouped by a column, all the rows that share a value end up in the same split, so that a
+/// key never leaks across splits. The splits are then only as close to the ratios as the group
+/// sizes allow.
+pub fn split_indices(
+    df: &DataFrame,
+    ratios: &[f64],
+    group_by: Option<&str>,
+    seed: Option<u64>,
+) -> Result<Vec<Vec<u32>>, OxenError> {
+    if ratios.is_empty() || ratios.iter().any(|r| *r < 0.0) {
+        return Err(OxenError::basic_str(
+            "Split ratios must be a list of positive numbers ie: 0.8,0.1,0.1",
+        ));
+    }
+    let sum: f64 = ratios.iter().sum();
+    if sum <= 0.0 {
+        return Err(OxenError::basic_str("Split ratios must not all be zero"));
+    }
+
+    let total = df.height();
+    let mut groups = match group_by {
+        Some(column) => group_indices(df, column)?,
+        None => (0..total as u32).map(|i| vec![i]).collect(),
+    };
+    groups.shuffle(&mut rng(seed));
+
+    // Number of rows that should be assigned once each split is full
+    let mut cumulative = 0.0;
+    let boundaries: Vec<usize> = ratios
+        .iter()
+        .map(|ratio| {
+            cumulative += ratio / sum;
+            (cumulative * total as f64).round() as usize
+        })
+        .collect();
+
+    let mut splits: Vec<Vec<u32>> = vec![vec![]; ratios.len()];
+    let mut assigned = 0;
+    let mut split_idx = 0;
+    for group in groups {
+        while split_idx < boundaries.len() - 1 && assigned >= boundaries[split_idx] {
+            split_idx += 1;
+        }
+        assigned += group.len();
+        splits[split_idx].extend(group);
+    }
+
+    for split in splits.iter_mut() {
+        split.sort_unstable();
+    }
+    Ok(splits)
+}
+
+/// Row indices grouped by the value in the column, in order of first appearance
+fn group_indices(df: &DataFrame, column: &str) -> Result<Vec<Vec<u32>>, OxenError> {
+    let series = df
+        .column(column)
+        .map_err(|_| OxenError::basic_str(format!("Column '{column}' not found in data frame")))?
+        .cast(&DataType::String)?;
+    let values = series.str()?;
+
+    let mut lookup: HashMap<Option<&str>, usize> = HashMap::new();
+    let mut groups: Vec<Vec<u32>> = vec![];
+    for (i, value) in values.into_iter().enumerate() {
+        let idx = *lookup.entry(value).or_insert_with(|| {
+            groups.push(vec![]);
+            groups.len() - 1
+        });
+        groups[idx].push(i as u32);
+    }
+    Ok(groups)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use polars::prelude::*;
+
+    use crate::core::df::sample::{self, SampleSize};
+    use crate::error::OxenError;
+
+    fn labeled_df() -> DataFrame {
+        let ids: Vec<u32> = (0..100).collect();
+        let labels: Vec<&str> = (0..100)
+            .map(|i| if i % 4 == 0 { "cat" } else { "dog" })
+            .collect();
+        let users: Vec<u32> = (0..100).map(|i| i / 10).collect();
+        df!("id" => ids, "label" => labels, "user" => users).unwrap()
+    }
+
+    #[test]
+    fn test_parse_sample_size() -> Result<(), OxenError> {
+        assert_eq!("10".parse::<SampleSize>()?, SampleSize::Count(10));
+        assert_eq!("0.25".parse::<SampleSize>()?, SampleSize::Fraction(0.25));
+        assert!("1.5".parse::<SampleSize>().is_err());
+        assert!("ten".parse::<SampleSize>().is_err());
+        assert_eq!(SampleSize::Fraction(0.25).num_rows(100), 25);
+        assert_eq!(SampleSize::Count(200).num_rows(100), 100);
+        Ok(())
+    }
+
+    #[test]
+    fn test_seeded_shuffle_is_reproducible() {
+        let a = sample::shuffle_indices(50, Some(42));
+        let b = sample::shuffle_indices(50, Some(42));
+        let c = sample::shuffle_indices(50, Some(7));
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn test_stratified_sample_keeps_proportions() -> Result<(), OxenError> {
+        let df = labeled_df();
+        let indices =
+            sample::sample_indices(&df, &SampleSize::Fraction(0.2), Some("label"), Some(1))?;
+        assert_eq!(indices.len(), 20);
+        let num_cats = indices.iter().filter(|i| *i % 4 == 0).count();
+        assert_eq!(num_cats, 5);
+
+        let again =
+            sample::sample_indices(&df, &SampleSize::Fraction(0.2), Some("label"), Some(1))?;
+        assert_eq!(indices, again);
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_indices_by_ratio() -> Result<(), OxenError> {
+        let df = labeled_df();
+        let splits = sample::split_indices(&df, &[0.8, 0.1, 0.1], None, Some(3))?;
+        let sizes: Vec<usize> = splits.iter().map(|s| s.len()).collect();
+        assert_eq!(sizes, vec![80, 10, 10]);
+
+        let all: HashSet<u32> = splits.iter().flatten().copied().collect();
+        assert_eq!(all.len(), 100);
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_indices_group_never_leaks() -> Result<(), OxenError> {
+        let df = labeled_df();
+        let splits = sample::split_indices(&df, &[0.6, 0.2, 0.2], Some("user"), Some(3))?;
+        let user_sets: Vec<HashSet<u32>> = splits
+            .iter()
+            .map(|split| split.iter().map(|i| i / 10).collect())
+            .collect();
+        for (i, a) in user_sets.iter().enumerate() {
+            assert!(!a.is_empty());
+            for b in user_sets.iter().skip(i + 1) {
+                assert!(a.is_disjoint(b));
+            }
+        }
+        let total: usize = splits.iter().map(|s| s.len()).sum();
+        assert_eq!(total, 100);
+        Ok(())
+    }
+}
@@ -0,0 +1,237 @@
+//! Resolve tables addressed as `path@revision` in sql queries
+//!
+//! ie: `SELECT * FROM "data/train.csv@main" UNION ALL SELECT * FROM "data/train.csv@a1b2c3"`
+//!
+//! Each table is swapped for a generated name in the query, and registered from the version file of
+//! the path at that revision when the query runs.
+
+use std::path::PathBuf;
+
+use crate::api;
+use crate::error::OxenError;
+use crate::model::LocalRepository;
+use crate::opts::DFOpts;
+
+const REVISION_DELIMITER: char = '@';
+const TABLE_KEYWORDS: [&str; 2] = ["from", "join"];
+const QUOTE_CHARS: [char; 3] = ['"', '\'', '`'];
+
+/// A table in a sql query that is read from a file on disk
+#[derive(Clone, Debug)]
+pub struct SqlTable {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+/// A `path@revision` reference found in a query
+#[derive(Clone, Debug, PartialEq)]
+pub struct RevisionTable {
+    pub name: String,
+    pub path: PathBuf,
+    pub revision: String,
+}
+
+pub fn has_revision_tables(query: impl AsRef<str>) -> bool {
+    !parse_revision_tables(query).1.is_empty()
+}
+
+/// Replace every `path@revision` table that follows a FROM or JOIN with a generated table name,
+/// returns the rewritten query and the tables that were found
+pub fn parse_revision_tables(query: impl AsRef<str>) -> (String, Vec<RevisionTable>) {
+    let query = query.as_ref();
+    let chars: Vec<char> = query.chars().collect();
+    let mut rewritten = String::new();
+    let mut tables: Vec<RevisionTable> = vec![];
+
+    let mut i = 0;
+    let mut expect_table = false;
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c.is_whitespace() {
+            rewritten.push(c);
+            i += 1;
+            continue;
+        }
+
+        // Read the next token, a quoted string or a run of non separator chars
+        let start = i;
+        let is_quoted = QUOTE_CHARS.contains(&c);
+        if is_quoted {
+            i += 1;
+            while i < chars.len() && chars[i] != c {
+                i += 1;
+            }
+            i = (i + 1).min(chars.len());
+        } else if is_separator(c) {
+            i += 1;
+        } else {
+            while i < chars.len() && !chars[i].is_whitespace() && !is_separator(chars[i]) {
+                i += 1;
+            }
+        }
+        let token: String = chars[start..i].iter().collect();
+
+        if expect_table {
+            expect_table = false;
+            let value = if is_quoted {
+                token[1..token.len().saturating_sub(1).max(1)].to_string()
+            } else {
+                token.clone()
+            };
+            if let Some(table) = parse_table(&value, &tables) {
+                rewritten.push_str(&table.name);
+                if !tables.contains(&table) {
+                    tables.push(table);
+                }
+                continue;
+            }
+        }
+
+        expect_table = !is_quoted && TABLE_KEYWORDS.contains(&token.to_lowercase().as_str());
+        rewritten.push_str(&token);
+    }
+
+    (rewritten, tables)
+}
+
+/// Rewrite the sql in the opts so that `path@revision` tables read from the version files in the
+/// repository
+pub fn resolve_revision_tables(repo: &LocalRepository, opts: &mut DFOpts) -> Result<(), OxenError> {
+    let Some(query) = &opts.sql else {
+        return Ok(());
+    };
+
+    let (query, tables) = parse_revision_tables(query);
+    if tables.is_empty() {
+        return Ok(());
+    }
+
+    let mut sql_tables: Vec<SqlTable> = vec![];
+    for table in tables {
+        let commit = api::local::revisions::get(repo, &table.revision)?.ok_or(
+            OxenError::revision_not_found(table.revision.to_owned().into()),
+        )?;
+        let path = repo.path.join(&table.path);
+        let version_path =
+            api::local::revisions::get_version_file_from_commit_id(repo, &commit.id, path)?;
+        log::debug!(
+            "resolve_revision_tables {:?}@{} -> {:?}",
+            table.path,
+            table.revision,
+            version_path
+        );
+        sql_tables.push(SqlTable {
+            name: table.name,
+            path: version_path,
+        });
+    }
+
+    opts.sql = Some(query);
+    opts.sql_tables = Some(sql_tables);
+    Ok(())
+}
+
+fn is_separator(c: char) -> bool {
+    matches!(c, ',' | ';' | '(' | ')')
+}
+
+fn parse_table(value: &str, tables: &[RevisionTable]) -> Option<RevisionTable> {
+    let (path, revision) = value.rsplit_once(REVISION_DELIMITER)?;
+    if path.is_empty() || revision.is_empty() {
+        return None;
+    }
+
+    let path = PathBuf::from(path);
+    let revision = revision.to_string();
+    // Reuse the name if the same table is referenced twice
+    if let Some(table) = tables
+        .iter()
+        .find(|t| t.path == path && t.revision == revision)
+    {
+        return Some(table.clone());
+    }
+
+    Some(RevisionTable {
+        name: format!("rev_{}", tables.len()),
+        path,
+        revision,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::command;
+    use crate::core::df::{sql, tabular};
+    use crate::error::OxenError;
+    use crate::opts::DFOpts;
+    use crate::test;
+    use crate::util;
+
+    #[test]
+    fn test_parse_revision_tables() {
+        let query = "SELECT * FROM \"data/train.csv@main\" UNION ALL SELECT * FROM data/train.csv@abc123 JOIN 'test.csv@main' ON a = b";
+        let (rewritten, tables) = sql::parse_revision_tables(query);
+        assert_eq!(
+            rewritten,
+            "SELECT * FROM rev_0 UNION ALL SELECT * FROM rev_1 JOIN rev_2 ON a = b"
+        );
+        assert_eq!(tables.len(), 3);
+        assert_eq!(tables[0].path, PathBuf::from("data/train.csv"));
+        assert_eq!(tables[0].revision, "main");
+        assert_eq!(tables[1].revision, "abc123");
+        assert_eq!(tables[2].path, PathBuf::from("test.csv"));
+    }
+
+    #[test]
+    fn test_parse_revision_tables_ignores_plain_tables() {
+        let query = "SELECT * FROM df WHERE email = 'ox@oxen.ai'";
+        let (rewritten, tables) = sql::parse_revision_tables(query);
+        assert_eq!(rewritten, query);
+        assert!(tables.is_empty());
+        assert!(!sql::has_revision_tables(query));
+    }
+
+    #[test]
+    fn test_parse_revision_tables_reuses_name() {
+        let query = "SELECT * FROM (SELECT * FROM \"a.csv@main\") JOIN \"a.csv@main\" USING (id)";
+        let (rewritten, tables) = sql::parse_revision_tables(query);
+        assert_eq!(
+            rewritten,
+            "SELECT * FROM (SELECT * FROM rev_0) JOIN rev_0 USING (id)"
+        );
+        assert_eq!(tables.len(), 1);
+    }
+
+    #[test]
+    fn test_sql_union_across_revisions() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            let path = repo.path.join("data.csv");
+            util::fs::write_to_path(&path, "id,label\n1,cat\n2,dog\n")?;
+            command::add(&repo, &path)?;
+            let first = command::commit(&repo, "Adding data")?;
+
+            util::fs::write_to_path(&path, "id,label\n1,cat\n2,dog\n3,fish\n")?;
+            command::add(&repo, &path)?;
+            command::commit(&repo, "Adding fish")?;
+
+            let mut opts = DFOpts::empty();
+            opts.sql = Some(format!(
+                "SELECT * FROM \"data.csv@{}\" UNION ALL SELECT * FROM \"data.csv@main\"",
+                first.id
+            ));
+            sql::resolve_revision_tables(&repo, &mut opts)?;
+            assert_eq!(
+                opts.sql,
+                Some("SELECT * FROM rev_0 UNION ALL SELECT * FROM rev_1".to_string())
+            );
+
+            let df = tabular::read_df(&path, opts)?;
+            assert_eq!(df.height(), 5);
+
+            Ok(())
+        })
+    }
+}
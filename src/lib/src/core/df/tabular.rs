@@ -1,10 +1,16 @@
 use duckdb::ToSql;
+use polars::io::avro::AvroReader;
+use polars::io::mmap::MmapBytesReader;
 use polars::prelude::*;
 use polars_sql::SQLContext;
 use std::fs::File;
 
+use crate::core::df::compressed;
+use crate::core::df::excel;
 use crate::core::df::filter::DFLogicalOp;
 use crate::core::df::pretty_print;
+use crate::core::df::sample;
+use crate::core::df::sql::SqlTable;
 use crate::error::OxenError;
 use crate::model::schema::DataType;
 use crate::model::{ContentType, DataFrameSize};
@@ -15,15 +21,13 @@ use crate::{api, constants};
 use colored::Colorize;
 use comfy_table::Table;
 use indicatif::ProgressBar;
-use rand::prelude::SliceRandom;
-use rand::thread_rng;
 use std::ffi::OsStr;
 use std::io::Cursor;
 use std::path::Path;
 
 use super::{
     agg::{DFAggFn, DFAggFnType, DFAggregation},
-    filter::{DFFilterExp, DFFilterOp, DFFilterVal},
+    filter::{self, DFFilterExp, DFFilterOp, DFFilterVal, DFFilterValue},
 };
 
 const DEFAULT_INFER_SCHEMA_LEN: usize = 100;
@@ -32,8 +36,14 @@ const READ_ERROR: &str = "Could not read tabular data from path";
 const COLLECT_ERROR: &str = "Could not collect DataFrame";
 const TAKE_ERROR: &str = "Could not take DataFrame";
 const CSV_READ_ERROR: &str = "Could not read csv from path";
+const TAKE_IDX_COL: &str = "_take_idx";
+const TAKE_ORDER_COL: &str = "_take_order";
+const COUNT_COL: &str = "_count";
 
-fn try_infer_schema_csv(reader: CsvReader<File>, delimiter: u8) -> Result<DataFrame, OxenError> {
+fn try_infer_schema_csv<R: MmapBytesReader>(
+    reader: CsvReader<R>,
+    delimiter: u8,
+) -> Result<DataFrame, OxenError> {
     log::debug!("try_infer_schema_csv delimiter: {:?}", delimiter as char);
     let result = reader
         .infer_schema(Some(DEFAULT_INFER_SCHEMA_LEN))
@@ -73,17 +83,28 @@ pub fn read_df_csv(path: impl AsRef<Path>, delimiter: u8) -> Result<DataFrame, O
     }
 }
 
+/// Lazily read a csv. Rows that do not parse, ragged lines and invalid utf8 are errors unless
+/// `lenient` is set, then they are skipped, truncated and replaced instead.
 pub fn scan_df_csv<P: AsRef<Path>>(
     path: P,
     delimiter: u8,
-    opts: &DFOpts,
-    total_rows: usize,
+    lenient: bool,
 ) -> Result<LazyFrame, OxenError> {
-    Ok(LazyCsvReader::new(&path)
+    let reader = LazyCsvReader::new(&path)
         .with_separator(delimiter)
         .with_infer_schema_length(Some(DEFAULT_INFER_SCHEMA_LEN))
-        .with_n_rows(get_max_rows_from_opts(opts, total_rows))
         .has_header(true)
+        .with_end_of_line_char(b'\n')
+        .with_quote_char(Some(b'"'));
+    let reader = if lenient {
+        reader
+            .with_ignore_errors(true)
+            .truncate_ragged_lines(true)
+            .with_encoding(CsvEncoding::LossyUtf8)
+    } else {
+        reader
+    };
+    Ok(reader
         .finish()
         .unwrap_or_else(|_| panic!("{}: {:?}", READ_ERROR, path.as_ref())))
 }
@@ -110,15 +131,10 @@ pub fn read_df_jsonl(path: impl AsRef<Path>) -> Result<DataFrame, OxenError> {
     Ok(df)
 }
 
-pub fn scan_df_jsonl(
-    path: impl AsRef<Path>,
-    opts: &DFOpts,
-    total_rows: usize,
-) -> Result<LazyFrame, OxenError> {
+pub fn scan_df_jsonl(path: impl AsRef<Path>) -> Result<LazyFrame, OxenError> {
     Ok(
         LazyJsonLineReader::new(path.as_ref().to_str().expect("Invalid json path."))
             .with_infer_schema_length(Some(DEFAULT_INFER_SCHEMA_LEN))
-            .with_n_rows(get_max_rows_from_opts(opts, total_rows))
             .finish()
             .unwrap_or_else(|_| panic!("{}: {:?}", READ_ERROR, path.as_ref())),
     )
@@ -146,27 +162,41 @@ pub fn read_df_parquet(path: impl AsRef<Path>) -> Result<DataFrame, OxenError> {
     }
 }
 
-pub fn scan_df_parquet(
-    path: impl AsRef<Path>,
-    opts: &DFOpts,
-    total_rows: usize,
-) -> Result<LazyFrame, OxenError> {
-    let args = ScanArgsParquet {
-        n_rows: get_max_rows_from_opts(opts, total_rows),
-        ..Default::default()
+pub fn scan_df_parquet(path: impl AsRef<Path>) -> Result<LazyFrame, OxenError> {
+    log::debug!("scan_df_parquet path: {:?}", path.as_ref());
+    Ok(
+        LazyFrame::scan_parquet(&path, ScanArgsParquet::default()).unwrap_or_else(|_| {
+            panic!(
+                "Panic scanning parquet file {}: {:?}",
+                READ_ERROR,
+                path.as_ref()
+            )
+        }),
+    )
+}
+
+/// Reads gzip or zstd csv and jsonl files, streaming them through a decompressed temp file
+pub fn read_df_compressed(path: impl AsRef<Path>, opts: &DFOpts) -> Result<DataFrame, OxenError> {
+    let path = path.as_ref();
+    let Some(format) = compressed::inner_format(path) else {
+        return Err(OxenError::basic_str(format!(
+            "Unknown file type in compressed file {path:?}"
+        )));
     };
-    log::debug!(
-        "scan_df_parquet_n_rows path: {:?} n_rows: {:?}",
-        path.as_ref(),
-        args.n_rows
-    );
-    Ok(LazyFrame::scan_parquet(&path, args).unwrap_or_else(|_| {
-        panic!(
-            "Panic scanning parquet file {}: {:?}",
-            READ_ERROR,
-            path.as_ref()
-        )
-    }))
+    let decompressed = compressed::decompress_to_file(path, format)?;
+    match format {
+        "jsonl" | "ndjson" => read_df_jsonl(&decompressed.path),
+        "tsv" => read_df_csv(&decompressed.path, b'\t'),
+        _ => {
+            let delimiter = sniff_db_csv_delimiter(&decompressed.path, opts)?;
+            read_df_csv(&decompressed.path, delimiter)
+        }
+    }
+}
+
+pub fn read_df_avro(path: impl AsRef<Path>) -> Result<DataFrame, OxenError> {
+    let file = File::open(path.as_ref())?;
+    Ok(AvroReader::new(file).finish()?)
 }
 
 fn read_df_arrow(path: impl AsRef<Path>) -> Result<DataFrame, OxenError> {
@@ -181,25 +211,6 @@ fn scan_df_arrow(path: impl AsRef<Path>) -> Result<LazyFrame, OxenError> {
         .unwrap_or_else(|_| panic!("{}: {:?}", READ_ERROR, path.as_ref())))
 }
 
-fn get_max_rows_from_opts(opts: &DFOpts, total_rows: usize) -> Option<usize> {
-    log::debug!(
-        "get_max_rows_from_opts total_rows {} opts.has_filter_transform() {}",
-        total_rows,
-        opts.has_filter_transform()
-    );
-    if opts.has_filter_transform() {
-        return Some(total_rows);
-    }
-
-    log::debug!("get_max_rows_from_opts {:?}", opts);
-    if let Some((_, end)) = &opts.slice_indices() {
-        log::debug!("get_max_rows_from_opts end {}", end);
-        return Some(*end as usize);
-    }
-
-    None
-}
-
 pub fn take(df: LazyFrame, indices: Vec<u32>) -> Result<DataFrame, OxenError> {
     let idx = IdxCa::new("idx", &indices);
     let collected = df.collect().expect(COLLECT_ERROR);
@@ -208,6 +219,50 @@ pub fn take(df: LazyFrame, indices: Vec<u32>) -> Result<DataFrame, OxenError> {
     Ok(collected.take(&idx).expect(TAKE_ERROR))
 }
 
+/// Take the rows at the indices, in the order of the indices, without collecting the data frame.
+/// The indices are joined against a row index so the scan can still stream.
+pub fn take_lazy(df: LazyFrame, indices: Vec<u32>) -> Result<LazyFrame, OxenError> {
+    let order: Vec<u32> = (0..indices.len() as u32).collect();
+    let indices = DataFrame::new(vec![
+        IdxCa::new(TAKE_IDX_COL, &indices).into_series(),
+        Series::new(TAKE_ORDER_COL, &order),
+    ])?;
+    Ok(df
+        .with_row_index(TAKE_IDX_COL, None)
+        .join(
+            indices.lazy(),
+            [col(TAKE_IDX_COL)],
+            [col(TAKE_IDX_COL)],
+            JoinArgs::new(JoinType::Inner),
+        )
+        .sort(TAKE_ORDER_COL, SortOptions::default())
+        .drop([TAKE_IDX_COL, TAKE_ORDER_COL]))
+}
+
+/// Number of rows the lazy frame would have, without holding the rows in memory
+pub fn count_rows(df: LazyFrame) -> Result<usize, OxenError> {
+    let counted = collect_streaming(df.select([len().alias(COUNT_COL)]))?;
+    let height = counted
+        .column(COUNT_COL)?
+        .cast(&polars::prelude::DataType::UInt64)?
+        .u64()?
+        .get(0)
+        .unwrap_or(0);
+    Ok(height as usize)
+}
+
+/// Collect with the streaming engine, so that scans are processed in batches instead of being
+/// loaded whole. Polars falls back to the in memory engine for the ops that cannot stream.
+pub fn collect_streaming(df: LazyFrame) -> Result<DataFrame, OxenError> {
+    match df.with_streaming(true).collect() {
+        Ok(df) => {
+            log::debug!("collect_streaming collected {:?}", df);
+            Ok(df)
+        }
+        Err(err) => Err(OxenError::basic_str(format!("DataFrame Error: {}", err))),
+    }
+}
+
 pub fn add_col_lazy(
     df: LazyFrame,
     name: &str,
@@ -334,61 +389,132 @@ fn val_from_str_and_dtype<'a>(s: &'a str, dtype: &polars::prelude::DataType) ->
     }
 }
 
-fn val_from_df_and_filter<'a>(df: &'a LazyFrame, filter: &'a DFFilterVal) -> AnyValue<'a> {
-    if let Some(value) = df
-        .schema()
-        .expect("Unable to get schema from data frame")
-        .iter_fields()
-        .find(|f| f.name == filter.field)
-    {
-        val_from_str_and_dtype(&filter.value, value.data_type())
-    } else {
-        log::error!("Unknown field {:?}", filter.field);
-        AnyValue::Null
+// Converts a filter value to a literal of the same type as the column it is compared against
+fn filter_lit(value: &DFFilterValue, dtype: &polars::prelude::DataType) -> Result<Expr, OxenError> {
+    let invalid =
+        |s: &str| OxenError::invalid_filter(s, format!("expected a value of type {dtype}"));
+    match value {
+        DFFilterValue::Null => Err(OxenError::invalid_filter(
+            "null",
+            "use 'is null' or 'is not null' to compare against null",
+        )),
+        DFFilterValue::Str(s) => match dtype {
+            polars::prelude::DataType::Boolean => {
+                s.parse::<bool>().map(lit).map_err(|_| invalid(s))
+            }
+            polars::prelude::DataType::UInt8
+            | polars::prelude::DataType::UInt16
+            | polars::prelude::DataType::UInt32
+            | polars::prelude::DataType::UInt64 => s
+                .parse::<u64>()
+                .map(|v| lit(v).cast(dtype.clone()))
+                .map_err(|_| invalid(s)),
+            polars::prelude::DataType::Int8
+            | polars::prelude::DataType::Int16
+            | polars::prelude::DataType::Int32
+            | polars::prelude::DataType::Int64 => s
+                .parse::<i64>()
+                .map(|v| lit(v).cast(dtype.clone()))
+                .map_err(|_| invalid(s)),
+            polars::prelude::DataType::Float32 | polars::prelude::DataType::Float64 => s
+                .parse::<f64>()
+                .map(|v| lit(v).cast(dtype.clone()))
+                .map_err(|_| invalid(s)),
+            polars::prelude::DataType::Date | polars::prelude::DataType::Datetime(_, _) => {
+                filter::parse_datetime(s)
+                    .map(|dt| lit(dt).cast(dtype.clone()))
+                    .ok_or_else(|| invalid(s))
+            }
+            _ => Ok(lit(s.as_str())),
+        },
+        // Dates compared against string columns compare as ISO 8601 strings
+        DFFilterValue::Date(date) => match dtype {
+            polars::prelude::DataType::Date | polars::prelude::DataType::Datetime(_, _) => {
+                Ok(lit(*date).cast(dtype.clone()))
+            }
+            _ => Ok(lit(date.format("%Y-%m-%d").to_string())),
+        },
+        DFFilterValue::Datetime(datetime) => match dtype {
+            polars::prelude::DataType::Date | polars::prelude::DataType::Datetime(_, _) => {
+                Ok(lit(*datetime).cast(dtype.clone()))
+            }
+            _ => Ok(lit(datetime.format("%Y-%m-%d %H:%M:%S").to_string())),
+        },
+        DFFilterValue::List(_) => Err(OxenError::invalid_filter(
+            format!("{value:?}"),
+            "lists can only be used with 'in'",
+        )),
     }
 }
 
-fn lit_from_any(value: &AnyValue) -> Expr {
+fn filter_str(value: &DFFilterValue) -> Result<&str, OxenError> {
     match value {
-        AnyValue::Boolean(val) => lit(*val),
-        AnyValue::Float64(val) => lit(*val),
-        AnyValue::Float32(val) => lit(*val),
-        AnyValue::Int64(val) => lit(*val),
-        AnyValue::Int32(val) => lit(*val),
-        AnyValue::String(val) => lit(*val),
-        val => panic!("Unknown data type for [{}] to create literal", val),
+        DFFilterValue::Str(s) => Ok(s),
+        value => Err(OxenError::invalid_filter(
+            format!("{value:?}"),
+            "expected a string",
+        )),
     }
 }
 
-fn filter_from_val(df: &LazyFrame, filter: &DFFilterVal) -> Expr {
-    let val = val_from_df_and_filter(df, filter);
-    let val = lit_from_any(&val);
-    match filter.op {
-        DFFilterOp::EQ => col(&filter.field).eq(val),
-        DFFilterOp::GT => col(&filter.field).gt(val),
-        DFFilterOp::LT => col(&filter.field).lt(val),
-        DFFilterOp::GTE => col(&filter.field).gt_eq(val),
-        DFFilterOp::LTE => col(&filter.field).lt_eq(val),
-        DFFilterOp::NEQ => col(&filter.field).neq(val),
-    }
+fn filter_from_val(schema: &Schema, filter: &DFFilterVal) -> Result<Expr, OxenError> {
+    let Some(dtype) = schema.get(&filter.field) else {
+        return Err(OxenError::invalid_filter(&filter.field, "unknown column"));
+    };
+    let column = col(&filter.field);
+    let expr = match filter.op {
+        DFFilterOp::EQ => column.eq(filter_lit(&filter.value, dtype)?),
+        DFFilterOp::GT => column.gt(filter_lit(&filter.value, dtype)?),
+        DFFilterOp::LT => column.lt(filter_lit(&filter.value, dtype)?),
+        DFFilterOp::GTE => column.gt_eq(filter_lit(&filter.value, dtype)?),
+        DFFilterOp::LTE => column.lt_eq(filter_lit(&filter.value, dtype)?),
+        DFFilterOp::NEQ => column.neq(filter_lit(&filter.value, dtype)?),
+        DFFilterOp::IsNull => column.is_null(),
+        DFFilterOp::IsNotNull => column.is_not_null(),
+        DFFilterOp::Contains => column
+            .cast(polars::prelude::DataType::String)
+            .str()
+            .contains_literal(lit(filter_str(&filter.value)?)),
+        DFFilterOp::Regex => column
+            .cast(polars::prelude::DataType::String)
+            .str()
+            .contains(lit(filter_str(&filter.value)?), true),
+        DFFilterOp::In => {
+            let DFFilterValue::List(values) = &filter.value else {
+                return Err(OxenError::invalid_filter(
+                    &filter.field,
+                    "'in' expects a list ie: [a, b]",
+                ));
+            };
+            let mut expr = lit(false);
+            for value in values {
+                expr = expr.or(column.clone().eq(filter_lit(value, dtype)?));
+            }
+            expr
+        }
+    };
+    Ok(expr)
 }
 
-fn filter_df(df: LazyFrame, filter: &DFFilterExp) -> Result<LazyFrame, OxenError> {
-    log::debug!("Got filter: {:?}", filter);
-    if filter.vals.is_empty() {
-        return Ok(df);
-    }
-    let mut vals = filter.vals.iter();
-    let mut expr: Expr = filter_from_val(&df, vals.next().unwrap());
-    for op in &filter.logical_ops {
-        let chain_expr: Expr = filter_from_val(&df, vals.next().unwrap());
-
-        match op {
-            DFLogicalOp::AND => expr = expr.and(chain_expr),
-            DFLogicalOp::OR => expr = expr.or(chain_expr),
+fn filter_from_exp(schema: &Schema, filter: &DFFilterExp) -> Result<Expr, OxenError> {
+    match filter {
+        DFFilterExp::Val(val) => filter_from_val(schema, val),
+        DFFilterExp::Not(exp) => Ok(filter_from_exp(schema, exp)?.not()),
+        DFFilterExp::Logical(op, left, right) => {
+            let left = filter_from_exp(schema, left)?;
+            let right = filter_from_exp(schema, right)?;
+            match op {
+                DFLogicalOp::AND => Ok(left.and(right)),
+                DFLogicalOp::OR => Ok(left.or(right)),
+            }
         }
     }
+}
 
+fn filter_df(df: LazyFrame, filter: &DFFilterExp) -> Result<LazyFrame, OxenError> {
+    log::debug!("Got filter: {:?}", filter);
+    let schema = df.schema()?;
+    let expr = filter_from_exp(&schema, filter)?;
     Ok(df.filter(expr))
 }
 
@@ -443,33 +569,25 @@ fn unique_df(df: LazyFrame, columns: Vec<String>) -> Result<LazyFrame, OxenError
 }
 
 pub fn transform(df: DataFrame, opts: DFOpts) -> Result<DataFrame, OxenError> {
-    let height = df.height();
-    let df = transform_lazy(df.lazy(), height, opts.clone())?;
-    transform_slice_lazy(df.lazy(), height, opts)
+    let df = transform_lazy(df.lazy(), &opts)?;
+    transform_slice_lazy(df, opts)
 }
 
-pub fn transform_lazy(
-    mut df: LazyFrame,
-    height: usize,
-    opts: DFOpts,
-) -> Result<DataFrame, OxenError> {
+/// Builds the query plan for the transforms without collecting it, so that polars can push the
+/// filters and projections down into the scan. Slicing is left to `transform_slice_lazy`.
+pub fn transform_lazy(mut df: LazyFrame, opts: &DFOpts) -> Result<LazyFrame, OxenError> {
     log::debug!("transform_lazy Got transform ops {:?}", opts);
     if let Some(vstack) = &opts.vstack {
         log::debug!("transform_lazy Got files to stack {:?}", vstack);
+        let mut frames = vec![df];
         for path in vstack.iter() {
-            let opts = DFOpts::empty();
-            let new_df = read_df(path, opts).expect(READ_ERROR);
-            df = df
-                .collect()
-                .expect(COLLECT_ERROR)
-                .vstack(&new_df)
-                .unwrap()
-                .lazy();
+            frames.push(scan_df(path, &DFOpts::empty())?);
         }
+        df = concat(frames, UnionArgs::default())?;
     }
 
     if let Some(data) = &opts.add_row {
-        df = add_row(df, data.to_owned(), &opts)?;
+        df = add_row(df, data.to_owned(), opts)?;
     }
 
     if let Some(col_vals) = opts.add_col_vals() {
@@ -483,7 +601,8 @@ pub fn transform_lazy(
     // For aggregations, sort by first column, because it is non-deterministic
     let mut should_sort_by_first_column = false;
     if let Some(query) = &opts.sql {
-        df = run_sql(df, query)?;
+        let tables = opts.sql_tables.clone().unwrap_or_default();
+        df = run_sql(df, query, &tables)?;
 
         if let Some(sql) = &opts.sql {
             if sql.to_lowercase().contains("group by")
@@ -506,15 +625,8 @@ pub fn transform_lazy(
         }
     }
 
-    match opts.get_filter() {
-        Ok(filter) => {
-            if let Some(filter) = filter {
-                df = filter_df(df, &filter)?;
-            }
-        }
-        Err(err) => {
-            log::error!("Could not parse filter: {err}");
-        }
+    if let Some(filter) = opts.get_filter()? {
+        df = filter_df(df, &filter)?;
     }
 
     if let Some(columns) = opts.unique_columns() {
@@ -539,10 +651,22 @@ pub fn transform_lazy(
         df = df.sort(first_column, SortOptions::default());
     }
 
+    // Sampling only needs the height, or the column to stratify by, to pick the rows
+    if let Some(size) = opts.get_sample_size()? {
+        let indices = match opts.stratify.as_deref() {
+            Some(stratify) => {
+                let column = collect_streaming(df.clone().select([col(stratify)]))?;
+                sample::sample_indices(&column, &size, Some(stratify), opts.seed)?
+            }
+            None => sample::sample_rows(count_rows(df.clone())?, &size, opts.seed),
+        };
+        df = take_lazy(df, indices)?;
+    }
+
     if opts.should_randomize {
-        let mut rand_indices: Vec<u32> = (0..height as u32).collect();
-        rand_indices.shuffle(&mut thread_rng());
-        df = take(df, rand_indices)?.lazy();
+        // Shuffle the rows that are left after filtering and sampling
+        let rand_indices = sample::shuffle_indices(count_rows(df.clone())?, opts.seed);
+        df = take_lazy(df, rand_indices)?;
     }
 
     if let Some(sort_by) = &opts.sort_by {
@@ -562,42 +686,24 @@ pub fn transform_lazy(
 
     // These ops should be the last ops since they depends on order
     if let Some(indices) = opts.take_indices() {
-        match take(df.clone(), indices) {
-            Ok(new_df) => {
-                df = new_df.lazy();
-            }
-            Err(err) => {
-                log::error!("error taking indices from df {err:?}")
-            }
-        }
+        df = take_lazy(df, indices)?;
     }
 
-    log::debug!("transform_lazy before collect");
-    match df.collect() {
-        Ok(df) => {
-            log::debug!("transform_lazy collected {:?}", df);
-            Ok(df)
-        }
-        Err(err) => Err(OxenError::basic_str(format!("DataFrame Error: {}", err))),
-    }
+    Ok(df)
 }
 
-pub fn transform_slice(df: DataFrame, height: usize, opts: DFOpts) -> Result<DataFrame, OxenError> {
-    transform_slice_lazy(df.lazy(), height, opts)
+pub fn transform_slice(df: DataFrame, opts: DFOpts) -> Result<DataFrame, OxenError> {
+    transform_slice_lazy(df.lazy(), opts)
 }
 
 // Separate out slice transform because it needs to be done after other transforms
-pub fn transform_slice_lazy(
-    mut df: LazyFrame,
-    height: usize,
-    opts: DFOpts,
-) -> Result<DataFrame, OxenError> {
+pub fn transform_slice_lazy(mut df: LazyFrame, opts: DFOpts) -> Result<DataFrame, OxenError> {
     // Maybe slice it up
     df = slice(df, &opts);
     df = head(df, &opts);
-    df = tail(df, height, &opts);
+    df = tail(df, &opts);
     if let Some(item) = opts.column_at() {
-        let full_df = df.collect().unwrap();
+        let full_df = collect_streaming(df)?;
         let value = full_df.column(&item.col).unwrap().get(item.index).unwrap();
         let s1 = Series::new("", &[value]);
         let df = DataFrame::new(vec![s1]).unwrap();
@@ -605,18 +711,15 @@ pub fn transform_slice_lazy(
     }
 
     log::debug!("transform_slice_lazy before collect");
-    match df.collect() {
-        Ok(df) => {
-            log::debug!("transform_lazy collected {:?}", df);
-            Ok(df)
-        }
-        Err(err) => Err(OxenError::basic_str(format!("DataFrame Error: {}", err))),
-    }
+    collect_streaming(df)
 }
 
-fn run_sql(df: LazyFrame, q: &str) -> Result<LazyFrame, OxenError> {
+fn run_sql(df: LazyFrame, q: &str, tables: &[SqlTable]) -> Result<LazyFrame, OxenError> {
     let mut ctx = SQLContext::new();
     ctx.register("df", df.clone());
+    for table in tables.iter() {
+        ctx.register(&table.name, scan_df(&table.path, &DFOpts::empty())?);
+    }
     match ctx.execute(q) {
         Ok(sql_df) => Ok(sql_df),
         Err(err) => {
@@ -635,7 +738,7 @@ async fn get_sql(df: LazyFrame, q: &str, host: String) -> Result<String, OxenErr
 pub fn run_text2sql(df: LazyFrame, q: &str, host: String) -> Result<LazyFrame, OxenError> {
     let sql = futures::executor::block_on(get_sql(df.clone(), q, host))?;
     println!("\n{}\n", sql);
-    run_sql(df, &sql)
+    run_sql(df, &sql, &[])
 }
 
 fn head(df: LazyFrame, opts: &DFOpts) -> LazyFrame {
@@ -646,11 +749,9 @@ fn head(df: LazyFrame, opts: &DFOpts) -> LazyFrame {
     }
 }
 
-fn tail(df: LazyFrame, height: usize, opts: &DFOpts) -> LazyFrame {
+fn tail(df: LazyFrame, opts: &DFOpts) -> LazyFrame {
     if let Some(tail) = opts.tail {
-        let start = (height - tail) as i64;
-        let end = (height - 1) as u32;
-        df.slice(start, end)
+        df.tail(tail as u32)
     } else {
         df
     }
@@ -717,13 +818,83 @@ pub fn any_val_to_bytes(value: &AnyValue) -> Vec<u8> {
 
 pub fn value_to_tosql(value: AnyValue) -> Box<dyn ToSql> {
     match value {
+        AnyValue::Null => Box::new(duckdb::types::Null),
         AnyValue::String(s) => Box::new(s.to_string()),
+        AnyValue::StringOwned(s) => Box::new(s.to_string()),
+        AnyValue::UInt8(n) => Box::new(n),
+        AnyValue::UInt16(n) => Box::new(n),
+        AnyValue::UInt32(n) => Box::new(n),
+        AnyValue::UInt64(n) => Box::new(n),
+        AnyValue::Int8(n) => Box::new(n),
+        AnyValue::Int16(n) => Box::new(n),
         AnyValue::Int32(n) => Box::new(n),
         AnyValue::Int64(n) => Box::new(n),
         AnyValue::Float32(f) => Box::new(f),
         AnyValue::Float64(f) => Box::new(f),
         AnyValue::Boolean(b) => Box::new(b),
-        _ => panic!("Unsupported dtype"),
+        AnyValue::Binary(b) => Box::new(b.to_vec()),
+        AnyValue::BinaryOwned(b) => Box::new(b),
+        // duckdb casts the string to the type of the column on insert
+        value => Box::new(value_to_sql_str(&value)),
+    }
+}
+
+/// String representation of temporal and nested values that duckdb can cast from
+fn value_to_sql_str(value: &AnyValue) -> String {
+    match value {
+        AnyValue::Duration(v, unit) => {
+            let micros = match unit {
+                TimeUnit::Nanoseconds => v / 1_000,
+                TimeUnit::Microseconds => *v,
+                TimeUnit::Milliseconds => v * 1_000,
+            };
+            format!("{micros} microseconds")
+        }
+        AnyValue::Categorical(_, _, _) | AnyValue::Enum(_, _, _) => {
+            value.get_str().unwrap_or_default().to_string()
+        }
+        AnyValue::List(series) => {
+            let values: Vec<String> = series.iter().map(|v| nested_value_to_sql_str(&v)).collect();
+            format!("[{}]", values.join(", "))
+        }
+        AnyValue::Struct(_, _, fields) => {
+            struct_to_sql_str(fields, value._iter_struct_av().collect())
+        }
+        AnyValue::StructOwned(payload) => struct_to_sql_str(&payload.1, payload.0.clone()),
+        value => value.to_string(),
+    }
+}
+
+fn struct_to_sql_str(fields: &[Field], values: Vec<AnyValue>) -> String {
+    let entries: Vec<String> = fields
+        .iter()
+        .zip(values.iter())
+        .map(|(field, value)| format!("'{}': {}", field.name(), nested_value_to_sql_str(value)))
+        .collect();
+    format!("{{{}}}", entries.join(", "))
+}
+
+// Inside of lists and structs strings have to be quoted and nulls spelled out
+fn nested_value_to_sql_str(value: &AnyValue) -> String {
+    match value {
+        AnyValue::Null => String::from("NULL"),
+        AnyValue::String(s) => format!("'{}'", s.replace('\'', "''")),
+        AnyValue::StringOwned(s) => format!("'{}'", s.replace('\'', "''")),
+        AnyValue::Boolean(_)
+        | AnyValue::UInt8(_)
+        | AnyValue::UInt16(_)
+        | AnyValue::UInt32(_)
+        | AnyValue::UInt64(_)
+        | AnyValue::Int8(_)
+        | AnyValue::Int16(_)
+        | AnyValue::Int32(_)
+        | AnyValue::Int64(_)
+        | AnyValue::Float32(_)
+        | AnyValue::Float64(_)
+        | AnyValue::List(_)
+        | AnyValue::Struct(_, _, _)
+        | AnyValue::StructOwned(_) => value_to_sql_str(value),
+        value => format!("'{}'", value_to_sql_str(value).replace('\'', "''")),
     }
 }
 
@@ -868,10 +1039,17 @@ pub fn read_df(path: impl AsRef<Path>, opts: DFOpts) -> Result<DataFrame, OxenEr
         return Err(OxenError::entry_does_not_exist(path));
     }
 
+    // Scan rather than read when there are transforms, so the file is streamed through them
+    if opts.has_transform() {
+        let df = scan_df(path, &opts)?;
+        let df = transform_lazy(df, &opts)?;
+        return transform_slice_lazy(df, opts);
+    }
+
     let extension = path.extension().and_then(OsStr::to_str);
     let err = format!("Unknown file type read_df {path:?} -> {extension:?}");
 
-    let df = match extension {
+    match extension {
         Some(extension) => match extension {
             "ndjson" => read_df_jsonl(path),
             "jsonl" => read_df_jsonl(path),
@@ -883,40 +1061,36 @@ pub fn read_df(path: impl AsRef<Path>, opts: DFOpts) -> Result<DataFrame, OxenEr
             "tsv" => read_df_csv(path, b'\t'),
             "parquet" => read_df_parquet(path),
             "arrow" => read_df_arrow(path),
+            "avro" => read_df_avro(path),
+            "gz" | "zst" | "zstd" => read_df_compressed(path, &opts),
+            _ if excel::is_excel(path) => excel::read_df_excel(path),
             _ => Err(OxenError::basic_str(err)),
         },
         None => Err(OxenError::basic_str(err)),
-    }?;
-
-    if opts.has_transform() {
-        let df = transform(df, opts)?;
-        Ok(df)
-    } else {
-        Ok(df)
     }
 }
 
-pub fn scan_df(
-    path: impl AsRef<Path>,
-    opts: &DFOpts,
-    total_rows: usize,
-) -> Result<LazyFrame, OxenError> {
+pub fn scan_df(path: impl AsRef<Path>, opts: &DFOpts) -> Result<LazyFrame, OxenError> {
     let input_path = path.as_ref();
     let extension = input_path.extension().and_then(OsStr::to_str);
     let err = format!("Unknown file type scan_df {input_path:?} {extension:?}");
 
     match extension {
         Some(extension) => match extension {
-            "ndjson" => scan_df_jsonl(path, opts, total_rows),
-            "jsonl" => scan_df_jsonl(path, opts, total_rows),
+            "ndjson" => scan_df_jsonl(path),
+            "jsonl" => scan_df_jsonl(path),
             "json" => scan_df_json(path),
             "csv" | "data" => {
                 let delimiter = sniff_db_csv_delimiter(&path, opts)?;
-                scan_df_csv(path, delimiter, opts, total_rows)
+                scan_df_csv(path, delimiter, opts.lenient)
             }
-            "tsv" => scan_df_csv(path, b'\t', opts, total_rows),
-            "parquet" => scan_df_parquet(path, opts, total_rows),
+            "tsv" => scan_df_csv(path, b'\t', opts.lenient),
+            "parquet" => scan_df_parquet(path),
             "arrow" => scan_df_arrow(path),
+            // cannot lazy read these, so they are read whole
+            "avro" => Ok(read_df_avro(path)?.lazy()),
+            "gz" | "zst" | "zstd" => Ok(read_df_compressed(path, opts)?.lazy()),
+            _ if excel::is_excel(&path) => Ok(excel::read_df_excel(path)?.lazy()),
             _ => Err(OxenError::basic_str(err)),
         },
         None => Err(OxenError::basic_str(err)),
@@ -924,9 +1098,8 @@ pub fn scan_df(
 }
 
 pub fn get_size(path: impl AsRef<Path>) -> Result<DataFrameSize, OxenError> {
-    // Don't need that many rows to get the width
-    let num_scan_rows = constants::DEFAULT_PAGE_SIZE;
-    let lazy_df = scan_df(&path, &DFOpts::empty(), num_scan_rows)?;
+    // Only reads the schema to get the width
+    let lazy_df = scan_df(&path, &DFOpts::empty())?;
     let schema = lazy_df.schema()?;
     let width = schema.len();
 
@@ -974,6 +1147,14 @@ pub fn get_size(path: impl AsRef<Path>) -> Result<DataFrameSize, OxenError> {
                 let height = df.height();
                 Ok(DataFrameSize { width, height })
             }
+            "avro" | "gz" | "zst" | "zstd" => {
+                let height = count_rows(lazy_df)?;
+                Ok(DataFrameSize { width, height })
+            }
+            _ if excel::is_excel(input_path) => {
+                let height = count_rows(lazy_df)?;
+                Ok(DataFrameSize { width, height })
+            }
             _ => Err(OxenError::basic_str(err)),
         },
         None => Err(OxenError::basic_str(err)),
@@ -1067,6 +1248,118 @@ pub fn write_df(df: &mut DataFrame, path: impl AsRef<Path>) -> Result<(), OxenEr
     }
 }
 
+/// Write over the file at `path` in the format it is already in, keeping the delimiter of csv
+/// and tsv files and the compression of compressed files
+pub fn overwrite_df(
+    df: &mut DataFrame,
+    path: impl AsRef<Path>,
+    opts: &DFOpts,
+) -> Result<(), OxenError> {
+    let path = path.as_ref();
+    write_df_like(df, path, path, opts)
+}
+
+/// Write to `output` in the format of its extension, keeping the delimiter and compression of
+/// `source` when the output is in the same format as the source
+pub fn write_df_like(
+    df: &mut DataFrame,
+    source: impl AsRef<Path>,
+    output: impl AsRef<Path>,
+    opts: &DFOpts,
+) -> Result<(), OxenError> {
+    let source = source.as_ref();
+    let output = output.as_ref();
+
+    // Sniff everything from the source before the output (which may be the source) is truncated
+    let same_format = source == output || format_extensions(source) == format_extensions(output);
+    let delimiter = if same_format {
+        source_delimiter(source, opts)?
+    } else {
+        None
+    };
+
+    if compressed::Compression::from_path(output).is_some() {
+        let format = compressed::inner_format(output).or_else(|| {
+            same_format
+                .then(|| compressed::inner_format(source))
+                .flatten()
+        });
+        return write_df_compressed(df, output, format, delimiter);
+    }
+
+    match (output.extension().and_then(OsStr::to_str), delimiter) {
+        (Some("csv") | Some("data") | Some("tsv"), Some(delimiter)) => {
+            write_df_csv(df, output, delimiter)
+        }
+        (Some("data"), None) => write_df_csv(df, output, b','),
+        _ => write_df(df, output),
+    }
+}
+
+// The extension and the inner extension of compressed files, ie: ("gz", "csv") for `data.csv.gz`
+fn format_extensions(path: &Path) -> (Option<&OsStr>, Option<&OsStr>) {
+    let inner = path
+        .file_stem()
+        .and_then(|stem| Path::new(stem).extension());
+    (path.extension(), inner)
+}
+
+// Delimiter the file at `path` was written with, `None` if it is not delimited text
+fn source_delimiter(path: &Path, opts: &DFOpts) -> Result<Option<u8>, OxenError> {
+    let format = match path.extension().and_then(OsStr::to_str) {
+        Some("gz") | Some("zst") | Some("zstd") => compressed::inner_format(path),
+        extension => extension,
+    };
+    match format {
+        Some("tsv") => Ok(Some(b'\t')),
+        Some("csv") | Some("data") if compressed::Compression::from_path(path).is_some() => {
+            Ok(Some(sniff_compressed_csv_delimiter(path, opts)?))
+        }
+        Some("csv") | Some("data") => Ok(Some(sniff_db_csv_delimiter(path, opts)?)),
+        _ => Ok(None),
+    }
+}
+
+fn sniff_compressed_csv_delimiter(path: &Path, opts: &DFOpts) -> Result<u8, OxenError> {
+    if opts.delimiter.is_some() {
+        return sniff_db_csv_delimiter(path, opts);
+    }
+    let bytes = compressed::head(path, compressed::SNIFF_BYTES)?;
+    Ok(qsv_sniffer::Sniffer::new()
+        .sniff_reader(Cursor::new(bytes))
+        .map(|metadata| metadata.dialect.delimiter)
+        .unwrap_or(b','))
+}
+
+fn write_df_compressed(
+    df: &mut DataFrame,
+    output: &Path,
+    format: Option<&str>,
+    delimiter: Option<u8>,
+) -> Result<(), OxenError> {
+    log::debug!("Writing compressed file {:?} as {:?}", output, format);
+    let writer = compressed::writer(output)?;
+    match format {
+        Some("jsonl") | Some("ndjson") => JsonWriter::new(writer)
+            .with_json_format(JsonFormat::JsonLines)
+            .finish(df)?,
+        Some("tsv") => CsvWriter::new(writer)
+            .include_header(true)
+            .with_separator(b'\t')
+            .finish(df)?,
+        Some(_) => CsvWriter::new(writer)
+            .include_header(true)
+            .with_separator(delimiter.unwrap_or(b','))
+            .finish(df)?,
+        None => {
+            return Err(OxenError::basic_str(format!(
+                "Unknown file type in compressed file {output:?}"
+            )))
+        }
+    }
+    Ok(())
+}
+
 pub fn copy_df(input: impl AsRef<Path>, output: impl AsRef<Path>) -> Result<DataFrame, OxenError> {
     let mut df = read_df(input, DFOpts::empty())?;
     write_df_arrow(&mut df, output)?;
@@ -1112,9 +1405,7 @@ pub fn show_path(input: impl AsRef<Path>, opts: DFOpts) -> Result<DataFrame, Oxe
 
 pub fn get_schema(input: impl AsRef<Path>) -> Result<crate::model::Schema, OxenError> {
     let opts = DFOpts::empty();
-    // don't need many rows to get schema
-    let total_rows = constants::DEFAULT_PAGE_SIZE;
-    let df = scan_df(input, &opts, total_rows)?;
+    let df = scan_df(input, &opts)?;
     let schema = df.schema().expect("Could not get schema");
 
     Ok(crate::model::Schema::from_polars(&schema))
@@ -1125,7 +1416,7 @@ pub fn schema_to_string<P: AsRef<Path>>(
     flatten: bool,
     opts: &DFOpts,
 ) -> Result<String, OxenError> {
-    let df = scan_df(input, opts, constants::DEFAULT_PAGE_SIZE)?;
+    let df = scan_df(input, opts)?;
     let schema = df.schema().expect("Could not get schema");
 
     if flatten {
@@ -1138,7 +1429,7 @@ pub fn schema_to_string<P: AsRef<Path>>(
         for field in schema.iter_fields() {
             let dtype = DataType::from_polars(field.data_type());
             let field_str = field.name().to_string();
-            let dtype_str = String::from(DataType::as_str(&dtype));
+            let dtype_str = dtype.to_string();
             table.add_row(vec![field_str, dtype_str]);
         }
 
@@ -1155,7 +1446,7 @@ fn schema_to_flat_str(schema: &Schema) -> String {
 
         let dtype = DataType::from_polars(field.data_type());
         let field_str = field.name().to_string();
-        let dtype_str = String::from(DataType::as_str(&dtype));
+        let dtype_str = dtype.to_string();
         result = format!("{result}{field_str}:{dtype_str}");
     }
 
@@ -1167,7 +1458,12 @@ mod tests {
     use crate::core::df::{filter, tabular};
     use crate::view::JsonDataFrameView;
     use crate::{error::OxenError, opts::DFOpts};
+    use crate::{test, util};
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
     use polars::prelude::*;
+    use std::fs::File;
+    use std::io::Write;
 
     #[test]
     fn test_filter_single_expr() -> Result<(), OxenError> {
@@ -1262,6 +1558,42 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_filter_grouped_typed_expr() -> Result<(), OxenError> {
+        let df = df!(
+            "image" => &["0000.jpg", "0001.jpg", "0002.png", "0003.jpg"],
+            "label" => &[Some("cat"), Some("dog"), None, Some("bird")],
+            "min_x" => &[0.0, 1.0, 2.0, 3.0],
+            "is_correct" => &[true, false, false, true],
+        )
+        .unwrap();
+
+        let query =
+            Some("label in [cat, bird] && (min_x >= 3 || !(is_correct == false))".to_string());
+        let filter = filter::parse(query)?.unwrap();
+        let filtered_df = tabular::filter_df(df.clone().lazy(), &filter)?
+            .collect()
+            .unwrap();
+        let images: Vec<Option<&str>> = filtered_df.column("image")?.str()?.into_iter().collect();
+        assert_eq!(images, vec![Some("0000.jpg"), Some("0003.jpg")]);
+
+        let query = Some("image =~ '\\.jpg$' && label is not null && label contains o".to_string());
+        let filter = filter::parse(query)?.unwrap();
+        let filtered_df = tabular::filter_df(df.clone().lazy(), &filter)?
+            .collect()
+            .unwrap();
+        let images: Vec<Option<&str>> = filtered_df.column("image")?.str()?.into_iter().collect();
+        assert_eq!(images, vec![Some("0001.jpg")]);
+
+        // Values that do not match the type of the column are an error instead of a panic
+        let filter = filter::parse(Some("min_x > big".to_string()))?.unwrap();
+        assert!(tabular::filter_df(df.clone().lazy(), &filter).is_err());
+        let filter = filter::parse(Some("unknown == 1".to_string()))?.unwrap();
+        assert!(tabular::filter_df(df.lazy(), &filter).is_err());
+
+        Ok(())
+    }
+
     #[test]
     fn test_unique_single_field() -> Result<(), OxenError> {
         let fields = "label";
@@ -1404,10 +1736,9 @@ mod tests {
     fn test_slice_parquet_lazy() -> Result<(), OxenError> {
         let mut opts = DFOpts::empty();
         opts.slice = Some("329..333".to_string());
-        let df = tabular::scan_df_parquet("data/test/parquet/wiki_1k.parquet", &opts, 333)?;
-        let height = 4;
-        let df = tabular::transform_lazy(df, height, opts.clone())?;
-        let mut df = tabular::transform_slice_lazy(df.lazy(), height, opts)?;
+        let df = tabular::scan_df_parquet("data/test/parquet/wiki_1k.parquet")?;
+        let df = tabular::transform_lazy(df, &opts)?;
+        let mut df = tabular::transform_slice_lazy(df, opts)?;
         println!("{df:?}");
 
         assert_eq!(df.width(), 3);
@@ -1448,4 +1779,69 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_take_lazy_keeps_index_order() -> Result<(), OxenError> {
+        let df = df!(
+            "id" => &[0, 1, 2, 3, 4],
+            "label" => &["a", "b", "c", "d", "e"],
+        )
+        .unwrap();
+
+        let df = tabular::take_lazy(df.lazy(), vec![3, 0, 3])?
+            .collect()
+            .unwrap();
+        assert_eq!(df.width(), 2);
+        let labels: Vec<Option<&str>> = df.column("label")?.str()?.into_iter().collect();
+        assert_eq!(labels, vec![Some("d"), Some("a"), Some("d")]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_scan_transform_counts_and_tails_without_height() -> Result<(), OxenError> {
+        let path = "data/test/parquet/wiki_1k.parquet";
+        let df = tabular::scan_df(path, &DFOpts::empty())?;
+        assert_eq!(tabular::count_rows(df)?, 1024);
+
+        let mut opts = DFOpts::empty();
+        opts.tail = Some(2);
+        opts.should_reverse = true;
+        let df = tabular::read_df(path, opts)?;
+        assert_eq!(df.height(), 2);
+
+        let reversed = tabular::read_df(path, DFOpts::empty())?.reverse();
+        let expected = reversed.slice(1022, 2);
+        assert_eq!(df.column("title")?, expected.column("title")?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_compressed_csv_and_jsonl() -> Result<(), OxenError> {
+        test::run_empty_dir_test(|dir| {
+            let csv_path = dir.join("labels.csv.gz");
+            let mut encoder = GzEncoder::new(File::create(&csv_path)?, Compression::default());
+            encoder.write_all(b"file;label\n0.jpg;cat\n1.jpg;dog\n2.jpg;cat\n")?;
+            encoder.finish()?;
+
+            assert!(util::fs::is_tabular(&csv_path));
+            let df = tabular::read_df(&csv_path, DFOpts::empty())?;
+            assert_eq!(df.width(), 2);
+            assert_eq!(df.height(), 3);
+            let size = tabular::get_size(&csv_path)?;
+            assert_eq!((size.width, size.height), (2, 3));
+
+            let jsonl_path = dir.join("labels.jsonl.zst");
+            let bytes = b"{\"file\": \"0.jpg\", \"label\": \"cat\"}\n{\"file\": \"1.jpg\", \"label\": \"dog\"}\n";
+            std::fs::write(&jsonl_path, zstd::encode_all(&bytes[..], 0)?)?;
+
+            let mut opts = DFOpts::empty();
+            opts.filter = Some("label == dog".to_string());
+            let df = tabular::read_df(&jsonl_path, opts)?;
+            assert_eq!(df.height(), 1);
+
+            Ok(())
+        })
+    }
 }
@@ -16,6 +16,7 @@ pub mod merge_conflict_reader;
 pub mod merge_conflict_writer;
 pub mod merger;
 pub mod mod_stager;
+pub mod mv;
 pub mod object_db_reader;
 pub mod oxenignore;
 pub mod puller;
@@ -51,6 +52,7 @@ pub use crate::core::index::legacy_schema_reader::LegacySchemaReader;
 pub use crate::core::index::merge_conflict_db_reader::MergeConflictDBReader;
 pub use crate::core::index::merge_conflict_reader::MergeConflictReader;
 pub use crate::core::index::merger::Merger;
+pub use crate::core::index::mv::mv;
 pub use crate::core::index::object_db_reader::ObjectDBReader;
 pub use crate::core::index::ref_db_reader::RefDBReader;
 pub use crate::core::index::ref_reader::RefReader;
@@ -1,5 +1,7 @@
 use crate::config::UserConfig;
-use crate::constants::{COMMITS_DIR, MERGE_HEAD_FILE, ORIG_HEAD_FILE};
+use crate::constants::{
+    COMMITS_DIR, COMMIT_METADATA_FILE, COMMIT_MOVES_METADATA_KEY, MERGE_HEAD_FILE, ORIG_HEAD_FILE,
+};
 use crate::core::db::path_db;
 
 use crate::core::db;
@@ -9,7 +11,7 @@ use crate::core::index::{
     RefWriter,
 };
 use crate::error::OxenError;
-use crate::model::{Branch, Commit, CommitEntry, NewCommit, StagedData, StagedEntry};
+use crate::model::{Branch, Commit, CommitEntry, MovedEntry, NewCommit, StagedData, StagedEntry};
 
 use crate::util::progress_bar::{oxen_progress_bar, ProgressBarType};
 use crate::{command, util};
@@ -151,15 +153,29 @@ impl CommitWriter {
         status: &StagedData,
         origin_path: &Path,
     ) -> Result<Commit, OxenError> {
-        let commit = self.gen_commit(new_commit, status);
+        let mut commit = self.gen_commit(new_commit, status);
         log::debug!(
             "commit_from_new commit Id computed {} -> [{}]",
             commit.id,
             commit.message
         );
 
+        let metadata_path =
+            util::fs::oxen_hidden_dir(&self.repository.path).join(COMMIT_METADATA_FILE);
+        if metadata_path.exists() {
+            let metadata = util::fs::read_from_path(&metadata_path)?;
+            commit.metadata = Some(serde_json::from_str(&metadata)?);
+        }
+
+        add_moves_to_metadata(&mut commit, status);
+
         let commit = self.add_commit_from_status(&commit, status, origin_path)?;
 
+        // Metadata only applies to the commit it was staged for
+        if metadata_path.exists() {
+            util::fs::remove_file(metadata_path)?;
+        }
+
         Ok(commit)
     }
 
@@ -171,7 +187,7 @@ impl CommitWriter {
         branch: &Branch,
         user_id: &str,
     ) -> Result<Commit, OxenError> {
-        let commit = self.gen_commit(new_commit, status);
+        let mut commit = self.gen_commit(new_commit, status);
 
         let entries = mod_stager::list_mod_entries(&self.repository, branch, user_id)?;
         let object_reader = ObjectDBReader::new(&self.repository)?;
@@ -193,6 +209,7 @@ impl CommitWriter {
             entries.len()
         );
         let staged = self.apply_mods(branch, user_id, &entries)?;
+        add_moves_to_metadata(&mut commit, &staged);
         // Write entries
         self.add_commit_from_status_on_remote_branch(&commit, &staged, origin_path, branch)?;
 
@@ -813,6 +830,33 @@ impl CommitWriter {
     }
 }
 
+/// Records the moved files on the commit, so that diffs and `oxen log --follow` can trace them
+fn add_moves_to_metadata(commit: &mut Commit, status: &StagedData) {
+    if status.moved_files.is_empty() {
+        return;
+    }
+
+    let mut moves: Vec<MovedEntry> = status
+        .moved_files
+        .iter()
+        .map(|(to, from, _hash)| MovedEntry {
+            from: from.to_owned(),
+            to: to.to_owned(),
+        })
+        .collect();
+    moves.sort_by(|a, b| a.to.cmp(&b.to));
+
+    let mut metadata = match commit.metadata.take() {
+        Some(serde_json::Value::Object(metadata)) => metadata,
+        _ => serde_json::Map::new(),
+    };
+    metadata.insert(
+        COMMIT_MOVES_METADATA_KEY.to_string(),
+        serde_json::to_value(moves).unwrap_or_default(),
+    );
+    commit.metadata = Some(serde_json::Value::Object(metadata));
+}
+
 #[cfg(test)]
 mod tests {
     use std::path::Path;
@@ -10,13 +10,14 @@ use polars::frame::DataFrame;
 
 use rocksdb::{DBWithThreadMode, MultiThreaded, SingleThreaded};
 
-use crate::constants::{FILES_DIR, MODS_DIR, OXEN_HIDDEN_DIR, STAGED_DIR};
+use crate::constants::{FILES_DIR, MODS_DIR, OXEN_HIDDEN_DIR, OXEN_ID_COL, STAGED_DIR};
 use crate::core::db::{self, df_db, staged_df_db, str_json_db};
 use crate::core::df::tabular;
 use crate::core::index::remote_df_stager;
 use crate::error::OxenError;
 use crate::model::diff::DiffResult;
-use crate::model::entry::mod_entry::NewMod;
+use crate::model::entry::mod_entry::{ModBatch, NewMod};
+use crate::model::ContentType;
 use crate::model::{Branch, CommitEntry, LocalRepository, Schema};
 
 use crate::{api, util};
@@ -141,7 +142,114 @@ pub fn delete_row(
         staged_df_db::delete_row(&conn, uuid)?
     };
 
-    // TODO: Better way of tracking when a file is restored to its original state without diffing
+    untrack_if_restored(repo, branch, identity, path)?;
+    Ok(deleted_row)
+}
+
+/// Update the row with the given id in place, only touching the columns present in the data.
+pub fn update_row(
+    repo: &LocalRepository,
+    branch: &Branch,
+    identifier: &str,
+    uuid: &str,
+    new_mod: &NewMod,
+) -> Result<DataFrame, OxenError> {
+    let schema = staged_schema(repo, &new_mod.entry)?;
+    let df = tabular::parse_data_into_df(&new_mod.data, &schema, new_mod.content_type.to_owned())?;
+
+    let db_path = mods_df_db_path(repo, branch, identifier, &new_mod.entry.path);
+    let updated_row = {
+        let conn = df_db::get_connection(db_path)?;
+        staged_df_db::update_row(&conn, uuid, &df)?
+    };
+
+    // Setting a row back to its committed values can restore the file
+    track_mod_commit_entry(repo, branch, identifier, &new_mod.entry)?;
+    untrack_if_restored(repo, branch, identifier, &new_mod.entry.path)?;
+    Ok(updated_row)
+}
+
+/// Apply a batch of inserts, updates and deletes to a staged data frame in a single transaction,
+/// returning the rows that were added or modified.
+pub fn apply_batch(
+    repo: &LocalRepository,
+    branch: &Branch,
+    identifier: &str,
+    entry: &CommitEntry,
+    batch: &ModBatch,
+) -> Result<DataFrame, OxenError> {
+    if batch.is_empty() {
+        return Err(OxenError::basic_str("Batch has no rows to modify"));
+    }
+    let schema = staged_schema(repo, entry)?;
+
+    let db_path = mods_df_db_path(repo, branch, identifier, &entry.path);
+    let rows = {
+        let mut conn = df_db::get_connection(db_path)?;
+        let tx = conn.transaction()?;
+        let mut row_ids: Vec<String> = vec![];
+
+        if !batch.added.is_empty() {
+            let data = batch
+                .added
+                .iter()
+                .map(serde_json::to_string)
+                .collect::<Result<Vec<String>, _>>()?
+                .join("\n");
+            let df = tabular::parse_data_into_df(&data, &schema, ContentType::Json)?;
+            let added = staged_df_db::append_row(&tx, &df)?;
+            for value in added.column(OXEN_ID_COL)?.str()?.into_iter().flatten() {
+                row_ids.push(value.to_string());
+            }
+        }
+
+        for row in batch.modified.iter() {
+            let mut row = row.to_owned();
+            let uuid = match row.as_object_mut().and_then(|obj| obj.remove(OXEN_ID_COL)) {
+                Some(serde_json::Value::String(uuid)) => uuid,
+                _ => {
+                    return Err(OxenError::basic_str(format!(
+                        "Modified row must be a json object with a string {OXEN_ID_COL}: {row}"
+                    )))
+                }
+            };
+            let data = serde_json::to_string(&row)?;
+            let df = tabular::parse_data_into_df(&data, &schema, ContentType::Json)?;
+            staged_df_db::update_row(&tx, &uuid, &df)?;
+            row_ids.push(uuid);
+        }
+
+        for uuid in batch.removed.iter() {
+            staged_df_db::delete_row(&tx, uuid)?;
+        }
+
+        let rows = staged_df_db::select_rows(&tx, &row_ids)?;
+        tx.commit()?;
+        rows
+    };
+
+    track_mod_commit_entry(repo, branch, identifier, entry)?;
+    untrack_if_restored(repo, branch, identifier, &entry.path)?;
+    Ok(rows)
+}
+
+fn staged_schema(repo: &LocalRepository, entry: &CommitEntry) -> Result<Schema, OxenError> {
+    let schema_reader = SchemaReader::new(repo, &entry.commit_id)?;
+    match schema_reader.get_schema_for_file(&entry.path)? {
+        Some(schema) => Ok(schema),
+        None => {
+            let err = format!("Schema not found for file {:?}", entry.path);
+            Err(OxenError::basic_str(err))
+        }
+    }
+}
+
+fn untrack_if_restored(
+    repo: &LocalRepository,
+    branch: &Branch,
+    identity: &str,
+    path: &Path,
+) -> Result<(), OxenError> {
     // TODO: Better way of tracking when a file is restored to its original state without diffing
     let diff = api::local::diff::diff_staged_df(repo, branch, PathBuf::from(path), identity)?;
 
@@ -158,7 +266,7 @@ pub fn delete_row(
             str_json_db::delete(&files_db, key)?;
         }
     }
-    Ok(deleted_row)
+    Ok(())
 }
 
 // pub fn list_mods_raw(
@@ -268,7 +376,7 @@ mod tests {
     use crate::error::OxenError;
     use crate::model::diff::DiffResult;
     use crate::model::entry::mod_entry::ModType;
-    use crate::model::entry::mod_entry::NewMod;
+    use crate::model::entry::mod_entry::{ModBatch, NewMod};
     use crate::model::ContentType;
     use crate::opts::DFOpts;
     use crate::test;
@@ -510,4 +618,139 @@ mod tests {
             Ok(())
         })
     }
+
+    #[test]
+    fn test_stage_update_row_is_modified() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            let branch_name = "test-update";
+            let branch = api::local::branches::create_checkout(&repo, branch_name)?;
+            let identity = UserConfig::identifier()?;
+            let file_path = Path::new("annotations")
+                .join("train")
+                .join("bounding_box.csv");
+            let commit = api::local::commits::get_by_id(&repo, &branch.commit_id)?.unwrap();
+            let commit_entry =
+                api::local::entries::get_commit_entry(&repo, &commit, &file_path)?.unwrap();
+
+            let opts = DFOpts::empty();
+            let indexed =
+                remote_df_stager::index_dataset(&repo, &branch, &file_path, &identity, &opts)?;
+            let row_id = indexed.column(OXEN_ID_COL)?.get(0)?.to_string();
+            let row_id = row_id.replace('"', "");
+            let original_label = indexed
+                .column("label")?
+                .get(0)?
+                .get_str()
+                .unwrap()
+                .to_string();
+
+            // Only the label changes, the rest of the row is kept
+            let new_mod = NewMod {
+                entry: commit_entry.clone(),
+                data: "{\"label\": \"wolf\"}".to_string(),
+                mod_type: ModType::Modify,
+                content_type: ContentType::Json,
+            };
+            let updated = mod_stager::update_row(&repo, &branch, &identity, &row_id, &new_mod)?;
+            assert_eq!(updated.column("label")?.get(0)?.get_str(), Some("wolf"));
+            assert_eq!(
+                updated.column("file")?.get(0)?,
+                indexed.column("file")?.get(0)?
+            );
+
+            let commit_entries = mod_stager::list_mod_entries(&repo, &branch, &identity)?;
+            assert_eq!(commit_entries.len(), 1);
+
+            let diff =
+                api::local::diff::diff_staged_df(&repo, &branch, file_path.clone(), &identity)?;
+            match diff {
+                DiffResult::Tabular(tabular_diff) => {
+                    let counts = tabular_diff.summary.modifications.row_counts;
+                    assert_eq!(counts.modified, 1);
+                    assert_eq!(counts.added, 0);
+                    assert_eq!(counts.removed, 0);
+                }
+                _ => panic!("Expected tabular diff result"),
+            }
+
+            // Setting the value back restores the file
+            let new_mod = NewMod {
+                data: format!("{{\"label\": \"{original_label}\"}}"),
+                ..new_mod
+            };
+            mod_stager::update_row(&repo, &branch, &identity, &row_id, &new_mod)?;
+            let commit_entries = mod_stager::list_mod_entries(&repo, &branch, &identity)?;
+            assert_eq!(commit_entries.len(), 0);
+
+            // Unknown rows are not found
+            let result = mod_stager::update_row(&repo, &branch, &identity, "not-a-row", &new_mod);
+            assert!(result.is_err());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_stage_apply_batch() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            let branch_name = "test-batch";
+            let branch = api::local::branches::create_checkout(&repo, branch_name)?;
+            let identity = UserConfig::identifier()?;
+            let file_path = Path::new("annotations")
+                .join("train")
+                .join("bounding_box.csv");
+            let commit = api::local::commits::get_by_id(&repo, &branch.commit_id)?.unwrap();
+            let commit_entry =
+                api::local::entries::get_commit_entry(&repo, &commit, &file_path)?.unwrap();
+
+            let opts = DFOpts::empty();
+            let indexed =
+                remote_df_stager::index_dataset(&repo, &branch, &file_path, &identity, &opts)?;
+            let ids: Vec<String> = (0..2)
+                .map(|i| {
+                    let id = indexed.column(OXEN_ID_COL).unwrap().get(i).unwrap();
+                    id.to_string().replace('"', "")
+                })
+                .collect();
+
+            let batch: ModBatch = serde_json::from_value(serde_json::json!({
+                "added": [
+                    {"file": "dawg1.jpg", "label": "dog", "min_x": 13, "min_y": 14, "width": 100, "height": 100},
+                    {"file": "dawg2.jpg", "label": "dog", "min_x": 13, "min_y": 14, "width": 100, "height": 100}
+                ],
+                "modified": [{"_oxen_id": ids[0], "label": "wolf"}],
+                "removed": [ids[1]]
+            }))?;
+            let rows = mod_stager::apply_batch(&repo, &branch, &identity, &commit_entry, &batch)?;
+            assert_eq!(rows.height(), 3);
+
+            let diff = api::local::diff::diff_staged_df(&repo, &branch, file_path, &identity)?;
+            match diff {
+                DiffResult::Tabular(tabular_diff) => {
+                    let counts = tabular_diff.summary.modifications.row_counts;
+                    assert_eq!(counts.added, 2);
+                    assert_eq!(counts.modified, 1);
+                    assert_eq!(counts.removed, 1);
+                }
+                _ => panic!("Expected tabular diff result"),
+            }
+
+            // A bad id rolls back the whole batch
+            let count_before =
+                remote_df_stager::count(&repo, &branch, commit_entry.path.clone(), &identity)?;
+            let batch: ModBatch = serde_json::from_value(serde_json::json!({
+                "added": [
+                    {"file": "dawg3.jpg", "label": "dog", "min_x": 13, "min_y": 14, "width": 100, "height": 100}
+                ],
+                "removed": ["not-a-row"]
+            }))?;
+            let result = mod_stager::apply_batch(&repo, &branch, &identity, &commit_entry, &batch);
+            assert!(result.is_err());
+            let count_after =
+                remote_df_stager::count(&repo, &branch, commit_entry.path, &identity)?;
+            assert_eq!(count_before, count_after);
+
+            Ok(())
+        })
+    }
 }
@@ -0,0 +1,307 @@
+//! Based on: <https://git-scm.com/docs/git-mv>
+//! Move or rename a file or directory and stage the move.
+//! The files keep the hashes and schemas they already have, so their contents are not read again,
+//! and the move is recorded on the next commit so the new paths keep the history of the old ones.
+
+use crate::api;
+use crate::config::UserConfig;
+use crate::error::OxenError;
+use crate::model::{LocalRepository, StagedEntryStatus};
+use crate::opts::MvOpts;
+use crate::util;
+
+use super::CommitEntryReader;
+use super::SchemaReader;
+use super::Stager;
+
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
+
+pub async fn mv(repo: &LocalRepository, opts: &MvOpts) -> Result<(), OxenError> {
+    let src = relative_path(repo, &opts.source)?;
+    let mut dst = relative_path(repo, &opts.destination)?;
+    if opts.remote {
+        return mv_remote(repo, &src, &dst).await;
+    }
+
+    let head = api::local::commits::head_commit(repo)?;
+    let entry_reader = CommitEntryReader::new(repo, &head)?;
+    let schema_reader = SchemaReader::new(repo, &head.id)?;
+    let stager = Stager::new(repo)?;
+
+    // Moving into an existing directory keeps the name
+    if repo.path.join(&dst).is_dir() {
+        let Some(file_name) = src.file_name() else {
+            return Err(OxenError::file_has_no_name(&src));
+        };
+        dst = dst.join(file_name);
+    }
+    if src == dst {
+        return Ok(());
+    }
+
+    let files = list_files_to_move(&stager, &entry_reader, &src)?;
+    if files.is_empty() {
+        let error = format!("Path {src:?} does not match any staged or committed files");
+        return Err(OxenError::basic_str(error));
+    }
+
+    let moves: Vec<(PathBuf, PathBuf)> = files
+        .into_iter()
+        .map(|file| {
+            let moved_to = if file == src {
+                dst.to_owned()
+            } else {
+                dst.join(file.strip_prefix(&src)?)
+            };
+            Ok((file, moved_to))
+        })
+        .collect::<Result<_, OxenError>>()?;
+
+    let full_src = repo.path.join(&src);
+    let full_dst = repo.path.join(&dst);
+    if full_src.exists() {
+        if full_dst.exists() {
+            let error = format!("Destination {dst:?} already exists");
+            return Err(OxenError::basic_str(error));
+        }
+        if let Some(parent) = full_dst.parent() {
+            util::fs::create_dir_all(parent)?;
+        }
+        util::fs::rename(&full_src, &full_dst)?;
+    } else if full_dst.exists() {
+        // The file might have been moved on disk before using `oxen mv`, which is only the same
+        // file if the contents did not change
+        for (file, moved_to) in moves.iter() {
+            let full_path = repo.path.join(moved_to);
+            let hash = if full_path.is_file() {
+                Some(util::hasher::hash_file_contents(&full_path)?)
+            } else {
+                None
+            };
+            if hash != stager.staged_or_committed_hash(file, &entry_reader)? {
+                let error = format!("Destination {moved_to:?} already exists");
+                return Err(OxenError::basic_str(error));
+            }
+        }
+    } else {
+        let error = format!("Neither {src:?} nor {dst:?} exist");
+        return Err(OxenError::basic_str(error));
+    }
+
+    for (file, moved_to) in moves {
+        stager.add_moved_file(&file, &moved_to, &entry_reader, &schema_reader)?;
+    }
+
+    Ok(())
+}
+
+// Relative paths are relative to the directory oxen is run from, like for the other commands
+fn relative_path(repo: &LocalRepository, path: &Path) -> Result<PathBuf, OxenError> {
+    let current_dir = std::env::current_dir()?;
+    let full_path = normalize(&current_dir.join(path));
+    let repo_path = normalize(&current_dir.join(&repo.path));
+    match full_path.strip_prefix(&repo_path) {
+        Ok(relative) => Ok(relative.to_owned()),
+        Err(_) => Err(OxenError::basic_str(format!(
+            "Path {path:?} is outside of the repository"
+        ))),
+    }
+}
+
+// Resolves `.` and `..` without reading the file system, since the destination does not exist
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+// The committed and staged files at `src`, or within it if it is a directory
+fn list_files_to_move(
+    stager: &Stager,
+    entry_reader: &CommitEntryReader,
+    src: &Path,
+) -> Result<Vec<PathBuf>, OxenError> {
+    let status = stager.status_without_untracked(entry_reader)?;
+    let mut files: HashSet<PathBuf> = HashSet::new();
+
+    if entry_reader.has_file(src) {
+        files.insert(src.to_owned());
+    } else if entry_reader.has_dir(src) {
+        for entry in entry_reader.list_directory(src)? {
+            files.insert(entry.path);
+        }
+    }
+
+    for (path, entry) in status.staged_files.iter() {
+        if !(path == src || path.starts_with(src)) {
+            continue;
+        }
+        match entry.status {
+            StagedEntryStatus::Removed => {
+                files.remove(path);
+            }
+            StagedEntryStatus::Added | StagedEntryStatus::Modified => {
+                files.insert(path.to_owned());
+            }
+        }
+    }
+
+    let mut files: Vec<PathBuf> = files.into_iter().collect();
+    files.sort();
+    Ok(files)
+}
+
+async fn mv_remote(repo: &LocalRepository, src: &Path, dst: &Path) -> Result<(), OxenError> {
+    let Some(branch) = api::local::branches::current_branch(repo)? else {
+        return Err(OxenError::must_be_on_valid_branch());
+    };
+    let branch_name = branch.name;
+    let remote_repo = api::remote::repositories::get_default_remote(repo).await?;
+    let user_id = UserConfig::identifier()?;
+    api::remote::staging::mv_file(&remote_repo, &branch_name, &user_id, src, dst).await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::api;
+    use crate::command;
+    use crate::core::index::mv;
+    use crate::error::OxenError;
+    use crate::model::StagedEntryStatus;
+    use crate::opts::MvOpts;
+    use crate::test;
+    use crate::util;
+
+    #[tokio::test]
+    async fn test_mv_committed_file() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            util::fs::write_to_path(repo.path.join("hello.txt"), "Hello")?;
+            command::add(&repo, &repo.path)?;
+            command::commit(&repo, "Adding hello")?;
+
+            let opts = MvOpts::from_paths(
+                repo.path.join("hello.txt"),
+                repo.path.join("greetings").join("hello.txt"),
+            );
+            mv::mv(&repo, &opts).await?;
+
+            assert!(!repo.path.join("hello.txt").exists());
+            assert!(repo.path.join("greetings").join("hello.txt").exists());
+
+            let status = command::status(&repo)?;
+            assert_eq!(status.moved_files.len(), 1);
+            let (to, from, _) = &status.moved_files[0];
+            assert_eq!(to, Path::new("greetings/hello.txt"));
+            assert_eq!(from, Path::new("hello.txt"));
+
+            let commit = command::commit(&repo, "Moving hello")?;
+            let moves = api::local::commits::list_moves(&commit);
+            assert_eq!(moves.len(), 1);
+            assert_eq!(moves[0].from, Path::new("hello.txt"));
+            assert_eq!(moves[0].to, Path::new("greetings/hello.txt"));
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_mv_dir_into_existing_dir() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            util::fs::write_to_path(repo.path.join("data").join("a.txt"), "a")?;
+            util::fs::write_to_path(repo.path.join("data").join("b.txt"), "b")?;
+            util::fs::write_to_path(repo.path.join("archive").join("README.md"), "old")?;
+            command::add(&repo, &repo.path)?;
+            command::commit(&repo, "Adding data")?;
+
+            // A staged file in the dir moves with the committed ones
+            util::fs::write_to_path(repo.path.join("data").join("c.txt"), "c")?;
+            command::add(&repo, repo.path.join("data").join("c.txt"))?;
+
+            let opts = MvOpts::from_paths(repo.path.join("data"), repo.path.join("archive"));
+            mv::mv(&repo, &opts).await?;
+
+            let status = command::status(&repo)?;
+            let moved_to = Path::new("archive").join("data");
+            for name in ["a.txt", "b.txt"] {
+                let entry = status.staged_files.get(&moved_to.join(name)).unwrap();
+                assert_eq!(entry.status, StagedEntryStatus::Added);
+                let entry = status
+                    .staged_files
+                    .get(&Path::new("data").join(name))
+                    .unwrap();
+                assert_eq!(entry.status, StagedEntryStatus::Removed);
+            }
+            assert!(status.staged_files.contains_key(&moved_to.join("c.txt")));
+            assert!(!status
+                .staged_files
+                .contains_key(&Path::new("data").join("c.txt")));
+            assert_eq!(status.moved_files.len(), 2);
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_mv_onto_existing_file_is_error() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            util::fs::write_to_path(repo.path.join("a.txt"), "a")?;
+            util::fs::write_to_path(repo.path.join("b.txt"), "b")?;
+            command::add(&repo, &repo.path)?;
+            command::commit(&repo, "Adding files")?;
+
+            let opts = MvOpts::from_paths(repo.path.join("a.txt"), repo.path.join("b.txt"));
+            let result = mv::mv(&repo, &opts).await;
+            assert!(result.is_err());
+            assert!(repo.path.join("a.txt").exists());
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_mv_after_moving_on_disk() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            util::fs::write_to_path(repo.path.join("a.txt"), "a")?;
+            util::fs::write_to_path(repo.path.join("b.txt"), "b")?;
+            command::add(&repo, &repo.path)?;
+            command::commit(&repo, "Adding files")?;
+
+            // Moved on disk with the same contents is staged as a move
+            util::fs::rename(repo.path.join("a.txt"), repo.path.join("c.txt"))?;
+            let opts = MvOpts::from_paths(repo.path.join("a.txt"), repo.path.join("c.txt"));
+            mv::mv(&repo, &opts).await?;
+            let status = command::status(&repo)?;
+            assert_eq!(status.moved_files.len(), 1);
+
+            // A different file at the destination is not
+            util::fs::rename(repo.path.join("b.txt"), repo.path.join("d.txt"))?;
+            util::fs::write_to_path(repo.path.join("d.txt"), "changed")?;
+            let opts = MvOpts::from_paths(repo.path.join("b.txt"), repo.path.join("d.txt"));
+            assert!(mv::mv(&repo, &opts).await.is_err());
+
+            // Neither is a file that was only deleted
+            util::fs::remove_file(repo.path.join("d.txt"))?;
+            let opts = MvOpts::from_paths(repo.path.join("b.txt"), repo.path.join("e.txt"));
+            assert!(mv::mv(&repo, &opts).await.is_err());
+            let status = command::status(&repo)?;
+            assert!(!status.staged_files.contains_key(Path::new("e.txt")));
+
+            Ok(())
+        })
+        .await
+    }
+}
@@ -343,6 +343,20 @@ async fn get_commit_objects_to_sync(
     Ok(commits_to_sync)
 }
 
+/// Total size of the entries that pushing `commits` would upload
+pub fn compute_push_size(
+    local_repo: &LocalRepository,
+    commits: &[Commit],
+) -> Result<u64, OxenError> {
+    let commit_reader = CommitReader::new(local_repo)?;
+    let mut total_size: u64 = 0;
+    for commit in commits {
+        let (_, commit_size) = get_unsynced_entries_for_commit(local_repo, commit, &commit_reader)?;
+        total_size += commit_size;
+    }
+    Ok(total_size)
+}
+
 fn get_unsynced_entries_for_commit(
     local_repo: &LocalRepository,
     commit: &Commit,
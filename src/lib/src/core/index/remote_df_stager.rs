@@ -3,7 +3,7 @@ use polars::frame::DataFrame;
 
 use sql_query_builder::Select;
 
-use crate::constants::{OXEN_ID_COL, TABLE_NAME};
+use crate::constants::{OXEN_ID_COL, OXEN_ROW_IDX_COL, OXEN_ROW_IDX_SEQ, TABLE_NAME};
 use crate::core::db::df_db;
 use crate::core::df::tabular;
 use crate::core::index::{mod_stager, remote_dir_stager};
@@ -88,12 +88,16 @@ pub fn index_dataset(
         }
     }
 
+    track_row_idx(&conn)?;
+
     let commit_path = mod_stager::mods_commit_ref_path(repo, branch, identifier, &entry.path);
     std::fs::write(commit_path, branch.commit_id.as_str())?;
 
     // Print whole table after index for debugging
 
-    let select_all = Select::new().select("*").from(TABLE_NAME);
+    let select_all = Select::new()
+        .select(&select_visible_cols())
+        .from(TABLE_NAME);
     let inserted_data = df_db::select_with_opts(&conn, &select_all, opts)?;
 
     Ok(inserted_data)
@@ -144,10 +148,14 @@ pub fn extract_dataset_to_versions_dir(
 
     match entry.path.extension() {
         Some(ext) => match ext.to_str() {
-            Some("csv") => export_csv(&version_path, &conn)?,
-            Some("tsv") => export_tsv(&version_path, &conn)?,
-            Some("json") | Some("jsonl") | Some("ndjson") => export_rest(&version_path, &conn)?,
-            Some("parquet") => export_parquet(&version_path, &conn)?,
+            Some("csv") => export_csv(&version_path, &conn, &[OXEN_ID_COL, OXEN_ROW_IDX_COL])?,
+            Some("tsv") => export_tsv(&version_path, &conn, &[OXEN_ID_COL, OXEN_ROW_IDX_COL])?,
+            Some("json") | Some("jsonl") | Some("ndjson") => {
+                export_rest(&version_path, &conn, &[OXEN_ID_COL, OXEN_ROW_IDX_COL])?
+            }
+            Some("parquet") => {
+                export_parquet(&version_path, &conn, &[OXEN_ID_COL, OXEN_ROW_IDX_COL])?
+            }
             _ => {
                 return Err(OxenError::basic_str(
                     "File format not supported, must be tabular.",
@@ -171,6 +179,8 @@ pub fn extract_dataset_to_versions_dir(
 }
 
 // TODONOW combine with versions dir export fn and genericize on path
+/// Exports the staged table to the branch working dir, keeping the row index column
+/// so the diff can line updated rows up with their committed versions.
 pub fn extract_dataset_to_working_dir(
     repo: &LocalRepository,
     branch_repo: &LocalRepository,
@@ -194,10 +204,12 @@ pub fn extract_dataset_to_working_dir(
 
     match entry.path.extension() {
         Some(ext) => match ext.to_str() {
-            Some("csv") => export_csv(&working_path, &conn)?,
-            Some("tsv") => export_tsv(&working_path, &conn)?,
-            Some("json") | Some("jsonl") | Some("ndjson") => export_rest(&working_path, &conn)?,
-            Some("parquet") => export_parquet(&working_path, &conn)?,
+            Some("csv") => export_csv(&working_path, &conn, &[OXEN_ID_COL])?,
+            Some("tsv") => export_tsv(&working_path, &conn, &[OXEN_ID_COL])?,
+            Some("json") | Some("jsonl") | Some("ndjson") => {
+                export_rest(&working_path, &conn, &[OXEN_ID_COL])?
+            }
+            Some("parquet") => export_parquet(&working_path, &conn, &[OXEN_ID_COL])?,
             _ => {
                 return Err(OxenError::basic_str(
                     "File format not supported, must be tabular.",
@@ -232,7 +244,7 @@ pub fn get_row_by_id(
     let conn = df_db::get_connection(db_path)?;
 
     let query = Select::new()
-        .select("*")
+        .select(&select_visible_cols())
         .from(TABLE_NAME)
         .where_clause(&format!("{} = '{}'", OXEN_ID_COL, row_id));
     let data = df_db::select(&conn, &query)?;
@@ -250,7 +262,9 @@ pub fn query_staged_df(
     let db_path = mod_stager::mods_df_db_path(repo, branch, identifier, path);
     let conn = df_db::get_connection(db_path)?;
 
-    let select = Select::new().select("*").from(TABLE_NAME);
+    let select = Select::new()
+        .select(&select_visible_cols())
+        .from(TABLE_NAME);
     let df = df_db::select_with_opts(&conn, &select, opts)?;
 
     Ok(df)
@@ -269,8 +283,44 @@ pub fn count(
     Ok(count)
 }
 
+/// Hidden columns added to every staged table: the row id and the row's position in the committed file
+fn hidden_cols_select() -> String {
+    format!(
+        "CAST(uuid() AS VARCHAR) AS {}, CAST(row_number() OVER () - 1 AS BIGINT) AS {}",
+        OXEN_ID_COL, OXEN_ROW_IDX_COL
+    )
+}
+
+/// Selects everything a client should see, which is all but the row index column
+fn select_visible_cols() -> String {
+    format!("* EXCLUDE ({})", OXEN_ROW_IDX_COL)
+}
+
+/// Rows appended after indexing get row indices past the end of the committed file
+fn track_row_idx(conn: &Connection) -> Result<(), OxenError> {
+    let num_rows = df_db::count(conn, TABLE_NAME)?;
+    let create_seq_query = format!(
+        "CREATE OR REPLACE SEQUENCE {} START WITH {} MINVALUE 0;",
+        OXEN_ROW_IDX_SEQ, num_rows
+    );
+    conn.execute(&create_seq_query, [])?;
+
+    let add_default_query = format!(
+        "ALTER TABLE {} ALTER COLUMN {} SET DEFAULT nextval('{}');",
+        TABLE_NAME, OXEN_ROW_IDX_COL, OXEN_ROW_IDX_SEQ
+    );
+    conn.execute(&add_default_query, [])?;
+
+    Ok(())
+}
+
 fn index_csv(path: &Path, conn: &Connection) -> Result<(), OxenError> {
-    let query = format!("CREATE TABLE {} AS SELECT *, CAST(uuid() AS VARCHAR) AS {} FROM read_csv('{}', AUTO_DETECT=TRUE, header=True);", TABLE_NAME, OXEN_ID_COL, path.to_string_lossy());
+    let query = format!(
+        "CREATE TABLE {} AS SELECT *, {} FROM read_csv('{}', AUTO_DETECT=TRUE, header=True);",
+        TABLE_NAME,
+        hidden_cols_select(),
+        path.to_string_lossy()
+    );
     conn.execute(&query, [])?;
 
     let add_default_query = format!(
@@ -287,7 +337,12 @@ fn index_csv(path: &Path, conn: &Connection) -> Result<(), OxenError> {
 }
 
 fn index_tsv(path: &Path, conn: &Connection) -> Result<(), OxenError> {
-    let query = format!("CREATE TABLE {} AS SELECT *, CAST(uuid() AS VARCHAR) AS {} FROM read_csv('{}', AUTO_DETECT=TRUE, header=True);", TABLE_NAME, OXEN_ID_COL, path.to_string_lossy());
+    let query = format!(
+        "CREATE TABLE {} AS SELECT *, {} FROM read_csv('{}', AUTO_DETECT=TRUE, header=True);",
+        TABLE_NAME,
+        hidden_cols_select(),
+        path.to_string_lossy()
+    );
     conn.execute(&query, [])?;
 
     let add_default_query = format!(
@@ -301,9 +356,9 @@ fn index_tsv(path: &Path, conn: &Connection) -> Result<(), OxenError> {
 
 fn index_json(path: &Path, conn: &Connection) -> Result<(), OxenError> {
     let query = format!(
-        "CREATE TABLE {} AS SELECT *, CAST(uuid() AS VARCHAR) AS {} FROM '{}';",
+        "CREATE TABLE {} AS SELECT *, {} FROM '{}';",
         TABLE_NAME,
-        OXEN_ID_COL,
+        hidden_cols_select(),
         path.to_string_lossy()
     );
     conn.execute(&query, [])?;
@@ -319,9 +374,9 @@ fn index_json(path: &Path, conn: &Connection) -> Result<(), OxenError> {
 
 fn index_parquet(path: &Path, conn: &Connection) -> Result<(), OxenError> {
     let query = format!(
-        "CREATE TABLE {} AS SELECT *, CAST(uuid() AS VARCHAR) AS {} FROM '{}';",
+        "CREATE TABLE {} AS SELECT *, {} FROM '{}';",
         TABLE_NAME,
-        OXEN_ID_COL,
+        hidden_cols_select(),
         path.to_string_lossy()
     );
     conn.execute(&query, [])?;
@@ -335,11 +390,11 @@ fn index_parquet(path: &Path, conn: &Connection) -> Result<(), OxenError> {
     Ok(())
 }
 
-fn export_rest(path: &Path, conn: &Connection) -> Result<(), OxenError> {
+fn export_rest(path: &Path, conn: &Connection, exclude: &[&str]) -> Result<(), OxenError> {
     log::debug!("export_rest()");
     let query = format!(
-        "COPY (SELECT * EXCLUDE {} FROM '{}') to '{}';",
-        OXEN_ID_COL,
+        "COPY (SELECT * EXCLUDE ({}) FROM '{}') to '{}';",
+        exclude.join(", "),
         TABLE_NAME,
         path.to_string_lossy()
     );
@@ -352,11 +407,11 @@ fn export_rest(path: &Path, conn: &Connection) -> Result<(), OxenError> {
     Ok(())
 }
 
-fn export_csv(path: &Path, conn: &Connection) -> Result<(), OxenError> {
+fn export_csv(path: &Path, conn: &Connection, exclude: &[&str]) -> Result<(), OxenError> {
     log::debug!("export_csv()");
     let query = format!(
-        "COPY (SELECT * EXCLUDE {} FROM '{}') to '{}' (HEADER, DELIMITER ',');",
-        OXEN_ID_COL,
+        "COPY (SELECT * EXCLUDE ({}) FROM '{}') to '{}' (HEADER, DELIMITER ',');",
+        exclude.join(", "),
         TABLE_NAME,
         path.to_string_lossy()
     );
@@ -371,11 +426,11 @@ fn export_csv(path: &Path, conn: &Connection) -> Result<(), OxenError> {
     Ok(())
 }
 
-fn export_tsv(path: &Path, conn: &Connection) -> Result<(), OxenError> {
+fn export_tsv(path: &Path, conn: &Connection, exclude: &[&str]) -> Result<(), OxenError> {
     log::debug!("export_tsv()");
     let query = format!(
-        "COPY (SELECT * EXCLUDE {} FROM '{}') to '{}' (HEADER, DELIMITER '\t');",
-        OXEN_ID_COL,
+        "COPY (SELECT * EXCLUDE ({}) FROM '{}') to '{}' (HEADER, DELIMITER '\t');",
+        exclude.join(", "),
         TABLE_NAME,
         path.to_string_lossy()
     );
@@ -389,11 +444,11 @@ fn export_tsv(path: &Path, conn: &Connection) -> Result<(), OxenError> {
     Ok(())
 }
 
-fn export_parquet(path: &Path, conn: &Connection) -> Result<(), OxenError> {
+fn export_parquet(path: &Path, conn: &Connection, exclude: &[&str]) -> Result<(), OxenError> {
     log::debug!("export_parquet()");
     let query = format!(
-        "COPY (SELECT * EXCLUDE {} FROM '{}') to '{}' (FORMAT PARQUET);",
-        OXEN_ID_COL,
+        "COPY (SELECT * EXCLUDE ({}) FROM '{}') to '{}' (FORMAT PARQUET);",
+        exclude.join(", "),
         TABLE_NAME,
         path.to_string_lossy()
     );
@@ -179,6 +179,42 @@ pub fn stage_file(
     Ok(relative_path)
 }
 
+// Stages moving a committed or staged file to `dst`, both relative to the repository
+pub fn stage_move(
+    repo: &LocalRepository,
+    branch_repo: &LocalRepository,
+    branch: &Branch,
+    user_id: &str,
+    src: &Path,
+    dst: &Path,
+) -> Result<(), OxenError> {
+    let staging_dir = branch_staging_dir(repo, branch, user_id);
+
+    // Stager will be in the branch repo
+    let stager = Stager::new(branch_repo)?;
+    // But we will read from the commit in the main repo
+    let commit = api::local::commits::get_by_id(repo, &branch.commit_id)?.unwrap();
+    let reader = CommitEntryReader::new(repo, &commit)?;
+    let schema_reader = SchemaReader::new(repo, &commit.id)?;
+
+    // Staged files are committed from the staging dir, so the file has to be there at dst
+    let staged_src = staging_dir.join(src);
+    let staged_dst = staging_dir.join(dst);
+    if let Some(parent) = staged_dst.parent() {
+        util::fs::create_dir_all(parent)?;
+    }
+    if staged_src.exists() {
+        util::fs::rename(&staged_src, &staged_dst)?;
+    } else {
+        let entry = reader
+            .get_entry(src)?
+            .ok_or(OxenError::entry_does_not_exist(src))?;
+        util::fs::copy(util::fs::version_path(repo, &entry), &staged_dst)?;
+    }
+
+    stager.add_moved_file(src, dst, &reader, &schema_reader)
+}
+
 pub fn has_file(branch_repo: &LocalRepository, filepath: &Path) -> Result<bool, OxenError> {
     // Stager will be in the branch repo
     let stager = Stager::new(branch_repo)?;
@@ -213,6 +249,10 @@ pub fn commit(
 
     log::debug!("got branch status: {:#?}", &status);
 
+    // Reject schemas that break their compatibility contract before anything is written
+    let schema_reader = SchemaReader::new(repo, &branch.commit_id)?;
+    Stager::new(branch_repo)?.check_schema_contracts(&schema_reader)?;
+
     let commit_writer = CommitWriter::new(repo)?;
     let timestamp = OffsetDateTime::now_utc();
 
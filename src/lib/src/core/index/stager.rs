@@ -43,7 +43,7 @@ use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::str;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use super::StagedDirEntryReader;
 
@@ -58,6 +58,8 @@ pub enum FileStatus {
 pub struct Stager {
     dir_db: DBWithThreadMode<MultiThreaded>,
     schemas_db: DBWithThreadMode<MultiThreaded>,
+    // Opened the first time a move is staged or read, most stagers never touch it
+    moves_db: Mutex<Option<DBWithThreadMode<MultiThreaded>>>,
     pub repository: LocalRepository,
     merger: Option<Merger>,
 }
@@ -87,6 +89,12 @@ impl Stager {
         Ok(path)
     }
 
+    pub fn moves_db_path(path: &Path) -> PathBuf {
+        util::fs::oxen_hidden_dir(path)
+            .join(Path::new(constants::STAGED_DIR))
+            .join(constants::MOVES_DIR)
+    }
+
     pub fn new(repository: &LocalRepository) -> Result<Stager, OxenError> {
         let dir_db_path = Stager::dirs_db_path(&repository.path)?;
         let schemas_db_path = Stager::schemas_db_path(&repository.path)?;
@@ -95,6 +103,7 @@ impl Stager {
         Ok(Stager {
             dir_db: DBWithThreadMode::open(&opts, dunce::simplified(&dir_db_path))?,
             schemas_db: DBWithThreadMode::open(&opts, dunce::simplified(&schemas_db_path))?,
+            moves_db: Mutex::new(None),
             repository: repository.clone(),
             merger: None,
         })
@@ -108,11 +117,37 @@ impl Stager {
         Ok(Stager {
             dir_db: DBWithThreadMode::open(&opts, dunce::simplified(&dir_db_path))?,
             schemas_db: DBWithThreadMode::open(&opts, dunce::simplified(&schemas_db_path))?,
+            moves_db: Mutex::new(None),
             repository: repository.clone(),
             merger: Some(Merger::new(&repository.clone())?),
         })
     }
 
+    /// Runs `f` on the moves db, opening it on first use. Returns None without running `f` if no
+    /// move has ever been staged and `create` is false.
+    fn with_moves_db<T>(
+        &self,
+        create: bool,
+        f: impl FnOnce(&DBWithThreadMode<MultiThreaded>) -> Result<T, OxenError>,
+    ) -> Result<Option<T>, OxenError> {
+        let mut moves_db = self.moves_db.lock().unwrap();
+        if moves_db.is_none() {
+            let path = Stager::moves_db_path(&self.repository.path);
+            if !path.exists() {
+                if !create {
+                    return Ok(None);
+                }
+                util::fs::create_dir_all(&path)?;
+            }
+            let opts = db::opts::default();
+            *moves_db = Some(DBWithThreadMode::open(&opts, dunce::simplified(&path))?);
+        }
+        match moves_db.as_ref() {
+            Some(db) => Ok(Some(f(db)?)),
+            None => Ok(None),
+        }
+    }
+
     fn should_ignore_path(&self, ignore: &Option<Gitignore>, path: &Path) -> bool {
         // If the path is the .oxen dir or is in the ignore file, ignore it
         let should_ignore = if let Some(ignore) = ignore {
@@ -375,7 +410,30 @@ impl Stager {
         let mut added_map: HashMap<String, Vec<&PathBuf>> = HashMap::new();
         let mut removed_map: HashMap<String, Vec<&PathBuf>> = HashMap::new();
 
+        // Moves staged with `oxen mv` are paired exactly, even when several files share a hash
+        let mut paired: HashSet<PathBuf> = HashSet::new();
+        for (new_path, old_path) in self.list_staged_moves()? {
+            match (files.get(&new_path), files.get(&old_path)) {
+                (Some(added), Some(removed))
+                    if added.status != StagedEntryStatus::Removed
+                        && removed.status == StagedEntryStatus::Removed =>
+                {
+                    staged_data.moved_files.push((
+                        new_path.clone(),
+                        old_path.clone(),
+                        added.hash.clone(),
+                    ));
+                    paired.insert(new_path);
+                    paired.insert(old_path);
+                }
+                _ => continue,
+            }
+        }
+
         for (path, entry) in files_vec.iter() {
+            if paired.contains(*path) {
+                continue;
+            }
             match entry.status {
                 StagedEntryStatus::Added => {
                     added_map.entry(entry.hash.clone()).or_default().push(path);
@@ -734,6 +792,7 @@ impl Stager {
                 log::debug!("remove_staged_file {:?} is tabular, removing schema", path);
                 self.remove_staged_schema(path)?;
             }
+            self.with_moves_db(false, |db| path_db::delete(db, path))?;
 
             staged_dir.remove_path(filename)
         } else {
@@ -1126,6 +1185,95 @@ impl Stager {
         Ok(relative)
     }
 
+    /// Hash of the staged version of a file, or of the committed one if it is not staged
+    pub fn staged_or_committed_hash(
+        &self,
+        path: &Path,
+        entry_reader: &CommitEntryReader,
+    ) -> Result<Option<String>, OxenError> {
+        if let Some(staged) = self.get_entry(path)? {
+            if staged.status != StagedEntryStatus::Removed {
+                return Ok(Some(staged.hash));
+            }
+        }
+        Ok(entry_reader.get_entry(path)?.map(|entry| entry.hash))
+    }
+
+    /// Stages moving the file at `src` to `dst`, both relative to the repository. `src` is staged as
+    /// removed and `dst` as added with the hash and schema that `src` already has, so the contents
+    /// are not read again. The move is recorded so that status and the commit pair up the two paths.
+    pub fn add_moved_file(
+        &self,
+        src: &Path,
+        dst: &Path,
+        entry_reader: &CommitEntryReader,
+        schema_reader: &SchemaReader,
+    ) -> Result<(), OxenError> {
+        log::debug!("add_moved_file {:?} -> {:?}", src, dst);
+        let committed = entry_reader.get_entry(src)?;
+        let Some(hash) = self.staged_or_committed_hash(src, entry_reader)? else {
+            return Err(OxenError::entry_does_not_exist(src));
+        };
+        let schema = if util::fs::is_tabular(src) {
+            self.maybe_get_existing_schema_from_reader(src, schema_reader)?
+        } else {
+            None
+        };
+        // If src was itself moved, keep pointing at where the file was committed
+        let origin: Option<PathBuf> = match self
+            .with_moves_db(false, |db| path_db::get_entry(db, src))?
+            .flatten()
+        {
+            Some(origin) => Some(origin),
+            None => committed.as_ref().map(|entry| entry.path.to_owned()),
+        };
+
+        // Files that were only staged are unstaged, committed files are staged as removed
+        match &committed {
+            Some(entry) => self.process_removed_file(&src.to_path_buf(), entry)?,
+            None => self.remove_staged_file(src)?,
+        }
+
+        let status = if entry_reader.has_file(dst) {
+            StagedEntryStatus::Modified
+        } else {
+            StagedEntryStatus::Added
+        };
+        let staged_entry = StagedEntry { hash, status };
+        let Some(file_name) = dst.file_name() else {
+            return Err(OxenError::file_has_no_name(dst));
+        };
+        let parent = dst.parent().unwrap_or(Path::new(""));
+        let staged_db: StagedDirEntryDB<MultiThreaded> =
+            StagedDirEntryDB::new(&self.repository, parent)?;
+        let mut components = dst.components().collect::<Vec<_>>();
+        while components.pop().is_some() {
+            let parent: PathBuf = components.iter().collect();
+            path_db::put(&self.dir_db, parent, &StagedEntryStatus::Added)?;
+        }
+        staged_db.add_staged_entry_to_db(file_name, &staged_entry)?;
+
+        if let Some(schema) = schema {
+            let staged_schema = StagedSchema {
+                schema,
+                status: StagedEntryStatus::Added,
+            };
+            path_db::put(&self.schemas_db, dst, &staged_schema)?;
+        }
+
+        if let Some(origin) = origin.filter(|origin| origin != dst) {
+            self.with_moves_db(true, |db| path_db::put(db, dst, &origin))?;
+        }
+        Ok(())
+    }
+
+    /// Moves staged with `oxen mv`, as (new path, original path)
+    pub fn list_staged_moves(&self) -> Result<Vec<(PathBuf, PathBuf)>, OxenError> {
+        let moves =
+            self.with_moves_db(false, |db| path_db::list_path_entries(db, Path::new("")))?;
+        Ok(moves.unwrap_or_default())
+    }
+
     pub fn get_staged_schema(
         &self,
         schema_ref: impl AsRef<str>,
@@ -1335,6 +1483,7 @@ impl Stager {
             }
             None => schema,
         };
+        api::local::schemas::check_contract(path, &schema, schema_reader)?;
 
         // Need to also include all parents as staged
         let relative = util::fs::path_relative_to_dir(path, &self.repository.path)?;
@@ -1361,6 +1510,20 @@ impl Stager {
         Ok(())
     }
 
+    /// Check every staged schema against the compatibility contract of its file
+    pub fn check_schema_contracts(&self, schema_reader: &SchemaReader) -> Result<(), OxenError> {
+        for (path, staged) in path_db::list_path_entries::<MultiThreaded, StagedSchema>(
+            &self.schemas_db,
+            Path::new(""),
+        )? {
+            if staged.status == StagedEntryStatus::Removed {
+                continue;
+            }
+            api::local::schemas::check_contract(&path, &staged.schema, schema_reader)?;
+        }
+        Ok(())
+    }
+
     /// Update the name of a staged schema, assuming it exists
     pub fn update_schema_names_for_hash(&self, hash: &str, name: &str) -> Result<(), OxenError> {
         for (path, mut staged) in path_db::list_path_entries::<MultiThreaded, StagedSchema>(
@@ -1415,6 +1578,8 @@ impl Stager {
             }
 
             schema.update_metadata_from_schema(new_schema);
+            // Set explicitly so that a contract can be removed
+            schema.set_compatibility(new_schema.compatibility);
 
             let staged_schema = StagedSchema {
                 schema: schema.to_owned(),
@@ -1632,6 +1797,7 @@ impl Stager {
         staged_dir_db.unstage()?;
         path_db::clear(&self.dir_db)?;
         path_db::clear(&self.schemas_db)?;
+        self.with_moves_db(false, path_db::clear)?;
         Ok(())
     }
 }
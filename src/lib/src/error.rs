@@ -10,6 +10,7 @@ use std::num::ParseIntError;
 use std::path::Path;
 use std::path::StripPrefixError;
 
+use crate::model::schema::SchemaCompatibility;
 use crate::model::Schema;
 use crate::model::{Commit, ParsedResource};
 use crate::model::{Remote, RepoNew};
@@ -54,6 +55,7 @@ pub enum OxenError {
     RootCommitDoesNotMatch(Box<Commit>),
     NothingToCommit(StringError),
     HeadNotFound(StringError),
+    MergeConflict(StringError),
 
     // Resources (paths, uris, etc.)
     ResourceNotFound(StringError),
@@ -161,6 +163,10 @@ impl OxenError {
         ))
     }
 
+    pub fn merge_conflict(desc: impl AsRef<str>) -> Self {
+        OxenError::MergeConflict(StringError::from(desc.as_ref()))
+    }
+
     pub fn incomplete_local_history() -> Self {
         OxenError::IncompleteLocalHistory(StringError::from(
             "\nCannot push to an empty repository with an incomplete local history. To fix, pull the complete history from your remote:\n\n  oxen pull <remote> <branch> --all\n",
@@ -453,11 +459,30 @@ impl OxenError {
         OxenError::IncompatibleSchemas(StringError::from(err))
     }
 
+    pub fn schema_contract_violation(
+        path: impl AsRef<Path>,
+        mode: SchemaCompatibility,
+        violations: &[String],
+    ) -> OxenError {
+        let err = format!(
+            "Schema for {:?} does not follow its {} compatibility contract:\n  {}",
+            path.as_ref(),
+            mode,
+            violations.join("\n  ")
+        );
+        OxenError::IncompatibleSchemas(StringError::from(err))
+    }
+
     pub fn parse_error(value: impl AsRef<str>) -> OxenError {
         let err = format!("Parse error: {:?}", value.as_ref());
         OxenError::basic_str(err)
     }
 
+    pub fn invalid_filter(filter: impl AsRef<str>, reason: impl AsRef<str>) -> OxenError {
+        let err = format!("Invalid filter {:?}: {}", filter.as_ref(), reason.as_ref());
+        OxenError::ParsingError(Box::new(StringError::from(err)))
+    }
+
     pub fn unknown_agg_fn(name: impl AsRef<str>) -> OxenError {
         let err = format!("Unknown aggregation function: {:?}", name.as_ref());
         OxenError::basic_str(err)
@@ -5,11 +5,13 @@ pub mod base_head;
 pub mod branch;
 pub mod commit;
 pub mod content_type;
+pub mod data_frame_profile;
 pub mod data_frame_size;
 pub mod diff;
 pub mod entry;
 pub mod file;
 pub mod merge_conflict;
+pub mod merge_request;
 pub mod metadata;
 pub mod namespace;
 pub mod object_id;
@@ -36,6 +38,10 @@ pub use crate::model::commit::{Commit, CommitStats, NewCommit, NewCommitBody};
 
 // Merge
 pub use crate::model::merge_conflict::MergeConflict;
+pub use crate::model::merge_request::{
+    MergeRequest, MergeRequestComment, MergeRequestStatus, MergeRequestUpdate, NewMergeRequest,
+    NewMergeRequestComment,
+};
 
 // Branch
 pub use crate::model::branch::Branch;
@@ -48,6 +54,7 @@ pub use crate::model::entry::commit_entry::CommitEntry;
 pub use crate::model::entry::entry_data_type::EntryDataType;
 pub use crate::model::entry::metadata_entry::MetadataEntry;
 pub use crate::model::entry::mod_entry::ModEntry;
+pub use crate::model::entry::moved_entry::MovedEntry;
 pub use crate::model::entry::remote_entry::RemoteEntry;
 pub use crate::model::entry::staged_entry::{StagedEntry, StagedEntryStatus};
 pub use crate::model::entry::ContentHashable;
@@ -55,6 +62,7 @@ pub use crate::model::entry::ContentHashable;
 // Metadata
 pub use crate::model::metadata::dir_metadata_item::DirMetadataItem;
 
+pub use crate::model::data_frame_profile::{ColumnProfile, DataFrameProfile};
 pub use crate::model::data_frame_size::DataFrameSize;
 
 pub use crate::model::user::User;
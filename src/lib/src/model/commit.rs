@@ -49,6 +49,9 @@ pub struct Commit {
     pub root_hash: Option<String>, // Option for now to facilciate migration from older stored commits
     #[serde(with = "time::serde::rfc3339")]
     pub timestamp: OffsetDateTime,
+    /// Extra information recorded with the commit, ie. the provenance of generated files
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<serde_json::Value>,
 }
 
 impl fmt::Display for Commit {
@@ -69,6 +72,8 @@ pub struct CommitWithSize {
     #[serde(with = "time::serde::rfc3339")]
     pub timestamp: OffsetDateTime,
     pub size: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<serde_json::Value>,
 }
 
 // TODO: is there a way to derive all these values...and just add one new?
@@ -83,6 +88,8 @@ pub struct CommitWithBranchName {
     #[serde(with = "time::serde::rfc3339")]
     pub timestamp: OffsetDateTime,
     pub size: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<serde_json::Value>,
     pub branch_name: String,
 }
 
@@ -111,6 +118,7 @@ impl Commit {
             email: new_commit.email.to_owned(),
             timestamp: new_commit.timestamp.to_owned(),
             root_hash: None,
+            metadata: None,
         }
     }
 
@@ -153,6 +161,7 @@ impl Commit {
             email: commit.email.to_owned(),
             timestamp: commit.timestamp.to_owned(),
             root_hash: commit.root_hash.to_owned(),
+            metadata: commit.metadata.to_owned(),
         }
     }
 
@@ -165,6 +174,7 @@ impl Commit {
             email: commit.email.to_owned(),
             timestamp: commit.timestamp.to_owned(),
             root_hash: commit.root_hash.to_owned(),
+            metadata: commit.metadata.to_owned(),
         }
     }
 
@@ -199,6 +209,7 @@ impl CommitWithSize {
             timestamp: commit.timestamp.to_owned(),
             root_hash: commit.root_hash.to_owned(),
             size,
+            metadata: commit.metadata.to_owned(),
         }
     }
 }
@@ -214,6 +225,7 @@ impl CommitWithBranchName {
             timestamp: commit.timestamp.to_owned(),
             root_hash: commit.root_hash.to_owned(),
             size,
+            metadata: commit.metadata.to_owned(),
             branch_name,
         }
     }
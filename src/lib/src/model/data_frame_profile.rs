@@ -0,0 +1,74 @@
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::error::OxenError;
+
+/// Column level statistics of a data frame
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DataFrameProfile {
+    pub height: usize,
+    pub width: usize,
+    pub columns: Vec<ColumnProfile>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ColumnProfile {
+    pub name: String,
+    pub dtype: String,
+    pub null_count: usize,
+    pub distinct_count: usize,
+    // min and max are strings so that they work for numbers, dates and strings alike
+    pub min: Option<String>,
+    pub max: Option<String>,
+    // mean, std and histogram are only computed for numeric columns
+    pub mean: Option<f64>,
+    pub std: Option<f64>,
+    pub top_values: Vec<ValueCount>,
+    pub histogram: Option<Vec<HistogramBin>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ValueCount {
+    pub value: String,
+    pub count: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct HistogramBin {
+    pub start: f64,
+    pub end: f64,
+    pub count: usize,
+}
+
+impl DataFrameProfile {
+    pub fn column(&self, name: &str) -> Option<&ColumnProfile> {
+        self.columns.iter().find(|column| column.name == name)
+    }
+
+    /// One row per column, for printing
+    pub fn to_df(&self) -> Result<DataFrame, OxenError> {
+        let opt_str = |value: &Option<String>| value.clone().unwrap_or_default();
+        let opt_f64 = |value: Option<f64>| value.map(|v| format!("{v:.4}")).unwrap_or_default();
+        let top = |column: &ColumnProfile| {
+            column
+                .top_values
+                .iter()
+                .map(|v| format!("{} ({})", v.value, v.count))
+                .collect::<Vec<String>>()
+                .join(", ")
+        };
+
+        let columns = &self.columns;
+        Ok(df!(
+            "column" => columns.iter().map(|c| c.name.clone()).collect::<Vec<_>>(),
+            "dtype" => columns.iter().map(|c| c.dtype.clone()).collect::<Vec<_>>(),
+            "nulls" => columns.iter().map(|c| c.null_count as u64).collect::<Vec<_>>(),
+            "distinct" => columns.iter().map(|c| c.distinct_count as u64).collect::<Vec<_>>(),
+            "min" => columns.iter().map(|c| opt_str(&c.min)).collect::<Vec<_>>(),
+            "max" => columns.iter().map(|c| opt_str(&c.max)).collect::<Vec<_>>(),
+            "mean" => columns.iter().map(|c| opt_f64(c.mean)).collect::<Vec<_>>(),
+            "std" => columns.iter().map(|c| opt_f64(c.std)).collect::<Vec<_>>(),
+            "top_values" => columns.iter().map(top).collect::<Vec<_>>(),
+        )?)
+    }
+}
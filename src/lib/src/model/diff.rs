@@ -19,6 +19,15 @@ pub mod generic_diff_summary;
 pub mod dir_diff;
 pub mod dir_diff_summary;
 
+pub mod image_diff;
+pub use image_diff::ImageDiff;
+
+pub mod json_diff;
+pub use json_diff::JsonDiff;
+
+pub mod patch;
+pub use patch::OxenPatch;
+
 pub mod schema_diff;
 
 pub mod tabular_diff;
@@ -8,6 +8,13 @@ pub struct AddRemoveModifyCounts {
     pub added: usize,
     pub removed: usize,
     pub modified: usize,
+    /// Only set for file diffs, rows are never renamed
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub renamed: usize,
+}
+
+fn is_zero(n: &usize) -> bool {
+    *n == 0
 }
 
 impl AddRemoveModifyCounts {
@@ -37,6 +44,7 @@ impl AddRemoveModifyCounts {
             added: added_rows,
             removed: removed_rows,
             modified: modified_rows,
+            renamed: 0,
         })
     }
 }
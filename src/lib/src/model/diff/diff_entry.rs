@@ -29,6 +29,9 @@ pub struct DiffEntry {
     pub status: String,
     pub data_type: EntryDataType,
     pub filename: String,
+    /// Where the file was renamed or copied from, if it moved
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub old_filename: Option<String>,
     pub is_dir: bool,
     pub size: u64,
 
@@ -103,6 +106,7 @@ impl DiffEntry {
             status: status.to_string(),
             data_type: EntryDataType::Dir,
             filename: current_dir.as_os_str().to_str().unwrap().to_string(),
+            old_filename: None,
             is_dir: true,
             size: current_entry.size,
             head_resource,
@@ -155,6 +159,7 @@ impl DiffEntry {
             status: status.to_string(),
             data_type: EntryDataType::Dir,
             filename: current_dir.as_os_str().to_str().unwrap().to_string(),
+            old_filename: None,
             is_dir: true,
             size: current_entry.size,
             head_resource,
@@ -189,6 +194,13 @@ impl DiffEntry {
 
         let data_type = util::fs::file_data_type(&version_path);
 
+        let old_filename = match (&base_entry, &head_entry) {
+            (Some(base), Some(head)) if base.path != head.path => {
+                Some(base.path.to_string_lossy().to_string())
+            }
+            _ => None,
+        };
+
         let base_resource = DiffEntry::resource_from_entry(base_entry.clone());
         let head_resource = DiffEntry::resource_from_entry(head_entry.clone());
 
@@ -223,6 +235,7 @@ impl DiffEntry {
         //     pagination.is_some()
         // );
 
+        let is_full_diff = df_opts.is_some() && should_do_full_diff;
         if let Some(df_opts) = df_opts {
             if data_type == EntryDataType::Tabular && should_do_full_diff {
                 let diff =
@@ -231,6 +244,7 @@ impl DiffEntry {
                     status: status.to_string(),
                     data_type: data_type.clone(),
                     filename: current_entry.path.as_os_str().to_str().unwrap().to_string(),
+                    old_filename,
                     is_dir: false,
                     size: current_entry.num_bytes,
                     head_resource,
@@ -245,10 +259,30 @@ impl DiffEntry {
             }
         }
 
+        // Json arrays are tabular, any other json document gets a structural diff
+        let diff = if is_full_diff
+            && data_type != EntryDataType::Tabular
+            && util::fs::is_json(&current_entry.path)
+        {
+            // Falls back to a text diff if either version is not valid json
+            Some(api::local::diff::json_diff::diff_commit_entries(
+                repo,
+                &base_entry,
+                &head_entry,
+            )?)
+        } else if is_full_diff && data_type == EntryDataType::Image {
+            let diff =
+                api::local::diff::image_diff::diff_commit_entries(repo, &base_entry, &head_entry)?;
+            Some(GenericDiff::ImageDiff(diff))
+        } else {
+            None // TODO: other full diffs...
+        };
+
         Ok(DiffEntry {
             status: status.to_string(),
             data_type: data_type.clone(),
             filename: current_entry.path.as_os_str().to_str().unwrap().to_string(),
+            old_filename,
             is_dir: false,
             size: current_entry.num_bytes,
             head_resource,
@@ -261,7 +295,68 @@ impl DiffEntry {
                 &base_entry,
                 &head_entry,
             )?,
-            diff: None, // TODO: other full diffs...
+            diff,
+        })
+    }
+
+    /// Diff entry between a committed file and a file in the working directory or staged index.
+    /// The head side has no committed version yet, so it has no resource and only file metadata.
+    pub fn from_working_file(
+        repo: &LocalRepository,
+        base_entry: Option<CommitEntry>,
+        base_commit: &Commit,
+        head_path: Option<PathBuf>,
+        status: DiffEntryStatus,
+    ) -> Result<DiffEntry, OxenError> {
+        let head_meta_entry = match &head_path {
+            Some(path) if repo.path.join(path).is_file() => {
+                Some(api::local::metadata::from_path(repo.path.join(path))?)
+            }
+            _ => None,
+        };
+
+        let base_resource = DiffEntry::resource_from_entry(base_entry.clone());
+        let mut base_meta_entry =
+            MetadataEntry::from_commit_entry(repo, base_entry.clone(), base_commit);
+        if let Some(meta) = base_meta_entry.as_mut() {
+            meta.resource.clone_from(&base_resource);
+        }
+
+        let path = head_path
+            .clone()
+            .or(base_entry.as_ref().map(|entry| entry.path.clone()))
+            .ok_or(OxenError::basic_str(
+                "DiffEntry::from_working_file needs a base entry or head path",
+            ))?;
+        let (data_type, size) = match (&head_meta_entry, &base_entry) {
+            (Some(meta), _) => (meta.data_type.clone(), meta.size),
+            (None, Some(entry)) => (
+                util::fs::file_data_type(&util::fs::version_path(repo, entry)),
+                entry.num_bytes,
+            ),
+            (None, None) => (util::fs::file_data_type(&repo.path.join(&path)), 0),
+        };
+
+        let old_filename = match (&base_entry, &head_path) {
+            (Some(base), Some(head)) if &base.path != head => {
+                Some(base.path.to_string_lossy().to_string())
+            }
+            _ => None,
+        };
+
+        Ok(DiffEntry {
+            status: status.to_string(),
+            data_type,
+            filename: path.to_string_lossy().to_string(),
+            old_filename,
+            is_dir: false,
+            size,
+            head_resource: None,
+            base_resource,
+            head_entry: head_meta_entry,
+            base_entry: base_meta_entry,
+            diff_summary: None,
+            diff: None,
         })
     }
 
@@ -416,7 +511,9 @@ impl DiffEntry {
                     added: num_added,
                     removed: num_removed,
                     modified: num_modified,
+                    renamed: 0,
                 },
+                data_types: vec![],
             },
         })))
     }
@@ -451,7 +548,9 @@ impl DiffEntry {
                     added: 0,
                     removed: num_removed,
                     modified: 0,
+                    renamed: 0,
                 },
+                data_types: vec![],
             },
         })))
     }
@@ -489,7 +588,9 @@ impl DiffEntry {
                     added: num_added,
                     removed: 0,
                     modified: 0,
+                    renamed: 0,
                 },
+                data_types: vec![],
             },
         })))
     }
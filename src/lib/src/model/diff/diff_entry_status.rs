@@ -6,6 +6,10 @@ pub enum DiffEntryStatus {
     Added,
     Modified,
     Removed,
+    /// Moved from another path, the old path is on the base entry
+    Renamed,
+    /// New path with the same content as a file that is still in base
+    Copied,
 }
 
 // Downcase the status
@@ -15,6 +19,8 @@ impl std::fmt::Display for DiffEntryStatus {
             DiffEntryStatus::Added => "added",
             DiffEntryStatus::Modified => "modified",
             DiffEntryStatus::Removed => "removed",
+            DiffEntryStatus::Renamed => "renamed",
+            DiffEntryStatus::Copied => "copied",
         };
         write!(f, "{}", status)
     }
@@ -29,6 +35,8 @@ impl std::str::FromStr for DiffEntryStatus {
             "added" => Ok(DiffEntryStatus::Added),
             "modified" => Ok(DiffEntryStatus::Modified),
             "removed" => Ok(DiffEntryStatus::Removed),
+            "renamed" => Ok(DiffEntryStatus::Renamed),
+            "copied" => Ok(DiffEntryStatus::Copied),
             _ => Err(format!("Could not parse {} as a DiffEntryStatus", s)),
         }
     }
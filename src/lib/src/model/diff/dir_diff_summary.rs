@@ -1,6 +1,9 @@
+use bytesize::ByteSize;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
 use crate::model::diff::AddRemoveModifyCounts;
+use crate::model::EntryDataType;
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct DirDiffSummary {
@@ -11,4 +14,74 @@ pub struct DirDiffSummary {
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct DirDiffSummaryImpl {
     pub file_counts: AddRemoveModifyCounts,
+    /// Changes of all the files below the dir, grouped by data type
+    #[serde(default)]
+    pub data_types: Vec<DataTypeDiffSummary>,
+}
+
+/// Changes to the files of one data type within a directory
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct DataTypeDiffSummary {
+    pub data_type: EntryDataType,
+    pub file_counts: AddRemoveModifyCounts,
+    pub num_bytes_added: u64,
+    pub num_bytes_removed: u64,
+    /// Only set for tabular files, modified files count the change in their number of rows
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub num_rows_added: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub num_rows_removed: Option<usize>,
+}
+
+impl DataTypeDiffSummary {
+    pub fn new(data_type: EntryDataType) -> DataTypeDiffSummary {
+        let is_tabular = data_type == EntryDataType::Tabular;
+        DataTypeDiffSummary {
+            data_type,
+            file_counts: AddRemoveModifyCounts {
+                added: 0,
+                removed: 0,
+                modified: 0,
+                renamed: 0,
+            },
+            num_bytes_added: 0,
+            num_bytes_removed: 0,
+            num_rows_added: is_tabular.then_some(0),
+            num_rows_removed: is_tabular.then_some(0),
+        }
+    }
+
+    pub fn num_bytes_delta(&self) -> i64 {
+        self.num_bytes_added as i64 - self.num_bytes_removed as i64
+    }
+}
+
+impl fmt::Display for DataTypeDiffSummary {
+    // ie. "image: 12000 added, 200 removed, 3 modified, +3.4 GB, -1.2 MB"
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let counts = &self.file_counts;
+        let mut parts = vec![];
+        for (num, label) in [
+            (counts.added, "added"),
+            (counts.removed, "removed"),
+            (counts.modified, "modified"),
+        ] {
+            if num > 0 {
+                parts.push(format!("{num} {label}"));
+            }
+        }
+        if self.num_bytes_added > 0 {
+            parts.push(format!("+{}", ByteSize::b(self.num_bytes_added)));
+        }
+        if self.num_bytes_removed > 0 {
+            parts.push(format!("-{}", ByteSize::b(self.num_bytes_removed)));
+        }
+        if let Some(rows) = self.num_rows_added.filter(|n| *n > 0) {
+            parts.push(format!("+{rows} rows"));
+        }
+        if let Some(rows) = self.num_rows_removed.filter(|n| *n > 0) {
+            parts.push(format!("-{rows} rows"));
+        }
+        write!(f, "{}: {}", self.data_type, parts.join(", "))
+    }
 }
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 
 use crate::model::diff::dir_diff::DirDiff;
+use crate::model::diff::image_diff::ImageDiff;
+use crate::model::diff::json_diff::JsonDiff;
 use crate::model::diff::text_diff::TextDiff;
 use crate::view::tabular_diff_view::TabularDiffView;
 
@@ -10,4 +12,6 @@ pub enum GenericDiff {
     DirDiff(DirDiff),
     TabularDiff(TabularDiffView),
     TextDiff(TextDiff),
+    JsonDiff(JsonDiff),
+    ImageDiff(ImageDiff),
 }
@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+
+use crate::model::metadata::MetadataImage;
+
+/// Changes between two versions of an image. The pixel scores are computed after scaling the
+/// head image to the dimensions of the base image. If either version cannot be decoded the diff
+/// is `hash_only` and only tells whether the bytes of the files changed.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ImageDiff {
+    pub base: Option<MetadataImage>,
+    pub head: Option<MetadataImage>,
+    pub dimensions_changed: bool,
+    pub color_space_changed: bool,
+    /// Number of pixels where any channel differs by more than the noise tolerance
+    pub num_changed_pixels: usize,
+    /// Share of the pixels that changed, 0.0 to 1.0
+    pub pixel_diff_ratio: f64,
+    /// Mean absolute difference over all channels, 0.0 to 1.0
+    pub mean_abs_diff: f64,
+    /// Similarity of the perceptual hashes, 1.0 when the images look the same
+    pub perceptual_similarity: f64,
+    /// Whether the bytes of the two versions differ
+    #[serde(default)]
+    pub content_changed: bool,
+    /// Set when either version could not be decoded, the pixel scores are then left at zero
+    #[serde(default)]
+    pub hash_only: bool,
+}
+
+impl ImageDiff {
+    pub fn has_changes(&self) -> bool {
+        if self.hash_only {
+            return self.content_changed;
+        }
+        self.dimensions_changed || self.color_space_changed || self.num_changed_pixels > 0
+    }
+}
@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+
+use crate::model::diff::{AddRemoveModifyCounts, ChangeType};
+
+/// A single change between two JSON documents
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct JsonChange {
+    /// JSON pointer (RFC 6901) to the value that changed, ie `/annotations/0/label`
+    pub path: String,
+    pub modification: ChangeType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub old: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub new: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct JsonDiff {
+    pub counts: AddRemoveModifyCounts,
+    pub changes: Vec<JsonChange>,
+}
+
+impl JsonDiff {
+    pub fn has_changes(&self) -> bool {
+        !self.changes.is_empty()
+    }
+}
@@ -0,0 +1,86 @@
+//! A portable bundle of changes produced by `oxen diff --output <file>.oxenpatch`
+//! that can be applied to another working tree with `oxen apply`.
+//!
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::PathBuf;
+
+pub const OXEN_PATCH_EXT: &str = "oxenpatch";
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct OxenPatch {
+    pub oxen_version: String,
+    pub entries: Vec<PatchEntry>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct PatchEntry {
+    /// Path of the file to patch, relative to the root of the working tree
+    pub path: PathBuf,
+    pub change: PatchChange,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PatchChange {
+    /// Unified diff of a text file
+    Text { unified: String },
+    /// Row level changes of a tabular file, matched on `keys`
+    Tabular {
+        keys: Vec<String>,
+        rows: Vec<PatchRow>,
+    },
+    /// Full replacement of a file, the contents are stored in the bundle under `hash`
+    Blob {
+        old_hash: Option<String>,
+        hash: String,
+    },
+    /// The file was deleted, it is only removed if it still has the contents it had at `old_hash`
+    Removed { old_hash: String },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PatchRowStatus {
+    Added,
+    Removed,
+    Modified,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct PatchRow {
+    pub status: PatchRowStatus,
+    /// Values of the key columns, used to find the row when applying
+    pub keys: serde_json::Map<String, serde_json::Value>,
+    /// Values of the non-key columns before the change, empty for added rows
+    #[serde(default)]
+    pub old: serde_json::Map<String, serde_json::Value>,
+    /// Values of the non-key columns after the change, empty for removed rows
+    #[serde(default)]
+    pub new: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PatchConflict {
+    pub path: PathBuf,
+    pub message: String,
+}
+
+impl fmt::Display for PatchConflict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.message)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PatchApplyResult {
+    pub applied: Vec<PathBuf>,
+    pub conflicts: Vec<PatchConflict>,
+}
+
+impl PatchApplyResult {
+    pub fn has_conflicts(&self) -> bool {
+        !self.conflicts.is_empty()
+    }
+}
@@ -1,8 +1,10 @@
 use crate::{
+    constants::DIFF_CHANGED_COLS_COL,
     error::OxenError,
     model::schema::{Field, Schema},
 };
 use polars::frame::DataFrame;
+use polars::prelude::{IntoSeries, StringChunked};
 use serde::{Deserialize, Serialize};
 
 use super::AddRemoveModifyCounts;
@@ -17,6 +19,14 @@ pub struct TabularSchemaDiff {
 pub struct TabularDiffMods {
     pub row_counts: AddRemoveModifyCounts,
     pub col_changes: TabularSchemaDiff,
+    /// How many modified rows changed in each target column
+    pub col_counts: Vec<ColumnChangeCount>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ColumnChangeCount {
+    pub name: String,
+    pub num_modified: usize,
 }
 
 #[derive(Debug, Clone)]
@@ -89,6 +99,29 @@ impl TabularSchemaDiff {
     }
 }
 
+impl ColumnChangeCount {
+    /// Counts the changed columns listed in a diff data frame, in order of first appearance
+    pub fn from_diff_df(df: &DataFrame) -> Result<Vec<ColumnChangeCount>, OxenError> {
+        let mut counts: Vec<ColumnChangeCount> = vec![];
+        let Ok(column) = df.column(DIFF_CHANGED_COLS_COL) else {
+            return Ok(counts);
+        };
+
+        for changed in column.list()?.into_iter().flatten() {
+            for name in changed.str()?.into_iter().flatten() {
+                match counts.iter_mut().find(|count| count.name == name) {
+                    Some(count) => count.num_modified += 1,
+                    None => counts.push(ColumnChangeCount {
+                        name: name.to_string(),
+                        num_modified: 1,
+                    }),
+                }
+            }
+        }
+        Ok(counts)
+    }
+}
+
 impl TabularDiff {
     pub fn has_changes(&self) -> bool {
         self.summary.modifications.row_counts.added > 0
@@ -97,4 +130,27 @@ impl TabularDiff {
             || !self.summary.modifications.col_changes.added.is_empty()
             || !self.summary.modifications.col_changes.removed.is_empty()
     }
+
+    /// The contents with the list of changed columns encoded as a json array, for writing to
+    /// formats without nested columns such as csv
+    pub fn flat_contents(&self) -> Result<DataFrame, OxenError> {
+        let mut df = self.contents.clone();
+        let Ok(column) = df.column(DIFF_CHANGED_COLS_COL) else {
+            return Ok(df);
+        };
+        let encoded: StringChunked = column
+            .list()?
+            .into_iter()
+            .map(|changed| {
+                changed
+                    .map(|changed| {
+                        let names: Vec<&str> = changed.str()?.into_iter().flatten().collect();
+                        Ok(serde_json::to_string(&names)?)
+                    })
+                    .transpose()
+            })
+            .collect::<Result<_, OxenError>>()?;
+        df.with_column(encoded.with_name(DIFF_CHANGED_COLS_COL).into_series())?;
+        Ok(df)
+    }
 }
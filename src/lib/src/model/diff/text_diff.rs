@@ -1,13 +1,240 @@
 use crate::model::diff::change_type::ChangeType;
 use serde::{Deserialize, Serialize};
 
+/// Number of unchanged lines shown around each change, same default as `diff -u`
+pub const DEFAULT_CONTEXT_LINES: usize = 3;
+
+const NO_NEWLINE_AT_EOF: &str = "\\ No newline at end of file";
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct LineDiff {
     pub modification: ChangeType,
     pub text: String,
+    /// 1-based line number in the original file, None for added lines
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub old_line: Option<usize>,
+    /// 1-based line number in the compared file, None for removed lines
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub new_line: Option<usize>,
+}
+
+/// A contiguous group of changes plus their surrounding context, `lines[start..end]`
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct TextDiffHunk {
+    pub old_start: usize,
+    pub old_lines: usize,
+    pub new_start: usize,
+    pub new_lines: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl TextDiffHunk {
+    /// Header in unified diff format, ie `@@ -1,4 +1,5 @@`
+    pub fn header(&self) -> String {
+        format!(
+            "@@ -{} +{} @@",
+            Self::range(self.old_start, self.old_lines),
+            Self::range(self.new_start, self.new_lines)
+        )
+    }
+
+    /// Number of lines of the file before the hunk, given its start and length on one side
+    fn lines_before(start: usize, count: usize) -> usize {
+        if count == 0 {
+            start
+        } else {
+            start - 1
+        }
+    }
+
+    fn range(start: usize, count: usize) -> String {
+        if count == 1 {
+            format!("{start}")
+        } else {
+            format!("{start},{count}")
+        }
+    }
 }
 
+/// Only the lines that are part of a hunk are kept in `lines`, the unchanged lines between
+/// hunks are left out.
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct TextDiff {
     pub lines: Vec<LineDiff>,
+    #[serde(default)]
+    pub hunks: Vec<TextDiffHunk>,
+    /// Unchanged lines kept around each change in the hunks
+    #[serde(default = "default_context")]
+    pub context: usize,
+    /// Number of lines in the original file
+    #[serde(default)]
+    pub old_num_lines: usize,
+    /// Number of lines in the compared file
+    #[serde(default)]
+    pub new_num_lines: usize,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub old_missing_newline: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub new_missing_newline: bool,
+}
+
+impl TextDiff {
+    pub fn num_added(&self) -> usize {
+        self.lines
+            .iter()
+            .filter(|l| l.modification == ChangeType::Added)
+            .count()
+    }
+
+    pub fn num_removed(&self) -> usize {
+        self.lines
+            .iter()
+            .filter(|l| l.modification == ChangeType::Removed)
+            .count()
+    }
+
+    pub fn has_changes(&self) -> bool {
+        self.lines
+            .iter()
+            .any(|l| l.modification != ChangeType::Unchanged)
+    }
+
+    pub fn hunk_lines(&self, hunk: &TextDiffHunk) -> &[LineDiff] {
+        &self.lines[hunk.start..hunk.end]
+    }
+
+    /// The hunks with `context` unchanged lines around each change. Only the context the diff
+    /// was computed with is available, so a larger `context` gives back `hunks`.
+    pub fn compute_hunks(&self, context: usize) -> Vec<TextDiffHunk> {
+        if context >= self.context {
+            return self.hunks.clone();
+        }
+
+        // Each hunk is a contiguous run of the files, so it can be split up on its own
+        self.hunks
+            .iter()
+            .flat_map(|hunk| {
+                hunks_in(
+                    &self.lines[hunk.start..hunk.end],
+                    hunk.start,
+                    TextDiffHunk::lines_before(hunk.old_start, hunk.old_lines),
+                    TextDiffHunk::lines_before(hunk.new_start, hunk.new_lines),
+                    context,
+                )
+            })
+            .collect()
+    }
+
+    /// Renders the diff in unified format so it can be fed to `patch` or `oxen apply`
+    pub fn to_unified(&self, old_name: &str, new_name: &str, context: usize) -> String {
+        let hunks = self.compute_hunks(context);
+        if hunks.is_empty() {
+            return String::new();
+        }
+
+        let mut result = format!("--- {old_name}\n+++ {new_name}\n");
+        for hunk in hunks.iter() {
+            result.push_str(&hunk.header());
+            result.push('\n');
+            for line in self.hunk_lines(hunk) {
+                let prefix = match line.modification {
+                    ChangeType::Added => '+',
+                    ChangeType::Removed => '-',
+                    _ => ' ',
+                };
+                result.push(prefix);
+                result.push_str(&line.text);
+                result.push('\n');
+
+                let is_last_old = line.old_line == Some(self.old_num_lines);
+                let is_last_new = line.new_line == Some(self.new_num_lines);
+                if (is_last_old && self.old_missing_newline)
+                    || (is_last_new && self.new_missing_newline)
+                {
+                    result.push_str(NO_NEWLINE_AT_EOF);
+                    result.push('\n');
+                }
+            }
+        }
+        result
+    }
+}
+
+fn default_context() -> usize {
+    DEFAULT_CONTEXT_LINES
+}
+
+/// Groups the changed lines of a full diff into hunks, keeping `context` unchanged lines around
+/// each change. Changes that are less than `2 * context` lines apart end up in the same hunk.
+pub fn build_hunks(lines: &[LineDiff], context: usize) -> Vec<TextDiffHunk> {
+    hunks_in(lines, 0, 0, 0, context)
+}
+
+/// Builds the hunks in a single pass over `lines`, which start at index `offset` of the diff and
+/// come after `old_before` and `new_before` lines of each file
+fn hunks_in(
+    lines: &[LineDiff],
+    offset: usize,
+    old_before: usize,
+    new_before: usize,
+    context: usize,
+) -> Vec<TextDiffHunk> {
+    let is_old = |l: &&LineDiff| l.modification != ChangeType::Added;
+    let is_new = |l: &&LineDiff| l.modification != ChangeType::Removed;
+
+    let mut hunks: Vec<TextDiffHunk> = vec![];
+    // Lines of each file before the current line, and before the last hunk
+    let (mut old_seen, mut new_seen) = (old_before, new_before);
+    let (mut hunk_old_before, mut hunk_new_before) = (0, 0);
+    for (i, line) in lines.iter().enumerate() {
+        if line.modification != ChangeType::Unchanged {
+            let start = i.saturating_sub(context);
+            let end = (i + context + 1).min(lines.len());
+            match hunks.last_mut() {
+                Some(hunk) if offset + start <= hunk.end => hunk.end = offset + end,
+                _ => {
+                    // Step back over the leading context to find where the hunk starts
+                    let leading = &lines[start..i];
+                    hunk_old_before = old_seen - leading.iter().filter(is_old).count();
+                    hunk_new_before = new_seen - leading.iter().filter(is_new).count();
+                    hunks.push(TextDiffHunk {
+                        old_start: 0,
+                        old_lines: 0,
+                        new_start: 0,
+                        new_lines: 0,
+                        start: offset + start,
+                        end: offset + end,
+                    });
+                }
+            }
+        }
+
+        if is_old(&line) {
+            old_seen += 1;
+        }
+        if is_new(&line) {
+            new_seen += 1;
+        }
+
+        // Fill in the line numbers at the end of the hunk, again if a later change extends it
+        if let Some(hunk) = hunks.last_mut() {
+            if offset + i + 1 == hunk.end {
+                hunk.old_lines = old_seen - hunk_old_before;
+                hunk.new_lines = new_seen - hunk_new_before;
+                // An empty side points at the line before the hunk, like `diff -u` does
+                hunk.old_start = if hunk.old_lines == 0 {
+                    hunk_old_before
+                } else {
+                    hunk_old_before + 1
+                };
+                hunk.new_start = if hunk.new_lines == 0 {
+                    hunk_new_before
+                } else {
+                    hunk_new_before + 1
+                };
+            }
+        }
+    }
+    hunks
 }
@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::api;
 use crate::model::metadata::generic_metadata::GenericMetadata;
-use crate::model::{Commit, CommitEntry, EntryDataType, LocalRepository};
+use crate::model::{Commit, CommitEntry, DataFrameProfile, EntryDataType, LocalRepository};
 use crate::view::entry::ResourceVersion;
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -19,6 +19,9 @@ pub struct CLIMetadataEntry {
     pub mime_type: String,
     // auto detected extension of the file
     pub extension: String,
+    // column stats of a tabular file, only filled in when asked for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<DataFrameProfile>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -27,6 +27,24 @@ pub struct NewMod {
     pub data: String,
 }
 
+/// Rows to insert, update and delete in a staged data frame in one go.
+/// Each modified row is a json object carrying the `_oxen_id` of the row it replaces.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct ModBatch {
+    #[serde(default)]
+    pub added: Vec<serde_json::Value>,
+    #[serde(default)]
+    pub modified: Vec<serde_json::Value>,
+    #[serde(default)]
+    pub removed: Vec<String>,
+}
+
+impl ModBatch {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.modified.is_empty() && self.removed.is_empty()
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct ModEntry {
     pub uuid: String,
@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// A file that was moved with `oxen mv` or detected as moved in status, recorded on the commit
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct MovedEntry {
+    pub from: PathBuf,
+    pub to: PathBuf,
+}
@@ -0,0 +1,115 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use super::diff::AddRemoveModifyCounts;
+use super::{Commit, DiffEntry, User};
+use crate::view::Pagination;
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeRequestStatus {
+    Open,
+    Merged,
+    Closed,
+}
+
+impl std::fmt::Display for MergeRequestStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let status = match self {
+            MergeRequestStatus::Open => "open",
+            MergeRequestStatus::Merged => "merged",
+            MergeRequestStatus::Closed => "closed",
+        };
+        write!(f, "{}", status)
+    }
+}
+
+impl std::str::FromStr for MergeRequestStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "open" => Ok(MergeRequestStatus::Open),
+            "merged" => Ok(MergeRequestStatus::Merged),
+            "closed" => Ok(MergeRequestStatus::Closed),
+            _ => Err(format!("Could not parse {} as a MergeRequestStatus", s)),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct MergeRequestComment {
+    pub id: u64,
+    pub author: User,
+    pub body: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub timestamp: OffsetDateTime,
+}
+
+/// A request to merge the `source_branch` (head) into the `target_branch` (base),
+/// persisted per repository so it can be reviewed before merging.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct MergeRequest {
+    pub id: u64,
+    pub title: String,
+    pub description: String,
+    pub source_branch: String,
+    pub target_branch: String,
+    pub author: User,
+    pub status: MergeRequestStatus,
+    pub comments: Vec<MergeRequestComment>,
+    pub merge_commit_id: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub updated_at: OffsetDateTime,
+}
+
+impl std::fmt::Display for MergeRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "#{} {} ({} -> {}) [{}]",
+            self.id, self.title, self.source_branch, self.target_branch, self.status
+        )
+    }
+}
+
+/// Body used to open a new merge request
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct NewMergeRequest {
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    pub source_branch: String,
+    pub target_branch: String,
+    pub author: User,
+}
+
+/// Body used to update an existing merge request, only the fields present are changed
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct MergeRequestUpdate {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub target_branch: Option<String>,
+    pub status: Option<MergeRequestStatus>,
+}
+
+/// Body used to comment on a merge request
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct NewMergeRequestComment {
+    pub author: User,
+    pub body: String,
+}
+
+/// Summary of the changes a merge request would bring into its target branch
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct MergeRequestDiffSummary {
+    pub base_commit: Commit,
+    pub head_commit: Commit,
+    pub is_mergeable: bool,
+    pub commits: Vec<Commit>,
+    pub counts: AddRemoveModifyCounts,
+    pub entries: Vec<DiffEntry>,
+    pub pagination: Pagination,
+}
@@ -1,8 +1,10 @@
+pub mod compatibility;
 pub mod custom_data_type;
 pub mod data_type;
 pub mod field;
 pub mod staged_schema;
 
+pub use compatibility::SchemaCompatibility;
 pub use custom_data_type::CustomDataType;
 pub use data_type::DataType;
 pub use field::Field;
@@ -20,6 +22,9 @@ pub struct Schema {
     pub fields: Vec<Field>,
     // Optional string metadata on the schema, to allow for user driven features.
     pub metadata: Option<Value>,
+    // How the columns are allowed to change when a new version of the file is added.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compatibility: Option<SchemaCompatibility>,
 }
 
 impl PartialEq for Schema {
@@ -35,6 +40,7 @@ impl Schema {
             hash: Schema::hash_fields(&fields),
             fields: fields.to_owned(),
             metadata: None,
+            compatibility: None,
         }
     }
 
@@ -44,6 +50,7 @@ impl Schema {
             hash: Schema::hash_fields(&fields),
             fields: fields.to_owned(),
             metadata: None,
+            compatibility: None,
         }
     }
 
@@ -63,7 +70,13 @@ impl Schema {
     pub fn from_polars(schema: &polars::prelude::Schema) -> Schema {
         let mut fields: Vec<Field> = vec![];
         for field in schema.iter_fields() {
-            let f = Field::new(field.name(), field.data_type().to_string().as_str());
+            // Keep the polars names so the hashes of existing schemas do not change, except for
+            // structs since polars only prints how many fields they have
+            let dtype = match DataType::from_polars(field.data_type()) {
+                dtype @ DataType::Struct(_) => dtype.to_string(),
+                _ => field.data_type().to_string(),
+            };
+            let f = Field::new(field.name(), &dtype);
             fields.push(f);
         }
 
@@ -72,6 +85,7 @@ impl Schema {
             hash: Schema::hash_fields(&fields),
             fields,
             metadata: None,
+            compatibility: None,
         }
     }
 
@@ -87,22 +101,32 @@ impl Schema {
         if let Some(f) = self.fields.iter_mut().find(|f| f.name == name) {
             f.metadata = Some(metadata.to_owned());
         }
-        self.hash = Schema::hash_fields(&self.fields);
+        self.rehash();
     }
 
-    /// Write metadata from schema columns to the schema
+    /// Write metadata from schema columns to the schema, along with the name and any custom
+    /// data types such as `path` that cannot be inferred from the data
     pub fn update_metadata_from_schema(&mut self, schema: &Schema) {
         if let Some(metadata) = &schema.metadata {
             self.metadata = Some(metadata.to_owned());
         }
+        if self.name.is_none() {
+            self.name.clone_from(&schema.name);
+        }
+        if schema.compatibility.is_some() {
+            self.compatibility = schema.compatibility;
+        }
         for field in schema.fields.iter() {
             if let Some(f) = self.fields.iter_mut().find(|f| f.name == field.name) {
                 if field.metadata.is_some() {
                     f.metadata.clone_from(&field.metadata);
                 }
+                if CustomDataType::from_string(&field.dtype) != CustomDataType::Unknown {
+                    f.dtype.clone_from(&field.dtype);
+                }
             }
         }
-        self.hash = Schema::hash_fields(&self.fields);
+        self.rehash();
     }
 
     pub fn has_all_field_names(&self, schema: &polars::prelude::Schema) -> bool {
@@ -172,10 +196,33 @@ impl Schema {
         self.fields.iter().find(|f| f.name == name)
     }
 
+    /// Set how the columns are allowed to change, `None` removes the contract
+    pub fn set_compatibility(&mut self, compatibility: Option<SchemaCompatibility>) {
+        self.compatibility = compatibility;
+        self.rehash();
+    }
+
+    // Schemas are versioned by hash, so the contract has to be part of it to be committed
+    fn rehash(&mut self) {
+        self.hash = match self.compatibility {
+            Some(mode) => {
+                let buffer = format!("{}{}", Schema::hash_fields(&self.fields), mode);
+                hasher::hash_buffer(buffer.as_bytes())
+            }
+            None => Schema::hash_fields(&self.fields),
+        };
+    }
+
     fn hash_fields(fields: &Vec<Field>) -> String {
         let mut hash_buffers: Vec<String> = vec![];
         for f in fields {
-            hash_buffers.push(format!("{}{}", f.name, f.dtype));
+            // Structs are hashed the way polars prints them, so that schemas from before the
+            // struct fields were kept have the same hash
+            let dtype = match DataType::from_string(&f.dtype) {
+                DataType::Struct(struct_fields) => format!("struct[{}]", struct_fields.len()),
+                _ => f.dtype.to_owned(),
+            };
+            hash_buffers.push(format!("{}{}", f.name, dtype));
             if let Some(metadata) = &f.metadata {
                 hash_buffers.push(metadata.to_string());
             }
@@ -252,7 +299,46 @@ impl Schema {
             hash: Schema::hash_fields(&fields),
             fields,
             metadata: None,
+            compatibility: None,
+        }
+    }
+
+    /// Describe every change from this schema to `new` that its compatibility mode does not
+    /// allow, empty if there is no mode set or `new` is a valid next version
+    pub fn compatibility_violations(&self, new: &Schema) -> Vec<String> {
+        let mut violations = vec![];
+        let Some(mode) = self.compatibility else {
+            return violations;
+        };
+
+        for field in self.fields.iter() {
+            match new.fields.iter().find(|f| f.name == field.name) {
+                Some(new_field) if new_field.dtype == field.dtype => {}
+                Some(new_field) => {
+                    let widened = DataType::from_string(&field.dtype)
+                        .can_widen_to(&DataType::from_string(&new_field.dtype));
+                    if !(mode.allows_widening() && widened) {
+                        violations.push(format!(
+                            "column '{}' changed type from {} to {}",
+                            field.name, field.dtype, new_field.dtype
+                        ));
+                    }
+                }
+                None => {
+                    violations.push(format!("column '{}' was removed", field.name));
+                }
+            }
+        }
+
+        if !mode.allows_new_columns() {
+            for field in new.fields.iter() {
+                if !self.fields.iter().any(|f| f.name == field.name) {
+                    violations.push(format!("column '{}' was added", field.name));
+                }
+            }
         }
+
+        violations
     }
 
     pub fn schemas_to_string(schemas: HashMap<PathBuf, Schema>) -> String {
@@ -303,10 +389,14 @@ impl Schema {
             }
             table.add_row(row);
         }
-        if let Some(metadata) = &self.metadata {
+        let result = if let Some(metadata) = &self.metadata {
             format!("\n{}\n\n{}", metadata, table)
         } else {
             format!("{}", table)
+        };
+        match self.compatibility {
+            Some(mode) => format!("compatibility: {mode}\n{result}"),
+            None => result,
         }
     }
 }
@@ -332,6 +422,7 @@ mod tests {
 
     use crate::model::schema::Field;
     use crate::model::schema::Schema;
+    use crate::model::schema::SchemaCompatibility;
 
     #[test]
     fn test_schemas_to_string_one_field() {
@@ -343,6 +434,7 @@ mod tests {
                 hash: "1234".to_string(),
                 fields: vec![Field::new("file", "")],
                 metadata: None,
+                compatibility: None,
             },
         );
         let table = Schema::schemas_to_string(schemas);
@@ -375,6 +467,7 @@ mod tests {
                     Field::new("h", "f64"),
                 ],
                 metadata: None,
+                compatibility: None,
             },
         );
         let table = Schema::schemas_to_string(schemas);
@@ -409,6 +502,7 @@ mod tests {
                     Field::new("h", "f64"),
                 ],
                 metadata: None,
+                compatibility: None,
             },
         );
         schemas.insert(
@@ -418,6 +512,7 @@ mod tests {
                 hash: "5432".to_string(),
                 fields: vec![Field::new("file", "str"), Field::new("x", "i64")],
                 metadata: None,
+                compatibility: None,
             },
         );
         let table = Schema::schemas_to_string(schemas);
@@ -436,4 +531,55 @@ mod tests {
                 .trim()
         )
     }
+
+    #[test]
+    fn test_compatibility_violations() {
+        let mut committed = Schema::from_fields(vec![
+            Field::new("file", "str"),
+            Field::new("x", "i32"),
+            Field::new("w", "f32"),
+        ]);
+        let new = Schema::from_fields(vec![
+            Field::new("file", "str"),
+            Field::new("x", "i64"),
+            Field::new("label", "str"),
+        ]);
+
+        assert!(committed.compatibility_violations(&new).is_empty());
+
+        committed.compatibility = Some(SchemaCompatibility::Strict);
+        assert_eq!(
+            committed.compatibility_violations(&new),
+            vec![
+                "column 'x' changed type from i32 to i64",
+                "column 'w' was removed",
+                "column 'label' was added",
+            ]
+        );
+
+        committed.compatibility = Some(SchemaCompatibility::Additive);
+        assert_eq!(
+            committed.compatibility_violations(&new),
+            vec![
+                "column 'x' changed type from i32 to i64",
+                "column 'w' was removed"
+            ]
+        );
+
+        committed.compatibility = Some(SchemaCompatibility::Backward);
+        assert_eq!(
+            committed.compatibility_violations(&new),
+            vec!["column 'w' was removed"]
+        );
+    }
+
+    #[test]
+    fn test_schema_struct_hash_matches_polars_name() {
+        let with_fields = Schema::new(
+            "bbox",
+            vec![Field::new("bbox", "struct[x: f64, label: str]")],
+        );
+        let polars_name = Schema::new("bbox", vec![Field::new("bbox", "struct[2]")]);
+        assert_eq!(with_fields.hash, polars_name.hash);
+    }
 }
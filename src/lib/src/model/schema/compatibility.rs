@@ -0,0 +1,54 @@
+//! How the schema of a file is allowed to change from one commit to the next
+//!
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::error::OxenError;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SchemaCompatibility {
+    /// Columns can not be added, removed or change type
+    Strict,
+    /// Columns can be added, but not removed or change type
+    Additive,
+    /// Columns can be added, and existing columns can be widened to a type that holds all of
+    /// their previous values, ie: i32 -> i64 or f32 -> f64
+    Backward,
+}
+
+impl fmt::Display for SchemaCompatibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl SchemaCompatibility {
+    pub fn from_string(s: impl AsRef<str>) -> Result<SchemaCompatibility, OxenError> {
+        match s.as_ref() {
+            "strict" => Ok(SchemaCompatibility::Strict),
+            "additive" => Ok(SchemaCompatibility::Additive),
+            "backward" => Ok(SchemaCompatibility::Backward),
+            s => Err(OxenError::basic_str(format!(
+                "Unknown schema compatibility {s:?}, must be one of: strict, additive, backward"
+            ))),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SchemaCompatibility::Strict => "strict",
+            SchemaCompatibility::Additive => "additive",
+            SchemaCompatibility::Backward => "backward",
+        }
+    }
+
+    pub fn allows_new_columns(&self) -> bool {
+        !matches!(self, SchemaCompatibility::Strict)
+    }
+
+    pub fn allows_widening(&self) -> bool {
+        matches!(self, SchemaCompatibility::Backward)
+    }
+}
@@ -3,6 +3,10 @@
 
 use std::fmt;
 
+use polars::prelude::TimeUnit;
+
+use crate::model::schema::Field;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum DataType {
     Boolean,
@@ -19,26 +23,54 @@ pub enum DataType {
     String,
     Date,
     Time,
+    // Time unit and optional time zone
+    Datetime(TimeUnit, Option<String>),
+    Duration(TimeUnit),
+    // Precision and scale
+    Decimal(Option<usize>, Option<usize>),
+    Binary,
+    Categorical,
     List(Box<DataType>),
+    Struct(Vec<Field>),
     // TODO: implement these when needed...
     // Object(&'static str),
-    // Datetime,
-    // Duration,
     Null,
     Unknown,
 }
 
+/// Matches the polars representation of the parameterized types, except for structs which
+/// polars only prints the number of fields for
 impl fmt::Display for DataType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.as_str())
+        match self {
+            DataType::Datetime(unit, None) => write!(f, "datetime[{unit}]"),
+            DataType::Datetime(unit, Some(tz)) => write!(f, "datetime[{unit}, {tz}]"),
+            DataType::Duration(unit) => write!(f, "duration[{unit}]"),
+            DataType::Decimal(Some(precision), Some(scale)) => {
+                write!(f, "decimal[{precision},{scale}]")
+            }
+            DataType::Decimal(None, Some(scale)) => write!(f, "decimal[*,{scale}]"),
+            DataType::Decimal(_, None) => write!(f, "decimal[?]"),
+            DataType::List(val) => write!(f, "list[{val}]"),
+            DataType::Struct(fields) => {
+                let fields: Vec<String> = fields
+                    .iter()
+                    .map(|field| format!("{}: {}", field.name, field.dtype))
+                    .collect();
+                write!(f, "struct[{}]", fields.join(", "))
+            }
+            _ => write!(f, "{}", self.as_str()),
+        }
     }
 }
 
 impl DataType {
     pub fn from_string(s: impl AsRef<str>) -> DataType {
-        match s.as_ref() {
+        let s = s.as_ref().trim();
+        match s {
             "bool" => DataType::Boolean,
             "uint8" => DataType::UInt8,
+            "u8" => DataType::UInt8,
             "u16" => DataType::UInt16,
             "u32" => DataType::UInt32,
             "u64" => DataType::UInt64,
@@ -54,8 +86,44 @@ impl DataType {
             "str" => DataType::String,
             "date" => DataType::Date,
             "time" => DataType::Time,
+            "datetime" => DataType::Datetime(TimeUnit::Microseconds, None),
+            "duration" => DataType::Duration(TimeUnit::Microseconds),
+            "binary" => DataType::Binary,
+            "cat" => DataType::Categorical,
             "null" => DataType::Null,
-            _ => DataType::Unknown,
+            _ => DataType::from_parameterized_string(s).unwrap_or(DataType::Unknown),
+        }
+    }
+
+    // Types with parameters in brackets, ie: `datetime[ms, UTC]` or `list[i64]`
+    fn from_parameterized_string(s: &str) -> Option<DataType> {
+        let (name, params) = s.strip_suffix(']')?.split_once('[')?;
+        let params = split_top_level(params);
+        match (name, params.as_slice()) {
+            ("datetime", [unit]) => Some(DataType::Datetime(time_unit_from_str(unit)?, None)),
+            ("datetime", [unit, tz]) => Some(DataType::Datetime(
+                time_unit_from_str(unit)?,
+                Some(tz.to_string()),
+            )),
+            ("duration", [unit]) => Some(DataType::Duration(time_unit_from_str(unit)?)),
+            ("decimal", [precision, scale]) => Some(DataType::Decimal(
+                precision.parse().ok(),
+                Some(scale.parse().ok()?),
+            )),
+            ("list", [inner]) => match DataType::from_string(inner) {
+                DataType::Unknown => None,
+                inner => Some(DataType::List(Box::new(inner))),
+            },
+            // polars prints `struct[2]` which does not have enough information to round trip
+            ("struct", fields) if fields.iter().all(|f| f.contains(':')) => {
+                let fields = fields
+                    .iter()
+                    .filter_map(|field| field.split_once(':'))
+                    .map(|(name, dtype)| Field::new(name.trim(), dtype.trim()))
+                    .collect();
+                Some(DataType::Struct(fields))
+            }
+            _ => None,
         }
     }
 
@@ -75,12 +143,36 @@ impl DataType {
             DataType::String => "str",
             DataType::Date => "date",
             DataType::Time => "time",
+            DataType::Datetime(_, _) => "datetime",
+            DataType::Duration(_) => "duration",
+            DataType::Decimal(_, _) => "decimal",
+            DataType::Binary => "binary",
+            DataType::Categorical => "cat",
             DataType::List(_val) => "list",
+            DataType::Struct(_) => "struct",
             DataType::Null => "null",
             DataType::Unknown => "?",
         }
     }
 
+    /// Whether every value of this type can be stored in `other` without losing information
+    pub fn can_widen_to(&self, other: &DataType) -> bool {
+        use DataType::*;
+        match (self, other) {
+            (a, b) if a == b => true,
+            (Null, _) => true,
+            (UInt8, UInt16 | UInt32 | UInt64 | Int16 | Int32 | Int64 | Float32 | Float64) => true,
+            (UInt16, UInt32 | UInt64 | Int32 | Int64 | Float32 | Float64) => true,
+            (UInt32, UInt64 | Int64 | Float64) => true,
+            (Int8, Int16 | Int32 | Int64 | Float32 | Float64) => true,
+            (Int16, Int32 | Int64 | Float32 | Float64) => true,
+            (Int32, Int64 | Float64) => true,
+            (Float32, Float64) => true,
+            (List(a), List(b)) => a.can_widen_to(b),
+            _ => false,
+        }
+    }
+
     pub fn to_polars(&self) -> polars::prelude::DataType {
         match self {
             DataType::Boolean => polars::prelude::DataType::Boolean,
@@ -97,7 +189,27 @@ impl DataType {
             DataType::String => polars::prelude::DataType::String,
             DataType::Date => polars::prelude::DataType::Date,
             DataType::Time => polars::prelude::DataType::Time,
+            DataType::Datetime(unit, tz) => polars::prelude::DataType::Datetime(*unit, tz.clone()),
+            DataType::Duration(unit) => polars::prelude::DataType::Duration(*unit),
+            DataType::Decimal(precision, scale) => {
+                polars::prelude::DataType::Decimal(*precision, *scale)
+            }
+            DataType::Binary => polars::prelude::DataType::Binary,
+            DataType::Categorical => {
+                polars::prelude::DataType::Categorical(None, Default::default())
+            }
             DataType::List(val) => polars::prelude::DataType::List(Box::new(val.to_polars())),
+            DataType::Struct(fields) => polars::prelude::DataType::Struct(
+                fields
+                    .iter()
+                    .map(|f| {
+                        polars::prelude::Field::new(
+                            &f.name,
+                            DataType::from_string(&f.dtype).to_polars(),
+                        )
+                    })
+                    .collect(),
+            ),
             DataType::Null => polars::prelude::DataType::Null,
             DataType::Unknown => polars::prelude::DataType::Unknown,
         }
@@ -117,36 +229,79 @@ impl DataType {
             polars::prelude::DataType::Float32 => DataType::Float32,
             polars::prelude::DataType::Float64 => DataType::Float64,
             polars::prelude::DataType::String => DataType::String,
+            polars::prelude::DataType::Date => DataType::Date,
+            polars::prelude::DataType::Time => DataType::Time,
+            polars::prelude::DataType::Datetime(unit, tz) => DataType::Datetime(*unit, tz.clone()),
+            polars::prelude::DataType::Duration(unit) => DataType::Duration(*unit),
+            polars::prelude::DataType::Decimal(precision, scale) => {
+                DataType::Decimal(*precision, *scale)
+            }
+            polars::prelude::DataType::Binary => DataType::Binary,
+            polars::prelude::DataType::Categorical(_, _)
+            | polars::prelude::DataType::Enum(_, _) => DataType::Categorical,
+            polars::prelude::DataType::List(val) => {
+                DataType::List(Box::new(DataType::from_polars(val)))
+            }
+            polars::prelude::DataType::Struct(fields) => DataType::Struct(
+                fields
+                    .iter()
+                    .map(|f| {
+                        let dtype = DataType::from_polars(f.data_type());
+                        Field::new(f.name(), &dtype.to_string())
+                    })
+                    .collect(),
+            ),
             polars::prelude::DataType::Null => DataType::Null,
             _ => DataType::Unknown,
         }
     }
 
-    pub fn to_sql(&self) -> &'static str {
+    pub fn to_sql(&self) -> String {
         match self {
-            DataType::Boolean => "BOOL",
-            DataType::UInt8 => "UTINYINT", // unsigned one-byte integer
-            DataType::UInt16 => "USMALLINT", // unsigned two-byte integer
-            DataType::UInt32 => "UINTEGER", // unsigned four-byte integer
-            DataType::UInt64 => "UBIGINT", // unsigned eight-byte integer
-            DataType::Int8 => "TINYINT",   // signed one-byte integer
-            DataType::Int16 => "SMALLINT", // signed two-byte integer
-            DataType::Int32 => "INTEGER",  // signed four-byte integer
-            DataType::Int64 => "BIGINT",   // signed eight-byte integer
-            DataType::Float32 => "FLOAT", // alias for REAL, single precision floating-point number (4 bytes)
-            DataType::Float64 => "DOUBLE", // double-precision floating point number
-            DataType::String => "VARCHAR", // variable-length character string
-            DataType::Date => "DATE",     // calendar date (year, month day)
-            DataType::Time => "TIME",     // time of day (no time zone)
-            DataType::List(_) => panic!("TODO: implement list type for SQL"), // https://duckdb.org/docs/sql/data_types/list
-            DataType::Null => "NULL",                                         // null value
+            DataType::Boolean => "BOOL".to_string(),
+            DataType::UInt8 => "UTINYINT".to_string(), // unsigned one-byte integer
+            DataType::UInt16 => "USMALLINT".to_string(), // unsigned two-byte integer
+            DataType::UInt32 => "UINTEGER".to_string(), // unsigned four-byte integer
+            DataType::UInt64 => "UBIGINT".to_string(), // unsigned eight-byte integer
+            DataType::Int8 => "TINYINT".to_string(),   // signed one-byte integer
+            DataType::Int16 => "SMALLINT".to_string(), // signed two-byte integer
+            DataType::Int32 => "INTEGER".to_string(),  // signed four-byte integer
+            DataType::Int64 => "BIGINT".to_string(),   // signed eight-byte integer
+            DataType::Float32 => "FLOAT".to_string(), // alias for REAL, single precision floating-point number (4 bytes)
+            DataType::Float64 => "DOUBLE".to_string(), // double-precision floating point number
+            DataType::String => "VARCHAR".to_string(), // variable-length character string
+            DataType::Date => "DATE".to_string(),     // calendar date (year, month day)
+            DataType::Time => "TIME".to_string(),     // time of day (no time zone)
+            // microseconds in UTC, the time zone is kept in a comment on the column by df_db
+            DataType::Datetime(_, Some(_)) => "TIMESTAMPTZ".to_string(),
+            DataType::Datetime(TimeUnit::Milliseconds, None) => "TIMESTAMP_MS".to_string(),
+            DataType::Datetime(TimeUnit::Microseconds, None) => "TIMESTAMP".to_string(),
+            DataType::Datetime(TimeUnit::Nanoseconds, None) => "TIMESTAMP_NS".to_string(),
+            DataType::Duration(_) => "INTERVAL".to_string(), // months, days and microseconds
+            DataType::Decimal(precision, scale) => {
+                // duckdb supports up to 38 digits
+                format!(
+                    "DECIMAL({},{})",
+                    precision.unwrap_or(38),
+                    scale.unwrap_or(0)
+                )
+            }
+            DataType::Binary => "BLOB".to_string(), // variable-length binary data
+            DataType::Categorical => "VARCHAR".to_string(),
+            DataType::List(val) => format!("{}[]", val.to_sql()), // https://duckdb.org/docs/sql/data_types/list
+            DataType::Struct(fields) => {
+                // https://duckdb.org/docs/sql/data_types/struct
+                let fields: Vec<String> = fields.iter().map(|f| f.to_sql()).collect();
+                format!("STRUCT({})", fields.join(", "))
+            }
+            DataType::Null => "NULL".to_string(), // null value
             DataType::Unknown => panic!("TODO: unknown SQL type: {}", self),
         }
     }
 
     pub fn from_sql(s: impl AsRef<str>) -> Self {
-        match s.as_ref() {
-            "BOOL" => DataType::Boolean,
+        match s.as_ref().trim() {
+            "BOOL" | "BOOLEAN" => DataType::Boolean,
             "UTINYINT" => DataType::UInt8, // unsigned one-byte integer
             "USMALLINT" => DataType::UInt16, // unsigned two-byte integer
             "UINTEGER" => DataType::UInt32, // unsigned four-byte integer
@@ -160,9 +315,151 @@ impl DataType {
             "VARCHAR" => DataType::String, // variable-length character string
             "DATE" => DataType::Date,     // calendar date (year, month day)
             "TIME" => DataType::Time,     // time of day (no time zone)
-            "NULL" => DataType::Null,     // null value
+            "TIMESTAMP_S" | "TIMESTAMP_MS" => DataType::Datetime(TimeUnit::Milliseconds, None),
+            "TIMESTAMP" => DataType::Datetime(TimeUnit::Microseconds, None),
+            "TIMESTAMP_NS" => DataType::Datetime(TimeUnit::Nanoseconds, None),
+            "TIMESTAMPTZ" | "TIMESTAMP WITH TIME ZONE" => {
+                DataType::Datetime(TimeUnit::Microseconds, Some("UTC".to_string()))
+            }
+            "INTERVAL" => DataType::Duration(TimeUnit::Microseconds),
+            "BLOB" => DataType::Binary,
+            "NULL" => DataType::Null, // null value
             "UUID" => DataType::String,
-            other => panic!("TODO: unknown SQL type: {}", other),
+            other => match DataType::from_parameterized_sql(other) {
+                Some(dtype) => dtype,
+                None => panic!("TODO: unknown SQL type: {}", other),
+            },
+        }
+    }
+
+    // Types with parameters, ie: `DECIMAL(18,3)`, `BIGINT[]` or `STRUCT(a BIGINT, b VARCHAR)`
+    fn from_parameterized_sql(s: &str) -> Option<DataType> {
+        if let Some(inner) = s.strip_suffix("[]") {
+            return Some(DataType::List(Box::new(DataType::from_sql(inner))));
+        }
+        if s.starts_with("ENUM(") {
+            return Some(DataType::Categorical);
+        }
+
+        let (name, params) = s.strip_suffix(')')?.split_once('(')?;
+        let params = split_top_level(params);
+        match (name, params.as_slice()) {
+            ("DECIMAL", [precision, scale]) => Some(DataType::Decimal(
+                Some(precision.parse().ok()?),
+                Some(scale.parse().ok()?),
+            )),
+            ("STRUCT", fields) => {
+                let mut result = vec![];
+                for field in fields {
+                    let (name, dtype) = split_sql_field(field)?;
+                    let dtype = DataType::from_sql(dtype).to_string();
+                    result.push(Field::new(name, &dtype));
+                }
+                Some(DataType::Struct(result))
+            }
+            _ => None,
+        }
+    }
+}
+
+fn time_unit_from_str(s: &str) -> Option<TimeUnit> {
+    match s {
+        "ns" => Some(TimeUnit::Nanoseconds),
+        "μs" | "us" => Some(TimeUnit::Microseconds),
+        "ms" => Some(TimeUnit::Milliseconds),
+        _ => None,
+    }
+}
+
+/// Split on the commas that are not nested inside of brackets or parentheses
+pub fn split_top_level(s: &str) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '[' | '(' => depth += 1,
+            ']' | ')' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(s[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
         }
     }
+    let last = s[start..].trim();
+    if !last.is_empty() || !parts.is_empty() {
+        parts.push(last);
+    }
+    parts
+}
+
+// A struct field in duckdb is `name TYPE` where the name may be quoted
+fn split_sql_field(field: &str) -> Option<(&str, &str)> {
+    if let Some(quoted) = field.strip_prefix('"') {
+        let (name, dtype) = quoted.split_once('"')?;
+        Some((name, dtype.trim()))
+    } else {
+        field.split_once(' ')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use polars::prelude::TimeUnit;
+
+    use crate::model::schema::{DataType, Field};
+
+    #[test]
+    fn test_data_type_string_round_trip() {
+        let dtypes = vec![
+            DataType::UInt8,
+            DataType::Datetime(TimeUnit::Milliseconds, Some("UTC".to_string())),
+            DataType::Duration(TimeUnit::Nanoseconds),
+            DataType::Decimal(Some(10), Some(2)),
+            DataType::Binary,
+            DataType::Categorical,
+            DataType::List(Box::new(DataType::Int64)),
+            DataType::Struct(vec![
+                Field::new("x", "i64"),
+                Field::new("tags", "list[str]"),
+                Field::new("seen", "datetime[μs, UTC]"),
+            ]),
+        ];
+
+        for dtype in dtypes {
+            assert_eq!(DataType::from_string(dtype.to_string()), dtype);
+            assert_eq!(DataType::from_polars(&dtype.to_polars()), dtype);
+        }
+
+        // polars only prints the number of struct fields, which we can not read back
+        assert_eq!(DataType::from_string("struct[2]"), DataType::Unknown);
+
+        // Existing schemas store these names
+        assert_eq!(DataType::UInt8.as_str(), "uint8");
+        assert_eq!(DataType::from_string("u8"), DataType::UInt8);
+    }
+
+    #[test]
+    fn test_data_type_sql_round_trip() {
+        let dtypes = vec![
+            DataType::Datetime(TimeUnit::Microseconds, None),
+            DataType::Decimal(Some(18), Some(3)),
+            DataType::Binary,
+            DataType::List(Box::new(DataType::String)),
+            DataType::Struct(vec![Field::new("x", "i64"), Field::new("label", "str")]),
+        ];
+
+        for dtype in dtypes {
+            assert_eq!(DataType::from_sql(dtype.to_sql()), dtype);
+        }
+
+        assert_eq!(
+            DataType::from_sql("STRUCT(\"min x\" DOUBLE, labels VARCHAR[])"),
+            DataType::Struct(vec![
+                Field::new("min x", "f64"),
+                Field::new("labels", "list[str]"),
+            ])
+        );
+    }
 }
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::model::schema::data_type;
 use crate::model::schema::DataType;
 
 use super::CustomDataType;
@@ -43,14 +44,12 @@ impl Field {
 
     pub fn fields_from_string(fields: &str) -> Vec<Field> {
         let mut fields_vec: Vec<Field> = vec![];
-        for field in fields.split(',') {
-            let field = field.trim();
-            let field_parts: Vec<&str> = field.split(':').collect();
-            if field_parts.len() != 2 {
+        // Split on the top level so that dtypes like `datetime[ms, UTC]` stay whole
+        for field in data_type::split_top_level(fields) {
+            let Some((name, dtype)) = field.split_once(':') else {
                 panic!("Invalid field: {}", field);
-            }
-            let name = field_parts[0];
-            let dtype = field_parts[1];
+            };
+            let (name, dtype) = (name.trim(), dtype.trim());
             if DataType::from_string(dtype) == DataType::Unknown
                 && CustomDataType::from_string(dtype) == CustomDataType::Unknown
             {
@@ -1,7 +1,7 @@
 use colored::{ColoredString, Colorize};
 use std::collections::{HashMap, HashSet};
 use std::fmt;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::model::{
     MergeConflict, StagedEntry, StagedEntryStatus, StagedSchema, SummarizedStagedDirStats,
@@ -307,22 +307,35 @@ impl StagedData {
             opts,
         );
 
-        let mut moved_entries = self.moved_files.clone();
-        moved_entries.sort_by(|(_, old_a, _), (_, old_b, _)| old_a.partial_cmp(old_b).unwrap());
-
+        let moved_groups = self.grouped_moved_files();
         self.__collapse_outputs(
-            &self.moved_files,
-            |(path, removed_path, _hash)| {
-                vec![
-                    "  moved: ".green(),
-                    format!(
-                        "{} -> {}\n",
-                        removed_path.to_str().unwrap(),
-                        path.to_str().unwrap()
-                    )
-                    .green()
-                    .bold(),
-                ]
+            &moved_groups,
+            |(old_dir, new_dir, moves)| {
+                if moves.len() > 1 {
+                    vec![
+                        "  moved: ".green(),
+                        format!(
+                            "{}/ -> {}/ ({} files)\n",
+                            old_dir.to_str().unwrap(),
+                            new_dir.to_str().unwrap(),
+                            moves.len()
+                        )
+                        .green()
+                        .bold(),
+                    ]
+                } else {
+                    let (path, removed_path) = &moves[0];
+                    vec![
+                        "  moved: ".green(),
+                        format!(
+                            "{} -> {}\n",
+                            removed_path.to_str().unwrap(),
+                            path.to_str().unwrap()
+                        )
+                        .green()
+                        .bold(),
+                    ]
+                }
             },
             outputs,
             opts,
@@ -337,6 +350,34 @@ impl StagedData {
         outputs.push("\n".normal());
     }
 
+    /// Groups moved files by (old parent dir, new parent dir) so that moving a whole
+    /// directory renders as a single line instead of one line per file.
+    #[allow(clippy::type_complexity)]
+    fn grouped_moved_files(&self) -> Vec<(PathBuf, PathBuf, Vec<(PathBuf, PathBuf)>)> {
+        let mut moved_entries = self.moved_files.clone();
+        moved_entries.sort_by(|(_, old_a, _), (_, old_b, _)| old_a.cmp(old_b));
+
+        let mut groups: Vec<(PathBuf, PathBuf, Vec<(PathBuf, PathBuf)>)> = vec![];
+        for (path, removed_path, _hash) in moved_entries {
+            let old_dir = removed_path.parent().unwrap_or(Path::new("")).to_path_buf();
+            let new_dir = path.parent().unwrap_or(Path::new("")).to_path_buf();
+            let is_same_name = path.file_name() == removed_path.file_name();
+            let group = groups.iter_mut().find(|(o, n, moves)| {
+                let (first_new, first_old) = &moves[0];
+                is_same_name
+                    && first_new.file_name() == first_old.file_name()
+                    && o == &old_dir
+                    && n == &new_dir
+                    && old_dir != new_dir
+            });
+            match group {
+                Some((_, _, moves)) => moves.push((path, removed_path)),
+                None => groups.push((old_dir, new_dir, vec![(path, removed_path)])),
+            }
+        }
+        groups
+    }
+
     fn staged_schemas(&self, outputs: &mut Vec<ColoredString>, opts: &StagedDataOpts) {
         if self.staged_schemas.is_empty() {
             return;
@@ -751,4 +792,43 @@ mod tests {
         assert_eq!(outputs[2], "  removed: ".red());
         assert_eq!(outputs[3], "README.md\n".red().bold());
     }
+
+    #[test]
+    fn test_staged_data_moved_dir_is_grouped() {
+        let mut staged_data = StagedData::empty();
+        for name in ["a.jpg", "b.jpg", "c.jpg"] {
+            let new_path = PathBuf::from("images/all").join(name);
+            let old_path = PathBuf::from("images/train").join(name);
+            staged_data.staged_files.insert(
+                new_path.clone(),
+                StagedEntry::empty_status(StagedEntryStatus::Added),
+            );
+            staged_data.staged_files.insert(
+                old_path.clone(),
+                StagedEntry::empty_status(StagedEntryStatus::Removed),
+            );
+            staged_data
+                .moved_files
+                .push((new_path, old_path, format!("hash_{name}")));
+        }
+        staged_data.moved_files.push((
+            PathBuf::from("README.md"),
+            PathBuf::from("docs/README.md"),
+            String::from("hash_readme"),
+        ));
+
+        let opts = StagedDataOpts {
+            is_remote: true,
+            ..StagedDataOpts::default()
+        };
+        let outputs = staged_data.__collect_outputs(&opts);
+        assert_eq!(outputs[0], "Files to be committed:\n".normal());
+        assert_eq!(outputs[1], "  moved: ".green());
+        assert_eq!(outputs[2], "docs/README.md -> README.md\n".green().bold());
+        assert_eq!(outputs[3], "  moved: ".green());
+        assert_eq!(
+            outputs[4],
+            "images/train/ -> images/all/ (3 files)\n".green().bold()
+        );
+    }
 }
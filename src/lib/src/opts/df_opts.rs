@@ -5,6 +5,8 @@ use serde_json::Value;
 
 use crate::constants::{DEFAULT_HOST, FILE_ROW_NUM_COL_NAME, ROW_HASH_COL_NAME, ROW_NUM_COL_NAME};
 use crate::core::df::agg::{self, DFAggregation};
+use crate::core::df::sample::SampleSize;
+use crate::core::df::sql::SqlTable;
 use crate::error::OxenError;
 use crate::model::schema::Field;
 use crate::model::{ContentType, Schema};
@@ -29,6 +31,8 @@ pub struct DFOpts {
     pub add_col: Option<String>,
     pub add_row: Option<String>,
     pub aggregate: Option<String>,
+    /// Json file of rows to add, modify and remove in a remote staged data frame in one request
+    pub batch: Option<PathBuf>,
     pub col_at: Option<String>,
     pub columns: Option<String>,
     pub committed: bool,
@@ -39,20 +43,41 @@ pub struct DFOpts {
     pub get_row: Option<String>,
     pub head: Option<usize>,
     pub host: Option<String>,
+    /// Write the result back over the input file in its original format and stage it
+    pub in_place: bool,
     pub index: bool,
+    /// Skip rows that do not parse, truncate ragged lines and replace invalid utf8 when
+    /// scanning csv files, rather than failing
+    pub lenient: bool,
     pub output: Option<PathBuf>,
     pub page_size: Option<usize>,
     pub page: Option<usize>,
+    /// Print the column stats of the result instead of the rows
+    pub profile: bool,
+    /// Values to write over the row given by `update_row`, in the format of `content_type`
+    pub row_data: Option<String>,
+    /// Read the file at this commit id or branch name instead of the working copy
+    pub revision: Option<String>,
     pub row: Option<usize>,
+    /// Take a random sample of rows, a count ie: `100` or a fraction ie: `0.1`
+    pub sample: Option<String>,
+    /// Seed for randomize and sample, so the same rows come back each time
+    pub seed: Option<u64>,
     pub should_randomize: bool,
     pub should_reverse: bool,
     pub slice: Option<String>,
     pub sort_by: Option<String>,
     pub sql: Option<String>,
+    /// Extra tables the sql can select from, resolved from `path@revision` references
+    pub sql_tables: Option<Vec<SqlTable>>,
+    /// Sample each value of this column in proportion to how often it occurs
+    pub stratify: Option<String>,
     pub text2sql: Option<String>,
     pub tail: Option<usize>,
     pub take: Option<String>,
     pub unique: Option<String>,
+    /// Id of the remote staged row to update in place with `row_data`
+    pub update_row: Option<String>,
     pub vstack: Option<Vec<PathBuf>>,
 }
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -72,6 +97,7 @@ impl DFOpts {
             add_col: None,
             add_row: None,
             aggregate: None,
+            batch: None,
             col_at: None,
             columns: None,
             committed: false,
@@ -80,22 +106,32 @@ impl DFOpts {
             delimiter: None,
             filter: None,
             head: None,
+            in_place: false,
             index: false,
             host: None,
+            lenient: false,
             output: None,
             page_size: None,
             page: None,
+            profile: false,
+            revision: None,
             row: None,
+            row_data: None,
             get_row: None,
+            sample: None,
+            seed: None,
             should_randomize: false,
             should_reverse: false,
             slice: None,
             sort_by: None,
             sql: None,
+            sql_tables: None,
+            stratify: None,
             text2sql: None,
             tail: None,
             take: None,
             unique: None,
+            update_row: None,
             vstack: None,
         }
     }
@@ -150,6 +186,7 @@ impl DFOpts {
     pub fn has_filter_transform(&self) -> bool {
         self.aggregate.is_some()
             || self.filter.is_some()
+            || self.sample.is_some()
             || self.sql.is_some()
             || self.text2sql.is_some()
             || self.unique.is_some()
@@ -167,6 +204,7 @@ impl DFOpts {
             || self.page.is_some()
             || self.row.is_some()
             || self.get_row.is_some()
+            || self.sample.is_some()
             || self.should_randomize
             || self.should_reverse
             || self.sort_by.is_some()
@@ -241,6 +279,10 @@ impl DFOpts {
         }
     }
 
+    pub fn get_sample_size(&self) -> Result<Option<SampleSize>, OxenError> {
+        self.sample.as_deref().map(str::parse).transpose()
+    }
+
     pub fn get_filter(&self) -> Result<Option<DFFilterExp>, OxenError> {
         filter::parse(self.filter.clone())
     }
@@ -316,6 +358,7 @@ impl DFOpts {
         } else {
             None
         };
+        let seed = self.seed.map(|seed| seed.to_string());
         let params = vec![
             ("aggregate", self.aggregate.clone()),
             ("col-at", self.col_at.clone()),
@@ -325,9 +368,12 @@ impl DFOpts {
             ("page", page),
             ("randomize", randomize),
             ("reverse", should_reverse),
+            ("sample", self.sample.clone()),
+            ("seed", seed),
             ("slice", self.slice.clone()),
             ("sort_by", self.sort_by.clone()),
             ("sql", self.sql.clone()),
+            ("stratify", self.stratify.clone()),
             ("take", self.take.clone()),
             ("unique", self.unique.clone()),
         ];
@@ -0,0 +1,12 @@
+use std::path::PathBuf;
+
+/// Options for diffing the whole working directory or staged index against a revision
+#[derive(Clone, Debug, Default)]
+pub struct DiffTreeOpts {
+    /// Commit id or branch name to compare against, defaults to HEAD
+    pub revision: Option<String>,
+    /// Compare the staged index instead of the working directory
+    pub staged: bool,
+    /// Only report changes to files within these paths
+    pub paths: Vec<PathBuf>,
+}
@@ -1,5 +1,84 @@
-#[derive(Clone, Debug)]
+use std::path::PathBuf;
+
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+use crate::error::OxenError;
+
+#[derive(Clone, Debug, Default)]
 pub struct LogOpts {
     pub revision: Option<String>, // commit id or branch name
     pub remote: bool,
+    /// Only list commits that changed files within these paths
+    pub paths: Vec<PathBuf>,
+    /// Only list commits where the author name or email contains this, ignoring case
+    pub author: Option<String>,
+    /// Only list commits made at or after this time
+    pub since: Option<OffsetDateTime>,
+    /// Only list commits made at or before this time
+    pub until: Option<OffsetDateTime>,
+    /// Only list commits where the message contains this, ignoring case
+    pub grep: Option<String>,
+    /// Stop after this many commits
+    pub max_count: Option<usize>,
+    /// Keep listing the history of a single path past the commits that moved it
+    pub follow: bool,
+}
+
+impl LogOpts {
+    /// Parses a `--since` or `--until` value, either RFC 3339 or YYYY-MM-DD for midnight UTC
+    pub fn parse_time(value: &str) -> Result<OffsetDateTime, OxenError> {
+        if let Ok(timestamp) = OffsetDateTime::parse(value, &Rfc3339) {
+            return Ok(timestamp);
+        }
+
+        let format = time::format_description::parse("[year]-[month]-[day]")
+            .map_err(|err| OxenError::basic_str(err.to_string()))?;
+        match time::Date::parse(value, &format) {
+            Ok(date) => Ok(date.midnight().assume_utc()),
+            Err(_) => Err(OxenError::basic_str(format!(
+                "Invalid date '{value}', expected RFC 3339 or YYYY-MM-DD"
+            ))),
+        }
+    }
+
+    /// Whether any of the filters are set, if not the whole history is listed
+    pub fn is_filtered(&self) -> bool {
+        !self.paths.is_empty()
+            || self.author.is_some()
+            || self.since.is_some()
+            || self.until.is_some()
+            || self.grep.is_some()
+            || self.max_count.is_some()
+            || self.follow
+    }
+
+    /// The filters as query params for the `commits/{revision}/history` endpoint
+    pub fn to_http_query_params(&self) -> String {
+        let format_time =
+            |time: &Option<OffsetDateTime>| time.and_then(|time| time.format(&Rfc3339).ok());
+        // Each path is its own `paths` param, so paths can contain commas
+        let mut params: Vec<(&str, Option<String>)> = self
+            .paths
+            .iter()
+            .map(|path| ("paths", Some(path.to_string_lossy().to_string())))
+            .collect();
+        params.extend([
+            ("author", self.author.clone()),
+            ("since", format_time(&self.since)),
+            ("until", format_time(&self.until)),
+            ("grep", self.grep.clone()),
+            ("max_count", self.max_count.map(|n| n.to_string())),
+            ("follow", self.follow.then(|| "true".to_string())),
+        ]);
+
+        params
+            .iter()
+            .filter_map(|(name, val)| {
+                val.as_ref()
+                    .map(|val| format!("{}={}", name, urlencoding::encode(val)))
+            })
+            .collect::<Vec<String>>()
+            .join("&")
+    }
 }
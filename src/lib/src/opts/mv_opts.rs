@@ -0,0 +1,19 @@
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Debug)]
+pub struct MvOpts {
+    pub source: PathBuf,
+    pub destination: PathBuf,
+    pub remote: bool,
+}
+
+impl MvOpts {
+    /// Moves `source` to `destination` in the local repository
+    pub fn from_paths<P: AsRef<Path>, Q: AsRef<Path>>(source: P, destination: Q) -> MvOpts {
+        MvOpts {
+            source: source.as_ref().to_owned(),
+            destination: destination.as_ref().to_owned(),
+            remote: false,
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use std::path::{Path, PathBuf};
+
+use crate::error::OxenError;
+
+#[derive(Clone, Debug)]
+pub struct SplitOpts {
+    pub path: PathBuf,
+    /// Relative size of each split, ie: [0.8, 0.1, 0.1]
+    pub ratios: Vec<f64>,
+    /// Name of each split, defaults to train, val and test
+    pub names: Option<Vec<String>>,
+    /// Keep all the rows with the same value in this column in the same split
+    pub group_by: Option<String>,
+    pub seed: Option<u64>,
+    /// Where to write the splits, defaults to the directory of the input file
+    pub output_dir: Option<PathBuf>,
+}
+
+impl SplitOpts {
+    pub fn from_path_ratios(path: impl AsRef<Path>, ratios: Vec<f64>) -> SplitOpts {
+        SplitOpts {
+            path: path.as_ref().to_path_buf(),
+            ratios,
+            names: None,
+            group_by: None,
+            seed: None,
+            output_dir: None,
+        }
+    }
+
+    /// The name of each split, one per ratio
+    pub fn split_names(&self) -> Result<Vec<String>, OxenError> {
+        let names = match &self.names {
+            Some(names) => names.to_owned(),
+            None => match self.ratios.len() {
+                2 => vec![String::from("train"), String::from("test")],
+                3 => vec![
+                    String::from("train"),
+                    String::from("val"),
+                    String::from("test"),
+                ],
+                n => (0..n).map(|i| format!("split_{i}")).collect(),
+            },
+        };
+
+        if names.len() != self.ratios.len() {
+            return Err(OxenError::basic_str(format!(
+                "Got {} split names for {} ratios",
+                names.len(),
+                self.ratios.len()
+            )));
+        }
+        Ok(names)
+    }
+}
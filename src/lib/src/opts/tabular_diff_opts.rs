@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+
+/// How the values of the target columns are compared when looking for modified rows
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct TabularDiffOpts {
+    /// Numbers that differ by no more than this are treated as equal
+    #[serde(default)]
+    pub numeric_tolerance: Option<f64>,
+    /// Compare strings regardless of upper and lower case
+    #[serde(default)]
+    pub ignore_case: bool,
+    /// Ignore leading and trailing whitespace and treat runs of whitespace as a single space
+    #[serde(default)]
+    pub ignore_whitespace: bool,
+    /// Unchanged lines kept around each change when the files are text, defaults to
+    /// `DEFAULT_CONTEXT_LINES`
+    #[serde(default)]
+    pub context_lines: Option<usize>,
+}
+
+impl TabularDiffOpts {
+    /// True if values only count as equal when they are identical
+    pub fn is_exact(&self) -> bool {
+        self.numeric_tolerance.is_none() && !self.ignore_case && !self.ignore_whitespace
+    }
+
+    /// Applies the case and whitespace options to a string value before comparing it
+    pub fn normalize_str(&self, value: &str) -> String {
+        let value = if self.ignore_whitespace {
+            value.split_whitespace().collect::<Vec<&str>>().join(" ")
+        } else {
+            value.to_string()
+        };
+        if self.ignore_case {
+            value.to_lowercase()
+        } else {
+            value
+        }
+    }
+}
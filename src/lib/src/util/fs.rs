@@ -19,6 +19,7 @@ use crate::constants::CONTENT_IS_VALID;
 use crate::constants::DATA_ARROW_FILE;
 use crate::constants::HISTORY_DIR;
 use crate::constants::VERSION_FILE_NAME;
+use crate::core::df::compressed;
 use crate::error::OxenError;
 use crate::model::entry::commit_entry::Entry;
 use crate::model::metadata::metadata_image::ImgResize;
@@ -769,13 +770,25 @@ pub fn is_tabular(path: &Path) -> bool {
         }
     }
 
-    let exts: HashSet<String> = vec!["csv", "tsv", "parquet", "arrow", "ndjson", "jsonl"]
-        .into_iter()
-        .map(String::from)
-        .collect();
+    // gzip or zstd compressed csv and jsonl, ie: data.csv.gz
+    if compressed::inner_format(path).is_some() {
+        return true;
+    }
+
+    let exts: HashSet<String> = vec![
+        "csv", "tsv", "parquet", "arrow", "ndjson", "jsonl", "avro", "xlsx", "xlsm", "xls", "ods",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect();
     contains_ext(path, &exts)
 }
 
+/// Any .json file, json arrays are also tabular so check `is_tabular` first if it matters
+pub fn is_json(path: &Path) -> bool {
+    has_ext(path, "json")
+}
+
 pub fn is_image(path: &Path) -> bool {
     let exts: HashSet<String> = vec!["jpg", "png"].into_iter().map(String::from).collect();
     contains_ext(path, &exts)
@@ -887,6 +900,26 @@ pub fn file_data_type(path: &Path) -> EntryDataType {
     datatype_from_mimetype(path, mimetype.as_str())
 }
 
+/// The data type of a file from its extension alone, without reading it. None if the contents are
+/// needed to tell, ie: json that may be an array or files without a known extension
+pub fn data_type_from_extension(path: &Path) -> Option<EntryDataType> {
+    if is_json(path) {
+        None
+    } else if is_tabular(path) {
+        Some(EntryDataType::Tabular)
+    } else if is_image(path) {
+        Some(EntryDataType::Image)
+    } else if is_video(path) {
+        Some(EntryDataType::Video)
+    } else if is_audio(path) {
+        Some(EntryDataType::Audio)
+    } else if is_markdown(path) || has_ext(path, "txt") {
+        Some(EntryDataType::Text)
+    } else {
+        None
+    }
+}
+
 pub fn file_extension(path: &Path) -> String {
     match path.extension() {
         Some(extension) => match extension.to_str() {
@@ -1377,6 +1410,20 @@ mod tests {
         })
     }
 
+    #[test]
+    fn detect_file_type_from_extension() {
+        let data_type = |path: &str| util::fs::data_type_from_extension(Path::new(path));
+        assert_eq!(data_type("train/labels.csv"), Some(EntryDataType::Tabular));
+        assert_eq!(
+            data_type("train/labels.csv.gz"),
+            Some(EntryDataType::Tabular)
+        );
+        assert_eq!(data_type("images/1.jpg"), Some(EntryDataType::Image));
+        assert_eq!(data_type("README.md"), Some(EntryDataType::Text));
+        assert_eq!(data_type("records.json"), None);
+        assert_eq!(data_type("test_id"), None);
+    }
+
     #[test]
     fn detect_file_type_json_array() -> Result<(), OxenError> {
         test::run_empty_dir_test(|_| {
@@ -1 +1,95 @@
+//! Pixel level helpers for comparing images
+//!
 
+use std::path::Path;
+
+use image::imageops::FilterType;
+use image::{DynamicImage, Rgba, RgbaImage};
+
+use crate::error::OxenError;
+
+/// Channel differences at or below this are treated as compression noise, not a change
+pub const PIXEL_DIFF_TOLERANCE: u8 = 8;
+
+const MASK_COLOR: Rgba<u8> = Rgba([255, 0, 0, 255]);
+
+pub fn open(path: impl AsRef<Path>) -> Result<DynamicImage, OxenError> {
+    Ok(image::open(path)?)
+}
+
+/// Returns `head` as rgba, resized to the dimensions of `base` if they differ
+/// so the two images can be compared pixel by pixel.
+pub fn rgba_like(base: &DynamicImage, head: &DynamicImage) -> RgbaImage {
+    if base.width() == head.width() && base.height() == head.height() {
+        head.to_rgba8()
+    } else {
+        head.resize_exact(base.width(), base.height(), FilterType::Triangle)
+            .to_rgba8()
+    }
+}
+
+fn pixel_distance(a: &Rgba<u8>, b: &Rgba<u8>) -> u8 {
+    a.0.iter()
+        .zip(b.0.iter())
+        .map(|(x, y)| x.abs_diff(*y))
+        .max()
+        .unwrap_or(0)
+}
+
+/// Counts the pixels that changed by more than `tolerance` in any channel and returns
+/// it with the mean absolute channel difference, scaled to 0.0..=1.0
+pub fn pixel_diff(base: &RgbaImage, head: &RgbaImage, tolerance: u8) -> (usize, f64) {
+    let mut num_changed = 0;
+    let mut total_diff: u64 = 0;
+    for (a, b) in base.pixels().zip(head.pixels()) {
+        if pixel_distance(a, b) > tolerance {
+            num_changed += 1;
+        }
+        total_diff +=
+            a.0.iter()
+                .zip(b.0.iter())
+                .map(|(x, y)| x.abs_diff(*y) as u64)
+                .sum::<u64>();
+    }
+
+    let num_values = (base.width() as u64 * base.height() as u64 * 4).max(1);
+    let mean_abs_diff = total_diff as f64 / (num_values as f64 * 255.0);
+    (num_changed, mean_abs_diff)
+}
+
+/// 64 bit difference hash, similar looking images have hashes with a small hamming distance
+pub fn perceptual_hash(img: &DynamicImage) -> u64 {
+    let small = img.resize_exact(9, 8, FilterType::Triangle).to_luma8();
+    let mut hash: u64 = 0;
+    for y in 0..8 {
+        for x in 0..8 {
+            hash <<= 1;
+            if small.get_pixel(x, y).0[0] < small.get_pixel(x + 1, y).0[0] {
+                hash |= 1;
+            }
+        }
+    }
+    hash
+}
+
+/// 1.0 for images that look the same, 0.0 for completely different ones
+pub fn perceptual_similarity(base: &DynamicImage, head: &DynamicImage) -> f64 {
+    let distance = (perceptual_hash(base) ^ perceptual_hash(head)).count_ones();
+    1.0 - distance as f64 / 64.0
+}
+
+/// Renders the changed pixels in red on top of a faded grayscale copy of `head`
+pub fn diff_mask(base: &RgbaImage, head: &RgbaImage, tolerance: u8) -> RgbaImage {
+    let mut mask = RgbaImage::new(head.width(), head.height());
+    for ((a, b), out) in base.pixels().zip(head.pixels()).zip(mask.pixels_mut()) {
+        *out = if pixel_distance(a, b) > tolerance {
+            MASK_COLOR
+        } else {
+            let [r, g, bl, _] = b.0;
+            let luma = (r as u32 * 299 + g as u32 * 587 + bl as u32 * 114) / 1000;
+            let faded = (128 + luma / 2) as u8;
+            Rgba([faded, faded, faded, 255])
+        };
+    }
+    mask
+}
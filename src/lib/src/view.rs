@@ -4,6 +4,7 @@
 pub mod branch;
 pub mod commit;
 pub mod compare;
+pub mod data_frame_profile;
 pub mod data_type_count;
 pub mod df;
 pub mod diff;
@@ -15,6 +16,7 @@ pub mod http;
 pub mod json_data_frame;
 pub mod json_data_frame_view;
 pub mod merge;
+pub mod merge_request;
 pub mod mime_type_count;
 pub mod namespace;
 pub mod oxen_response;
@@ -28,6 +30,7 @@ pub mod tabular_diff_view;
 pub mod version;
 
 pub use crate::view::compare::CompareEntriesResponse;
+pub use crate::view::data_frame_profile::DataFrameProfileResponse;
 pub use crate::view::data_type_count::DataTypeCount;
 pub use crate::view::file_metadata::{FileMetadata, FileMetadataResponse, FilePathsResponse};
 pub use crate::view::mime_type_count::MimeTypeCount;
@@ -40,7 +43,10 @@ pub use crate::view::json_data_frame::JsonDataFrame;
 pub use crate::view::json_data_frame_view::{
     JsonDataFrameView, JsonDataFrameViewResponse, JsonDataFrameViews,
 };
-pub use crate::view::namespace::{ListNamespacesResponse, NamespaceResponse, NamespaceView};
+pub use crate::view::namespace::{
+    ListNamespacesResponse, NamespaceResponse, NamespaceUsageResponse, NamespaceUsageView,
+    NamespaceView, RepositoryUsageView,
+};
 pub use crate::view::schema::ListSchemaResponse;
 
 pub use crate::view::repository::{
@@ -48,8 +54,9 @@ pub use crate::view::repository::{
 };
 
 pub use crate::view::entry::{
-    CommitEntryVersion, EntryResponse, PaginatedDirEntries, PaginatedDirEntriesResponse,
-    PaginatedEntries, PaginatedEntryVersions, PaginatedEntryVersionsResponse, RemoteEntryResponse,
+    CommitEntryVersion, EntryResponse, MoveFileBody, PaginatedDirEntries,
+    PaginatedDirEntriesResponse, PaginatedEntries, PaginatedEntryVersions,
+    PaginatedEntryVersionsResponse, RemoteEntryResponse,
 };
 
 pub use crate::view::commit::{
@@ -63,6 +70,10 @@ pub use crate::view::branch::{
 
 pub use crate::view::compare::CompareResult;
 
+pub use crate::view::merge_request::{
+    ListMergeRequestsResponse, MergeRequestDiffResponse, MergeRequestResponse,
+};
+
 pub use crate::view::entry_metadata::MetadataEntryResponse;
 
 pub use crate::view::pagination::Pagination;
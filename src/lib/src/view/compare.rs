@@ -6,11 +6,12 @@ use serde::{Deserialize, Serialize};
 use crate::constants::DIFF_STATUS_COL;
 use crate::error::OxenError;
 use crate::message::{MessageLevel, OxenMessage};
-use crate::model::diff::tabular_diff::{TabularDiffDupes, TabularSchemaDiff};
+use crate::model::diff::tabular_diff::{ColumnChangeCount, TabularDiffDupes, TabularSchemaDiff};
 use crate::model::diff::text_diff::TextDiff;
 use crate::model::diff::{AddRemoveModifyCounts, TabularDiff};
 use crate::model::schema::Field;
 use crate::model::{Commit, DiffEntry, Schema};
+use crate::opts::TabularDiffOpts;
 use crate::view::Pagination;
 
 use super::schema::SchemaWithPath;
@@ -130,6 +131,8 @@ pub struct CompareTabularMods {
     pub added_rows: usize,
     pub removed_rows: usize,
     pub modified_rows: usize,
+    #[serde(default)]
+    pub modified_cols: Vec<ColumnChangeCount>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -254,6 +257,9 @@ pub struct TabularCompareBody {
     pub keys: Vec<TabularCompareFieldBody>,
     pub compare: Vec<TabularCompareTargetBody>,
     pub display: Vec<TabularCompareTargetBody>,
+    /// Tolerance, case and whitespace handling when comparing the `compare` columns
+    #[serde(default)]
+    pub options: TabularDiffOpts,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -429,6 +435,7 @@ impl CompareSummary {
                 added_rows,
                 removed_rows,
                 modified_rows,
+                modified_cols: ColumnChangeCount::from_diff_df(df)?,
             },
             schema: Schema::from_polars(&df.schema()),
         })
@@ -578,6 +585,7 @@ impl From<TabularDiff> for CompareTabular {
                     added_rows: diff.summary.modifications.row_counts.added,
                     removed_rows: diff.summary.modifications.row_counts.removed,
                     modified_rows: diff.summary.modifications.row_counts.modified,
+                    modified_cols: diff.summary.modifications.col_counts.clone(),
                 },
                 schema: diff.summary.schemas.diff.clone(),
             }),
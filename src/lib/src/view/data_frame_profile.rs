@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+use crate::model::{Commit, DataFrameProfile};
+use crate::view::entry::ResourceVersion;
+
+use super::StatusMessage;
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct DataFrameProfileResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub profile: DataFrameProfile,
+    pub commit: Commit,
+    pub resource: ResourceVersion,
+}
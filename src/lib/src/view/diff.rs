@@ -3,6 +3,7 @@ use std::path::PathBuf;
 use serde::{Deserialize, Serialize};
 
 use crate::model::diff::diff_entry_status::DiffEntryStatus;
+use crate::model::diff::dir_diff_summary::DataTypeDiffSummary;
 
 use super::StatusMessage;
 #[derive(Deserialize, Serialize, Debug)]
@@ -19,6 +20,9 @@ pub struct DirDiffTreeSummary {
     pub num_subdirs: usize,
     pub can_display: bool,
     pub children: Vec<DirDiffStatus>,
+    /// Changes of all the files below the dir, grouped by data type
+    #[serde(default)]
+    pub data_types: Vec<DataTypeDiffSummary>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
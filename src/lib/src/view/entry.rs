@@ -34,6 +34,12 @@ impl ResourceVersion {
     }
 }
 
+/// Request body for moving a file in the remote staging area
+#[derive(Deserialize, Serialize, Debug)]
+pub struct MoveFileBody {
+    pub destination: PathBuf,
+}
+
 #[derive(Deserialize, Serialize, Debug)]
 pub struct PaginatedEntries {
     #[serde(flatten)]
@@ -8,9 +8,12 @@ pub const MSG_RESOURCE_FOUND: &str = "resource_found";
 pub const MSG_RESOURCE_NOT_FOUND: &str = "resource_not_found";
 pub const MSG_CONTENT_IS_INVALID: &str = "content_is_invalid";
 pub const MSG_BAD_REQUEST: &str = "bad_request";
+pub const MSG_FORBIDDEN: &str = "forbidden";
+pub const MSG_CONFLICT: &str = "conflict";
 pub const MSG_RESOURCE_ALREADY_EXISTS: &str = "resource_already_exists";
 pub const MSG_RESOURCE_IS_PROCESSING: &str = "resource_is_processing";
 pub const MSG_FAILED_PROCESS: &str = "failed_process";
 pub const MSG_INTERNAL_SERVER_ERROR: &str = "internal_server_error";
 pub const MSG_NOT_IMPLEMENTED: &str = "not_implemented";
 pub const MSG_UPDATE_REQUIRED: &str = "update_required";
+pub const MSG_QUOTA_EXCEEDED: &str = "quota_exceeded";
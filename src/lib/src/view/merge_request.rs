@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+use crate::model::merge_request::MergeRequestDiffSummary;
+use crate::model::MergeRequest;
+
+use super::StatusMessage;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MergeRequestResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub merge_request: MergeRequest,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ListMergeRequestsResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub merge_requests: Vec<MergeRequest>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MergeRequestDiffResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub merge_request: MergeRequest,
+    pub diff: MergeRequestDiffSummary,
+}
@@ -15,6 +15,28 @@ pub struct ListNamespacesResponse {
     pub namespaces: Vec<NamespaceView>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RepositoryUsageView {
+    pub name: String,
+    pub usage_bytes: u64,
+    pub quota_bytes: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NamespaceUsageView {
+    pub namespace: String,
+    pub usage_bytes: u64,
+    pub quota_bytes: Option<u64>,
+    pub repositories: Vec<RepositoryUsageView>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct NamespaceUsageResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub usage: NamespaceUsageView,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct NamespaceResponse {
     #[serde(flatten)]
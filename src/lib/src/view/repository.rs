@@ -64,6 +64,11 @@ pub struct DataTypeView {
 pub struct RepositoryStatsView {
     pub data_size: u64,
     pub data_types: Vec<DataTypeView>,
+    /// Bytes of version files stored on the server, counted against the quota
+    #[serde(default)]
+    pub usage_bytes: u64,
+    #[serde(default)]
+    pub quota_bytes: Option<u64>,
 }
 
 impl RepositoryView {
@@ -44,6 +44,33 @@ impl StatusMessageDescription {
             status_description: String::from(description.as_ref()),
         }
     }
+
+    pub fn forbidden(description: impl AsRef<str>) -> StatusMessageDescription {
+        StatusMessageDescription {
+            status: String::from(view::http::STATUS_ERROR),
+            status_message: String::from(view::http::MSG_FORBIDDEN),
+            oxen_version: Some(OXEN_VERSION.to_string()),
+            status_description: String::from(description.as_ref()),
+        }
+    }
+
+    pub fn conflict(description: impl AsRef<str>) -> StatusMessageDescription {
+        StatusMessageDescription {
+            status: String::from(view::http::STATUS_ERROR),
+            status_message: String::from(view::http::MSG_CONFLICT),
+            oxen_version: Some(OXEN_VERSION.to_string()),
+            status_description: String::from(description.as_ref()),
+        }
+    }
+
+    pub fn quota_exceeded(description: impl AsRef<str>) -> StatusMessageDescription {
+        StatusMessageDescription {
+            status: String::from(view::http::STATUS_ERROR),
+            status_message: String::from(view::http::MSG_QUOTA_EXCEEDED),
+            oxen_version: Some(OXEN_VERSION.to_string()),
+            status_description: String::from(description.as_ref()),
+        }
+    }
 }
 
 impl StatusMessage {
@@ -0,0 +1,422 @@
+//! Append-only log of the mutations made through the server
+//!
+//! Every event is a line of JSON in `.oxen/audit_log.jsonl` under the sync dir, recording who made
+//! the change (from their access token), what they did, and to which repository.
+
+use crate::app_data::OxenAppData;
+use crate::auth::access_keys::AccessKeyManager;
+
+use actix_web::http::header::AUTHORIZATION;
+use actix_web::HttpRequest;
+use lazy_static::lazy_static;
+use liboxen::error::OxenError;
+use liboxen::util;
+use liboxen::view::Pagination;
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use time::OffsetDateTime;
+
+pub const AUDIT_LOG_FILENAME: &str = "audit_log.jsonl";
+
+lazy_static! {
+    /// Serializes writers within the process so events never interleave
+    static ref AUDIT_LOG_LOCK: Mutex<()> = Mutex::new(());
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    RepoCreate,
+    RepoDelete,
+    RepoTransfer,
+    BranchUpdate,
+    BranchDelete,
+    BranchLock,
+    BranchUnlock,
+    CommitComplete,
+    StagingCommit,
+    Merge,
+}
+
+impl std::fmt::Display for AuditAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let action = match self {
+            AuditAction::RepoCreate => "repo_create",
+            AuditAction::RepoDelete => "repo_delete",
+            AuditAction::RepoTransfer => "repo_transfer",
+            AuditAction::BranchUpdate => "branch_update",
+            AuditAction::BranchDelete => "branch_delete",
+            AuditAction::BranchLock => "branch_lock",
+            AuditAction::BranchUnlock => "branch_unlock",
+            AuditAction::CommitComplete => "commit_complete",
+            AuditAction::StagingCommit => "staging_commit",
+            AuditAction::Merge => "merge",
+        };
+        write!(f, "{}", action)
+    }
+}
+
+impl std::str::FromStr for AuditAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "repo_create" => Ok(AuditAction::RepoCreate),
+            "repo_delete" => Ok(AuditAction::RepoDelete),
+            "repo_transfer" => Ok(AuditAction::RepoTransfer),
+            "branch_update" => Ok(AuditAction::BranchUpdate),
+            "branch_delete" => Ok(AuditAction::BranchDelete),
+            "branch_lock" => Ok(AuditAction::BranchLock),
+            "branch_unlock" => Ok(AuditAction::BranchUnlock),
+            "commit_complete" => Ok(AuditAction::CommitComplete),
+            "staging_commit" => Ok(AuditAction::StagingCommit),
+            "merge" => Ok(AuditAction::Merge),
+            _ => Err(format!("Could not parse {} as an AuditAction", s)),
+        }
+    }
+}
+
+/// The identity from the access token the request was made with
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct AuditActor {
+    pub id: String,
+    pub name: String,
+    pub email: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct AuditEvent {
+    #[serde(with = "time::serde::rfc3339")]
+    pub timestamp: OffsetDateTime,
+    /// None if the request did not carry a known access token
+    pub actor: Option<AuditActor>,
+    pub action: AuditAction,
+    pub namespace: String,
+    pub repo_name: String,
+    /// What was acted on within the repo, ie. the branch name or the commit id
+    pub target: String,
+    pub commit_id: Option<String>,
+}
+
+impl std::fmt::Display for AuditEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let actor = match &self.actor {
+            Some(actor) => actor.email.as_str(),
+            None => "anonymous",
+        };
+        let timestamp = self
+            .timestamp
+            .format(&time::format_description::well_known::Rfc3339)
+            .unwrap_or_default();
+        write!(
+            f,
+            "{}\t{}\t{}\t{}/{}\t{}",
+            timestamp, actor, self.action, self.namespace, self.repo_name, self.target
+        )?;
+        if let Some(commit_id) = &self.commit_id {
+            write!(f, "\t{}", commit_id)?;
+        }
+        Ok(())
+    }
+}
+
+/// Which events to return when reading the log, all fields are optional
+#[derive(Debug, Clone, Default)]
+pub struct AuditFilter {
+    pub namespace: Option<String>,
+    pub repo_name: Option<String>,
+    pub action: Option<AuditAction>,
+    pub email: Option<String>,
+    pub since: Option<OffsetDateTime>,
+    pub until: Option<OffsetDateTime>,
+}
+
+impl AuditFilter {
+    pub fn matches(&self, event: &AuditEvent) -> bool {
+        if let Some(namespace) = &self.namespace {
+            if namespace != &event.namespace {
+                return false;
+            }
+        }
+
+        if let Some(repo_name) = &self.repo_name {
+            if repo_name != &event.repo_name {
+                return false;
+            }
+        }
+
+        if let Some(action) = &self.action {
+            if action != &event.action {
+                return false;
+            }
+        }
+
+        if let Some(email) = &self.email {
+            match &event.actor {
+                Some(actor) if &actor.email == email => {}
+                _ => return false,
+            }
+        }
+
+        if let Some(since) = self.since {
+            if event.timestamp < since {
+                return false;
+            }
+        }
+
+        if let Some(until) = self.until {
+            if event.timestamp > until {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// Parse a `since` or `until` bound given either as RFC 3339 or as a `YYYY-MM-DD` date (midnight UTC)
+pub fn parse_timestamp(value: &str) -> Result<OffsetDateTime, OxenError> {
+    if let Ok(timestamp) =
+        OffsetDateTime::parse(value, &time::format_description::well_known::Rfc3339)
+    {
+        return Ok(timestamp);
+    }
+
+    let format = time::format_description::parse("[year]-[month]-[day]")
+        .map_err(|err| OxenError::basic_str(err.to_string()))?;
+    match time::Date::parse(value, &format) {
+        Ok(date) => Ok(date.midnight().assume_utc()),
+        Err(_) => Err(OxenError::basic_str(format!(
+            "Invalid timestamp '{value}', expected RFC 3339 or YYYY-MM-DD"
+        ))),
+    }
+}
+
+pub fn log_path(sync_dir: &Path) -> PathBuf {
+    util::fs::oxen_hidden_dir(sync_dir).join(AUDIT_LOG_FILENAME)
+}
+
+/// Look up who the bearer token on the request belongs to
+pub fn actor_from_request(sync_dir: &Path, req: &HttpRequest) -> Option<AuditActor> {
+    let header = req.headers().get(AUTHORIZATION)?.to_str().ok()?;
+    let token = header.strip_prefix("Bearer ")?;
+
+    // There will not be a key db if no users have been added to the server
+    let keygen = AccessKeyManager::new_read_only(sync_dir).ok()?;
+    match keygen.get_claim(token) {
+        Ok(Some(claim)) => Some(AuditActor {
+            id: claim.id,
+            name: claim.name,
+            email: claim.email,
+        }),
+        Ok(None) => None,
+        Err(err) => {
+            log::error!("audit could not read claim for token: {}", err);
+            None
+        }
+    }
+}
+
+/// Append an event to the end of the log
+pub fn append(sync_dir: &Path, event: &AuditEvent) -> Result<(), OxenError> {
+    let path = log_path(sync_dir);
+    if let Some(parent) = path.parent() {
+        if !parent.exists() {
+            util::fs::create_dir_all(parent)?;
+        }
+    }
+
+    let mut line = serde_json::to_string(event)?;
+    line.push('\n');
+
+    let _lock = AUDIT_LOG_LOCK.lock().unwrap();
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+    file.write_all(line.as_bytes())?;
+    Ok(())
+}
+
+/// Build the event for a mutation made by the request, None if there is no app data
+pub fn event_from_request(
+    req: &HttpRequest,
+    action: AuditAction,
+    namespace: &str,
+    repo_name: &str,
+    target: impl AsRef<str>,
+    commit_id: Option<&str>,
+) -> Option<AuditEvent> {
+    let Some(app_data) = req.app_data::<OxenAppData>() else {
+        log::error!("audit could not get app data");
+        return None;
+    };
+
+    Some(AuditEvent {
+        timestamp: OffsetDateTime::now_utc(),
+        actor: actor_from_request(&app_data.path, req),
+        action,
+        namespace: namespace.to_string(),
+        repo_name: repo_name.to_string(),
+        target: target.as_ref().to_string(),
+        commit_id: commit_id.map(String::from),
+    })
+}
+
+/// Record a mutation made by the request. Failing to write the log does not fail the request,
+/// since the change has already been made by the time we get here.
+pub fn record(
+    req: &HttpRequest,
+    action: AuditAction,
+    namespace: &str,
+    repo_name: &str,
+    target: impl AsRef<str>,
+    commit_id: Option<&str>,
+) {
+    let Some(event) = event_from_request(req, action, namespace, repo_name, target, commit_id)
+    else {
+        return;
+    };
+
+    let app_data = req.app_data::<OxenAppData>().unwrap();
+    if let Err(err) = append(&app_data.path, &event) {
+        log::error!("audit::record could not write event {:?}: {}", event, err);
+    }
+}
+
+/// Call `f` on each event in the log that matches the filter, oldest first, reading the log
+/// a line at a time
+pub fn for_each(
+    sync_dir: &Path,
+    filter: &AuditFilter,
+    mut f: impl FnMut(AuditEvent) -> Result<(), OxenError>,
+) -> Result<(), OxenError> {
+    let path = log_path(sync_dir);
+    if !path.exists() {
+        return Ok(());
+    }
+
+    let file = std::fs::File::open(&path)?;
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match serde_json::from_str::<AuditEvent>(&line) {
+            Ok(event) => {
+                if filter.matches(&event) {
+                    f(event)?;
+                }
+            }
+            Err(err) => {
+                log::error!("audit could not parse line {:?}: {}", line, err);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Read all the events in the log that match the filter, oldest first
+pub fn list(sync_dir: &Path, filter: &AuditFilter) -> Result<Vec<AuditEvent>, OxenError> {
+    let mut events: Vec<AuditEvent> = vec![];
+    for_each(sync_dir, filter, |event| {
+        events.push(event);
+        Ok(())
+    })?;
+    Ok(events)
+}
+
+/// Read one page of the events that match the filter, oldest first. Only the events on the
+/// page are kept in memory.
+pub fn page(
+    sync_dir: &Path,
+    filter: &AuditFilter,
+    page_number: usize,
+    page_size: usize,
+) -> Result<(Vec<AuditEvent>, Pagination), OxenError> {
+    let start = page_number.saturating_sub(1) * page_size;
+    let end = start + page_size;
+
+    let mut events: Vec<AuditEvent> = vec![];
+    let mut total_entries = 0;
+    for_each(sync_dir, filter, |event| {
+        if (start..end).contains(&total_entries) {
+            events.push(event);
+        }
+        total_entries += 1;
+        Ok(())
+    })?;
+
+    let total_pages = (total_entries as f64 / page_size as f64).ceil() as usize;
+    Ok((
+        events,
+        Pagination {
+            page_size,
+            page_number,
+            total_pages,
+            total_entries,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::audit::{self, AuditAction, AuditEvent, AuditFilter};
+    use crate::test;
+
+    use liboxen::error::OxenError;
+    use time::OffsetDateTime;
+
+    fn event(action: AuditAction, repo_name: &str, target: &str) -> AuditEvent {
+        AuditEvent {
+            timestamp: OffsetDateTime::now_utc(),
+            actor: None,
+            action,
+            namespace: "Testing-Namespace".to_string(),
+            repo_name: repo_name.to_string(),
+            target: target.to_string(),
+            commit_id: None,
+        }
+    }
+
+    #[test]
+    fn test_audit_append_and_list() -> Result<(), OxenError> {
+        test::run_empty_sync_dir_test(|sync_dir| {
+            assert!(audit::list(sync_dir, &AuditFilter::default())?.is_empty());
+
+            audit::append(
+                sync_dir,
+                &event(AuditAction::RepoCreate, "repo-a", "repo-a"),
+            )?;
+            audit::append(sync_dir, &event(AuditAction::BranchLock, "repo-a", "main"))?;
+            audit::append(sync_dir, &event(AuditAction::BranchLock, "repo-b", "main"))?;
+
+            let events = audit::list(sync_dir, &AuditFilter::default())?;
+            assert_eq!(events.len(), 3);
+            assert_eq!(events[0].action, AuditAction::RepoCreate);
+
+            let filter = AuditFilter {
+                repo_name: Some("repo-a".to_string()),
+                ..AuditFilter::default()
+            };
+            assert_eq!(audit::list(sync_dir, &filter)?.len(), 2);
+
+            let filter = AuditFilter {
+                action: Some(AuditAction::BranchLock),
+                ..AuditFilter::default()
+            };
+            assert_eq!(audit::list(sync_dir, &filter)?.len(), 2);
+
+            // Nothing was made by a known user
+            let filter = AuditFilter {
+                email: Some("ox@oxen.ai".to_string()),
+                ..AuditFilter::default()
+            };
+            assert!(audit::list(sync_dir, &filter)?.is_empty());
+
+            Ok(())
+        })
+    }
+}
@@ -1,2 +1,3 @@
 pub mod access_keys;
+pub mod admins;
 pub mod validator;
@@ -12,9 +12,9 @@ pub const SECRET_KEY_FILENAME: &str = "SECRET_KEY_BASE";
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct JWTClaim {
-    id: String,
-    name: String,
-    email: String,
+    pub id: String,
+    pub name: String,
+    pub email: String,
 }
 
 pub struct AccessKeyManager {
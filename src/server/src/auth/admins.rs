@@ -0,0 +1,64 @@
+//! Users that can read server wide data, such as the full audit log
+//!
+//! Admins are listed by email in `.oxen/admins.toml` in the sync dir, and are added with
+//! `oxen-server add-user --admin`.
+
+use crate::audit;
+
+use actix_web::HttpRequest;
+use liboxen::error::OxenError;
+use liboxen::util;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+pub const ADMINS_FILENAME: &str = "admins.toml";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct AdminConfig {
+    #[serde(default)]
+    pub emails: BTreeSet<String>,
+}
+
+impl AdminConfig {
+    pub fn path(sync_dir: &Path) -> PathBuf {
+        util::fs::oxen_hidden_dir(sync_dir).join(ADMINS_FILENAME)
+    }
+
+    pub fn load(sync_dir: &Path) -> Result<AdminConfig, OxenError> {
+        let path = AdminConfig::path(sync_dir);
+        if !path.exists() {
+            return Ok(AdminConfig::default());
+        }
+        let contents = util::fs::read_from_path(&path)?;
+        toml::from_str(&contents).map_err(|err| {
+            OxenError::basic_str(format!("Could not parse admins {:?}: {}", path, err))
+        })
+    }
+
+    pub fn save(&self, sync_dir: &Path) -> Result<(), OxenError> {
+        let path = AdminConfig::path(sync_dir);
+        if let Some(parent) = path.parent() {
+            util::fs::create_dir_all(parent)?;
+        }
+        let contents =
+            toml::to_string(self).map_err(|err| OxenError::basic_str(err.to_string()))?;
+        util::fs::write_to_path(&path, contents)?;
+        Ok(())
+    }
+}
+
+/// Whether the bearer token on the request belongs to an admin
+pub fn is_admin(sync_dir: &Path, req: &HttpRequest) -> bool {
+    let Some(actor) = audit::actor_from_request(sync_dir, req) else {
+        return false;
+    };
+
+    match AdminConfig::load(sync_dir) {
+        Ok(config) => config.emails.contains(&actor.email),
+        Err(err) => {
+            log::error!("Could not load admins: {}", err);
+            false
+        }
+    }
+}
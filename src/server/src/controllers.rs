@@ -1,4 +1,5 @@
 pub mod action;
+pub mod audit;
 pub mod branches;
 pub mod commits;
 pub mod data_frames;
@@ -7,12 +8,15 @@ pub mod dir;
 pub mod entries;
 pub mod file;
 pub mod health;
+pub mod merge_requests;
 pub mod merger;
 pub mod metadata;
+pub mod metrics;
 pub mod migrations;
 pub mod namespaces;
 pub mod not_found;
 pub mod repositories;
 pub mod schemas;
 pub mod stager;
+pub mod tasks;
 pub mod version;
@@ -0,0 +1,161 @@
+use crate::audit;
+use crate::auth::admins;
+use crate::errors::OxenHttpError;
+use crate::helpers::get_repo;
+use crate::params::{app_data, path_param, AuditQuery};
+use crate::view::AuditLogResponse;
+
+use actix_web::{web, HttpRequest, HttpResponse};
+use liboxen::constants;
+use liboxen::view::StatusMessage;
+
+/// Every event on the server, filtered by the query params. Only available to admins.
+pub async fn index(
+    req: HttpRequest,
+    query: web::Query<AuditQuery>,
+) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    if !admins::is_admin(&app_data.path, &req) {
+        return Err(OxenHttpError::Forbidden(
+            "The server audit log is only available to admins".into(),
+        ));
+    }
+    let filter = query.to_filter()?;
+
+    let page = query.page.unwrap_or(constants::DEFAULT_PAGE_NUM);
+    let page_size = query.page_size.unwrap_or(constants::DEFAULT_PAGE_SIZE);
+    let (events, pagination) = audit::page(&app_data.path, &filter, page, page_size)?;
+
+    Ok(HttpResponse::Ok().json(AuditLogResponse {
+        status: StatusMessage::resource_found(),
+        events,
+        pagination,
+    }))
+}
+
+/// Events for a single repository
+pub async fn show(
+    req: HttpRequest,
+    query: web::Query<AuditQuery>,
+) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    // Events for deleted repos are still available from the admin endpoint
+    get_repo(&app_data.path, &namespace, &name)?;
+
+    let mut filter = query.to_filter()?;
+    filter.namespace = Some(namespace);
+    filter.repo_name = Some(name);
+
+    let page = query.page.unwrap_or(constants::DEFAULT_PAGE_NUM);
+    let page_size = query.page_size.unwrap_or(constants::DEFAULT_PAGE_SIZE);
+    let (events, pagination) = audit::page(&app_data.path, &filter, page, page_size)?;
+
+    Ok(HttpResponse::Ok().json(AuditLogResponse {
+        status: StatusMessage::resource_found(),
+        events,
+        pagination,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::body::to_bytes;
+    use actix_web::{http, web};
+
+    use liboxen::error::OxenError;
+    use liboxen::model::User;
+    use liboxen::util;
+
+    use crate::audit::AuditAction;
+    use crate::auth::access_keys::AccessKeyManager;
+    use crate::auth::admins::AdminConfig;
+    use crate::controllers;
+    use crate::errors::OxenHttpError;
+    use crate::params::AuditQuery;
+    use crate::test;
+    use crate::view::AuditLogResponse;
+
+    #[actix_web::test]
+    async fn test_controllers_audit_records_branch_lock() -> Result<(), OxenError> {
+        let sync_dir = test::get_sync_dir()?;
+        let queue = test::init_queue();
+        let namespace = "Testing-Namespace";
+        let name = "Testing-Audit";
+        test::create_local_repo(&sync_dir, namespace, name)?;
+        test::create_local_repo(&sync_dir, namespace, "Testing-Audit-Other")?;
+
+        let uri = format!("/api/repos/{namespace}/{name}/branches/main/lock");
+        let req = test::repo_request_with_param(
+            &sync_dir,
+            queue.clone(),
+            &uri,
+            namespace,
+            name,
+            "branch_name",
+            "main",
+        );
+        let resp = controllers::branches::lock(req).await.unwrap();
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let uri = format!("/api/repos/{namespace}/Testing-Audit-Other/branches/main/lock");
+        let req = test::repo_request_with_param(
+            &sync_dir,
+            queue.clone(),
+            &uri,
+            namespace,
+            "Testing-Audit-Other",
+            "branch_name",
+            "main",
+        );
+        controllers::branches::lock(req).await.unwrap();
+
+        // Only the events for this repo
+        let uri = format!("/api/repos/{namespace}/{name}/audit");
+        let req = test::repo_request(&sync_dir, queue.clone(), &uri, namespace, name);
+        let query: web::Query<AuditQuery> = web::Query::from_query("").unwrap();
+        let resp = controllers::audit::show(req, query).await.unwrap();
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        let body = to_bytes(resp.into_body()).await.unwrap();
+        let text = std::str::from_utf8(&body).unwrap();
+        let response: AuditLogResponse = serde_json::from_str(text)?;
+        assert_eq!(response.events.len(), 1);
+        assert_eq!(response.events[0].action, AuditAction::BranchLock);
+        assert_eq!(response.events[0].target, "main");
+
+        // Every event on the server is only for admins
+        let req = test::request(&sync_dir, queue.clone(), "/api/audit");
+        let query: web::Query<AuditQuery> = web::Query::from_query("").unwrap();
+        let resp = controllers::audit::index(req, query).await;
+        assert!(matches!(resp, Err(OxenHttpError::Forbidden(_))));
+
+        let token = {
+            let keygen = AccessKeyManager::new(&sync_dir)?;
+            let user = User {
+                name: "Ox".to_string(),
+                email: "ox@oxen.ai".to_string(),
+            };
+            let (_, token) = keygen.create(&user)?;
+            token
+        };
+        let mut admins = AdminConfig::default();
+        admins.emails.insert("ox@oxen.ai".to_string());
+        admins.save(&sync_dir)?;
+
+        let req = test::request_with_token(&sync_dir, queue, "/api/audit", &token);
+        let query: web::Query<AuditQuery> =
+            web::Query::from_query("action=branch_lock&page=1&page_size=10").unwrap();
+        let resp = controllers::audit::index(req, query).await.unwrap();
+        let body = to_bytes(resp.into_body()).await.unwrap();
+        let text = std::str::from_utf8(&body).unwrap();
+        let response: AuditLogResponse = serde_json::from_str(text)?;
+        assert_eq!(response.events.len(), 2);
+        assert_eq!(response.pagination.total_entries, 2);
+
+        // cleanup
+        util::fs::remove_dir_all(sync_dir)?;
+
+        Ok(())
+    }
+}
@@ -1,5 +1,6 @@
 use std::path::PathBuf;
 
+use crate::audit::{self, AuditAction};
 use crate::errors::OxenHttpError;
 use crate::helpers::get_repo;
 use crate::params::{app_data, path_param, PageNumQuery};
@@ -87,12 +88,20 @@ pub async fn delete(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHtt
     let namespace = path_param(&req, "namespace")?;
     let name = path_param(&req, "repo_name")?;
     let branch_name = path_param(&req, "branch_name")?;
-    let repository = get_repo(&app_data.path, namespace, name)?;
+    let repository = get_repo(&app_data.path, &namespace, &name)?;
 
     let branch = api::local::branches::get_by_name(&repository, &branch_name)?
         .ok_or(OxenError::remote_branch_not_found(&branch_name))?;
 
     api::local::branches::force_delete(&repository, &branch.name)?;
+    audit::record(
+        &req,
+        AuditAction::BranchDelete,
+        &namespace,
+        &name,
+        &branch.name,
+        Some(&branch.commit_id),
+    );
     Ok(HttpResponse::Ok().json(BranchResponse {
         status: StatusMessage::resource_deleted(),
         branch,
@@ -107,12 +116,20 @@ pub async fn update(
     let namespace = path_param(&req, "namespace")?;
     let name = path_param(&req, "repo_name")?;
     let branch_name = path_param(&req, "branch_name")?;
-    let repository = get_repo(&app_data.path, namespace, name)?;
+    let repository = get_repo(&app_data.path, &namespace, &name)?;
 
     let data: Result<BranchUpdate, serde_json::Error> = serde_json::from_str(&body);
     let data = data.map_err(|err| OxenHttpError::BadRequest(format!("{:?}", err).into()))?;
 
     let branch = api::local::branches::update(&repository, &branch_name, &data.commit_id)?;
+    audit::record(
+        &req,
+        AuditAction::BranchUpdate,
+        &namespace,
+        &name,
+        &branch_name,
+        Some(&data.commit_id),
+    );
 
     Ok(HttpResponse::Ok().json(BranchResponse {
         status: StatusMessage::resource_updated(),
@@ -190,14 +207,24 @@ pub async fn lock(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpE
     let namespace = path_param(&req, "namespace")?;
     let name = path_param(&req, "repo_name")?;
     let branch_name = path_param(&req, "branch_name")?;
-    let repository = get_repo(&app_data.path, namespace, name)?;
+    let repository = get_repo(&app_data.path, &namespace, &name)?;
 
     match api::local::branches::lock(&repository, &branch_name) {
-        Ok(_) => Ok(HttpResponse::Ok().json(BranchLockResponse {
-            status: StatusMessage::resource_updated(),
-            branch_name: branch_name.clone(),
-            is_locked: true,
-        })),
+        Ok(_) => {
+            audit::record(
+                &req,
+                AuditAction::BranchLock,
+                &namespace,
+                &name,
+                &branch_name,
+                None,
+            );
+            Ok(HttpResponse::Ok().json(BranchLockResponse {
+                status: StatusMessage::resource_updated(),
+                branch_name: branch_name.clone(),
+                is_locked: true,
+            }))
+        }
         Err(e) => {
             // Log the error for debugging
             log::error!("Failed to lock branch: {}", e);
@@ -216,9 +243,17 @@ pub async fn unlock(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHtt
     let namespace = path_param(&req, "namespace")?;
     let name = path_param(&req, "repo_name")?;
     let branch_name = path_param(&req, "branch_name")?;
-    let repository = get_repo(&app_data.path, namespace, name)?;
+    let repository = get_repo(&app_data.path, &namespace, &name)?;
 
     api::local::branches::unlock(&repository, &branch_name)?;
+    audit::record(
+        &req,
+        AuditAction::BranchUnlock,
+        &namespace,
+        &name,
+        &branch_name,
+        None,
+    );
 
     Ok(HttpResponse::Ok().json(BranchLockResponse {
         status: StatusMessage::resource_updated(),
@@ -19,6 +19,7 @@ use liboxen::error::OxenError;
 use liboxen::model::commit::CommitWithBranchName;
 use liboxen::model::RepoNew;
 use liboxen::model::{Commit, LocalRepository};
+use liboxen::opts::LogOpts;
 use liboxen::util;
 use liboxen::view::branch::BranchName;
 use liboxen::view::commit::CommitSyncStatusResponse;
@@ -34,13 +35,16 @@ use liboxen::view::{CommitResponse, IsValidStatusMessage, ListCommitResponse, St
 use os_path::OsPath;
 
 use crate::app_data::OxenAppData;
+use crate::audit::{self, AuditAction};
 use crate::errors::OxenHttpError;
 use crate::helpers::get_repo;
-use crate::params::PageNumQuery;
 use crate::params::{app_data, path_param};
+use crate::params::{LogQuery, PageNumQuery};
+use crate::quotas;
 use crate::tasks;
 use crate::tasks::post_push_complete::PostPushComplete;
 
+use actix_web::http::header::CONTENT_LENGTH;
 use actix_web::{web, Error, HttpRequest, HttpResponse};
 use bytesize::ByteSize;
 use flate2::read::GzDecoder;
@@ -90,7 +94,7 @@ pub async fn index(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttp
 // List history for a branch or commit
 pub async fn commit_history(
     req: HttpRequest,
-    query: web::Query<PageNumQuery>,
+    query: web::Query<LogQuery>,
 ) -> actix_web::Result<HttpResponse, OxenHttpError> {
     let app_data = app_data(&req)?;
     let namespace: Option<&str> = req.match_info().get("namespace");
@@ -104,7 +108,9 @@ pub async fn commit_history(
         (namespace, repo_name, commit_or_branch)
     {
         let repo_dir = app_data.path.join(namespace).join(repo_name);
-        match p_index_commit_or_branch_history(&repo_dir, commit_or_branch, page, page_size) {
+        let opts = query.to_log_opts(commit_or_branch, req.query_string())?;
+        match p_index_commit_or_branch_history(&repo_dir, commit_or_branch, &opts, page, page_size)
+        {
             Ok(response) => Ok(HttpResponse::Ok().json(response)),
             Err(err) => {
                 let msg = format!("{err}");
@@ -221,8 +227,9 @@ pub async fn latest_synced(req: HttpRequest) -> actix_web::Result<HttpResponse,
                 match content_validator::is_valid(&repository, &commit) {
                     Ok(true) => {
                         // Iterating backwards, so this is the latest synced commit
-                        // For this to work, we need to maintain relative order of commits in redis queue push // one worker, for now.
-                        // TODO: If we want to move to multiple workers or break this order,
+                        // For this to work, we need to maintain relative order of commits in the queue,
+                        // a task is only claimed once every earlier task for its repo has finished.
+                        // TODO: If we want to break this order,
                         // we can make this more robust (but slower) by checking the full commit history
                         log::debug!("latest_synced commit is valid: {}", commit);
                         latest_synced = Some(commit);
@@ -420,14 +427,25 @@ fn p_index(repo_dir: &Path) -> Result<ListCommitResponse, OxenError> {
 fn p_index_commit_or_branch_history(
     repo_dir: &Path,
     commit_or_branch: &str,
+    opts: &LogOpts,
     page_num: usize,
     page_size: usize,
 ) -> Result<PaginatedCommits, OxenError> {
     let repo = LocalRepository::new(repo_dir)?;
-    let commits =
-        api::local::commits::list_from_paginated(&repo, commit_or_branch, page_num, page_size)?;
-    // log::debug!("controllers::commits: : {:#?}", commits);
-    Ok(commits)
+    if !opts.is_filtered() {
+        let commits =
+            api::local::commits::list_from_paginated(&repo, commit_or_branch, page_num, page_size)?;
+        // log::debug!("controllers::commits: : {:#?}", commits);
+        return Ok(commits);
+    }
+
+    let commits = api::local::commits::list_from_with_opts(&repo, commit_or_branch, opts)?;
+    let (commits, pagination) = util::paginate(commits, page_num, page_size);
+    Ok(PaginatedCommits {
+        status: StatusMessage::resource_found(),
+        commits,
+        pagination,
+    })
 }
 
 /// Download the database that holds all the commits and their parents
@@ -661,7 +679,7 @@ pub async fn upload_chunk(
     let namespace = path_param(&req, "namespace")?;
     let name = path_param(&req, "repo_name")?;
     let commit_id = path_param(&req, "commit_id")?;
-    let repo = get_repo(&app_data.path, namespace, name)?;
+    let repo = get_repo(&app_data.path, &namespace, &name)?;
 
     let commit_reader = CommitReader::new(&repo)?;
 
@@ -703,6 +721,23 @@ pub async fn upload_chunk(
         bytes.extend_from_slice(&item.unwrap());
     }
 
+    // Check the quota on every chunk against what has actually been received so far,
+    // so a client cannot get around it by under reporting the total size
+    let received = uploaded_chunks_size(&tmp_dir, &chunk_file) + bytes.len() as u64;
+    let declared = u64::try_from(size).unwrap_or(u64::MAX);
+    if let Err(err) = quotas::check(
+        &app_data.path,
+        &repo,
+        &namespace,
+        &name,
+        received.max(declared),
+    ) {
+        if let Err(err) = util::fs::remove_dir_all(&tmp_dir) {
+            log::error!("upload_chunk could not remove {:?}: {:?}", tmp_dir, err);
+        }
+        return Err(err);
+    }
+
     // Write to tmp file
     log::debug!("upload_chunk writing file {:?}", chunk_file);
     match OpenOptions::new()
@@ -718,6 +753,7 @@ pub async fn upload_chunk(
                     log::debug!("upload_chunk successfully wrote chunk {:?}", chunk_file);
 
                     check_if_upload_complete_and_unpack(
+                        &repo,
                         hidden_dir,
                         tmp_dir,
                         total_chunks,
@@ -753,7 +789,19 @@ pub async fn upload_chunk(
     }
 }
 
+/// Total size of the chunks already written for an upload, not counting `skip`
+/// which is about to be overwritten
+fn uploaded_chunks_size(tmp_dir: &Path, skip: &Path) -> u64 {
+    util::fs::list_files_in_dir(tmp_dir)
+        .iter()
+        .filter(|path| path.as_path() != skip)
+        .filter_map(|path| path.metadata().ok())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
 fn check_if_upload_complete_and_unpack(
+    repo: &LocalRepository,
     hidden_dir: PathBuf,
     tmp_dir: PathBuf,
     total_chunks: usize,
@@ -808,11 +856,12 @@ fn check_if_upload_complete_and_unpack(
         // Combine into actual file data
         if is_compressed {
             match unpack_compressed_data(&files, &hidden_dir) {
-                Ok(_) => {
+                Ok(num_bytes) => {
                     log::debug!(
                         "check_if_upload_complete_and_unpack unpacked {} files successfully",
                         files.len()
                     );
+                    add_usage(repo, num_bytes);
                 }
                 Err(err) => {
                     log::error!(
@@ -825,8 +874,9 @@ fn check_if_upload_complete_and_unpack(
             match filename {
                 Some(filename) => {
                     match unpack_to_file(&files, &hidden_dir, &filename) {
-                        Ok(_) => {
+                        Ok(num_bytes) => {
                             log::debug!("check_if_upload_complete_and_unpack unpacked {} files successfully", files.len());
+                            add_usage(repo, num_bytes);
                         }
                         Err(err) => {
                             log::error!("check_if_upload_complete_and_unpack could not unpack compressed data {:?}", err);
@@ -908,12 +958,21 @@ pub async fn can_push(
     let namespace = path_param(&req, "namespace")?;
     let name = path_param(&req, "repo_name")?;
     let client_head_id = path_param(&req, "commit_id")?;
-    let repo = get_repo(&app_data.path, namespace, name)?;
+    let repo = get_repo(&app_data.path, &namespace, &name)?;
     let server_head_id = query.get("remote_head").unwrap();
     let lca_id = query.get("lca").unwrap();
 
     log::debug!("in the new_can_push endpoint");
 
+    // Reject the push before any data is sent if it would go over quota
+    let push_size = match query.get("size") {
+        Some(size) => size
+            .parse::<u64>()
+            .map_err(|_| OxenHttpError::BadRequest(format!("Invalid size param: {size}").into()))?,
+        None => 0,
+    };
+    quotas::check(&app_data.path, &repo, &namespace, &name, push_size)?;
+
     // Ensuring these commits exist on server
     let _server_head_commit = api::local::commits::get_by_id(&repo, server_head_id)?.ok_or(
         OxenError::revision_not_found(server_head_id.to_owned().into()),
@@ -965,7 +1024,8 @@ pub async fn root_commit(req: HttpRequest) -> Result<HttpResponse, OxenHttpError
     }))
 }
 
-fn unpack_compressed_data(files: &[PathBuf], hidden_dir: &Path) -> Result<(), OxenError> {
+/// Returns the number of bytes of version files that were unpacked
+fn unpack_compressed_data(files: &[PathBuf], hidden_dir: &Path) -> Result<u64, OxenError> {
     let mut buffer: Vec<u8> = Vec::new();
     for file in files.iter() {
         log::debug!("Reading file bytes {:?}", file);
@@ -977,12 +1037,13 @@ fn unpack_compressed_data(files: &[PathBuf], hidden_dir: &Path) -> Result<(), Ox
 
     // Unpack tarball to our hidden dir
     let mut archive = Archive::new(GzDecoder::new(&buffer[..]));
-    unpack_entry_tarball(hidden_dir, &mut archive);
+    let num_bytes = unpack_entry_tarball(hidden_dir, &mut archive);
 
-    Ok(())
+    Ok(num_bytes)
 }
 
-fn unpack_to_file(files: &[PathBuf], hidden_dir: &Path, filename: &str) -> Result<(), OxenError> {
+/// Returns the number of bytes written to the version file
+fn unpack_to_file(files: &[PathBuf], hidden_dir: &Path, filename: &str) -> Result<u64, OxenError> {
     // Append each buffer to the end of the large file
     // TODO: better error handling...
     log::debug!("Got filename {}", filename);
@@ -1004,6 +1065,7 @@ fn unpack_to_file(files: &[PathBuf], hidden_dir: &Path, filename: &str) -> Resul
     let mut outf = std::fs::File::create(&full_path)
         .map_err(|e| OxenError::file_create_error(&full_path, e))?;
 
+    let mut num_bytes: u64 = 0;
     for file in files.iter() {
         log::debug!("Reading file bytes {:?}", file);
         let mut buffer: Vec<u8> = Vec::new();
@@ -1018,13 +1080,21 @@ fn unpack_to_file(files: &[PathBuf], hidden_dir: &Path, filename: &str) -> Resul
         match outf.write_all(&buffer) {
             Ok(_) => {
                 log::debug!("Unpack successful! {:?}", full_path);
+                num_bytes += buffer.len() as u64;
             }
             Err(err) => {
                 log::error!("Could not write all data to disk {:?}", err);
             }
         }
     }
-    Ok(())
+    Ok(num_bytes)
+}
+
+/// Count pushed version files towards the repository's usage
+fn add_usage(repo: &LocalRepository, num_bytes: u64) {
+    if let Err(err) = api::local::usage::add(repo, num_bytes) {
+        log::error!("Could not add {} bytes to usage: {}", num_bytes, err);
+    }
 }
 
 /// Controller to upload the commit database
@@ -1037,7 +1107,17 @@ pub async fn upload(
     let namespace = path_param(&req, "namespace")?;
     let name = path_param(&req, "repo_name")?;
     let commit_id = path_param(&req, "commit_id")?;
-    let repo = get_repo(&app_data.path, namespace, name)?;
+    let repo = get_repo(&app_data.path, &namespace, &name)?;
+
+    // Check the quota before reading the body
+    let content_length = req
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|len| len.to_str().ok())
+        .and_then(|len| len.parse::<u64>().ok());
+    if let Some(content_length) = content_length {
+        quotas::check(&app_data.path, &repo, &namespace, &name, content_length)?;
+    }
 
     // Match commit as either the provided commit id if it exists, or the head commit of the repo otherwise.
 
@@ -1062,13 +1142,19 @@ pub async fn upload(
         ByteSize::b(total_size)
     );
 
+    // Streamed bodies do not have a content length, check before unpacking instead
+    if content_length.is_none() {
+        quotas::check(&app_data.path, &repo, &namespace, &name, total_size)?;
+    }
+
     // Unpack in background thread because could take awhile
     // std::thread::spawn(move || {
     // Get tar.gz bytes for history/COMMIT_ID data
     log::debug!("Decompressing {} bytes to {:?}", bytes.len(), hidden_dir);
     // Unpack tarball to our hidden dir
     let mut archive = Archive::new(GzDecoder::new(&bytes[..]));
-    unpack_entry_tarball(&hidden_dir, &mut archive);
+    let num_bytes = unpack_entry_tarball(&hidden_dir, &mut archive);
+    add_usage(&repo, num_bytes);
     // });
 
     Ok(HttpResponse::Ok().json(CommitResponse {
@@ -1090,28 +1176,30 @@ pub async fn complete(req: HttpRequest) -> Result<HttpResponse, Error> {
         Ok(Some(repo)) => {
             match api::local::commits::get_by_id(&repo, commit_id) {
                 Ok(Some(commit)) => {
-                    // Kick off processing in background thread because could take awhile
-                    std::thread::spawn(move || {
-                        log::debug!("Processing commit {:?} on repo {:?}", commit, repo.path);
-                        let force = false;
-                        match commit_cacher::run_all(&repo, &commit, force) {
-                            Ok(_) => {
-                                log::debug!(
-                                    "Success processing commit {:?} on repo {:?}",
-                                    commit,
-                                    repo.path
-                                );
-                            }
-                            Err(err) => {
-                                log::error!(
-                                    "Could not process commit {:?} on repo {:?}: {}",
-                                    commit,
-                                    repo.path,
-                                    err
-                                );
-                            }
-                        }
-                    });
+                    if let Err(err) = api::local::schemas::check_contracts(&repo, &commit) {
+                        log::debug!("Commit [{}] breaks a schema contract: {}", commit_id, err);
+                        return Ok(
+                            HttpResponse::BadRequest().json(StatusMessage::error(err.to_string()))
+                        );
+                    }
+
+                    audit::record(
+                        &req,
+                        AuditAction::CommitComplete,
+                        namespace,
+                        repo_name,
+                        &commit.id,
+                        Some(&commit.id),
+                    );
+
+                    // Queue up processing because it could take awhile
+                    let mut queue = app_data.queue.clone();
+                    let task = PostPushComplete { commit, repo };
+                    if let Err(err) = queue.push(tasks::Task::PostPushComplete(task)) {
+                        log::error!("Could not queue commit [{}]: {}", commit_id, err);
+                        return Ok(HttpResponse::InternalServerError()
+                            .json(StatusMessage::internal_server_error()));
+                    }
 
                     Ok(HttpResponse::Ok().json(StatusMessage::resource_created()))
                 }
@@ -1180,25 +1268,43 @@ pub async fn complete_bulk(req: HttpRequest, body: String) -> Result<HttpRespons
                 commit.clone()
             );
 
-            queue.push(tasks::Task::PostPushComplete(task))
+            queue.push(tasks::Task::PostPushComplete(task))?;
         }
     }
 
     let commit_reader = CommitReader::new(&repo)?;
+    let commits = commits
+        .into_iter()
+        .map(|req_commit| {
+            commit_reader
+                .get_commit_by_id(&req_commit.id)?
+                .ok_or(OxenError::revision_not_found(req_commit.id.into()))
+        })
+        .collect::<Result<Vec<Commit>, OxenError>>()?;
+
+    // Check the schema contracts of every pushed commit before processing any of them, an error
+    // here stops the client from moving the branch onto the commits
+    for commit in commits.iter() {
+        api::local::schemas::check_contracts(&repo, commit)?;
+    }
 
-    for req_commit in commits {
-        let commit_id = req_commit.id;
-        let commit = commit_reader
-            .get_commit_by_id(&commit_id)?
-            .ok_or(OxenError::revision_not_found(commit_id.clone().into()))?;
+    for commit in commits {
+        audit::record(
+            &req,
+            AuditAction::CommitComplete,
+            namespace,
+            repo_name,
+            &commit.id,
+            Some(&commit.id),
+        );
 
         // Append a task to the queue
         let task = PostPushComplete {
-            commit: commit.clone(),
+            commit,
             repo: repo.clone(),
         };
 
-        queue.push(tasks::Task::PostPushComplete(task))
+        queue.push(tasks::Task::PostPushComplete(task))?;
     }
     Ok(HttpResponse::Ok().json(StatusMessage::resource_created()))
 }
@@ -1244,10 +1350,12 @@ fn unpack_tree_tarball(tmp_dir: &Path, archive: &mut Archive<GzDecoder<&[u8]>>)
     }
 }
 
-fn unpack_entry_tarball(hidden_dir: &Path, archive: &mut Archive<GzDecoder<&[u8]>>) {
+/// Returns the number of bytes of version files that were unpacked
+fn unpack_entry_tarball(hidden_dir: &Path, archive: &mut Archive<GzDecoder<&[u8]>>) -> u64 {
     // Unpack and compute HASH and save next to the file to speed up computation later
     log::debug!("unpack_entry_tarball hidden_dir {:?}", hidden_dir);
 
+    let mut num_bytes: u64 = 0;
     match archive.entries() {
         Ok(entries) => {
             for file in entries {
@@ -1277,6 +1385,7 @@ fn unpack_entry_tarball(hidden_dir: &Path, archive: &mut Archive<GzDecoder<&[u8]
                         }
                         file.unpack(&version_path).unwrap();
                         log::debug!("unpack_entry_tarball unpacked! {:?}", version_path);
+                        num_bytes += file.size();
 
                         let hash_dir = version_path.parent().unwrap();
                         let hash_file = hash_dir.join(HASH_FILE);
@@ -1318,12 +1427,15 @@ fn unpack_entry_tarball(hidden_dir: &Path, archive: &mut Archive<GzDecoder<&[u8]
     }
 
     log::debug!("Done decompressing.");
+    num_bytes
 }
 
 #[cfg(test)]
 mod tests {
 
     use actix_web::body::to_bytes;
+    use actix_web::http::header::CONTENT_LENGTH;
+    use actix_web::http::StatusCode;
     use actix_web::{web, App};
     use flate2::write::GzEncoder;
     use flate2::Compression;
@@ -1335,11 +1447,13 @@ mod tests {
     use liboxen::constants::OXEN_HIDDEN_DIR;
     use liboxen::error::OxenError;
     use liboxen::util;
-    use liboxen::view::{CommitResponse, ListCommitResponse};
+    use liboxen::view::http::MSG_QUOTA_EXCEEDED;
+    use liboxen::view::{CommitResponse, ListCommitResponse, StatusMessageDescription};
 
     use crate::app_data::OxenAppData;
     use crate::controllers;
-    use crate::params::PageNumQuery;
+    use crate::params::LogQuery;
+    use crate::quotas::QuotaConfig;
     use crate::test::{self, init_test_env};
 
     #[actix_web::test]
@@ -1430,8 +1544,7 @@ mod tests {
             branch_name,
         );
 
-        let query: web::Query<PageNumQuery> =
-            web::Query::from_query("page=1&page_size=10").unwrap();
+        let query: web::Query<LogQuery> = web::Query::from_query("page=1&page_size=10").unwrap();
         let resp = controllers::commits::commit_history(req, query)
             .await
             .unwrap();
@@ -1483,8 +1596,7 @@ mod tests {
             og_branch.name,
         );
 
-        let query: web::Query<PageNumQuery> =
-            web::Query::from_query("page=1&page_size=10").unwrap();
+        let query: web::Query<LogQuery> = web::Query::from_query("page=1&page_size=10").unwrap();
         let resp = controllers::commits::commit_history(req, query)
             .await
             .unwrap();
@@ -1500,6 +1612,55 @@ mod tests {
         Ok(())
     }
 
+    #[actix_web::test]
+    async fn test_controllers_commits_history_filtered() -> Result<(), OxenError> {
+        let sync_dir = test::get_sync_dir()?;
+        let queue = test::init_queue();
+        let namespace = "Testing-Namespace";
+        let repo_name = "Testing-Name";
+        let repo = test::create_local_repo(&sync_dir, namespace, repo_name)?;
+        let branch = api::local::branches::current_branch(&repo)?.unwrap();
+
+        util::fs::write_to_path(repo.path.join("data").join("a.txt"), "a")?;
+        command::add(&repo, &repo.path)?;
+        command::commit(&repo, "Adding data")?;
+
+        util::fs::write_to_path(repo.path.join("README.md"), "readme")?;
+        command::add(&repo, repo.path.join("README.md"))?;
+        command::commit(&repo, "Adding readme")?;
+
+        let query_string = "page=1&page_size=10&paths=data&paths=missing.txt&grep=adding";
+        let uri = format!(
+            "/oxen/{}/{}/commits/{}/history?{}",
+            namespace, repo_name, branch.name, query_string
+        );
+        let req = test::repo_request_with_param(
+            &sync_dir,
+            queue,
+            &uri,
+            namespace,
+            repo_name,
+            "commit_or_branch",
+            branch.name,
+        );
+
+        let query: web::Query<LogQuery> = web::Query::from_query(query_string).unwrap();
+        let resp = controllers::commits::commit_history(req, query)
+            .await
+            .unwrap();
+        let body = to_bytes(resp.into_body()).await.unwrap();
+        let text = std::str::from_utf8(&body).unwrap();
+        let list: ListCommitResponse = serde_json::from_str(text)?;
+        // Only the commit that changed the data dir
+        assert_eq!(list.commits.len(), 1);
+        assert_eq!(list.commits[0].message, "Adding data");
+
+        // cleanup
+        util::fs::remove_dir_all(sync_dir)?;
+
+        Ok(())
+    }
+
     #[actix_web::test]
     async fn test_controllers_commits_upload() -> Result<(), OxenError> {
         let sync_dir = test::get_sync_dir()?;
@@ -1581,4 +1742,48 @@ mod tests {
 
         Ok(())
     }
+
+    #[actix_web::test]
+    async fn test_controllers_commits_upload_over_quota() -> Result<(), OxenError> {
+        let sync_dir = test::get_sync_dir()?;
+        let queue = test::init_queue();
+        let namespace = "Testing-Namespace";
+        let repo_name = "Testing-Quota-Upload";
+        let repo = test::create_local_repo(&sync_dir, namespace, repo_name)?;
+        let commit = api::local::commits::head_commit(&repo)?;
+        let usage = api::local::usage::add(&repo, 0)?;
+
+        let mut config = QuotaConfig::default();
+        config.set_limit(namespace, Some(repo_name), Some(usage + 10));
+        config.save(&sync_dir)?;
+
+        let uri = format!("/oxen/{}/{}/commits/{}", namespace, repo_name, commit.id);
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(OxenAppData::new(sync_dir.clone(), queue))
+                .route(
+                    "/oxen/{namespace}/{repo_name}/commits/{commit_id}",
+                    web::post().to(controllers::commits::upload),
+                ),
+        )
+        .await;
+
+        let req = actix_web::test::TestRequest::post()
+            .uri(&uri)
+            .insert_header((CONTENT_LENGTH, 100))
+            .set_payload(vec![0u8; 100])
+            .to_request();
+
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::INSUFFICIENT_STORAGE);
+        let bytes = actix_http::body::to_bytes(resp.into_body()).await.unwrap();
+        let body = std::str::from_utf8(&bytes).unwrap();
+        let resp: StatusMessageDescription = serde_json::from_str(body)?;
+        assert_eq!(resp.status_message, MSG_QUOTA_EXCEEDED);
+        assert_eq!(api::local::usage::get(&repo)?, usage);
+
+        util::fs::remove_dir_all(sync_dir)?;
+
+        Ok(())
+    }
 }
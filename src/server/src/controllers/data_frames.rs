@@ -13,7 +13,7 @@ use liboxen::view::json_data_frame_view::JsonDataFrameSource;
 use liboxen::{constants, current_function};
 
 use actix_web::{web, HttpRequest, HttpResponse};
-use liboxen::core::df::tabular;
+use liboxen::core::df::{sql, tabular};
 use liboxen::opts::{DFOpts, PaginateOpts};
 use liboxen::view::{
     JsonDataFrameView, JsonDataFrameViewResponse, JsonDataFrameViews, Pagination, StatusMessage,
@@ -57,9 +57,12 @@ pub async fn get(
 
     // Parse the query params
     let mut opts = DFOpts::empty();
-    opts = df_opts_query::parse_opts(&query, &mut opts);
+    opts = df_opts_query::parse_opts(&query, &mut opts)?;
     log::debug!("controllers::data_frames got opts {:?}", opts);
 
+    // Let the sql select from this file at other revisions, ie. `SELECT * FROM "path@commit_id"`
+    sql::resolve_revision_tables(&repo, &mut opts)?;
+
     // Paginate or slice, after we do the original transform
     let mut page_opts = PaginateOpts {
         page_num: constants::DEFAULT_PAGE_NUM,
@@ -87,7 +90,7 @@ pub async fn get(
         opts.slice = Some(format!("{}..{}", start, end));
     }
 
-    let df = tabular::scan_df(&version_path, &opts, data_frame_size.height)?;
+    let df = tabular::scan_df(&version_path, &opts)?;
 
     // Try to get the schema from disk
     let og_schema = if let Some(schema) =
@@ -117,25 +120,29 @@ pub async fn get(
         data_frame_size.height
     );
 
-    match tabular::transform_lazy(df, data_frame_size.height, opts.clone()) {
+    match tabular::transform_lazy(df, &opts) {
         Ok(df_view) => {
-            log::debug!("controllers::data_frames DF view {:?}", df_view);
+            log::debug!(
+                "controllers::data_frames DF view {:?}",
+                df_view.describe_plan()
+            );
 
             let resource_version = ResourceVersion {
                 path: resource.file_path.to_string_lossy().into(),
                 version: resource.version().to_owned(),
             };
 
-            // Have to do the pagination after the transform
+            // Have to do the pagination after the transform, count the rows in the view without
+            // collecting them so that we only ever hold the requested page in memory
             let view_height = if opts.has_filter_transform() {
-                df_view.height()
+                tabular::count_rows(df_view.clone())?
             } else {
                 data_frame_size.height
             };
 
             let total_pages = (view_height as f64 / page_opts.page_size as f64).ceil() as usize;
 
-            let mut df = tabular::transform_slice(df_view, data_frame_size.height, opts.clone())?;
+            let mut df = tabular::transform_slice_lazy(df_view, opts.clone())?;
 
             let mut slice_schema = Schema::from_polars(&df.schema());
             log::debug!("OG schema {:?}", og_schema);
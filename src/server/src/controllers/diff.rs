@@ -31,7 +31,7 @@ use liboxen::{api, constants, util};
 use crate::helpers::get_repo;
 use crate::params::{
     app_data, df_opts_query, parse_base_head, path_param, resolve_base_head, DFOptsQuery,
-    PageNumQuery,
+    DirDiffQuery, PageNumQuery,
 };
 
 pub async fn commits(
@@ -149,7 +149,17 @@ pub async fn dir_tree(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenH
 
     let dir_diffs = api::local::diff::list_changed_dirs(&repository, &base_commit, &head_commit)?;
 
-    let dir_diff_tree = group_dir_diffs_by_dir(dir_diffs);
+    let mut dir_diff_tree = group_dir_diffs_by_dir(dir_diffs);
+    let dirs: Vec<PathBuf> = dir_diff_tree.iter().map(|s| s.name.clone()).collect();
+    let mut data_types = api::local::diff::get_dir_diff_summaries_by_data_type(
+        &repository,
+        &dirs,
+        &base_commit,
+        &head_commit,
+    )?;
+    for summary in dir_diff_tree.iter_mut() {
+        summary.data_types = data_types.remove(&summary.name).unwrap_or_default();
+    }
 
     let response = DirTreeDiffResponse {
         dirs: dir_diff_tree,
@@ -161,7 +171,7 @@ pub async fn dir_tree(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenH
 
 pub async fn dir_entries(
     req: HttpRequest,
-    query: web::Query<PageNumQuery>,
+    query: web::Query<DirDiffQuery>,
 ) -> actix_web::Result<HttpResponse, OxenHttpError> {
     let app_data = app_data(&req)?;
     let namespace = path_param(&req, "namespace")?;
@@ -197,6 +207,7 @@ pub async fn dir_entries(
     let summary = GenericDiffSummary::DirDiffSummary(DirDiffSummary {
         dir: DirDiffSummaryImpl {
             file_counts: entries_diff.counts.clone(),
+            data_types: vec![],
         },
     });
 
@@ -208,6 +219,7 @@ pub async fn dir_entries(
         &base_commit,
         &head_commit,
         summary,
+        query.data_types.unwrap_or(false),
     )?;
 
     let compare = CompareEntries {
@@ -256,7 +268,7 @@ pub async fn file(
     let head_entry = head_entry_reader.get_entry(&resource)?;
 
     let mut opts = DFOpts::empty();
-    opts = df_opts_query::parse_opts(&query, &mut opts);
+    opts = df_opts_query::parse_opts(&query, &mut opts)?;
 
     let page_size = query.page_size.unwrap_or(constants::DEFAULT_PAGE_SIZE);
     let page = query.page.unwrap_or(constants::DEFAULT_PAGE_NUM);
@@ -347,12 +359,13 @@ pub async fn create_df_diff(
         &commit_2.id,
         &resource_2,
     )?;
-    let diff_result = api::local::diff::diff_files(
+    let diff_result = api::local::diff::diff_files_with_opts(
         file_1,
         file_2,
         keys,
         targets,
         display_by_column, // TODONOW: add display handling here
+        &data.options,
     )?;
 
     let view = match diff_result {
@@ -449,12 +462,13 @@ pub async fn update_df_diff(
         &commit_2.id,
         &resource_2,
     )?;
-    let diff_result = api::local::diff::diff_files(
+    let diff_result = api::local::diff::diff_files_with_opts(
         file_1,
         file_2,
         keys,
         targets,
         display_by_column, // TODONOW: add display handling here
+        &data.options,
     )?;
 
     let view = match diff_result {
@@ -589,7 +603,7 @@ pub async fn get_derived_df(
     let og_schema = Schema::from_polars(&df.schema());
 
     let mut opts = DFOpts::empty();
-    opts = df_opts_query::parse_opts(&query, &mut opts);
+    opts = df_opts_query::parse_opts(&query, &mut opts)?;
     // Clear these for the first transform
     opts.page = None;
     opts.page_size = None;
@@ -813,6 +827,7 @@ fn group_dir_diffs_by_dir(dir_diffs: Vec<(PathBuf, DiffEntryStatus)>) -> Vec<Dir
             num_subdirs,
             can_display,
             children: cropped_entries,
+            data_types: vec![],
         };
         dir_tree.push(summary);
     }
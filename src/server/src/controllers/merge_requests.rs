@@ -0,0 +1,193 @@
+use std::str::FromStr;
+
+use crate::audit::{self, AuditAction};
+use crate::errors::OxenHttpError;
+use crate::helpers::get_repo;
+use crate::params::{app_data, path_param, MergeRequestQuery, PageNumQuery};
+
+use actix_web::{web, HttpRequest, HttpResponse};
+
+use liboxen::error::OxenError;
+use liboxen::model::{
+    LocalRepository, MergeRequest, MergeRequestStatus, MergeRequestUpdate, NewMergeRequest,
+    NewMergeRequestComment,
+};
+use liboxen::view::{
+    ListMergeRequestsResponse, MergeRequestDiffResponse, MergeRequestResponse, StatusMessage,
+};
+use liboxen::{api, constants};
+
+fn parse_merge_request_id(req: &HttpRequest) -> Result<u64, OxenHttpError> {
+    let id = path_param(req, "merge_request_id")?;
+    id.parse::<u64>()
+        .map_err(|_| OxenHttpError::BadRequest(format!("Invalid merge request id '{id}'").into()))
+}
+
+fn get_merge_request(repo: &LocalRepository, id: u64) -> Result<MergeRequest, OxenHttpError> {
+    api::local::merge_requests::get(repo, id)?.ok_or(OxenHttpError::NotFound)
+}
+
+pub async fn index(
+    req: HttpRequest,
+    query: web::Query<MergeRequestQuery>,
+) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repo = get_repo(&app_data.path, namespace, name)?;
+
+    let merge_requests = match &query.status {
+        Some(status) => {
+            let status = MergeRequestStatus::from_str(status)
+                .map_err(|err| OxenHttpError::BadRequest(err.into()))?;
+            api::local::merge_requests::list_by_status(&repo, &status)?
+        }
+        None => api::local::merge_requests::list(&repo)?,
+    };
+
+    Ok(HttpResponse::Ok().json(ListMergeRequestsResponse {
+        status: StatusMessage::resource_found(),
+        merge_requests,
+    }))
+}
+
+pub async fn create(
+    req: HttpRequest,
+    body: String,
+) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repo = get_repo(&app_data.path, namespace, name)?;
+
+    let data: Result<NewMergeRequest, serde_json::Error> = serde_json::from_str(&body);
+    let data = data.map_err(|err| OxenHttpError::BadRequest(format!("{:?}", err).into()))?;
+
+    let merge_request = api::local::merge_requests::create(&repo, &data)
+        .map_err(|err| OxenHttpError::BadRequest(err.to_string().into()))?;
+
+    Ok(HttpResponse::Ok().json(MergeRequestResponse {
+        status: StatusMessage::resource_created(),
+        merge_request,
+    }))
+}
+
+pub async fn show(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repo = get_repo(&app_data.path, namespace, name)?;
+    let id = parse_merge_request_id(&req)?;
+
+    let merge_request = get_merge_request(&repo, id)?;
+
+    Ok(HttpResponse::Ok().json(MergeRequestResponse {
+        status: StatusMessage::resource_found(),
+        merge_request,
+    }))
+}
+
+pub async fn update(
+    req: HttpRequest,
+    body: String,
+) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repo = get_repo(&app_data.path, namespace, name)?;
+    let id = parse_merge_request_id(&req)?;
+    get_merge_request(&repo, id)?;
+
+    let data: Result<MergeRequestUpdate, serde_json::Error> = serde_json::from_str(&body);
+    let data = data.map_err(|err| OxenHttpError::BadRequest(format!("{:?}", err).into()))?;
+
+    let merge_request = api::local::merge_requests::update(&repo, id, &data)
+        .map_err(|err| OxenHttpError::BadRequest(err.to_string().into()))?;
+
+    Ok(HttpResponse::Ok().json(MergeRequestResponse {
+        status: StatusMessage::resource_updated(),
+        merge_request,
+    }))
+}
+
+pub async fn comment(
+    req: HttpRequest,
+    body: String,
+) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repo = get_repo(&app_data.path, namespace, name)?;
+    let id = parse_merge_request_id(&req)?;
+    get_merge_request(&repo, id)?;
+
+    let data: Result<NewMergeRequestComment, serde_json::Error> = serde_json::from_str(&body);
+    let data = data.map_err(|err| OxenHttpError::BadRequest(format!("{:?}", err).into()))?;
+
+    let merge_request = api::local::merge_requests::add_comment(&repo, id, &data)
+        .map_err(|err| OxenHttpError::BadRequest(err.to_string().into()))?;
+
+    Ok(HttpResponse::Ok().json(MergeRequestResponse {
+        status: StatusMessage::resource_created(),
+        merge_request,
+    }))
+}
+
+pub async fn merge(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repo = get_repo(&app_data.path, &namespace, &name)?;
+    let id = parse_merge_request_id(&req)?;
+    get_merge_request(&repo, id)?;
+
+    match api::local::merge_requests::merge(&repo, id) {
+        Ok(merge_request) => {
+            audit::record(
+                &req,
+                AuditAction::Merge,
+                &namespace,
+                &name,
+                format!("merge_requests/{id}"),
+                merge_request.merge_commit_id.as_deref(),
+            );
+            Ok(HttpResponse::Ok().json(MergeRequestResponse {
+                status: StatusMessage::resource_updated(),
+                merge_request,
+            }))
+        }
+        Err(err) => {
+            log::debug!("Err merging merge request #{} {:?}", id, err);
+            match err {
+                OxenError::BranchNotFound(_)
+                | OxenError::RevisionNotFound(_)
+                | OxenError::ResourceNotFound(_)
+                | OxenError::MergeConflict(_) => Err(err.into()),
+                err => Err(OxenHttpError::BadRequest(err.to_string().into())),
+            }
+        }
+    }
+}
+
+pub async fn diff(
+    req: HttpRequest,
+    query: web::Query<PageNumQuery>,
+) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repo = get_repo(&app_data.path, namespace, name)?;
+    let id = parse_merge_request_id(&req)?;
+    let merge_request = get_merge_request(&repo, id)?;
+
+    let page = query.page.unwrap_or(constants::DEFAULT_PAGE_NUM);
+    let page_size = query.page_size.unwrap_or(constants::DEFAULT_PAGE_SIZE);
+
+    let diff = api::local::merge_requests::diff_summary(&repo, &merge_request, page, page_size)?;
+
+    Ok(HttpResponse::Ok().json(MergeRequestDiffResponse {
+        status: StatusMessage::resource_found(),
+        merge_request,
+        diff,
+    }))
+}
@@ -1,3 +1,4 @@
+use crate::audit::{self, AuditAction};
 use crate::errors::OxenHttpError;
 use crate::helpers::get_repo;
 use crate::params::{app_data, parse_base_head, path_param, resolve_base_head_branches};
@@ -61,7 +62,7 @@ pub async fn merge(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttp
     let base_head = path_param(&req, "base_head")?;
 
     // Get the repository or return error
-    let repository = get_repo(&app_data.path, namespace, name)?;
+    let repository = get_repo(&app_data.path, &namespace, &name)?;
 
     // Parse the base and head from the base..head string
     let (base, head) = parse_base_head(&base_head)?;
@@ -72,7 +73,15 @@ pub async fn merge(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttp
     // Check if mergeable
     let merger = Merger::new(&repository)?;
     match merger.merge_into_base(&head, &base) {
-        Ok(Some(_merge_commit)) => {
+        Ok(Some(merge_commit)) => {
+            audit::record(
+                &req,
+                AuditAction::Merge,
+                &namespace,
+                &name,
+                &base_head,
+                Some(&merge_commit.id),
+            );
             let response = MergeSuccessResponse {
                 status: StatusMessage::resource_found(),
                 base_commit: base.commit_id,
@@ -10,10 +10,10 @@ use liboxen::opts::DFOpts;
 use liboxen::view::entry::ResourceVersion;
 use liboxen::view::json_data_frame_view::JsonDataFrameSource;
 use liboxen::view::{
-    JsonDataFrame, JsonDataFrameView, JsonDataFrameViewResponse, JsonDataFrameViews,
-    MetadataEntryResponse, Pagination, StatusMessage,
+    DataFrameProfileResponse, JsonDataFrame, JsonDataFrameView, JsonDataFrameViewResponse,
+    JsonDataFrameViews, MetadataEntryResponse, Pagination, StatusMessage,
 };
-use liboxen::{api, current_function};
+use liboxen::{api, current_function, util};
 
 use actix_web::{web, HttpRequest, HttpResponse};
 
@@ -249,3 +249,42 @@ pub async fn images(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHtt
     };
     Ok(HttpResponse::Ok().json(meta))
 }
+
+/// Column stats of a tabular file at a commit, from the commit cache if it has been computed
+pub async fn profile(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let repo_name = path_param(&req, "repo_name")?;
+    let repo = get_repo(&app_data.path, namespace, &repo_name)?;
+    let resource = parse_resource(&req, &repo)?;
+
+    log::debug!(
+        "{} resource {}/{}",
+        current_function!(),
+        repo_name,
+        resource
+    );
+
+    let entry =
+        api::local::entries::get_commit_entry(&repo, &resource.commit, &resource.file_path)?
+            .ok_or(OxenError::path_does_not_exist(resource.file_path.clone()))?;
+    let version_path = util::fs::version_path(&repo, &entry);
+    if !util::fs::is_tabular(&version_path) {
+        return Err(OxenHttpError::BadRequest(
+            format!("{:?} is not a tabular file", resource.file_path).into(),
+        ));
+    }
+
+    let profile = core::cache::cachers::df_profile::get_cache_for_entry(&repo, &entry)?;
+
+    let response = DataFrameProfileResponse {
+        status: StatusMessage::resource_found(),
+        profile,
+        commit: resource.commit.clone(),
+        resource: ResourceVersion {
+            path: resource.file_path.to_string_lossy().into(),
+            version: resource.version().to_owned(),
+        },
+    };
+    Ok(HttpResponse::Ok().json(response))
+}
@@ -0,0 +1,63 @@
+use crate::errors::OxenHttpError;
+use crate::metrics;
+use crate::params::app_data;
+
+use actix_web::{HttpRequest, HttpResponse};
+use prometheus::{Encoder, TextEncoder};
+
+/// Prometheus scrape endpoint
+pub async fn index(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    metrics::update(app_data).await;
+
+    let encoder = TextEncoder::new();
+    let mut buffer = vec![];
+    if let Err(err) = encoder.encode(&prometheus::gather(), &mut buffer) {
+        log::error!("Error encoding metrics: {:?}", err);
+        return Err(OxenHttpError::InternalServerError);
+    }
+
+    Ok(HttpResponse::Ok()
+        .content_type(encoder.format_type())
+        .body(buffer))
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::body::to_bytes;
+    use actix_web::http;
+
+    use liboxen::api;
+    use liboxen::error::OxenError;
+    use liboxen::util;
+
+    use crate::controllers;
+    use crate::test;
+
+    #[actix_web::test]
+    async fn test_controllers_metrics_index() -> Result<(), OxenError> {
+        let sync_dir = test::get_sync_dir()?;
+        let queue = test::init_queue();
+        let namespace = "Testing-Namespace";
+        let name = "Testing-Metrics";
+        let repo = test::create_local_repo(&sync_dir, namespace, name)?;
+        api::local::branches::lock(&repo, "main")?;
+
+        let req = test::request(&sync_dir, queue, "/metrics");
+        let resp = controllers::metrics::index(req).await.unwrap();
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        let body = to_bytes(resp.into_body()).await.unwrap();
+        let text = std::str::from_utf8(&body).unwrap();
+
+        assert!(text.contains("oxen_task_queue_depth"));
+        assert!(text.contains("oxen_active_pushes"));
+        assert!(text.contains(&format!(
+            "oxen_locked_branches{{namespace=\"{namespace}\",repo_name=\"{name}\"}} 1"
+        )));
+
+        // cleanup
+        util::fs::remove_dir_all(sync_dir)?;
+
+        Ok(())
+    }
+}
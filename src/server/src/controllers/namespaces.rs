@@ -1,8 +1,12 @@
 use crate::errors::OxenHttpError;
-use crate::params::app_data;
+use crate::params::{app_data, path_param};
+use crate::quotas::QuotaConfig;
 
 use liboxen::api;
-use liboxen::view::{ListNamespacesResponse, NamespaceResponse, NamespaceView, StatusMessage};
+use liboxen::view::{
+    ListNamespacesResponse, NamespaceResponse, NamespaceUsageResponse, NamespaceUsageView,
+    NamespaceView, RepositoryUsageView, StatusMessage,
+};
 
 use actix_web::{HttpRequest, HttpResponse, Result};
 
@@ -47,3 +51,87 @@ pub async fn show(req: HttpRequest) -> Result<HttpResponse, OxenHttpError> {
         Err(OxenHttpError::BadRequest(msg.into()))
     }
 }
+
+/// Storage used by each repository in the namespace, along with their quotas
+pub async fn usage(req: HttpRequest) -> Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+
+    let namespace_path = app_data.path.join(&namespace);
+    if !namespace_path.is_dir() {
+        log::debug!("404 Could not find namespace: {}", namespace);
+        return Err(OxenHttpError::NotFound);
+    }
+
+    let config = QuotaConfig::load(&app_data.path)?;
+    let mut repositories: Vec<RepositoryUsageView> = vec![];
+    for repo in api::local::repositories::list_repos_in_namespace(&namespace_path) {
+        let name = repo.dirname();
+        repositories.push(RepositoryUsageView {
+            usage_bytes: api::local::usage::get(&repo)?,
+            quota_bytes: config.repo_limit(&namespace, &name),
+            name,
+        });
+    }
+    repositories.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let view = NamespaceUsageResponse {
+        status: StatusMessage::resource_found(),
+        usage: NamespaceUsageView {
+            usage_bytes: repositories.iter().map(|r| r.usage_bytes).sum(),
+            quota_bytes: config.namespace_limit(&namespace),
+            namespace,
+            repositories,
+        },
+    };
+
+    Ok(HttpResponse::Ok().json(view))
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::body::to_bytes;
+    use actix_web::http::StatusCode;
+
+    use liboxen::api;
+    use liboxen::error::OxenError;
+    use liboxen::util;
+    use liboxen::view::NamespaceUsageResponse;
+
+    use crate::controllers;
+    use crate::quotas::QuotaConfig;
+    use crate::test;
+
+    #[actix_web::test]
+    async fn test_controllers_namespaces_usage() -> Result<(), OxenError> {
+        let sync_dir = test::get_sync_dir()?;
+        let namespace = "Testing-Namespace";
+        let repo_a = test::create_local_repo(&sync_dir, namespace, "Testing-Usage-A")?;
+        test::create_local_repo(&sync_dir, namespace, "Testing-Usage-B")?;
+        let usage_a = api::local::usage::add(&repo_a, 0)?;
+
+        let mut config = QuotaConfig::default();
+        config.set_limit(namespace, None, Some(1_000_000));
+        config.set_limit(namespace, Some("Testing-Usage-A"), Some(1000));
+        config.save(&sync_dir)?;
+
+        let uri = format!("/api/namespaces/{namespace}/usage");
+        let queue = test::init_queue();
+        let req = test::namespace_request(&sync_dir, queue, &uri, namespace);
+        let resp = controllers::namespaces::usage(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body = to_bytes(resp.into_body()).await.unwrap();
+        let text = std::str::from_utf8(&body).unwrap();
+        let resp: NamespaceUsageResponse = serde_json::from_str(text)?;
+        assert_eq!(resp.usage.quota_bytes, Some(1_000_000));
+        assert_eq!(resp.usage.repositories.len(), 2);
+        assert_eq!(resp.usage.repositories[0].usage_bytes, usage_a);
+        assert_eq!(resp.usage.repositories[0].quota_bytes, Some(1000));
+        assert_eq!(resp.usage.repositories[1].quota_bytes, None);
+
+        util::fs::remove_dir_all(sync_dir)?;
+
+        Ok(())
+    }
+}
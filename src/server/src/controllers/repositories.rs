@@ -1,6 +1,8 @@
+use crate::audit::{self, AuditAction};
 use crate::errors::OxenHttpError;
 use crate::helpers::get_repo;
 use crate::params::{app_data, path_param};
+use crate::quotas::QuotaConfig;
 
 use liboxen::api;
 use liboxen::error::OxenError;
@@ -83,11 +85,29 @@ pub async fn stats(req: HttpRequest) -> HttpResponse {
                         data_size: s.data_size,
                     })
                     .collect();
+                let usage_bytes = match api::local::usage::get(&repo) {
+                    Ok(usage_bytes) => usage_bytes,
+                    Err(err) => {
+                        log::error!("Could not get usage for repo {}: {}", name, err);
+                        return HttpResponse::InternalServerError()
+                            .json(StatusMessage::internal_server_error());
+                    }
+                };
+                let quota_bytes = match QuotaConfig::load(&app_data.path) {
+                    Ok(config) => config.repo_limit(namespace, name),
+                    Err(err) => {
+                        log::error!("Could not load quotas: {}", err);
+                        return HttpResponse::InternalServerError()
+                            .json(StatusMessage::internal_server_error());
+                    }
+                };
                 HttpResponse::Ok().json(RepositoryStatsResponse {
                     status: StatusMessage::resource_found(),
                     repository: RepositoryStatsView {
                         data_size: stats.data_size,
                         data_types,
+                        usage_bytes,
+                        quota_bytes,
                     },
                 })
             }
@@ -112,14 +132,24 @@ pub async fn create(req: HttpRequest, body: String) -> HttpResponse {
     let data: Result<RepoNew, serde_json::Error> = serde_json::from_str(&body);
     match data {
         Ok(data) => match api::local::repositories::create(&app_data.path, data.to_owned()) {
-            Ok(_) => HttpResponse::Ok().json(RepositoryResponse {
-                status: STATUS_SUCCESS.to_string(),
-                status_message: MSG_RESOURCE_FOUND.to_string(),
-                repository: RepositoryView {
-                    namespace: data.namespace.clone(),
-                    name: data.name,
-                },
-            }),
+            Ok(_) => {
+                audit::record(
+                    &req,
+                    AuditAction::RepoCreate,
+                    &data.namespace,
+                    &data.name,
+                    &data.name,
+                    None,
+                );
+                HttpResponse::Ok().json(RepositoryResponse {
+                    status: STATUS_SUCCESS.to_string(),
+                    status_message: MSG_RESOURCE_FOUND.to_string(),
+                    repository: RepositoryView {
+                        namespace: data.namespace.clone(),
+                        name: data.name,
+                    },
+                })
+            }
             Err(OxenError::RepoAlreadyExists(path)) => {
                 log::debug!("Repo already exists: {:?}", path);
                 HttpResponse::Conflict().json(StatusMessage::error("Repo already exists."))
@@ -146,10 +176,27 @@ pub async fn delete(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHtt
     let name = path_param(&req, "repo_name")?;
 
     let repository = get_repo(&app_data.path, &namespace, &name)?;
+    // Only logged once the delete has succeeded, but the actor has to come from the request
+    let event = audit::event_from_request(
+        &req,
+        AuditAction::RepoDelete,
+        &namespace,
+        &name,
+        &name,
+        None,
+    );
+    let sync_dir = app_data.path.clone();
 
     // Delete in a background thread because it could take awhile
     std::thread::spawn(move || match api::local::repositories::delete(repository) {
-        Ok(_) => log::info!("Deleted repo: {}/{}", namespace, name),
+        Ok(_) => {
+            log::info!("Deleted repo: {}/{}", namespace, name);
+            if let Some(event) = event {
+                if let Err(err) = audit::append(&sync_dir, &event) {
+                    log::error!("Could not write audit event {:?}: {}", event, err);
+                }
+            }
+        }
         Err(err) => log::error!("Err deleting repo: {}", err),
     });
 
@@ -172,6 +219,14 @@ pub async fn transfer_namespace(
         &from_namespace,
         &to_namespace,
     )?;
+    audit::record(
+        &req,
+        AuditAction::RepoTransfer,
+        &from_namespace,
+        &name,
+        &to_namespace,
+        None,
+    );
 
     // Return repository view under new namespace
     Ok(HttpResponse::Ok().json(RepositoryResponse {
@@ -369,6 +424,7 @@ mod tests {
             email: String::from("ox@oxen.ai"),
             timestamp,
             root_hash: None,
+            metadata: None,
         };
         let repo_new = RepoNew::from_root_commit("Testing-Name", "Testing-Namespace", root_commit);
         let data = serde_json::to_string(&repo_new)?;
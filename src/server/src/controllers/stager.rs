@@ -1,3 +1,4 @@
+use crate::audit::{self, AuditAction};
 use crate::errors::OxenHttpError;
 use crate::helpers::get_repo;
 use crate::params::{
@@ -10,7 +11,7 @@ use liboxen::core::df::tabular;
 use liboxen::core::index::mod_stager;
 use liboxen::error::OxenError;
 use liboxen::model::diff::DiffResult;
-use liboxen::model::entry::mod_entry::NewMod;
+use liboxen::model::entry::mod_entry::{ModBatch, NewMod};
 use liboxen::model::metadata::metadata_image::ImgResize;
 use liboxen::model::DataFrameSize;
 use liboxen::model::{
@@ -25,7 +26,8 @@ use liboxen::view::json_data_frame_view::{JsonDataFrameRowResponse, JsonDataFram
 use liboxen::view::remote_staged_status::RemoteStagedStatus;
 use liboxen::view::{
     CommitResponse, FilePathsResponse, JsonDataFrameView, JsonDataFrameViewResponse,
-    JsonDataFrameViews, Pagination, RemoteStagedStatusResponse, StatusMessage,
+    JsonDataFrameViews, MoveFileBody, Pagination, RemoteStagedStatusResponse, StatusMessage,
+    StatusMessageDescription,
 };
 use liboxen::{api, constants, core::index};
 
@@ -327,6 +329,117 @@ pub async fn df_add_row(req: HttpRequest, bytes: Bytes) -> Result<HttpResponse,
     Ok(HttpResponse::Ok().json(response))
 }
 
+pub async fn df_update_row(req: HttpRequest, bytes: Bytes) -> Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+
+    let namespace = path_param(&req, "namespace")?;
+    let repo_name = path_param(&req, "repo_name")?;
+    let identifier = path_param(&req, "identifier")?;
+    let row_id = path_param(&req, "row_id")?;
+    let repo = get_repo(&app_data.path, namespace, repo_name)?;
+    let resource = parse_resource(&req, &repo)?;
+
+    let content_type_str = get_content_type(&req).unwrap_or("text/plain");
+    let content_type = ContentType::from_http_content_type(content_type_str)?;
+
+    let data = String::from_utf8(bytes.to_vec())
+        .map_err(|_| OxenError::basic_str("Could not parse body as utf8"))?;
+
+    let branch = resource
+        .branch
+        .clone()
+        .ok_or(OxenError::parsed_resource_not_found(resource.to_owned()))?;
+
+    // Have to initialize this branch repo before we can do any operations on it
+    let _branch_repo = index::remote_dir_stager::init_or_get(&repo, &branch, &identifier)?;
+
+    let commit = api::local::commits::get_by_id(&repo, &branch.commit_id)?.ok_or(
+        OxenError::revision_not_found(branch.commit_id.to_owned().into()),
+    )?;
+    let entry = api::local::entries::get_commit_entry(&repo, &commit, &resource.file_path)?
+        .ok_or(OxenError::entry_does_not_exist(resource.file_path.clone()))?;
+
+    let new_mod = NewMod {
+        content_type,
+        mod_type: ModType::Modify,
+        entry,
+        data,
+    };
+
+    let row_df = mod_stager::update_row(&repo, &branch, &identifier, &row_id, &new_mod)?;
+    let row_id: Option<String> = get_row_id(&row_df)?;
+
+    let opts = DFOpts::empty();
+    let row_schema = Schema::from_polars(&row_df.schema().clone());
+    let row_df_source = JsonDataFrameSource::from_df(&row_df, &row_schema);
+    let row_df_view = JsonDataFrameView::from_df_opts(row_df, row_schema, &opts);
+
+    let response = JsonDataFrameRowResponse {
+        data_frame: JsonDataFrameViews {
+            source: row_df_source,
+            view: row_df_view,
+        },
+        commit: None,
+        derived_resource: None,
+        status: StatusMessage::resource_updated(),
+        resource: None,
+        row_id,
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+pub async fn df_batch(req: HttpRequest, body: String) -> Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+
+    let namespace = path_param(&req, "namespace")?;
+    let repo_name = path_param(&req, "repo_name")?;
+    let identifier = path_param(&req, "identifier")?;
+    let repo = get_repo(&app_data.path, namespace, repo_name)?;
+    let resource = parse_resource(&req, &repo)?;
+
+    let branch = resource
+        .branch
+        .clone()
+        .ok_or(OxenError::parsed_resource_not_found(resource.to_owned()))?;
+
+    // Have to initialize this branch repo before we can do any operations on it
+    let _branch_repo = index::remote_dir_stager::init_or_get(&repo, &branch, &identifier)?;
+
+    let commit = api::local::commits::get_by_id(&repo, &branch.commit_id)?.ok_or(
+        OxenError::revision_not_found(branch.commit_id.to_owned().into()),
+    )?;
+    let entry = api::local::entries::get_commit_entry(&repo, &commit, &resource.file_path)?
+        .ok_or(OxenError::entry_does_not_exist(resource.file_path.clone()))?;
+
+    let batch: ModBatch = serde_json::from_str(&body)?;
+    log::debug!(
+        "stager::df_batch {resource} added {} modified {} removed {}",
+        batch.added.len(),
+        batch.modified.len(),
+        batch.removed.len()
+    );
+    let rows_df = mod_stager::apply_batch(&repo, &branch, &identifier, &entry, &batch)?;
+
+    let opts = DFOpts::empty();
+    let rows_schema = Schema::from_polars(&rows_df.schema().clone());
+    let rows_df_source = JsonDataFrameSource::from_df(&rows_df, &rows_schema);
+    let rows_df_view = JsonDataFrameView::from_df_opts(rows_df, rows_schema, &opts);
+
+    let response = JsonDataFrameViewResponse {
+        data_frame: JsonDataFrameViews {
+            source: rows_df_source,
+            view: rows_df_view,
+        },
+        commit: None,
+        derived_resource: None,
+        status: StatusMessage::resource_updated(),
+        resource: None,
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
 pub async fn df_delete_row(req: HttpRequest, _bytes: Bytes) -> Result<HttpResponse, Error> {
     let app_data = app_data(&req).unwrap();
 
@@ -480,6 +593,43 @@ pub async fn add_file(req: HttpRequest, payload: Multipart) -> Result<HttpRespon
     }))
 }
 
+pub async fn mv_file(req: HttpRequest, body: String) -> Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+
+    let namespace = path_param(&req, "namespace")?;
+    let repo_name = path_param(&req, "repo_name")?;
+    let user_id = path_param(&req, "identifier")?;
+    let repo = get_repo(&app_data.path, namespace, &repo_name)?;
+    let resource = parse_resource(&req, &repo)?;
+
+    let branch = resource
+        .branch
+        .clone()
+        .ok_or(OxenError::parsed_resource_not_found(resource.to_owned()))?;
+
+    let data: MoveFileBody = serde_json::from_str(&body)?;
+    log::debug!(
+        "stager::mv_file {resource} -> {:?} on branch {}",
+        data.destination,
+        branch.name
+    );
+
+    let branch_repo = index::remote_dir_stager::init_or_get(&repo, &branch, &user_id)?;
+    index::remote_dir_stager::stage_move(
+        &repo,
+        &branch_repo,
+        &branch,
+        &user_id,
+        &resource.file_path,
+        &data.destination,
+    )?;
+
+    Ok(HttpResponse::Ok().json(FilePathsResponse {
+        status: StatusMessage::resource_updated(),
+        paths: vec![data.destination],
+    }))
+}
+
 pub async fn commit(req: HttpRequest, body: String) -> Result<HttpResponse, Error> {
     let app_data = app_data(&req)?;
 
@@ -511,6 +661,14 @@ pub async fn commit(req: HttpRequest, body: String) -> Result<HttpResponse, Erro
                 {
                     Ok(commit) => {
                         log::debug!("stager::commit ✅ success! commit {:?}", commit);
+                        audit::record(
+                            &req,
+                            AuditAction::StagingCommit,
+                            namespace,
+                            repo_name,
+                            branch_name,
+                            Some(&commit.id),
+                        );
 
                         // Clone the commit so we can move it into the thread
                         let ret_commit = commit.clone();
@@ -543,6 +701,11 @@ pub async fn commit(req: HttpRequest, body: String) -> Result<HttpResponse, Erro
                             commit: ret_commit,
                         }))
                     }
+                    Err(OxenError::IncompatibleSchemas(desc)) => {
+                        log::error!("unable to commit branch {:?}. Err: {}", branch_name, desc);
+                        Ok(HttpResponse::BadRequest()
+                            .json(StatusMessageDescription::bad_request(format!("{desc}"))))
+                    }
                     Err(err) => {
                         log::error!("unable to commit branch {:?}. Err: {}", branch_name, err);
                         Ok(HttpResponse::UnprocessableEntity()
@@ -673,7 +836,7 @@ pub async fn index_dataset(
     let _branch_repo = index::remote_dir_stager::init_or_get(&repo, &branch, &identifier)?;
 
     let mut opts = DFOpts::empty();
-    opts = df_opts_query::parse_opts(&query, &mut opts);
+    opts = df_opts_query::parse_opts(&query, &mut opts)?;
 
     match liboxen::core::index::remote_df_stager::index_dataset(
         &repo,
@@ -743,7 +906,7 @@ pub async fn get_staged_df(
     let _branch_repo = index::remote_dir_stager::init_or_get(&repo, &branch, &identifier)?;
 
     let mut opts = DFOpts::empty();
-    opts = df_opts_query::parse_opts(&query, &mut opts);
+    opts = df_opts_query::parse_opts(&query, &mut opts)?;
 
     if index::remote_df_stager::dataset_is_indexed(
         &repo,
@@ -802,7 +965,7 @@ pub async fn get_staged_df(
             data_frame_size
         );
         let mut opts = DFOpts::empty();
-        opts = df_opts_query::parse_opts(&query, &mut opts);
+        opts = df_opts_query::parse_opts(&query, &mut opts)?;
         log::debug!("controllers::data_frames got opts {:?}", opts);
 
         // Paginate or slice, after we do the original transform
@@ -832,7 +995,7 @@ pub async fn get_staged_df(
             opts.slice = Some(format!("{}..{}", start, end));
         }
 
-        let df = tabular::scan_df(&version_path, &opts, data_frame_size.height)?;
+        let df = tabular::scan_df(&version_path, &opts)?;
 
         // Try to get the schema from disk
         let og_schema = if let Some(schema) = api::local::schemas::get_by_path_from_ref(
@@ -864,26 +1027,29 @@ pub async fn get_staged_df(
             data_frame_size.height
         );
 
-        match tabular::transform_lazy(df, data_frame_size.height, opts.clone()) {
+        match tabular::transform_lazy(df, &opts) {
             Ok(df_view) => {
-                log::debug!("controllers::data_frames DF view {:?}", df_view);
+                log::debug!(
+                    "controllers::data_frames DF view {:?}",
+                    df_view.describe_plan()
+                );
 
                 let resource_version = ResourceVersion {
                     path: resource.file_path.to_string_lossy().into(),
                     version: resource.version().to_owned(),
                 };
 
-                // Have to do the pagination after the transform
+                // Have to do the pagination after the transform, count the rows in the view without
+                // collecting them so that we only ever hold the requested page in memory
                 let view_height = if opts.has_filter_transform() {
-                    df_view.height()
+                    tabular::count_rows(df_view.clone())?
                 } else {
                     data_frame_size.height
                 };
 
                 let total_pages = (view_height as f64 / page_opts.page_size as f64).ceil() as usize;
 
-                let mut df =
-                    tabular::transform_slice(df_view, data_frame_size.height, opts.clone())?;
+                let mut df = tabular::transform_slice_lazy(df_view, opts.clone())?;
 
                 let mut slice_schema = Schema::from_polars(&df.schema());
                 log::debug!("OG schema {:?}", og_schema);
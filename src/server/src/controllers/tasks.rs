@@ -0,0 +1,95 @@
+use crate::errors::OxenHttpError;
+use crate::helpers::get_repo;
+use crate::params::{app_data, path_param};
+use crate::view::ListTaskStatusResponse;
+
+use actix_web::{HttpRequest, HttpResponse};
+use liboxen::api;
+use liboxen::error::OxenError;
+use liboxen::view::StatusMessage;
+
+/// Status of the background tasks queued for a commit, so clients know when it has been processed
+pub async fn list_for_commit(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let commit_id = path_param(&req, "commit_id")?;
+    let repo = get_repo(&app_data.path, &namespace, &name)?;
+
+    let commit = api::local::commits::get_by_id(&repo, &commit_id)?
+        .ok_or(OxenError::revision_not_found(commit_id.into()))?;
+    let tasks = app_data
+        .queue
+        .list_statuses_for_commit(&namespace, &name, &commit.id)?;
+
+    Ok(HttpResponse::Ok().json(ListTaskStatusResponse {
+        status: StatusMessage::resource_found(),
+        tasks,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::body::to_bytes;
+    use actix_web::http;
+
+    use liboxen::api;
+    use liboxen::error::OxenError;
+    use liboxen::util;
+
+    use crate::controllers;
+    use crate::queues::TaskState;
+    use crate::test;
+    use crate::view::ListTaskStatusResponse;
+
+    #[actix_web::test]
+    async fn test_controllers_tasks_list_for_commit() -> Result<(), OxenError> {
+        let sync_dir = test::get_sync_dir()?;
+        let queue = test::init_queue();
+        let namespace = "Testing-Namespace";
+        let name = "Testing-Tasks";
+        let repo = test::create_local_repo(&sync_dir, namespace, name)?;
+        let commit = api::local::commits::head_commit(&repo)?;
+
+        // Completing the commit queues up the post push processing
+        let uri = format!(
+            "/api/repos/{namespace}/{name}/commits/{}/complete",
+            commit.id
+        );
+        let req = test::repo_request_with_param(
+            &sync_dir,
+            queue.clone(),
+            &uri,
+            namespace,
+            name,
+            "commit_id",
+            commit.id.clone(),
+        );
+        let resp = controllers::commits::complete(req).await.unwrap();
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let uri = format!("/api/repos/{namespace}/{name}/commits/{}/tasks", commit.id);
+        let req = test::repo_request_with_param(
+            &sync_dir,
+            queue,
+            &uri,
+            namespace,
+            name,
+            "commit_id",
+            commit.id.clone(),
+        );
+        let resp = controllers::tasks::list_for_commit(req).await.unwrap();
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        let body = to_bytes(resp.into_body()).await.unwrap();
+        let text = std::str::from_utf8(&body).unwrap();
+        let response: ListTaskStatusResponse = serde_json::from_str(text)?;
+        assert_eq!(response.tasks.len(), 1);
+        assert_eq!(response.tasks[0].name, "post_push_complete");
+        assert_eq!(response.tasks[0].state, TaskState::Queued);
+
+        // cleanup
+        util::fs::remove_dir_all(sync_dir)?;
+
+        Ok(())
+    }
+}
@@ -11,12 +11,14 @@ use std::io;
 pub enum OxenHttpError {
     InternalServerError,
     BadRequest(StringError),
+    Forbidden(StringError),
     NotFound,
     AppDataDoesNotExist,
     PathParamDoesNotExist(StringError),
     SQLParseError(StringError),
 
     UpdateRequired(StringError),
+    QuotaExceeded(StringError),
 
     // Translate OxenError to OxenHttpError
     InternalOxenError(OxenError),
@@ -78,9 +80,13 @@ impl error::ResponseError for OxenHttpError {
             }
             OxenHttpError::BadRequest(desc) => HttpResponse::BadRequest()
                 .json(StatusMessageDescription::bad_request(desc.to_string())),
+            OxenHttpError::Forbidden(desc) => HttpResponse::Forbidden()
+                .json(StatusMessageDescription::forbidden(desc.to_string())),
             OxenHttpError::SQLParseError(query) => {
                 HttpResponse::BadRequest().json(SQLParseError::new(query.to_string()))
             }
+            OxenHttpError::QuotaExceeded(desc) => HttpResponse::InsufficientStorage()
+                .json(StatusMessageDescription::quota_exceeded(desc.to_string())),
             OxenHttpError::AppDataDoesNotExist => {
                 log::error!("AppData does not exist");
                 HttpResponse::BadRequest().json(StatusMessage::bad_request())
@@ -169,6 +175,18 @@ impl error::ResponseError for OxenHttpError {
                         HttpResponse::NotFound()
                             .json(StatusMessageDescription::not_found(format!("{msg}")))
                     }
+                    OxenError::ResourceNotFound(desc) => {
+                        log::debug!("Resource not found: {}", desc);
+
+                        HttpResponse::NotFound()
+                            .json(StatusMessageDescription::not_found(format!("{}", desc)))
+                    }
+                    OxenError::MergeConflict(desc) => {
+                        log::debug!("Merge conflict: {}", desc);
+
+                        HttpResponse::Conflict()
+                            .json(StatusMessageDescription::conflict(format!("{}", desc)))
+                    }
                     OxenError::InvalidSchema(schema) => {
                         log::error!("Invalid schema: {}", schema);
 
@@ -183,6 +201,12 @@ impl error::ResponseError for OxenHttpError {
                             format!("Parsing error: '{}'", error),
                         ))
                     }
+                    OxenError::IncompatibleSchemas(desc) => {
+                        log::error!("Incompatible schemas: {}", desc);
+
+                        HttpResponse::BadRequest()
+                            .json(StatusMessageDescription::bad_request(format!("{}", desc)))
+                    }
                     OxenError::RemoteAheadOfLocal(desc) => {
                         log::error!("Remote ahead of local: {}", desc);
 
@@ -211,9 +235,11 @@ impl error::ResponseError for OxenHttpError {
             OxenHttpError::AppDataDoesNotExist => StatusCode::BAD_REQUEST,
             OxenHttpError::PathParamDoesNotExist(_) => StatusCode::BAD_REQUEST,
             OxenHttpError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            OxenHttpError::Forbidden(_) => StatusCode::FORBIDDEN,
             OxenHttpError::SQLParseError(_) => StatusCode::BAD_REQUEST,
             OxenHttpError::NotFound => StatusCode::NOT_FOUND,
             OxenHttpError::UpdateRequired(_) => StatusCode::UPGRADE_REQUIRED,
+            OxenHttpError::QuotaExceeded(_) => StatusCode::INSUFFICIENT_STORAGE,
             OxenHttpError::ActixError(_) => StatusCode::INTERNAL_SERVER_ERROR,
             OxenHttpError::SerdeError(_) => StatusCode::BAD_REQUEST,
             OxenHttpError::RedisError(_) => StatusCode::INTERNAL_SERVER_ERROR,
@@ -221,8 +247,12 @@ impl error::ResponseError for OxenHttpError {
             OxenHttpError::InternalOxenError(error) => match error {
                 OxenError::RepoNotFound(_) => StatusCode::NOT_FOUND,
                 OxenError::RevisionNotFound(_) => StatusCode::NOT_FOUND,
+                OxenError::BranchNotFound(_) => StatusCode::NOT_FOUND,
+                OxenError::ResourceNotFound(_) => StatusCode::NOT_FOUND,
+                OxenError::MergeConflict(_) => StatusCode::CONFLICT,
                 OxenError::InvalidSchema(_) => StatusCode::BAD_REQUEST,
                 OxenError::ParsingError(_) => StatusCode::BAD_REQUEST,
+                OxenError::IncompatibleSchemas(_) => StatusCode::BAD_REQUEST,
                 _ => StatusCode::INTERNAL_SERVER_ERROR,
             },
         }
@@ -3,17 +3,21 @@ use liboxen::config::UserConfig;
 use liboxen::model::User;
 
 pub mod app_data;
+pub mod audit;
 pub mod auth;
 pub mod controllers;
 pub mod errors;
 pub mod helpers;
+pub mod metrics;
 pub mod middleware;
 pub mod params;
 pub mod queues;
+pub mod quotas;
 pub mod routes;
 pub mod tasks;
 pub mod test;
 pub mod view;
+pub mod workers;
 
 extern crate log;
 extern crate lru;
@@ -28,11 +32,8 @@ use env_logger::Env;
 use std::io::Write;
 
 use std::path::{Path, PathBuf};
-use std::time::Duration;
-use tokio::time::sleep;
 
-use crate::queues::{InMemoryTaskQueue, RedisTaskQueue, TaskQueue};
-use crate::tasks::Runnable;
+use crate::queues::{InMemoryTaskQueue, RedisTaskQueue, RocksDBTaskQueue, TaskQueue};
 
 const VERSION: &str = liboxen::constants::OXEN_VERSION;
 
@@ -41,6 +42,12 @@ const ADD_USER_USAGE: &str =
 
 const START_SERVER_USAGE: &str = "Usage: `oxen-server start -i 0.0.0.0 -p 3000`";
 
+const AUDIT_USAGE: &str =
+    "Usage: `oxen-server audit --namespace <namespace> --repo <repo> --since 2024-01-01 -o audit.jsonl`";
+
+const SET_QUOTA_USAGE: &str =
+    "Usage: `oxen-server set-quota --namespace <namespace> --repo <repo> --limit 10GB`";
+
 const INVALID_PORT_MSG: &str = "Port must a valid number between 0-65535";
 
 #[actix_web::main]
@@ -65,33 +72,45 @@ async fn main() -> std::io::Result<()> {
         Err(_) => String::from("data"),
     };
 
-    // Polling worker setup
-    async fn poll_queue(mut queue: TaskQueue) {
-        log::debug!("Starting queue poller");
-        loop {
-            match queue.pop() {
-                Some(task) => {
-                    log::debug!("Got queue item: {:?}", task);
-                    task.run();
-                    log::debug!("finished task {:?}", task);
-                }
-                None => {
-                    // log::debug!("No queue items found, sleeping");
-                    sleep(Duration::from_millis(1000)).await;
+    // Durable rocksdb queue by default, redis if asked for and available, or in-memory
+    pub fn init_queue(backend: &str, sync_dir: &Path) -> TaskQueue {
+        let mut queue = open_queue(backend, sync_dir);
+        // Tasks that were running when the server last stopped are run again, without the locks
+        // they left behind
+        match queue.requeue_claimed() {
+            Ok(requeued) => {
+                for queued in requeued {
+                    if let Err(err) = queued.task.clear_lock() {
+                        println!("Could not clear lock of task {}: {err:?}", queued.id);
+                    }
                 }
             }
+            Err(err) => {
+                println!("Could not requeue claimed tasks: {err:?}");
+            }
         }
+        queue
     }
 
-    // If redis connection is available, use redis queue, else in-memory
-    pub fn init_queue() -> TaskQueue {
-        match helpers::get_redis_connection() {
-            Ok(pool) => {
-                println!("connecting to redis established, initializing queue");
-                TaskQueue::Redis(RedisTaskQueue { pool })
-            }
-            Err(_) => {
-                println!("Failed to connect to Redis. Falling back to in-memory queue.");
+    fn open_queue(backend: &str, sync_dir: &Path) -> TaskQueue {
+        match backend {
+            "redis" => match helpers::get_redis_connection() {
+                Ok(pool) => {
+                    println!("connecting to redis established, initializing queue");
+                    return TaskQueue::Redis(RedisTaskQueue::new(pool));
+                }
+                Err(_) => {
+                    println!("Failed to connect to Redis. Falling back to rocksdb queue.");
+                }
+            },
+            "memory" => return TaskQueue::InMemory(InMemoryTaskQueue::new()),
+            _ => {}
+        }
+
+        match RocksDBTaskQueue::new(sync_dir) {
+            Ok(queue) => TaskQueue::RocksDB(queue),
+            Err(err) => {
+                println!("Failed to open rocksdb queue: {err}. Falling back to in-memory queue.");
                 TaskQueue::InMemory(InMemoryTaskQueue::new())
             }
         }
@@ -130,6 +149,28 @@ async fn main() -> std::io::Result<()> {
                         .short('a')
                         .help("Start the server with token-based authentication enforced")
                         .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("queue")
+                        .long("queue")
+                        .short('q')
+                        .default_value("rocksdb")
+                        .value_parser(["rocksdb", "redis", "memory"])
+                        .help("Where to store the tasks that run after a push")
+                        .action(clap::ArgAction::Set),
+                )
+                .arg(
+                    Arg::new("workers")
+                        .long("workers")
+                        .short('w')
+                        .help("Number of tasks to process in parallel, defaults to 1")
+                        .action(clap::ArgAction::Set),
+                )
+                .arg(
+                    Arg::new("max-retries")
+                        .long("max-retries")
+                        .help("Number of times to retry a failed task before moving it to the dead letter queue, defaults to 3")
+                        .action(clap::ArgAction::Set),
                 ),
         )
         .subcommand(
@@ -159,6 +200,88 @@ async fn main() -> std::io::Result<()> {
                         .default_missing_value("always")
                         .help("Where to write the output config file to give to the user")
                         .action(clap::ArgAction::Set),
+                )
+                .arg(
+                    Arg::new("admin")
+                        .long("admin")
+                        .help("Let the user read server wide data, such as the full audit log")
+                        .action(clap::ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            Command::new("audit")
+                .about(AUDIT_USAGE)
+                .arg(
+                    Arg::new("namespace")
+                        .long("namespace")
+                        .help("Only show events for repositories in this namespace")
+                        .action(clap::ArgAction::Set),
+                )
+                .arg(
+                    Arg::new("repo")
+                        .long("repo")
+                        .short('r')
+                        .help("Only show events for repositories with this name")
+                        .action(clap::ArgAction::Set),
+                )
+                .arg(
+                    Arg::new("action")
+                        .long("action")
+                        .short('a')
+                        .help("Only show events of this type, ie. repo_create, branch_lock, merge")
+                        .action(clap::ArgAction::Set),
+                )
+                .arg(
+                    Arg::new("email")
+                        .long("email")
+                        .short('e')
+                        .help("Only show events made by the user with this email")
+                        .action(clap::ArgAction::Set),
+                )
+                .arg(
+                    Arg::new("since")
+                        .long("since")
+                        .help("Only show events at or after this time (RFC 3339 or YYYY-MM-DD)")
+                        .action(clap::ArgAction::Set),
+                )
+                .arg(
+                    Arg::new("until")
+                        .long("until")
+                        .help("Only show events at or before this time (RFC 3339 or YYYY-MM-DD)")
+                        .action(clap::ArgAction::Set),
+                )
+                .arg(
+                    Arg::new("output")
+                        .long("output")
+                        .short('o')
+                        .help("Export the matching events as JSON lines to this file")
+                        .action(clap::ArgAction::Set),
+                ),
+        )
+        .subcommand(
+            Command::new("set-quota")
+                .about(SET_QUOTA_USAGE)
+                .arg(
+                    Arg::new("namespace")
+                        .long("namespace")
+                        .help("Namespace to limit, or the namespace of the repository")
+                        .required(true)
+                        .action(clap::ArgAction::Set),
+                )
+                .arg(
+                    Arg::new("repo")
+                        .long("repo")
+                        .short('r')
+                        .help("Limit a single repository instead of the whole namespace")
+                        .action(clap::ArgAction::Set),
+                )
+                .arg(
+                    Arg::new("limit")
+                        .long("limit")
+                        .short('l')
+                        .help("Storage limit, ie. 500MB or 10GB. Use `none` to remove the limit")
+                        .required(true)
+                        .action(clap::ArgAction::Set),
                 ),
         );
     let matches = command.get_matches();
@@ -176,13 +299,25 @@ async fn main() -> std::io::Result<()> {
                     println!("Syncing to directory: {sync_dir}");
                     let enable_auth = sub_matches.get_flag("auth");
 
+                    let worker_config = workers::WorkerConfig {
+                        num_workers: parse_arg(sub_matches, "workers")
+                            .unwrap_or(workers::DEFAULT_NUM_WORKERS),
+                        max_retries: parse_arg(sub_matches, "max-retries")
+                            .unwrap_or(workers::DEFAULT_MAX_RETRIES),
+                        ..workers::WorkerConfig::default()
+                    };
+
                     log::debug!("initializing queue");
-                    let queue = init_queue();
+                    let backend = sub_matches.get_one::<String>("queue").unwrap();
+                    let queue = init_queue(backend, Path::new(&sync_dir));
                     log::debug!("initialized queue");
                     let data = app_data::OxenAppData::new(PathBuf::from(sync_dir), queue.clone());
                     // Poll for post-commit tasks in background
-                    log::debug!("initialized app data, spawning polling worker");
-                    tokio::spawn(async { poll_queue(queue).await });
+                    log::debug!(
+                        "initialized app data, spawning {} polling workers",
+                        worker_config.num_workers
+                    );
+                    workers::spawn(queue, worker_config);
 
                     HttpServer::new(move || {
                         App::new()
@@ -193,6 +328,8 @@ async fn main() -> std::io::Result<()> {
                                 web::get().to(controllers::version::min_version),
                             )
                             .route("/api/health", web::get().to(controllers::health::index))
+                            .route("/metrics", web::get().to(controllers::metrics::index))
+                            .route("/api/audit", web::get().to(controllers::audit::index))
                             .route(
                                 "/api/namespaces",
                                 web::get().to(controllers::namespaces::index),
@@ -201,6 +338,10 @@ async fn main() -> std::io::Result<()> {
                                 "/api/namespaces/{namespace}",
                                 web::get().to(controllers::namespaces::show),
                             )
+                            .route(
+                                "/api/namespaces/{namespace}/usage",
+                                web::get().to(controllers::namespaces::usage),
+                            )
                             .route(
                                 "/api/migrations/{migration_tstamp}",
                                 web::get().to(controllers::migrations::list_unmigrated),
@@ -213,6 +354,7 @@ async fn main() -> std::io::Result<()> {
                             .default_service(web::route().to(controllers::not_found::index))
                             .wrap(Logger::default())
                             .wrap(Logger::new("user agent is %a %{User-Agent}i"))
+                            .wrap(middleware::Metrics)
                     })
                     .bind((host.to_owned(), port))?
                     .run()
@@ -240,6 +382,17 @@ async fn main() -> std::io::Result<()> {
                         };
                         match keygen.create(&new_user) {
                             Ok((user, token)) => {
+                                if sub_matches.get_flag("admin") {
+                                    let result = auth::admins::AdminConfig::load(path).and_then(
+                                        |mut config| {
+                                            config.emails.insert(user.email.clone());
+                                            config.save(path)
+                                        },
+                                    );
+                                    if let Err(err) = result {
+                                        eprintln!("Err could not save admin: {err}");
+                                    }
+                                }
                                 let cfg = UserConfig::from_user(&user);
                                 match cfg.save(Path::new(output)) {
                                     Ok(_) => {
@@ -263,6 +416,99 @@ async fn main() -> std::io::Result<()> {
 
             Ok(())
         }
+        Some(("audit", sub_matches)) => {
+            let filter = match audit_filter(sub_matches) {
+                Ok(filter) => filter,
+                Err(err) => {
+                    eprintln!("{err}\n\n{AUDIT_USAGE}");
+                    return Ok(());
+                }
+            };
+
+            let path = Path::new(&sync_dir);
+            let result = match sub_matches.get_one::<String>("output") {
+                Some(output) => {
+                    let mut file = std::fs::File::create(output)?;
+                    let mut num_events = 0;
+                    let result = audit::for_each(path, &filter, |event| {
+                        writeln!(file, "{}", serde_json::to_string(&event)?)?;
+                        num_events += 1;
+                        Ok(())
+                    });
+                    if result.is_ok() {
+                        println!("Wrote {} events to {}", num_events, output);
+                    }
+                    result
+                }
+                None => audit::for_each(path, &filter, |event| {
+                    println!("{event}");
+                    Ok(())
+                }),
+            };
+            if let Err(err) = result {
+                eprintln!("Err: {err}");
+            }
+
+            Ok(())
+        }
+        Some(("set-quota", sub_matches)) => {
+            let namespace = sub_matches.get_one::<String>("namespace").unwrap();
+            let repo_name = sub_matches.get_one::<String>("repo").map(|r| r.as_str());
+            let limit = sub_matches.get_one::<String>("limit").unwrap();
+            let limit = if limit == "none" {
+                None
+            } else {
+                match limit.parse::<bytesize::ByteSize>() {
+                    Ok(limit) => Some(limit.as_u64()),
+                    Err(err) => {
+                        eprintln!("Invalid limit '{limit}': {err}\n\n{SET_QUOTA_USAGE}");
+                        return Ok(());
+                    }
+                }
+            };
+
+            let path = Path::new(&sync_dir);
+            let result = quotas::QuotaConfig::load(path).and_then(|mut config| {
+                config.set_limit(namespace, repo_name, limit);
+                config.save(path)
+            });
+            match result {
+                Ok(_) => println!("Saved quotas to {:?}", quotas::QuotaConfig::path(path)),
+                Err(err) => eprintln!("Err: {err}"),
+            }
+
+            Ok(())
+        }
         _ => unreachable!(), // If all subcommands are defined above, anything else is unreachabe!()
     }
 }
+
+fn audit_filter(matches: &clap::ArgMatches) -> Result<audit::AuditFilter, String> {
+    let action = match matches.get_one::<String>("action") {
+        Some(action) => Some(action.parse::<audit::AuditAction>()?),
+        None => None,
+    };
+    let since = match matches.get_one::<String>("since") {
+        Some(since) => Some(audit::parse_timestamp(since).map_err(|err| err.to_string())?),
+        None => None,
+    };
+    let until = match matches.get_one::<String>("until") {
+        Some(until) => Some(audit::parse_timestamp(until).map_err(|err| err.to_string())?),
+        None => None,
+    };
+
+    Ok(audit::AuditFilter {
+        namespace: matches.get_one::<String>("namespace").cloned(),
+        repo_name: matches.get_one::<String>("repo").cloned(),
+        action,
+        email: matches.get_one::<String>("email").cloned(),
+        since,
+        until,
+    })
+}
+
+fn parse_arg<T: std::str::FromStr>(matches: &clap::ArgMatches, name: &str) -> Option<T> {
+    matches
+        .get_one::<String>(name)
+        .and_then(|value| value.parse::<T>().ok())
+}
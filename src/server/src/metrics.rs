@@ -0,0 +1,182 @@
+//! Prometheus metrics exposed on `/metrics`
+//!
+//! Request level metrics are recorded by the `Metrics` middleware as requests come in,
+//! everything else is read from the repositories and task queue when the endpoint is scraped.
+//! Counting the locked branches walks every repository, so the counts are reused for
+//! `LOCK_SCAN_INTERVAL` between scrapes.
+
+use crate::app_data::OxenAppData;
+
+use liboxen::api;
+use liboxen::core::cache::commit_cacher;
+use liboxen::error::OxenError;
+use liboxen::model::LocalRepository;
+
+use actix_web::web;
+use lazy_static::lazy_static;
+use prometheus::{
+    register_histogram_vec, register_int_counter, register_int_counter_vec, register_int_gauge,
+    register_int_gauge_vec, HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec,
+};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+lazy_static! {
+    pub static ref HTTP_REQUESTS_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "oxen_http_requests_total",
+        "Number of HTTP requests handled, by route, method and status code",
+        &["route", "method", "status"]
+    )
+    .unwrap();
+    pub static ref HTTP_REQUEST_DURATION_SECONDS: HistogramVec = register_histogram_vec!(
+        "oxen_http_request_duration_seconds",
+        "Time spent handling HTTP requests, by route and method",
+        &["route", "method"]
+    )
+    .unwrap();
+    pub static ref BYTES_UPLOADED_TOTAL: IntCounter = register_int_counter!(
+        "oxen_bytes_uploaded_total",
+        "Number of bytes received in request bodies"
+    )
+    .unwrap();
+    pub static ref BYTES_DOWNLOADED_TOTAL: IntCounter = register_int_counter!(
+        "oxen_bytes_downloaded_total",
+        "Number of bytes sent in sized response bodies"
+    )
+    .unwrap();
+    pub static ref ACTIVE_PUSHES: IntGauge = register_int_gauge!(
+        "oxen_active_pushes",
+        "Number of push requests currently uploading or completing data"
+    )
+    .unwrap();
+    pub static ref LOCKED_BRANCHES: IntGaugeVec = register_int_gauge_vec!(
+        "oxen_locked_branches",
+        "Number of branches currently locked by a push, by repository",
+        &["namespace", "repo_name"]
+    )
+    .unwrap();
+    pub static ref TASK_QUEUE_DEPTH: IntGauge = register_int_gauge!(
+        "oxen_task_queue_depth",
+        "Number of post push tasks waiting to be processed"
+    )
+    .unwrap();
+    pub static ref CACHER_SUCCESSES: IntGaugeVec = register_int_gauge_vec!(
+        "oxen_commit_cacher_successes",
+        "Number of successful commit cacher runs since the server started, by cacher",
+        &["cacher"]
+    )
+    .unwrap();
+    pub static ref CACHER_FAILURES: IntGaugeVec = register_int_gauge_vec!(
+        "oxen_commit_cacher_failures",
+        "Number of failed commit cacher runs since the server started, by cacher",
+        &["cacher"]
+    )
+    .unwrap();
+    /// When and for which sync dir the locked branches were last counted
+    static ref LAST_LOCK_SCAN: Mutex<Option<(PathBuf, Instant)>> = Mutex::new(None);
+}
+
+const LOCK_SCAN_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Routes that upload or finalize data as part of `oxen push`
+const PUSH_ROUTE_SUFFIXES: [&str; 4] = [
+    "/commits/{commit_id}/data",
+    "/commits/{commit_id}/upload_chunk",
+    "/commits/{commit_id}/complete",
+    "/commits/complete",
+];
+
+pub fn is_push_route(method: &actix_web::http::Method, route: &str) -> bool {
+    method == actix_web::http::Method::POST
+        && PUSH_ROUTE_SUFFIXES
+            .iter()
+            .any(|suffix| route.ends_with(suffix))
+}
+
+/// Register every metric up front so they are reported before anything has been recorded
+fn register() {
+    lazy_static::initialize(&HTTP_REQUESTS_TOTAL);
+    lazy_static::initialize(&HTTP_REQUEST_DURATION_SECONDS);
+    lazy_static::initialize(&BYTES_UPLOADED_TOTAL);
+    lazy_static::initialize(&BYTES_DOWNLOADED_TOTAL);
+    lazy_static::initialize(&ACTIVE_PUSHES);
+    lazy_static::initialize(&LOCKED_BRANCHES);
+    lazy_static::initialize(&TASK_QUEUE_DEPTH);
+    lazy_static::initialize(&CACHER_SUCCESSES);
+    lazy_static::initialize(&CACHER_FAILURES);
+}
+
+/// Refresh the gauges that are computed from the state on disk and in the queue
+pub async fn update(app_data: &OxenAppData) {
+    register();
+    TASK_QUEUE_DEPTH.set(app_data.queue.len() as i64);
+
+    for (name, counts) in commit_cacher::run_counts() {
+        CACHER_SUCCESSES
+            .with_label_values(&[&name])
+            .set(counts.successes as i64);
+        CACHER_FAILURES
+            .with_label_values(&[&name])
+            .set(counts.failures as i64);
+    }
+
+    if !lock_scan_due(&app_data.path) {
+        return;
+    }
+
+    // Walking the repositories is blocking IO, keep it off the async runtime
+    let sync_dir = app_data.path.clone();
+    match web::block(move || count_locked_branches(&sync_dir)).await {
+        Ok(Ok(counts)) => {
+            // Repositories and branches come and go, so start from a clean slate
+            LOCKED_BRANCHES.reset();
+            for (namespace, repo_name, num_locked) in counts {
+                LOCKED_BRANCHES
+                    .with_label_values(&[&namespace, &repo_name])
+                    .set(num_locked);
+            }
+        }
+        Ok(Err(err)) => log::error!("Could not count locked branches: {}", err),
+        Err(err) => log::error!("Could not count locked branches: {}", err),
+    }
+}
+
+/// True if the locked branches in `sync_dir` have not been counted within `LOCK_SCAN_INTERVAL`,
+/// marks them as counted so concurrent scrapes do not scan at the same time
+fn lock_scan_due(sync_dir: &Path) -> bool {
+    let mut last_scan = LAST_LOCK_SCAN.lock().unwrap();
+    let due = match last_scan.as_ref() {
+        Some((path, scanned_at)) => path != sync_dir || scanned_at.elapsed() >= LOCK_SCAN_INTERVAL,
+        None => true,
+    };
+    if due {
+        *last_scan = Some((sync_dir.to_path_buf(), Instant::now()));
+    }
+    due
+}
+
+/// The number of locked branches of every repository, repositories that cannot be read are skipped
+fn count_locked_branches(sync_dir: &Path) -> Result<Vec<(String, String, i64)>, OxenError> {
+    let mut counts = vec![];
+    for namespace in api::local::repositories::list_namespaces(sync_dir)? {
+        let namespace_path = sync_dir.join(&namespace);
+        for repo in api::local::repositories::list_repos_in_namespace(&namespace_path) {
+            match count_locked(&repo) {
+                Ok(num_locked) => counts.push((namespace.clone(), repo.dirname(), num_locked)),
+                Err(err) => log::warn!("Skipping locked branches of {:?}: {}", repo.path, err),
+            }
+        }
+    }
+    Ok(counts)
+}
+
+fn count_locked(repo: &LocalRepository) -> Result<i64, OxenError> {
+    let mut num_locked = 0;
+    for branch in api::local::branches::list(repo)? {
+        if api::local::branches::is_locked(repo, &branch.name)? {
+            num_locked += 1;
+        }
+    }
+    Ok(num_locked)
+}
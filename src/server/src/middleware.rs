@@ -1 +1,112 @@
+use crate::metrics;
 
+use actix_web::body::{BodySize, MessageBody};
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::header::CONTENT_LENGTH;
+use actix_web::Error;
+use futures_util::future::LocalBoxFuture;
+
+use std::future::{ready, Ready};
+use std::time::Instant;
+
+/// Records request counts, latencies and bytes transferred for the `/metrics` endpoint
+pub struct Metrics;
+
+impl<S, B> Transform<S, ServiceRequest> for Metrics
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = MetricsMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(MetricsMiddleware { service }))
+    }
+}
+
+pub struct MetricsMiddleware<S> {
+    service: S,
+}
+
+/// Keeps the active push gauge up to date even if the request future is dropped
+struct ActivePush;
+
+impl ActivePush {
+    fn start() -> ActivePush {
+        metrics::ACTIVE_PUSHES.inc();
+        ActivePush
+    }
+}
+
+impl Drop for ActivePush {
+    fn drop(&mut self) {
+        metrics::ACTIVE_PUSHES.dec();
+    }
+}
+
+impl<S, B> Service<ServiceRequest> for MetricsMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let start = Instant::now();
+        // Label by the matched pattern rather than the path so repos and commits don't blow up cardinality
+        let route = req
+            .match_pattern()
+            .unwrap_or_else(|| String::from("unmatched"));
+        let method = req.method().to_string();
+
+        let uploaded = req
+            .headers()
+            .get(CONTENT_LENGTH)
+            .and_then(|len| len.to_str().ok())
+            .and_then(|len| len.parse::<u64>().ok());
+        if let Some(uploaded) = uploaded {
+            metrics::BYTES_UPLOADED_TOTAL.inc_by(uploaded);
+        }
+
+        let active_push = if metrics::is_push_route(req.method(), &route) {
+            Some(ActivePush::start())
+        } else {
+            None
+        };
+
+        let fut = self.service.call(req);
+        Box::pin(async move {
+            let res = fut.await;
+            drop(active_push);
+
+            let status = match &res {
+                Ok(res) => res.status(),
+                Err(err) => err.as_response_error().status_code(),
+            };
+            metrics::HTTP_REQUESTS_TOTAL
+                .with_label_values(&[&route, &method, status.as_str()])
+                .inc();
+            metrics::HTTP_REQUEST_DURATION_SECONDS
+                .with_label_values(&[&route, &method])
+                .observe(start.elapsed().as_secs_f64());
+
+            if let Ok(res) = &res {
+                if let BodySize::Sized(size) = res.response().body().size() {
+                    metrics::BYTES_DOWNLOADED_TOTAL.inc_by(size);
+                }
+            }
+
+            res
+        })
+    }
+}
@@ -18,9 +18,21 @@ pub use aggregate_query::AggregateQuery;
 pub mod page_num_query;
 pub use page_num_query::PageNumQuery;
 
+pub mod dir_diff_query;
+pub use dir_diff_query::DirDiffQuery;
+
 pub mod df_opts_query;
 pub use df_opts_query::DFOptsQuery;
 
+pub mod merge_request_query;
+pub use merge_request_query::MergeRequestQuery;
+
+pub mod audit_query;
+pub use audit_query::AuditQuery;
+
+pub mod log_query;
+pub use log_query::LogQuery;
+
 pub fn app_data(req: &HttpRequest) -> Result<&OxenAppData, OxenHttpError> {
     log::debug!(
         "Get user agent from app data (app_data) {:?}",
@@ -0,0 +1,53 @@
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+use crate::audit::{self, AuditAction, AuditFilter};
+use crate::errors::OxenHttpError;
+
+#[derive(Deserialize, Debug)]
+pub struct AuditQuery {
+    pub namespace: Option<String>,
+    pub repo_name: Option<String>,
+    pub action: Option<String>,
+    pub email: Option<String>,
+    pub since: Option<String>,
+    pub until: Option<String>,
+    pub page: Option<usize>,
+    pub page_size: Option<usize>,
+}
+
+impl AuditQuery {
+    pub fn to_filter(&self) -> Result<AuditFilter, OxenHttpError> {
+        let action = match &self.action {
+            Some(action) => Some(
+                AuditAction::from_str(action)
+                    .map_err(|err| OxenHttpError::BadRequest(err.into()))?,
+            ),
+            None => None,
+        };
+        let since = match &self.since {
+            Some(since) => Some(
+                audit::parse_timestamp(since)
+                    .map_err(|err| OxenHttpError::BadRequest(err.to_string().into()))?,
+            ),
+            None => None,
+        };
+        let until = match &self.until {
+            Some(until) => Some(
+                audit::parse_timestamp(until)
+                    .map_err(|err| OxenHttpError::BadRequest(err.to_string().into()))?,
+            ),
+            None => None,
+        };
+
+        Ok(AuditFilter {
+            namespace: self.namespace.clone(),
+            repo_name: self.repo_name.clone(),
+            action,
+            email: self.email.clone(),
+            since,
+            until,
+        })
+    }
+}
@@ -2,6 +2,8 @@ use actix_web::web;
 use liboxen::opts::DFOpts;
 use serde::Deserialize;
 
+use crate::errors::OxenHttpError;
+
 #[derive(Deserialize, Debug)]
 pub struct DFOptsQuery {
     pub aggregate: Option<String>,
@@ -13,14 +15,20 @@ pub struct DFOptsQuery {
     pub row: Option<usize>,
     pub randomize: Option<bool>,
     pub reverse: Option<bool>,
+    pub sample: Option<String>,
+    pub seed: Option<u64>,
     pub slice: Option<String>,
     pub sort_by: Option<String>,
     pub sql: Option<String>,
+    pub stratify: Option<String>,
     pub take: Option<String>,
 }
 
-/// Provide some default vals for opts
-pub fn parse_opts(query: &web::Query<DFOptsQuery>, filter_ops: &mut DFOpts) -> DFOpts {
+/// Provide some default vals for opts, returns a parse error if the filter is invalid
+pub fn parse_opts(
+    query: &web::Query<DFOptsQuery>,
+    filter_ops: &mut DFOpts,
+) -> Result<DFOpts, OxenHttpError> {
     // Default to 0..10 unless they ask for "all"
     log::debug!("Parsing opts {:?}", query);
     if let Some(slice) = query.slice.clone() {
@@ -44,11 +52,17 @@ pub fn parse_opts(query: &web::Query<DFOptsQuery>, filter_ops: &mut DFOpts) -> D
     filter_ops.page = query.page;
     filter_ops.page_size = query.page_size;
     filter_ops.row = query.row;
+    filter_ops.sample.clone_from(&query.sample);
+    filter_ops.seed = query.seed;
     filter_ops.should_randomize = query.randomize.unwrap_or(false);
     filter_ops.should_reverse = query.reverse.unwrap_or(false);
     filter_ops.sort_by.clone_from(&query.sort_by);
     filter_ops.sql.clone_from(&query.sql);
+    filter_ops.stratify.clone_from(&query.stratify);
     filter_ops.take.clone_from(&query.take);
 
-    filter_ops.clone()
+    // Fail early so that invalid filters are a bad request rather than an error mid transform
+    filter_ops.get_filter()?;
+
+    Ok(filter_ops.clone())
 }
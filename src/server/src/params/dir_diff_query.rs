@@ -0,0 +1,9 @@
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct DirDiffQuery {
+    pub page: Option<usize>,
+    pub page_size: Option<usize>,
+    /// Also summarize the changes below the dir by data type, which has to look at every changed file
+    pub data_types: Option<bool>,
+}
@@ -0,0 +1,64 @@
+use std::path::PathBuf;
+
+use liboxen::opts::LogOpts;
+use serde::Deserialize;
+
+use crate::errors::OxenHttpError;
+
+#[derive(Deserialize, Debug)]
+pub struct LogQuery {
+    pub page: Option<usize>,
+    pub page_size: Option<usize>,
+    pub author: Option<String>,
+    pub since: Option<String>,
+    pub until: Option<String>,
+    pub grep: Option<String>,
+    pub max_count: Option<usize>,
+    pub follow: Option<bool>,
+}
+
+impl LogQuery {
+    /// The paths are given as repeated `paths` params, ie: `paths=data&paths=a,b.csv`, which the
+    /// query struct cannot hold, so they are read from the full query string
+    pub fn to_log_opts(
+        &self,
+        revision: &str,
+        query_string: &str,
+    ) -> Result<LogOpts, OxenHttpError> {
+        let paths = query_string
+            .split('&')
+            .filter_map(|param| param.split_once('='))
+            .filter(|(key, value)| *key == "paths" && !value.is_empty())
+            .map(|(_, value)| {
+                urlencoding::decode(&value.replace('+', " "))
+                    .map(|path| PathBuf::from(path.as_ref()))
+            })
+            .collect::<Result<Vec<PathBuf>, _>>()?;
+        let since = match &self.since {
+            Some(since) => Some(
+                LogOpts::parse_time(since)
+                    .map_err(|err| OxenHttpError::BadRequest(err.to_string().into()))?,
+            ),
+            None => None,
+        };
+        let until = match &self.until {
+            Some(until) => Some(
+                LogOpts::parse_time(until)
+                    .map_err(|err| OxenHttpError::BadRequest(err.to_string().into()))?,
+            ),
+            None => None,
+        };
+
+        Ok(LogOpts {
+            revision: Some(revision.to_string()),
+            remote: false,
+            paths,
+            author: self.author.clone(),
+            since,
+            until,
+            grep: self.grep.clone(),
+            max_count: self.max_count,
+            follow: self.follow.unwrap_or(false),
+        })
+    }
+}
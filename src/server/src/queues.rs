@@ -1,94 +1,753 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Mutex;
 use std::{collections::VecDeque, sync::Arc};
 
 use crate::tasks::post_push_complete::PostPushComplete;
 use crate::tasks::Task;
 use liboxen::constants::COMMIT_QUEUE_NAME;
+use liboxen::core::db;
+use liboxen::error::OxenError;
+use liboxen::util;
+use rocksdb::{DBWithThreadMode, Direction, IteratorMode, MultiThreaded};
+use serde::{Deserialize, Serialize};
+use time::{Duration, OffsetDateTime};
+
+pub const TASK_QUEUE_DIR: &str = "task_queue";
+pub const DEAD_LETTER_QUEUE_NAME: &str = "commit_queue_dead_letter";
+pub const CLAIMED_QUEUE_NAME: &str = "commit_queue_claimed";
+pub const CLAIMED_INSTANCES_SET_NAME: &str = "commit_queue_claimed_instances";
+pub const TASK_STATUS_HASH_NAME: &str = "commit_queue_status";
+
+/// Statuses that have not been updated for this long are pruned
+pub const TASK_STATUS_TTL: Duration = Duration::days(7);
+
+const QUEUE_PREFIX: &str = "queue/";
+const DEAD_LETTER_PREFIX: &str = "dead/";
+const STATUS_PREFIX: &str = "status/";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskState {
+    Queued,
+    Running,
+    Retrying,
+    Succeeded,
+    /// Ran out of retries and was moved to the dead letter queue
+    Failed,
+}
+
+/// A task along with the bookkeeping needed to retry it
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct QueuedTask {
+    pub id: String,
+    pub task: Task,
+    pub attempts: u32,
+}
+
+impl QueuedTask {
+    pub fn new(task: Task) -> QueuedTask {
+        QueuedTask {
+            id: uuid::Uuid::new_v4().to_string(),
+            task,
+            attempts: 0,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TaskStatus {
+    pub id: String,
+    pub name: String,
+    pub namespace: String,
+    pub repo_name: String,
+    pub commit_id: String,
+    pub state: TaskState,
+    pub attempts: u32,
+    pub error: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub updated_at: OffsetDateTime,
+}
+
+impl TaskStatus {
+    pub fn new(queued: &QueuedTask, state: TaskState, error: Option<String>) -> TaskStatus {
+        // Repositories live at {sync_dir}/{namespace}/{repo_name}
+        let repo_path = queued.task.repo_path();
+        let repo_name = path_component(Some(repo_path));
+        let namespace = path_component(repo_path.parent());
+        TaskStatus {
+            id: queued.id.clone(),
+            name: queued.task.name().to_string(),
+            namespace,
+            repo_name,
+            commit_id: queued.task.commit_id().to_string(),
+            state,
+            attempts: queued.attempts,
+            error,
+            updated_at: OffsetDateTime::now_utc(),
+        }
+    }
+}
+
+fn path_component(path: Option<&Path>) -> String {
+    path.and_then(|p| p.file_name())
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// Index of the first task in `queued`, oldest first, that can be claimed. A task is skipped if
+/// it is already claimed, or if an earlier task for the same repository is still in the queue.
+fn first_claimable<'a>(
+    queued: impl Iterator<Item = &'a QueuedTask>,
+    is_claimed: impl Fn(&QueuedTask) -> bool,
+    mut busy_repos: HashSet<PathBuf>,
+) -> Option<usize> {
+    for (i, queued) in queued.enumerate() {
+        let repo_path = queued.task.repo_path().to_path_buf();
+        if !is_claimed(queued) && !busy_repos.contains(&repo_path) {
+            return Some(i);
+        }
+        busy_repos.insert(repo_path);
+    }
+    None
+}
 
 #[derive(Clone)]
 pub enum TaskQueue {
     InMemory(InMemoryTaskQueue),
     Redis(RedisTaskQueue),
+    RocksDB(RocksDBTaskQueue),
 }
 
 impl TaskQueue {
-    pub fn push(&mut self, task: Task) {
+    /// Add a new task to the end of the queue, returns the id used to track its status
+    pub fn push(&mut self, task: Task) -> Result<String, OxenError> {
+        let queued = QueuedTask::new(task);
+        self.set_status(&TaskStatus::new(&queued, TaskState::Queued, None))?;
+        self.push_queued(&queued)?;
+        Ok(queued.id)
+    }
+
+    fn push_queued(&mut self, queued: &QueuedTask) -> Result<(), OxenError> {
+        match self {
+            TaskQueue::InMemory(queue) => queue.push(queued),
+            TaskQueue::Redis(queue) => queue.push(queued),
+            TaskQueue::RocksDB(queue) => queue.push(queued),
+        }
+    }
+
+    /// Claim the oldest task whose repository does not have an earlier task queued or running.
+    /// The task stays in the queue until it is acked, so it is run again if the server stops
+    /// before it finishes, and tasks for the same repository run one at a time in order.
+    pub fn claim(&mut self) -> Result<Option<QueuedTask>, OxenError> {
+        match self {
+            TaskQueue::InMemory(queue) => queue.claim(),
+            TaskQueue::Redis(queue) => queue.claim(),
+            TaskQueue::RocksDB(queue) => queue.claim(),
+        }
+    }
+
+    /// Remove a claimed task from the queue once it has succeeded or been dead lettered
+    pub fn ack(&mut self, queued: &QueuedTask) -> Result<(), OxenError> {
+        match self {
+            TaskQueue::InMemory(queue) => queue.ack(queued),
+            TaskQueue::Redis(queue) => queue.ack(queued),
+            TaskQueue::RocksDB(queue) => queue.ack(queued),
+        }
+    }
+
+    /// Number of tasks waiting or running
+    pub fn len(&self) -> usize {
         match self {
-            TaskQueue::InMemory(queue) => queue.push(task),
-            TaskQueue::Redis(queue) => queue.push(task),
+            TaskQueue::InMemory(queue) => queue.len(),
+            TaskQueue::Redis(queue) => queue.len(),
+            TaskQueue::RocksDB(queue) => queue.len(),
         }
     }
 
-    pub fn pop(&mut self) -> Option<Task> {
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Tasks that were claimed when the server last stopped, put back so they are run again
+    pub fn requeue_claimed(&mut self) -> Result<Vec<QueuedTask>, OxenError> {
         match self {
-            TaskQueue::InMemory(queue) => queue.pop(),
-            TaskQueue::Redis(queue) => queue.pop(),
+            TaskQueue::InMemory(_) => Ok(vec![]),
+            TaskQueue::Redis(queue) => queue.requeue_claimed(),
+            // Claims are not persisted, so any task left in the queue may have been running
+            TaskQueue::RocksDB(queue) => queue.list_queued(),
         }
     }
+
+    /// Move a task that ran out of retries to the dead letter queue
+    pub fn push_dead_letter(&mut self, queued: &QueuedTask) -> Result<(), OxenError> {
+        match self {
+            TaskQueue::InMemory(queue) => queue.push_dead_letter(queued),
+            TaskQueue::Redis(queue) => queue.push_dead_letter(queued),
+            TaskQueue::RocksDB(queue) => queue.push_dead_letter(queued),
+        }
+    }
+
+    pub fn list_dead_letters(&self) -> Result<Vec<QueuedTask>, OxenError> {
+        match self {
+            TaskQueue::InMemory(queue) => queue.list_dead_letters(),
+            TaskQueue::Redis(queue) => queue.list_dead_letters(),
+            TaskQueue::RocksDB(queue) => queue.list_dead_letters(),
+        }
+    }
+
+    pub fn set_status(&mut self, status: &TaskStatus) -> Result<(), OxenError> {
+        match self {
+            TaskQueue::InMemory(queue) => queue.set_status(status),
+            TaskQueue::Redis(queue) => queue.set_status(status),
+            TaskQueue::RocksDB(queue) => queue.set_status(status),
+        }
+    }
+
+    fn list_statuses(&self) -> Result<Vec<TaskStatus>, OxenError> {
+        match self {
+            TaskQueue::InMemory(queue) => queue.list_statuses(),
+            TaskQueue::Redis(queue) => queue.list_statuses(),
+            TaskQueue::RocksDB(queue) => queue.list_statuses(),
+        }
+    }
+
+    /// Remove the statuses that have not been updated within `TASK_STATUS_TTL`, returns how many
+    pub fn prune_statuses(&mut self) -> Result<usize, OxenError> {
+        let cutoff = OffsetDateTime::now_utc() - TASK_STATUS_TTL;
+        let ids: Vec<String> = self
+            .list_statuses()?
+            .into_iter()
+            .filter(|s| s.updated_at < cutoff)
+            .map(|s| s.id)
+            .collect();
+        if ids.is_empty() {
+            return Ok(0);
+        }
+        match self {
+            TaskQueue::InMemory(queue) => queue.remove_statuses(&ids)?,
+            TaskQueue::Redis(queue) => queue.remove_statuses(&ids)?,
+            TaskQueue::RocksDB(queue) => queue.remove_statuses(&ids)?,
+        }
+        Ok(ids.len())
+    }
+
+    /// Status of every task that has been queued for a commit, oldest first
+    pub fn list_statuses_for_commit(
+        &self,
+        namespace: &str,
+        repo_name: &str,
+        commit_id: &str,
+    ) -> Result<Vec<TaskStatus>, OxenError> {
+        let mut statuses: Vec<TaskStatus> = self
+            .list_statuses()?
+            .into_iter()
+            .filter(|s| {
+                s.namespace == namespace && s.repo_name == repo_name && s.commit_id == commit_id
+            })
+            .collect();
+        statuses.sort_by_key(|s| s.updated_at);
+        Ok(statuses)
+    }
 }
 
 #[derive(Clone)]
 pub struct RedisTaskQueue {
     pub pool: r2d2::Pool<redis::Client>,
+    // Each server keeps its claims in its own list, so that on restart it only requeues the tasks
+    // it was running and not the ones other servers are still running
+    instance_id: String,
+    // Claims scan the queue and then remove the task, one at a time so two workers in this
+    // process cannot both pass over the same repository
+    claim_lock: Arc<Mutex<()>>,
+    // The data of the tasks claimed by this process by id, to remove them from the claimed list
+    claimed: Arc<Mutex<HashMap<String, Vec<u8>>>>,
 }
 
 impl RedisTaskQueue {
+    /// Claims are kept under `OXEN_SERVER_ID`, or the host name if it is not set. It has to stay
+    /// the same across restarts of a server for its claims to be requeued.
     pub fn new(pool: r2d2::Pool<redis::Client>) -> Self {
-        RedisTaskQueue { pool }
+        let instance_id = std::env::var("OXEN_SERVER_ID")
+            .or_else(|_| std::env::var("HOSTNAME"))
+            .unwrap_or_else(|_| String::from("default"));
+        RedisTaskQueue::with_instance_id(pool, instance_id)
     }
 
-    fn push(&mut self, task: Task) {
-        let mut conn = self.pool.get().unwrap();
+    pub fn with_instance_id(pool: r2d2::Pool<redis::Client>, instance_id: String) -> Self {
+        RedisTaskQueue {
+            pool,
+            instance_id,
+            claim_lock: Arc::new(Mutex::new(())),
+            claimed: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
 
-        let data: Vec<u8> = match task {
-            Task::PostPushComplete(task) => bincode::serialize(&task).unwrap(),
-        };
+    // Json rather than bincode, commit metadata is free form json which bincode cannot read back
+    fn serialize(queued: &QueuedTask) -> Result<Vec<u8>, OxenError> {
+        Ok(serde_json::to_vec(queued)?)
+    }
+
+    fn deserialize(data: &[u8]) -> Result<QueuedTask, OxenError> {
+        match serde_json::from_slice::<QueuedTask>(data) {
+            Ok(queued) => Ok(queued),
+            Err(err) => {
+                // Tasks pushed by older servers were the bare PostPushComplete in bincode
+                let task: PostPushComplete = bincode::deserialize(data)
+                    .map_err(|_| OxenError::basic_str(err.to_string()))?;
+                Ok(QueuedTask::new(Task::PostPushComplete(task)))
+            }
+        }
+    }
 
+    fn claimed_key(&self) -> String {
+        format!("{CLAIMED_QUEUE_NAME}:{}", self.instance_id)
+    }
+
+    // The claimed lists of every server that has claimed a task
+    fn claimed_keys(&self, conn: &mut redis::Connection) -> Result<Vec<String>, OxenError> {
+        let instances: Vec<String> = redis::cmd("SMEMBERS")
+            .arg(CLAIMED_INSTANCES_SET_NAME)
+            .query(conn)?;
+        Ok(instances
+            .iter()
+            .map(|id| format!("{CLAIMED_QUEUE_NAME}:{id}"))
+            .collect())
+    }
+
+    /// Put the tasks this server was running when it stopped back at the front of the queue
+    pub fn requeue_claimed(&self) -> Result<Vec<QueuedTask>, OxenError> {
+        let mut conn = self.pool.get()?;
+        // The newest claim is on the left, so the oldest ends up at the front of the queue
+        let script = redis::Script::new(
+            r"
+            local moved = {}
+            while true do
+                local data = redis.call('LPOP', KEYS[1])
+                if not data then break end
+                redis.call('RPUSH', KEYS[2], data)
+                table.insert(moved, data)
+            end
+            return moved
+            ",
+        );
+        let moved: Vec<Vec<u8>> = script
+            .key(self.claimed_key())
+            .key(COMMIT_QUEUE_NAME)
+            .invoke(&mut *conn)?;
+        moved
+            .iter()
+            .map(|data| RedisTaskQueue::deserialize(data))
+            .collect()
+    }
+
+    fn push(&mut self, queued: &QueuedTask) -> Result<(), OxenError> {
+        let mut conn = self.pool.get()?;
+        let data = RedisTaskQueue::serialize(queued)?;
+        // Tasks are pushed on the left and taken from the right so they are processed in order
         let _: isize = redis::cmd("LPUSH")
             .arg(COMMIT_QUEUE_NAME)
             .arg(data)
-            .query(&mut conn)
-            .unwrap();
+            .query(&mut conn)?;
+        Ok(())
     }
 
-    fn pop(&mut self) -> Option<Task> {
-        let mut conn = self.pool.get().unwrap();
-        let outcome: Option<Vec<u8>> = redis::cmd("LPOP")
+    fn claim(&mut self) -> Result<Option<QueuedTask>, OxenError> {
+        let _lock = self.claim_lock.lock().unwrap();
+        let mut conn = self.pool.get()?;
+
+        // Oldest first, the right end of the list is the front of the queue
+        let mut values: Vec<Vec<u8>> = redis::cmd("LRANGE")
             .arg(COMMIT_QUEUE_NAME)
-            .query(&mut conn)
-            .unwrap();
-
-        match outcome {
-            Some(data) => {
-                // TODO: Support multiple task types
-                let task: PostPushComplete = bincode::deserialize(&data).unwrap();
-                Some(Task::PostPushComplete(task))
+            .arg(0)
+            .arg(-1)
+            .query(&mut conn)?;
+        values.reverse();
+        let queued = values
+            .iter()
+            .map(|data| RedisTaskQueue::deserialize(data))
+            .collect::<Result<Vec<QueuedTask>, OxenError>>()?;
+
+        let mut claimed: Vec<Vec<u8>> = vec![];
+        for key in self.claimed_keys(&mut conn)? {
+            let values: Vec<Vec<u8>> = redis::cmd("LRANGE")
+                .arg(key)
+                .arg(0)
+                .arg(-1)
+                .query(&mut conn)?;
+            claimed.extend(values);
+        }
+        let busy_repos = claimed
+            .iter()
+            .map(|data| RedisTaskQueue::deserialize(data))
+            .map(|queued| queued.map(|queued| queued.task.repo_path().to_path_buf()))
+            .collect::<Result<HashSet<PathBuf>, OxenError>>()?;
+
+        let Some(i) = first_claimable(queued.iter(), |_| false, busy_repos) else {
+            return Ok(None);
+        };
+
+        // Move the task to the claimed list in one step, so it is never missing from both
+        let script = redis::Script::new(
+            r"
+            if redis.call('LREM', KEYS[1], 1, ARGV[1]) == 1 then
+                redis.call('LPUSH', KEYS[2], ARGV[1])
+                redis.call('SADD', KEYS[3], ARGV[2])
+                return 1
+            end
+            return 0
+            ",
+        );
+        let moved: i64 = script
+            .key(COMMIT_QUEUE_NAME)
+            .key(self.claimed_key())
+            .key(CLAIMED_INSTANCES_SET_NAME)
+            .arg(&values[i])
+            .arg(&self.instance_id)
+            .invoke(&mut *conn)?;
+        if moved == 0 {
+            // Another server took it first
+            return Ok(None);
+        }
+        let mut claimed = self.claimed.lock().unwrap();
+        claimed.insert(queued[i].id.clone(), values[i].clone());
+        Ok(Some(queued[i].clone()))
+    }
+
+    fn ack(&mut self, queued: &QueuedTask) -> Result<(), OxenError> {
+        let Some(data) = self.claimed.lock().unwrap().remove(&queued.id) else {
+            return Ok(());
+        };
+        let mut conn = self.pool.get()?;
+        let _: isize = redis::cmd("LREM")
+            .arg(self.claimed_key())
+            .arg(1)
+            .arg(data)
+            .query(&mut conn)?;
+        Ok(())
+    }
+
+    fn len(&self) -> usize {
+        match self.query_len() {
+            Ok(len) => len,
+            Err(err) => {
+                log::error!("Could not get length of redis queue: {:?}", err);
+                0
             }
-            None => None,
         }
     }
+
+    fn query_len(&self) -> Result<usize, OxenError> {
+        let mut conn = self.pool.get()?;
+        let mut len: usize = redis::cmd("LLEN").arg(COMMIT_QUEUE_NAME).query(&mut conn)?;
+        for key in self.claimed_keys(&mut conn)? {
+            let claimed: usize = redis::cmd("LLEN").arg(key).query(&mut conn)?;
+            len += claimed;
+        }
+        Ok(len)
+    }
+
+    fn push_dead_letter(&mut self, queued: &QueuedTask) -> Result<(), OxenError> {
+        let mut conn = self.pool.get()?;
+        let data = RedisTaskQueue::serialize(queued)?;
+        let _: isize = redis::cmd("RPUSH")
+            .arg(DEAD_LETTER_QUEUE_NAME)
+            .arg(data)
+            .query(&mut conn)?;
+        Ok(())
+    }
+
+    fn list_dead_letters(&self) -> Result<Vec<QueuedTask>, OxenError> {
+        let mut conn = self.pool.get()?;
+        let values: Vec<Vec<u8>> = redis::cmd("LRANGE")
+            .arg(DEAD_LETTER_QUEUE_NAME)
+            .arg(0)
+            .arg(-1)
+            .query(&mut conn)?;
+        values
+            .iter()
+            .map(|data| RedisTaskQueue::deserialize(data))
+            .collect()
+    }
+
+    fn set_status(&mut self, status: &TaskStatus) -> Result<(), OxenError> {
+        let mut conn = self.pool.get()?;
+        let _: isize = redis::cmd("HSET")
+            .arg(TASK_STATUS_HASH_NAME)
+            .arg(&status.id)
+            .arg(serde_json::to_string(status)?)
+            .query(&mut conn)?;
+        Ok(())
+    }
+
+    fn list_statuses(&self) -> Result<Vec<TaskStatus>, OxenError> {
+        let mut conn = self.pool.get()?;
+        let values: Vec<String> = redis::cmd("HVALS")
+            .arg(TASK_STATUS_HASH_NAME)
+            .query(&mut conn)?;
+        let mut statuses = vec![];
+        for value in values {
+            statuses.push(serde_json::from_str(&value)?);
+        }
+        Ok(statuses)
+    }
+
+    fn remove_statuses(&mut self, ids: &[String]) -> Result<(), OxenError> {
+        let mut conn = self.pool.get()?;
+        let _: isize = redis::cmd("HDEL")
+            .arg(TASK_STATUS_HASH_NAME)
+            .arg(ids)
+            .query(&mut conn)?;
+        Ok(())
+    }
+}
+
+/// Queue that survives restarts, stored in a rocksdb database in the sync dir
+#[derive(Clone)]
+pub struct RocksDBTaskQueue {
+    db: Arc<DBWithThreadMode<MultiThreaded>>,
+    // Sequence number of the next task pushed, also serializes pushes and claims
+    next_seq: Arc<Mutex<u64>>,
+    // Keys of the tasks claimed by this process, by task id. Not persisted, so a task that was
+    // running when the server stopped is claimed again after a restart.
+    claimed: Arc<Mutex<HashMap<String, Vec<u8>>>>,
+    // Number of tasks in the queue, counted once on open so len() does not read the queue
+    count: Arc<AtomicUsize>,
+}
+
+impl RocksDBTaskQueue {
+    pub fn new(sync_dir: &Path) -> Result<RocksDBTaskQueue, OxenError> {
+        let path = util::fs::oxen_hidden_dir(sync_dir).join(TASK_QUEUE_DIR);
+        if !path.exists() {
+            util::fs::create_dir_all(&path)?;
+        }
+
+        let opts = db::opts::default();
+        let db: DBWithThreadMode<MultiThreaded> =
+            DBWithThreadMode::open(&opts, dunce::simplified(&path))?;
+
+        // Pick up numbering where we left off
+        let mut next_seq = 0;
+        let end = format!("{QUEUE_PREFIX}{}", u64::MAX);
+        let mut iter = db.iterator(IteratorMode::From(end.as_bytes(), Direction::Reverse));
+        if let Some(item) = iter.next() {
+            let (key, _) = item?;
+            let key = String::from_utf8(key.to_vec())?;
+            if let Some(seq) = key.strip_prefix(QUEUE_PREFIX) {
+                next_seq = seq.parse::<u64>()? + 1;
+            }
+        }
+        drop(iter);
+
+        let queue = RocksDBTaskQueue {
+            db: Arc::new(db),
+            next_seq: Arc::new(Mutex::new(next_seq)),
+            claimed: Arc::new(Mutex::new(HashMap::new())),
+            count: Arc::new(AtomicUsize::new(0)),
+        };
+        let count = queue.queue_keys()?.len();
+        queue.count.store(count, Ordering::SeqCst);
+        Ok(queue)
+    }
+
+    fn queue_key(seq: u64) -> String {
+        // Zero padded so the keys sort in the order they were pushed
+        format!("{QUEUE_PREFIX}{seq:020}")
+    }
+
+    fn queue_keys(&self) -> Result<Vec<Vec<u8>>, OxenError> {
+        let mut keys = vec![];
+        let iter = self.db.iterator(IteratorMode::From(
+            QUEUE_PREFIX.as_bytes(),
+            Direction::Forward,
+        ));
+        for item in iter {
+            let (key, _) = item?;
+            if !key.starts_with(QUEUE_PREFIX.as_bytes()) {
+                break;
+            }
+            keys.push(key.to_vec());
+        }
+        Ok(keys)
+    }
+
+    fn list_prefix<T: serde::de::DeserializeOwned>(
+        &self,
+        prefix: &str,
+    ) -> Result<Vec<(Vec<u8>, T)>, OxenError> {
+        let mut values = vec![];
+        let iter = self
+            .db
+            .iterator(IteratorMode::From(prefix.as_bytes(), Direction::Forward));
+        for item in iter {
+            let (key, value) = item?;
+            if !key.starts_with(prefix.as_bytes()) {
+                break;
+            }
+            values.push((key.to_vec(), serde_json::from_slice(&value)?));
+        }
+        Ok(values)
+    }
+
+    fn push(&mut self, queued: &QueuedTask) -> Result<(), OxenError> {
+        let mut next_seq = self.next_seq.lock().unwrap();
+        let key = RocksDBTaskQueue::queue_key(*next_seq);
+        self.db.put(key, serde_json::to_vec(queued)?)?;
+        *next_seq += 1;
+        self.count.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    fn claim(&mut self) -> Result<Option<QueuedTask>, OxenError> {
+        let _next_seq = self.next_seq.lock().unwrap();
+        let mut claimed = self.claimed.lock().unwrap();
+
+        let (keys, queued): (Vec<Vec<u8>>, Vec<QueuedTask>) =
+            self.list_prefix(QUEUE_PREFIX)?.into_iter().unzip();
+        let is_claimed = |queued: &QueuedTask| claimed.contains_key(&queued.id);
+        let Some(i) = first_claimable(queued.iter(), is_claimed, HashSet::new()) else {
+            return Ok(None);
+        };
+
+        claimed.insert(queued[i].id.clone(), keys[i].clone());
+        Ok(Some(queued[i].clone()))
+    }
+
+    fn ack(&mut self, queued: &QueuedTask) -> Result<(), OxenError> {
+        let mut claimed = self.claimed.lock().unwrap();
+        if let Some(key) = claimed.remove(&queued.id) {
+            self.db.delete(key)?;
+            self.count.fetch_sub(1, Ordering::SeqCst);
+        }
+        Ok(())
+    }
+
+    fn len(&self) -> usize {
+        self.count.load(Ordering::SeqCst)
+    }
+
+    fn push_dead_letter(&mut self, queued: &QueuedTask) -> Result<(), OxenError> {
+        let key = format!("{DEAD_LETTER_PREFIX}{}", queued.id);
+        self.db.put(key, serde_json::to_vec(queued)?)?;
+        Ok(())
+    }
+
+    fn list_dead_letters(&self) -> Result<Vec<QueuedTask>, OxenError> {
+        Ok(self
+            .list_prefix(DEAD_LETTER_PREFIX)?
+            .into_iter()
+            .map(|(_, queued)| queued)
+            .collect())
+    }
+
+    fn set_status(&mut self, status: &TaskStatus) -> Result<(), OxenError> {
+        let key = format!("{STATUS_PREFIX}{}", status.id);
+        self.db.put(key, serde_json::to_vec(status)?)?;
+        Ok(())
+    }
+
+    fn list_statuses(&self) -> Result<Vec<TaskStatus>, OxenError> {
+        Ok(self
+            .list_prefix(STATUS_PREFIX)?
+            .into_iter()
+            .map(|(_, status)| status)
+            .collect())
+    }
+
+    fn remove_statuses(&mut self, ids: &[String]) -> Result<(), OxenError> {
+        for id in ids {
+            self.db.delete(format!("{STATUS_PREFIX}{id}"))?;
+        }
+        Ok(())
+    }
+
+    fn list_queued(&self) -> Result<Vec<QueuedTask>, OxenError> {
+        Ok(self
+            .list_prefix(QUEUE_PREFIX)?
+            .into_iter()
+            .map(|(_, queued)| queued)
+            .collect())
+    }
 }
 
 #[derive(Clone)]
 pub struct InMemoryTaskQueue {
-    queue: Arc<Mutex<VecDeque<Task>>>,
+    queue: Arc<Mutex<VecDeque<QueuedTask>>>,
+    // Ids of the tasks that have been claimed but not acked
+    claimed: Arc<Mutex<HashSet<String>>>,
+    dead_letters: Arc<Mutex<Vec<QueuedTask>>>,
+    statuses: Arc<Mutex<HashMap<String, TaskStatus>>>,
 }
 
 impl InMemoryTaskQueue {
     pub fn new() -> Self {
         InMemoryTaskQueue {
             queue: Arc::new(Mutex::new(VecDeque::new())),
+            claimed: Arc::new(Mutex::new(HashSet::new())),
+            dead_letters: Arc::new(Mutex::new(Vec::new())),
+            statuses: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
-    fn push(&mut self, task: Task) {
+    fn push(&mut self, queued: &QueuedTask) -> Result<(), OxenError> {
         let mut queue = self.queue.lock().unwrap();
-        queue.push_back(task);
+        queue.push_back(queued.clone());
+        Ok(())
+    }
+
+    fn claim(&mut self) -> Result<Option<QueuedTask>, OxenError> {
+        let queue = self.queue.lock().unwrap();
+        let mut claimed = self.claimed.lock().unwrap();
+        let is_claimed = |queued: &QueuedTask| claimed.contains(&queued.id);
+        let Some(i) = first_claimable(queue.iter(), is_claimed, HashSet::new()) else {
+            return Ok(None);
+        };
+        claimed.insert(queue[i].id.clone());
+        Ok(Some(queue[i].clone()))
     }
 
-    fn pop(&mut self) -> Option<Task> {
+    fn ack(&mut self, queued: &QueuedTask) -> Result<(), OxenError> {
         let mut queue = self.queue.lock().unwrap();
-        queue.pop_front()
+        let mut claimed = self.claimed.lock().unwrap();
+        claimed.remove(&queued.id);
+        queue.retain(|q| q.id != queued.id);
+        Ok(())
+    }
+
+    fn len(&self) -> usize {
+        let queue = self.queue.lock().unwrap();
+        queue.len()
+    }
+
+    fn push_dead_letter(&mut self, queued: &QueuedTask) -> Result<(), OxenError> {
+        let mut dead_letters = self.dead_letters.lock().unwrap();
+        dead_letters.push(queued.clone());
+        Ok(())
+    }
+
+    fn list_dead_letters(&self) -> Result<Vec<QueuedTask>, OxenError> {
+        let dead_letters = self.dead_letters.lock().unwrap();
+        Ok(dead_letters.clone())
+    }
+
+    fn set_status(&mut self, status: &TaskStatus) -> Result<(), OxenError> {
+        let mut statuses = self.statuses.lock().unwrap();
+        statuses.insert(status.id.clone(), status.clone());
+        Ok(())
+    }
+
+    fn list_statuses(&self) -> Result<Vec<TaskStatus>, OxenError> {
+        let statuses = self.statuses.lock().unwrap();
+        Ok(statuses.values().cloned().collect())
+    }
+
+    fn remove_statuses(&mut self, ids: &[String]) -> Result<(), OxenError> {
+        let mut statuses = self.statuses.lock().unwrap();
+        for id in ids {
+            statuses.remove(id);
+        }
+        Ok(())
     }
 }
 impl Default for InMemoryTaskQueue {
@@ -96,3 +755,42 @@ impl Default for InMemoryTaskQueue {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::queues::{QueuedTask, RedisTaskQueue};
+    use crate::tasks::post_push_complete::PostPushComplete;
+    use crate::tasks::Task;
+    use crate::test;
+
+    use liboxen::api;
+    use liboxen::error::OxenError;
+    use liboxen::util;
+
+    #[test]
+    fn test_redis_queue_serializes_commit_metadata() -> Result<(), OxenError> {
+        let sync_dir = test::get_sync_dir()?;
+        let repo = test::create_local_repo(&sync_dir, "Testing-Namespace", "Testing-Metadata")?;
+        let mut commit = api::local::commits::head_commit(&repo)?;
+        commit.metadata = Some(serde_json::json!({
+            "moves": [{"from": "a.txt", "to": "b/a.txt"}],
+            "source": {"script": "train.py", "args": ["--seed", 42]}
+        }));
+
+        let queued = QueuedTask::new(Task::PostPushComplete(PostPushComplete {
+            commit: commit.clone(),
+            repo,
+        }));
+        let data = RedisTaskQueue::serialize(&queued)?;
+        let dequeued = RedisTaskQueue::deserialize(&data)?;
+
+        assert_eq!(dequeued.id, queued.id);
+        let Task::PostPushComplete(task) = dequeued.task;
+        assert_eq!(task.commit.id, commit.id);
+        assert_eq!(task.commit.metadata, commit.metadata);
+
+        util::fs::remove_dir_all(sync_dir)?;
+
+        Ok(())
+    }
+}
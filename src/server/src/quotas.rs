@@ -0,0 +1,196 @@
+//! Storage quotas per namespace and per repository
+//!
+//! Limits are read from `.oxen/quotas.toml` in the sync dir, and compared against the usage that is
+//! counted as version files are pushed (see `api::local::usage`).
+
+use crate::errors::OxenHttpError;
+
+use bytesize::ByteSize;
+use liboxen::api;
+use liboxen::error::OxenError;
+use liboxen::model::LocalRepository;
+use liboxen::util;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+pub const QUOTAS_FILENAME: &str = "quotas.toml";
+
+/// Limits in bytes, anything without a limit is unlimited
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct QuotaConfig {
+    /// Applies to namespaces that are not listed in `namespaces`
+    pub default_namespace_bytes: Option<u64>,
+    /// Applies to repositories that are not listed in `repos`
+    pub default_repo_bytes: Option<u64>,
+    #[serde(default)]
+    pub namespaces: BTreeMap<String, u64>,
+    /// Keyed by `namespace/repo_name`
+    #[serde(default)]
+    pub repos: BTreeMap<String, u64>,
+}
+
+impl QuotaConfig {
+    pub fn path(sync_dir: &Path) -> PathBuf {
+        util::fs::oxen_hidden_dir(sync_dir).join(QUOTAS_FILENAME)
+    }
+
+    pub fn load(sync_dir: &Path) -> Result<QuotaConfig, OxenError> {
+        let path = QuotaConfig::path(sync_dir);
+        if !path.exists() {
+            return Ok(QuotaConfig::default());
+        }
+        let contents = util::fs::read_from_path(&path)?;
+        toml::from_str(&contents).map_err(|err| {
+            OxenError::basic_str(format!("Could not parse quotas {:?}: {}", path, err))
+        })
+    }
+
+    pub fn save(&self, sync_dir: &Path) -> Result<(), OxenError> {
+        let path = QuotaConfig::path(sync_dir);
+        if let Some(parent) = path.parent() {
+            util::fs::create_dir_all(parent)?;
+        }
+        let contents =
+            toml::to_string(self).map_err(|err| OxenError::basic_str(err.to_string()))?;
+        util::fs::write_to_path(&path, contents)?;
+        Ok(())
+    }
+
+    pub fn namespace_limit(&self, namespace: &str) -> Option<u64> {
+        self.namespaces
+            .get(namespace)
+            .copied()
+            .or(self.default_namespace_bytes)
+    }
+
+    pub fn repo_limit(&self, namespace: &str, repo_name: &str) -> Option<u64> {
+        self.repos
+            .get(&repo_key(namespace, repo_name))
+            .copied()
+            .or(self.default_repo_bytes)
+    }
+
+    /// Set or clear (with `None`) the limit on a namespace, or a repository within it
+    pub fn set_limit(&mut self, namespace: &str, repo_name: Option<&str>, limit: Option<u64>) {
+        let (limits, key) = match repo_name {
+            Some(repo_name) => (&mut self.repos, repo_key(namespace, repo_name)),
+            None => (&mut self.namespaces, namespace.to_string()),
+        };
+        match limit {
+            Some(limit) => {
+                limits.insert(key, limit);
+            }
+            None => {
+                limits.remove(&key);
+            }
+        }
+    }
+}
+
+fn repo_key(namespace: &str, repo_name: &str) -> String {
+    format!("{namespace}/{repo_name}")
+}
+
+/// Reject a push that would add `num_bytes` to a repository that is, or would go, over its quota
+pub fn check(
+    sync_dir: &Path,
+    repo: &LocalRepository,
+    namespace: &str,
+    repo_name: &str,
+    num_bytes: u64,
+) -> Result<(), OxenHttpError> {
+    let config = QuotaConfig::load(sync_dir)?;
+
+    if let Some(limit) = config.repo_limit(namespace, repo_name) {
+        let usage = api::local::usage::get(repo)?;
+        check_limit(&format!("{namespace}/{repo_name}"), usage, num_bytes, limit)?;
+    }
+
+    if let Some(limit) = config.namespace_limit(namespace) {
+        let usage = api::local::usage::get_for_namespace(sync_dir, namespace)?;
+        check_limit(&format!("Namespace {namespace}"), usage, num_bytes, limit)?;
+    }
+
+    Ok(())
+}
+
+fn check_limit(name: &str, usage: u64, num_bytes: u64, limit: u64) -> Result<(), OxenHttpError> {
+    if usage.saturating_add(num_bytes) > limit {
+        let msg = format!(
+            "{} is using {} of its {} storage quota, cannot push {} more",
+            name,
+            ByteSize::b(usage),
+            ByteSize::b(limit),
+            ByteSize::b(num_bytes)
+        );
+        return Err(OxenHttpError::QuotaExceeded(msg.into()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::errors::OxenHttpError;
+    use crate::quotas::{self, QuotaConfig};
+    use crate::test;
+
+    use liboxen::api;
+    use liboxen::error::OxenError;
+    use liboxen::util;
+
+    #[test]
+    fn test_quotas_save_and_load() -> Result<(), OxenError> {
+        test::run_empty_sync_dir_test(|sync_dir| {
+            let mut config = QuotaConfig::load(sync_dir)?;
+            assert_eq!(config.namespace_limit("ox"), None);
+
+            config.default_repo_bytes = Some(10);
+            config.set_limit("ox", None, Some(100));
+            config.set_limit("ox", Some("CatsVsDogs"), Some(50));
+            config.save(sync_dir)?;
+
+            let mut config = QuotaConfig::load(sync_dir)?;
+            assert_eq!(config.namespace_limit("ox"), Some(100));
+            assert_eq!(config.namespace_limit("other"), None);
+            assert_eq!(config.repo_limit("ox", "CatsVsDogs"), Some(50));
+            assert_eq!(config.repo_limit("ox", "Other"), Some(10));
+
+            config.set_limit("ox", Some("CatsVsDogs"), None);
+            assert_eq!(config.repo_limit("ox", "CatsVsDogs"), Some(10));
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_quotas_check_rejects_over_quota() -> Result<(), OxenError> {
+        let sync_dir = test::get_sync_dir()?;
+        let namespace = "Testing-Namespace";
+        let name = "Testing-Quota";
+        let repo = test::create_local_repo(&sync_dir, namespace, name)?;
+        api::local::usage::add(&repo, 0)?;
+        let usage = api::local::usage::get(&repo)?;
+
+        // No quota
+        assert!(quotas::check(&sync_dir, &repo, namespace, name, 1_000_000).is_ok());
+
+        let mut config = QuotaConfig::default();
+        config.set_limit(namespace, Some(name), Some(usage + 100));
+        config.save(&sync_dir)?;
+        assert!(quotas::check(&sync_dir, &repo, namespace, name, 100).is_ok());
+        let result = quotas::check(&sync_dir, &repo, namespace, name, 101);
+        assert!(matches!(result, Err(OxenHttpError::QuotaExceeded(_))));
+
+        // Namespace limit applies across repos
+        let mut config = QuotaConfig::default();
+        config.set_limit(namespace, None, Some(usage));
+        config.save(&sync_dir)?;
+        let result = quotas::check(&sync_dir, &repo, namespace, name, 1);
+        assert!(matches!(result, Err(OxenHttpError::QuotaExceeded(_))));
+
+        util::fs::remove_dir_all(sync_dir)?;
+
+        Ok(())
+    }
+}
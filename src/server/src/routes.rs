@@ -84,6 +84,10 @@ pub fn config(cfg: &mut web::ServiceConfig) {
             "/{namespace}/{repo_name}/commits/{commit_id}/upload_chunk",
             web::post().to(controllers::commits::upload_chunk),
         )
+        .route(
+            "/{namespace}/{repo_name}/commits/{commit_id}/tasks",
+            web::get().to(controllers::tasks::list_for_commit),
+        )
         .route(
             "/{namespace}/{repo_name}/commits/{commit_or_branch:.*}/history",
             web::get().to(controllers::commits::commit_history),
@@ -200,6 +204,40 @@ pub fn config(cfg: &mut web::ServiceConfig) {
             "/{namespace}/{repo_name}/merge/{base_head:.*}",
             web::post().to(controllers::merger::merge),
         )
+        // ----- Merge Requests ----- //
+        .route(
+            "/{namespace}/{repo_name}/merge_requests",
+            web::get().to(controllers::merge_requests::index),
+        )
+        .route(
+            "/{namespace}/{repo_name}/merge_requests",
+            web::post().to(controllers::merge_requests::create),
+        )
+        .route(
+            "/{namespace}/{repo_name}/merge_requests/{merge_request_id}",
+            web::get().to(controllers::merge_requests::show),
+        )
+        .route(
+            "/{namespace}/{repo_name}/merge_requests/{merge_request_id}",
+            web::put().to(controllers::merge_requests::update),
+        )
+        .route(
+            "/{namespace}/{repo_name}/merge_requests/{merge_request_id}/comments",
+            web::post().to(controllers::merge_requests::comment),
+        )
+        .route(
+            "/{namespace}/{repo_name}/merge_requests/{merge_request_id}/diff",
+            web::get().to(controllers::merge_requests::diff),
+        )
+        .route(
+            "/{namespace}/{repo_name}/merge_requests/{merge_request_id}/merge",
+            web::post().to(controllers::merge_requests::merge),
+        )
+        // ----- Audit Log ----- //
+        .route(
+            "/{namespace}/{repo_name}/audit",
+            web::get().to(controllers::audit::show),
+        )
         // ----- Stage Remote Data ----- //
         .route(
             "/{namespace}/{repo_name}/staging/{identifier}/status/{resource:.*}",
@@ -216,6 +254,10 @@ pub fn config(cfg: &mut web::ServiceConfig) {
             "/{namespace}/{repo_name}/staging/{identifier}/entries/{resource:.*}",
             web::delete().to(controllers::stager::delete_file),
         )
+        .route(
+            "/{namespace}/{repo_name}/staging/{identifier}/mv/{resource:.*}",
+            web::post().to(controllers::stager::mv_file),
+        )
         // END STAGING
         // DEPRECIATED STAGING
         .route(
@@ -252,6 +294,14 @@ pub fn config(cfg: &mut web::ServiceConfig) {
             "/{namespace}/{repo_name}/staging/{identifier}/df/rows/{row_id}/{resource:.*}",
             web::delete().to(controllers::stager::df_delete_row),
         )
+        .route(
+            "/{namespace}/{repo_name}/staging/{identifier}/df/rows/{row_id}/{resource:.*}",
+            web::put().to(controllers::stager::df_update_row),
+        )
+        .route(
+            "/{namespace}/{repo_name}/staging/{identifier}/df/batch/{resource:.*}",
+            web::post().to(controllers::stager::df_batch),
+        )
         .route(
             "/{namespace}/{repo_name}/staging/{identifier}/df/{resource:.*}",
             web::get().to(controllers::stager::get_staged_df),
@@ -292,6 +342,10 @@ pub fn config(cfg: &mut web::ServiceConfig) {
             "/{namespace}/{repo_name}/meta/images/{resource:.*}",
             web::get().to(controllers::metadata::images),
         )
+        .route(
+            "/{namespace}/{repo_name}/meta/profile/{resource:.*}",
+            web::get().to(controllers::metadata::profile),
+        )
         .route(
             "/{namespace}/{repo_name}/meta/{resource:.*}",
             web::get().to(controllers::metadata::file),
@@ -1,16 +1,52 @@
 pub mod post_push_complete;
 
+use liboxen::core::cache::commit_cacher;
+use liboxen::error::OxenError;
+use serde::{Deserialize, Serialize};
+
 pub trait Runnable {
-    fn run(&self);
+    fn run(&self) -> Result<(), OxenError>;
 }
 
-#[derive(Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum Task {
     PostPushComplete(post_push_complete::PostPushComplete),
 }
 
+impl Task {
+    /// Short name of the task, used when reporting status
+    pub fn name(&self) -> &str {
+        match self {
+            Task::PostPushComplete(_) => "post_push_complete",
+        }
+    }
+
+    /// The commit the task is processing
+    pub fn commit_id(&self) -> &str {
+        match self {
+            Task::PostPushComplete(task) => &task.commit.id,
+        }
+    }
+
+    /// The repository the task is processing, tasks on the same repository are run in order
+    pub fn repo_path(&self) -> &std::path::Path {
+        match self {
+            Task::PostPushComplete(task) => &task.repo.path,
+        }
+    }
+
+    /// Remove the lock a run of the task left behind if the server stopped while it was running
+    pub fn clear_lock(&self) -> Result<(), OxenError> {
+        match self {
+            Task::PostPushComplete(task) => {
+                commit_cacher::force_remove_lock(&task.repo, &task.commit)
+            }
+        }
+    }
+}
+
 impl Runnable for Task {
-    fn run(&self) {
+    fn run(&self) -> Result<(), OxenError> {
         match self {
             Task::PostPushComplete(task) => task.run(),
         }
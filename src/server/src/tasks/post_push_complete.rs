@@ -1,42 +1,54 @@
 use liboxen::{
     core::cache::commit_cacher,
+    error::OxenError,
     model::{Commit, LocalRepository},
 };
 use serde::{Deserialize, Serialize};
 
 use super::Runnable;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PostPushComplete {
     pub commit: Commit,
     pub repo: LocalRepository,
 }
 
 impl Runnable for PostPushComplete {
-    fn run(&self) {
+    fn run(&self) -> Result<(), OxenError> {
         log::debug!(
-            "Running cachers for commit {:?} on repo {:?} from redis queue",
+            "Running cachers for commit {:?} on repo {:?} from queue",
             self.commit.id,
             &self.repo.path
         );
-        // sleep to debug
-        println!("Here is the commit id: {}", self.commit.id);
         let force = false;
-        match commit_cacher::run_all(&self.repo, &self.commit, force) {
+        let result = commit_cacher::run_all(&self.repo, &self.commit, force).and_then(|_| {
+            // Individual cachers record their failures rather than erroring, surface them so the task is retried
+            let failures = commit_cacher::get_failures(&self.repo, &self.commit)?;
+            if failures.is_empty() {
+                Ok(())
+            } else {
+                let messages: Vec<String> =
+                    failures.into_iter().map(|f| f.status_message).collect();
+                Err(OxenError::basic_str(messages.join(", ")))
+            }
+        });
+        match result {
             Ok(_) => {
                 log::debug!(
-                    "Cachers ran successfully for commit {:?} on repo {:?} from redis queue",
+                    "Cachers ran successfully for commit {:?} on repo {:?} from queue",
                     self.commit.id,
                     &self.repo.path
                 );
+                Ok(())
             }
             Err(e) => {
                 log::error!(
-                    "Cachers failed to run for commit {:?} on repo {:?} from redis queue",
+                    "Cachers failed to run for commit {:?} on repo {:?} from queue",
                     self.commit.id,
                     &self.repo.path
                 );
                 log::error!("Error: {:?}", e);
+                Err(e)
             }
         }
     }
@@ -7,6 +7,7 @@ use liboxen::error::OxenError;
 use liboxen::model::LocalRepository;
 use liboxen::util;
 
+use actix_web::http::header::AUTHORIZATION;
 use env_logger::Env;
 use serde::Serialize;
 use std::borrow::Cow;
@@ -27,7 +28,7 @@ pub fn init_queue() -> TaskQueue {
     match helpers::get_redis_connection() {
         Ok(pool) => {
             println!("connecting to redis established, initializing queue");
-            TaskQueue::Redis(RedisTaskQueue { pool })
+            TaskQueue::Redis(RedisTaskQueue::new(pool))
         }
         Err(_) => {
             println!("Failed to connect to Redis. Falling back to in-memory queue.");
@@ -82,6 +83,18 @@ pub fn request(sync_dir: &Path, queue: TaskQueue, uri: &str) -> actix_web::HttpR
         .to_http_request()
 }
 
+pub fn request_with_token(
+    sync_dir: &Path,
+    queue: TaskQueue,
+    uri: &str,
+    token: &str,
+) -> actix_web::HttpRequest {
+    actix_web::test::TestRequest::with_uri(uri)
+        .app_data(OxenAppData::new(sync_dir.to_path_buf(), queue))
+        .insert_header((AUTHORIZATION, format!("Bearer {token}")))
+        .to_http_request()
+}
+
 pub fn namespace_request(
     sync_dir: &Path,
     queue: TaskQueue,
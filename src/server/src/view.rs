@@ -1,3 +1,7 @@
+use crate::audit::AuditEvent;
+use crate::queues::TaskStatus;
+
+use liboxen::view::{Pagination, StatusMessage};
 use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -10,3 +14,18 @@ pub struct PaginatedLinesResponse {
     pub total_pages: usize,
     pub total_entries: usize,
 }
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct AuditLogResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub events: Vec<AuditEvent>,
+    pub pagination: Pagination,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct ListTaskStatusResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub tasks: Vec<TaskStatus>,
+}
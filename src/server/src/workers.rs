@@ -0,0 +1,348 @@
+//! Background workers that process the tasks in the queue
+//!
+//! A task is only claimed once every earlier task for its repository has finished, so commits are
+//! processed in the order they were pushed. Failed tasks are retried in place with exponential
+//! backoff, holding up the later tasks for the repository, and moved to the dead letter queue once
+//! they run out of retries.
+
+use crate::queues::{QueuedTask, TaskQueue, TaskState, TaskStatus};
+use crate::tasks::Runnable;
+
+use liboxen::error::OxenError;
+
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+
+pub const DEFAULT_NUM_WORKERS: usize = 1;
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+pub const DEFAULT_RETRY_BACKOFF_MS: u64 = 1000;
+
+const POLL_INTERVAL_MS: u64 = 1000;
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Clone)]
+pub struct WorkerConfig {
+    pub num_workers: usize,
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled on every attempt after that
+    pub retry_backoff_ms: u64,
+}
+
+impl Default for WorkerConfig {
+    fn default() -> Self {
+        WorkerConfig {
+            num_workers: DEFAULT_NUM_WORKERS,
+            max_retries: DEFAULT_MAX_RETRIES,
+            retry_backoff_ms: DEFAULT_RETRY_BACKOFF_MS,
+        }
+    }
+}
+
+impl WorkerConfig {
+    /// How long to wait before running the task again after it has failed `attempts` times
+    pub fn backoff_ms(&self, attempts: u32) -> u64 {
+        let exponent = attempts.saturating_sub(1).min(16);
+        self.retry_backoff_ms.saturating_mul(1 << exponent)
+    }
+}
+
+/// Spawn `num_workers` workers polling the queue
+pub fn spawn(queue: TaskQueue, config: WorkerConfig) {
+    for worker_id in 0..config.num_workers.max(1) {
+        let queue = queue.clone();
+        let config = config.clone();
+        tokio::spawn(async move { poll(worker_id, queue, config).await });
+    }
+}
+
+async fn poll(worker_id: usize, mut queue: TaskQueue, config: WorkerConfig) {
+    log::debug!("Starting queue worker {}", worker_id);
+    let mut last_pruned = Instant::now();
+    loop {
+        let queued = match queue.claim() {
+            Ok(Some(queued)) => queued,
+            Ok(None) => {
+                if last_pruned.elapsed() >= PRUNE_INTERVAL {
+                    last_pruned = Instant::now();
+                    match queue.prune_statuses() {
+                        Ok(pruned) => {
+                            log::debug!("Worker {} pruned {} statuses", worker_id, pruned)
+                        }
+                        Err(err) => {
+                            log::error!("Worker {} could not prune statuses: {}", worker_id, err)
+                        }
+                    }
+                }
+                sleep(Duration::from_millis(POLL_INTERVAL_MS)).await;
+                continue;
+            }
+            Err(err) => {
+                log::error!("Worker {} could not claim from queue: {}", worker_id, err);
+                sleep(Duration::from_millis(POLL_INTERVAL_MS)).await;
+                continue;
+            }
+        };
+
+        log::debug!("Worker {} got queue item: {:?}", worker_id, queued.id);
+        process(&mut queue, &config, queued).await;
+    }
+}
+
+/// Run a claimed task until it succeeds or runs out of retries
+pub async fn process(queue: &mut TaskQueue, config: &WorkerConfig, mut queued: QueuedTask) {
+    loop {
+        queued.attempts += 1;
+        if let Err(err) = queue.set_status(&TaskStatus::new(&queued, TaskState::Running, None)) {
+            log::error!("Could not set status of task {}: {}", queued.id, err);
+        }
+
+        // Tasks do blocking IO, keep them off the async runtime
+        let task = queued.task.clone();
+        let result = match tokio::task::spawn_blocking(move || task.run()).await {
+            Ok(result) => result,
+            Err(err) => Err(OxenError::basic_str(format!("Task panicked: {err}"))),
+        };
+
+        match handle_result(queue, config, &queued, result) {
+            Ok(TaskState::Retrying) => {
+                sleep(Duration::from_millis(config.backoff_ms(queued.attempts))).await;
+            }
+            Ok(_) => return,
+            Err(err) => {
+                // The task stays claimed, it is run again once the server restarts
+                log::error!("Could not record result of task {}: {}", queued.id, err);
+                return;
+            }
+        }
+    }
+}
+
+/// Record the outcome of running a task. Tasks that succeeded or ran out of retries are removed
+/// from the queue, the rest stay claimed to be retried.
+pub fn handle_result(
+    queue: &mut TaskQueue,
+    config: &WorkerConfig,
+    queued: &QueuedTask,
+    result: Result<(), OxenError>,
+) -> Result<TaskState, OxenError> {
+    let err = match result {
+        Ok(_) => {
+            log::debug!("finished task {:?}", queued.id);
+            queue.set_status(&TaskStatus::new(queued, TaskState::Succeeded, None))?;
+            queue.ack(queued)?;
+            return Ok(TaskState::Succeeded);
+        }
+        Err(err) => err.to_string(),
+    };
+
+    if queued.attempts <= config.max_retries {
+        log::warn!(
+            "Task {} failed on attempt {}, retrying in {}ms: {}",
+            queued.id,
+            queued.attempts,
+            config.backoff_ms(queued.attempts),
+            err
+        );
+        queue.set_status(&TaskStatus::new(queued, TaskState::Retrying, Some(err)))?;
+        Ok(TaskState::Retrying)
+    } else {
+        log::error!(
+            "Task {} failed after {} attempts, moving to dead letter queue: {}",
+            queued.id,
+            queued.attempts,
+            err
+        );
+        queue.set_status(&TaskStatus::new(queued, TaskState::Failed, Some(err)))?;
+        queue.push_dead_letter(queued)?;
+        queue.ack(queued)?;
+        Ok(TaskState::Failed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::queues::{
+        InMemoryTaskQueue, QueuedTask, RocksDBTaskQueue, TaskQueue, TaskState, TaskStatus,
+        TASK_STATUS_TTL,
+    };
+    use crate::tasks::post_push_complete::PostPushComplete;
+    use crate::tasks::Task;
+    use crate::test;
+    use crate::workers::{self, WorkerConfig};
+
+    use liboxen::api;
+    use liboxen::error::OxenError;
+    use liboxen::model::{Commit, LocalRepository};
+    use liboxen::util;
+
+    fn task(repo: &LocalRepository, commit: &Commit) -> Task {
+        Task::PostPushComplete(PostPushComplete {
+            commit: commit.clone(),
+            repo: repo.clone(),
+        })
+    }
+
+    #[test]
+    fn test_workers_backoff_doubles() {
+        let config = WorkerConfig {
+            retry_backoff_ms: 100,
+            ..WorkerConfig::default()
+        };
+        assert_eq!(config.backoff_ms(1), 100);
+        assert_eq!(config.backoff_ms(2), 200);
+        assert_eq!(config.backoff_ms(3), 400);
+    }
+
+    #[test]
+    fn test_workers_retry_then_dead_letter() -> Result<(), OxenError> {
+        let sync_dir = test::get_sync_dir()?;
+        let repo = test::create_local_repo(&sync_dir, "Testing-Namespace", "Testing-Retry")?;
+        let commit = api::local::commits::head_commit(&repo)?;
+
+        let mut queue = TaskQueue::InMemory(InMemoryTaskQueue::new());
+        let config = WorkerConfig {
+            max_retries: 1,
+            retry_backoff_ms: 0,
+            ..WorkerConfig::default()
+        };
+        queue.push(task(&repo, &commit))?;
+
+        // First failure is retried, the task stays claimed in the queue
+        let mut queued = queue.claim()?.unwrap();
+        queued.attempts += 1;
+        let result = Err(OxenError::basic_str("boom"));
+        let state = workers::handle_result(&mut queue, &config, &queued, result)?;
+        assert_eq!(state, TaskState::Retrying);
+        assert_eq!(queue.len(), 1);
+        assert!(queue.claim()?.is_none());
+
+        // Second failure is out of retries
+        queued.attempts += 1;
+        let result = Err(OxenError::basic_str("boom"));
+        let state = workers::handle_result(&mut queue, &config, &queued, result)?;
+        assert_eq!(state, TaskState::Failed);
+        assert!(queue.is_empty());
+        assert_eq!(queue.list_dead_letters()?.len(), 1);
+
+        let statuses =
+            queue.list_statuses_for_commit("Testing-Namespace", "Testing-Retry", &commit.id)?;
+        assert_eq!(statuses.len(), 1);
+        assert_eq!(statuses[0].state, TaskState::Failed);
+        assert_eq!(statuses[0].attempts, 2);
+        assert_eq!(statuses[0].error, Some("boom".to_string()));
+
+        util::fs::remove_dir_all(sync_dir)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_workers_claim_keeps_repo_order() -> Result<(), OxenError> {
+        let sync_dir = test::get_sync_dir()?;
+        let repo_a = test::create_local_repo(&sync_dir, "Testing-Namespace", "Testing-Order-A")?;
+        let repo_b = test::create_local_repo(&sync_dir, "Testing-Namespace", "Testing-Order-B")?;
+        let commit_a = api::local::commits::head_commit(&repo_a)?;
+        let commit_b = api::local::commits::head_commit(&repo_b)?;
+
+        let mut queue = TaskQueue::InMemory(InMemoryTaskQueue::new());
+        let first_a = queue.push(task(&repo_a, &commit_a))?;
+        let second_a = queue.push(task(&repo_a, &commit_a))?;
+        let first_b = queue.push(task(&repo_b, &commit_b))?;
+
+        // The second task for repo a waits behind the first, repo b is free to run
+        let claimed_a = queue.claim()?.unwrap();
+        assert_eq!(claimed_a.id, first_a);
+        assert_eq!(queue.claim()?.unwrap().id, first_b);
+        assert!(queue.claim()?.is_none());
+
+        queue.ack(&claimed_a)?;
+        assert_eq!(queue.claim()?.unwrap().id, second_a);
+
+        util::fs::remove_dir_all(sync_dir)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_workers_rocksdb_queue_survives_restart() -> Result<(), OxenError> {
+        let sync_dir = test::get_sync_dir()?;
+        let repo = test::create_local_repo(&sync_dir, "Testing-Namespace", "Testing-Durable")?;
+        let commit = api::local::commits::head_commit(&repo)?;
+
+        let first = {
+            let mut queue = TaskQueue::RocksDB(RocksDBTaskQueue::new(&sync_dir)?);
+            let first = queue.push(task(&repo, &commit))?;
+            queue.push(task(&repo, &commit))?;
+            assert_eq!(queue.len(), 2);
+
+            // Claimed but never acked, as if the server stopped while running it
+            assert_eq!(queue.claim()?.unwrap().id, first);
+            first
+        };
+
+        let mut queue = TaskQueue::RocksDB(RocksDBTaskQueue::new(&sync_dir)?);
+        assert_eq!(queue.len(), 2);
+        let claimed = queue.claim()?.unwrap();
+        assert_eq!(claimed.id, first);
+        queue.ack(&claimed)?;
+        assert_eq!(queue.len(), 1);
+
+        queue.push(task(&repo, &commit))?;
+        let second = queue.claim()?.unwrap();
+        assert_ne!(second.id, first);
+        assert_eq!(queue.len(), 2);
+
+        let statuses =
+            queue.list_statuses_for_commit("Testing-Namespace", "Testing-Durable", &commit.id)?;
+        assert_eq!(statuses.len(), 3);
+        assert!(statuses.iter().all(|s| s.state == TaskState::Queued));
+
+        util::fs::remove_dir_all(sync_dir)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_workers_requeue_claimed_rocksdb() -> Result<(), OxenError> {
+        let sync_dir = test::get_sync_dir()?;
+        let repo = test::create_local_repo(&sync_dir, "Testing-Namespace", "Testing-Requeue")?;
+        let commit = api::local::commits::head_commit(&repo)?;
+
+        let mut queue = TaskQueue::RocksDB(RocksDBTaskQueue::new(&sync_dir)?);
+        let first = queue.push(task(&repo, &commit))?;
+        queue.claim()?.unwrap();
+
+        // Every task left in the queue is handed back so its lock can be cleared
+        let requeued = queue.requeue_claimed()?;
+        assert_eq!(requeued.len(), 1);
+        assert_eq!(requeued[0].id, first);
+
+        util::fs::remove_dir_all(sync_dir)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_workers_prune_old_statuses() -> Result<(), OxenError> {
+        let sync_dir = test::get_sync_dir()?;
+        let repo = test::create_local_repo(&sync_dir, "Testing-Namespace", "Testing-Prune")?;
+        let commit = api::local::commits::head_commit(&repo)?;
+
+        let mut queue = TaskQueue::InMemory(InMemoryTaskQueue::new());
+        let old = QueuedTask::new(task(&repo, &commit));
+        let mut status = TaskStatus::new(&old, TaskState::Succeeded, None);
+        status.updated_at -= TASK_STATUS_TTL + time::Duration::hours(1);
+        queue.set_status(&status)?;
+        let recent = queue.push(task(&repo, &commit))?;
+
+        assert_eq!(queue.prune_statuses()?, 1);
+        let statuses =
+            queue.list_statuses_for_commit("Testing-Namespace", "Testing-Prune", &commit.id)?;
+        assert_eq!(statuses.len(), 1);
+        assert_eq!(statuses[0].id, recent);
+
+        util::fs::remove_dir_all(sync_dir)?;
+
+        Ok(())
+    }
+}